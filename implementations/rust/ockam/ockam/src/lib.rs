@@ -26,6 +26,10 @@ pub use ockam_macros::{node, test};
 // ---
 
 // Export node implementation
+#[cfg(feature = "opentelemetry")]
+pub use ockam_node::opentelemetry;
+#[cfg(feature = "simulation")]
+pub use ockam_node::simulation;
 pub use ockam_node::{Context, DelayedEvent, Executor, NodeBuilder, WorkerBuilder};
 // ---
 
@@ -1,2 +1,105 @@
 #[cfg(feature = "direct-authenticator")]
 pub mod direct;
+
+use core::time::Duration;
+use ockam_core::Result;
+use ockam_identity::authenticated_storage::AuthenticatedStorage;
+use ockam_identity::credential::{AttributeSchema, AttributeType, Schema, SchemaId};
+use ockam_identity::IdentityIdentifier;
+
+/// Schema identifier for a project membership credential.
+///
+/// The credential will consist of the following attributes:
+///
+/// - `project_id` : bytes
+/// - `role`: b"member"
+pub const PROJECT_MEMBER_SCHEMA: SchemaId = SchemaId(1);
+pub const PROJECT_ID: &str = "project_id";
+pub const ROLE: &str = "role";
+
+/// How long the credentials issued to members are valid for, by default.
+///
+/// Removing a member only stops it from getting new credentials, and peers keep
+/// accepting the ones it already has until they expire. Credentials are therefore
+/// short-lived, and nodes refresh theirs before they expire.
+pub const CREDENTIAL_TTL: Duration = Duration::from_secs(10 * 60);
+
+const MEMBER: &str = "member";
+
+/// Storage identifier under which the member index is kept.
+const INDEX: &str = "direct_authenticator";
+const MEMBERS: &str = "members";
+
+/// The schema of [`PROJECT_MEMBER_SCHEMA`] credentials.
+///
+/// Enrollers and the Orchestrator may attach arbitrary attributes to
+/// members, which end up in their credentials next to the project
+/// identifier.  Their values are not checked, so that credentials issued
+/// before this schema was defined are still accepted.
+pub fn project_member_schema() -> Schema {
+    Schema::new(PROJECT_MEMBER_SCHEMA, "project_member")
+        .with_attribute(AttributeSchema::required(PROJECT_ID, AttributeType::Bytes))
+        .with_attribute(AttributeSchema::optional(ROLE, AttributeType::Bytes))
+        .with_additional_attributes(AttributeType::Bytes)
+}
+
+/// Store the (encoded) attributes of a member and add it to the member index.
+///
+/// Authenticators enrolling members this way share their list of members.
+pub(crate) async fn put_member<S: AuthenticatedStorage>(
+    store: &S,
+    member: &IdentityIdentifier,
+    attributes: Vec<u8>,
+) -> Result<()> {
+    store
+        .set(member.key_id(), MEMBER.to_string(), attributes)
+        .await?;
+    update_index(store, MEMBERS, |ids| {
+        if !ids.contains(member) {
+            ids.push(member.clone())
+        }
+    })
+    .await
+}
+
+async fn read_index<S: AuthenticatedStorage>(
+    store: &S,
+    key: &str,
+) -> Result<Vec<IdentityIdentifier>> {
+    match store.get(INDEX, key).await? {
+        Some(data) => Ok(minicbor::decode(&data)?),
+        None => Ok(Vec::new()),
+    }
+}
+
+async fn update_index<S, F>(store: &S, key: &str, f: F) -> Result<()>
+where
+    S: AuthenticatedStorage,
+    F: FnOnce(&mut Vec<IdentityIdentifier>),
+{
+    let mut ids = read_index(store, key).await?;
+    f(&mut ids);
+    store
+        .set(INDEX, key.to_string(), minicbor::to_vec(&ids)?)
+        .await
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use core::time::Duration;
+    use ockam_identity::credential::Attributes;
+
+    #[test]
+    fn project_member_schema_accepts_any_extra_attribute() {
+        let mut attrs = Attributes::new();
+        attrs.put(PROJECT_ID, b"project42");
+        attrs.put("trust_context", &[0xff, 0x00]);
+        let schema = project_member_schema();
+        assert!(schema.validate(&attrs, Duration::from_secs(600)).is_ok());
+
+        let mut attrs = Attributes::new();
+        attrs.put(ROLE, b"member");
+        assert!(schema.validate(&attrs, Duration::from_secs(600)).is_err());
+    }
+}
@@ -1,34 +1,42 @@
 pub mod types;
 
+use crate::error::ApiError;
 use core::{fmt, str};
-use minicbor::{Decoder, Encode};
+use minicbor::{Decode, Decoder, Encode};
 use ockam_core::api::{self, assert_request_match, assert_response_match};
 use ockam_core::api::{Error, Method, Request, RequestBuilder, Response, ResponseBuilder, Status};
+use ockam_core::compat::rand::random;
 use ockam_core::errcode::{Kind, Origin};
 use ockam_core::{self, Address, Result, Route, Routed, Worker};
 use ockam_identity::authenticated_storage::AuthenticatedStorage;
-use ockam_identity::credential::{Credential, SchemaId};
-use ockam_identity::{Identity, IdentityIdentifier, IdentitySecureChannelLocalInfo, IdentityVault};
+use ockam_identity::credential::{Credential, Timestamp, MAX_CREDENTIAL_VALIDITY};
+use ockam_identity::{
+    Identity, IdentityIdentifier, IdentitySecureChannelLocalInfo, IdentityStateConst, IdentityVault,
+};
 use ockam_node::Context;
 use serde_json as json;
 use std::collections::HashMap;
 use std::path::{Path, PathBuf};
+use std::time::Duration;
 use tracing::{trace, warn};
-use types::AddMember;
+use types::{AddMember, CreateToken, MemberInfo, MemberToken};
 
 use self::types::Enroller;
 
-const MEMBER: &str = "member";
+use super::{put_member, read_index, update_index, MEMBER, MEMBERS};
+pub use super::{CREDENTIAL_TTL, PROJECT_ID, PROJECT_MEMBER_SCHEMA, ROLE};
 
-/// Schema identifier for a project membership credential.
-///
-/// The credential will consist of the following attributes:
-///
-/// - `project_id` : bytes
-/// - `role`: b"member"
-pub const PROJECT_MEMBER_SCHEMA: SchemaId = SchemaId(1);
-pub const PROJECT_ID: &str = "project_id";
-pub const ROLE: &str = "role";
+const TOKEN: &str = "enrollment_token";
+
+/// A one-time enrollment token as kept in storage.
+#[derive(Debug, Encode, Decode)]
+#[rustfmt::skip]
+#[cbor(map)]
+struct TokenEntry {
+    #[n(1)] attributes: HashMap<String, String>,
+    /// Unix timestamp after which the token can no longer be redeemed.
+    #[n(2)] expires: u64,
+}
 
 pub struct Server<S, V: IdentityVault> {
     project: Vec<u8>,
@@ -36,6 +44,20 @@ pub struct Server<S, V: IdentityVault> {
     ident: Identity<V>,
     epath: PathBuf,
     enrollers: HashMap<IdentityIdentifier, Enroller>,
+    credential_ttl: Duration,
+}
+
+/// Decode the body of a request, answering with a bad request if it is malformed.
+macro_rules! decode_body {
+    ($req:expr, $dec:expr) => {
+        match $dec.decode() {
+            Ok(body) => body,
+            Err(e) => {
+                let msg = format!("invalid request body: {}", e);
+                return Ok(api::bad_request($req, &msg).to_vec()?);
+            }
+        }
+    };
 }
 
 #[ockam_core::worker]
@@ -47,6 +69,13 @@ where
     type Context = Context;
     type Message = Vec<u8>;
 
+    async fn initialize(&mut self, _: &mut Context) -> Result<()> {
+        self.ident
+            .register_schema(super::project_member_schema())
+            .await;
+        Ok(())
+    }
+
     async fn handle_message(&mut self, c: &mut Context, m: Routed<Self::Message>) -> Result<()> {
         if let Ok(i) = IdentitySecureChannelLocalInfo::find_info(m.local_message()) {
             let r = self.on_request(i.their_identity_id(), m.as_body()).await?;
@@ -75,9 +104,17 @@ where
             ident: identity,
             epath: enrollers.as_ref().to_path_buf(),
             enrollers: HashMap::new(),
+            credential_ttl: CREDENTIAL_TTL,
         }
     }
 
+    /// Issue credentials valid for the given duration instead of [`CREDENTIAL_TTL`],
+    /// up to [`MAX_CREDENTIAL_VALIDITY`].
+    pub fn with_credential_ttl(mut self, ttl: Duration) -> Self {
+        self.credential_ttl = ttl.min(MAX_CREDENTIAL_VALIDITY);
+        self
+    }
+
     async fn on_request(&mut self, from: &IdentityIdentifier, data: &[u8]) -> Result<Vec<u8>> {
         let mut dec = Decoder::new(data);
         let req: Request = dec.decode()?;
@@ -92,47 +129,164 @@ where
             "request"
         }
 
-        let res = match req.method() {
-            Some(Method::Post) => match req.path_segments::<2>().as_slice() {
-                // Enroller wants to add a member.
-                ["members"] => match self.check_enroller(&req, from).await {
-                    Ok(None) => {
-                        let add: AddMember = dec.decode()?;
-                        let attributes = minicbor::to_vec(add.attributes())?;
-                        self.store
-                            .set(add.member().key_id(), MEMBER.to_string(), attributes)
-                            .await?;
-                        Response::ok(req.id()).to_vec()?
+        let path = req.path_segments::<3>();
+        let res = match (req.method(), path.as_slice()) {
+            // Member wants a credential.
+            (Some(Method::Post), ["credential"]) => match self.get_member(&req, from).await {
+                Ok(Some(attrs)) => {
+                    let crd = attrs
+                        .iter()
+                        .fold(
+                            Credential::builder(from.clone())
+                                .with_schema(PROJECT_MEMBER_SCHEMA)
+                                .valid_for(self.credential_ttl),
+                            |crd, (a, v)| crd.with_attribute(a, v.as_bytes()),
+                        )
+                        .with_attribute(PROJECT_ID, &self.project);
+
+                    if let Err(error) = self.ident.validate_credential(&crd).await {
+                        return Ok(api::bad_request(&req, &error.to_string()).to_vec()?);
                     }
-                    Ok(Some(e)) => e.to_vec()?,
-                    Err(error) => api::internal_error(&req, &error.to_string()).to_vec()?,
-                },
-                // Member wants a credential.
-                ["credential"] => match self.get_member(&req, from).await {
-                    Ok(Some(attrs)) => {
-                        let crd = attrs
-                            .iter()
-                            .fold(
-                                Credential::builder(from.clone())
-                                    .with_schema(PROJECT_MEMBER_SCHEMA),
-                                |crd, (a, v)| crd.with_attribute(a, v.as_bytes()),
-                            )
-                            .with_attribute(PROJECT_ID, &self.project);
-
-                        let crd = self.ident.issue_credential(crd).await?;
-                        Response::ok(req.id()).body(crd).to_vec()?
+                    let crd = self.ident.issue_credential(crd).await?;
+                    Response::ok(req.id()).body(crd).to_vec()?
+                }
+                Ok(None) => api::forbidden(&req, "unauthorized member").to_vec()?,
+                Err(error) => api::internal_error(&req, &error.to_string()).to_vec()?,
+            },
+            // Identity wants to redeem an enrollment token.
+            (Some(Method::Post), ["enroll"]) => {
+                let tkn: MemberToken = decode_body!(&req, dec);
+                match self.redeem_token(tkn.token()).await? {
+                    Some(attrs) => {
+                        put_member(&self.store, from, minicbor::to_vec(attrs)?).await?;
+                        Response::ok(req.id()).to_vec()?
                     }
-                    Ok(None) => api::forbidden(&req, "unauthorized member").to_vec()?,
-                    Err(error) => api::internal_error(&req, &error.to_string()).to_vec()?,
-                },
-                _ => api::unknown_path(&req).to_vec()?,
+                    None => api::forbidden(&req, "invalid or expired token").to_vec()?,
+                }
+            }
+            // Enroller wants to manage members or tokens.
+            (Some(Method::Get | Method::Post | Method::Put), ["members"])
+            | (Some(Method::Delete), ["members", _])
+            | (Some(Method::Post), ["tokens"]) => match self.check_enroller(&req, from).await {
+                Ok(None) => self.on_enroller_request(&req, &mut dec).await?,
+                Ok(Some(e)) => e.to_vec()?,
+                Err(error) => api::internal_error(&req, &error.to_string()).to_vec()?,
             },
-            _ => api::invalid_method(&req).to_vec()?,
+            (Some(_), _) => api::unknown_path(&req).to_vec()?,
+            (None, _) => api::invalid_method(&req).to_vec()?,
         };
 
         Ok(res)
     }
 
+    async fn on_enroller_request(
+        &mut self,
+        req: &Request<'_>,
+        dec: &mut Decoder<'_>,
+    ) -> Result<Vec<u8>> {
+        let res = match (req.method(), req.path_segments::<3>().as_slice()) {
+            (Some(Method::Post), ["members"]) => {
+                let add: AddMember = decode_body!(req, dec);
+                let attributes = minicbor::to_vec(add.attributes())?;
+                put_member(&self.store, add.member(), attributes).await?;
+                Response::ok(req.id()).to_vec()?
+            }
+            (Some(Method::Put), ["members"]) => {
+                let upd: AddMember = decode_body!(req, dec);
+                if self.member_attributes(upd.member()).await?.is_none() {
+                    return Ok(Response::not_found(req.id()).to_vec()?);
+                }
+                let attributes = minicbor::to_vec(upd.attributes())?;
+                put_member(&self.store, upd.member(), attributes).await?;
+                Response::ok(req.id()).to_vec()?
+            }
+            (Some(Method::Get), ["members"]) => {
+                let mut members = Vec::new();
+                for id in read_index(&self.store, MEMBERS).await? {
+                    if let Some(attrs) = self.member_attributes(&id).await? {
+                        let attrs = attrs.into_iter().map(|(k, v)| (k.into(), v.into()));
+                        members.push(MemberInfo::new(id, attrs.collect()))
+                    }
+                }
+                Response::ok(req.id()).body(members).to_vec()?
+            }
+            (Some(Method::Delete), ["members", id]) => {
+                let id = match IdentityIdentifier::try_from(*id) {
+                    Ok(id) => id,
+                    Err(_) => return Ok(api::bad_request(req, "invalid identifier").to_vec()?),
+                };
+                if self.remove_member(&id).await? {
+                    Response::ok(req.id()).to_vec()?
+                } else {
+                    Response::not_found(req.id()).to_vec()?
+                }
+            }
+            (Some(Method::Post), ["tokens"]) => {
+                let crt: CreateToken = decode_body!(req, dec);
+                let now =
+                    Timestamp::now().ok_or_else(|| ApiError::generic("invalid system time"))?;
+                let expires = match u64::from(now).checked_add(crt.valid_for().as_secs()) {
+                    Some(expires) => expires,
+                    None => return Ok(api::bad_request(req, "invalid token validity").to_vec()?),
+                };
+                let entry = TokenEntry {
+                    attributes: crt
+                        .attributes()
+                        .iter()
+                        .map(|(k, v)| (k.to_string(), v.to_string()))
+                        .collect(),
+                    expires,
+                };
+                let token = hex::encode(random::<[u8; 16]>());
+                self.store
+                    .set(&token, TOKEN.to_string(), minicbor::to_vec(&entry)?)
+                    .await?;
+                Response::ok(req.id())
+                    .body(MemberToken::new(token))
+                    .to_vec()?
+            }
+            _ => api::unknown_path(req).to_vec()?,
+        };
+        Ok(res)
+    }
+
+    /// Remove a member, and the attributes it has presented to the authority node.
+    ///
+    /// The authority stops issuing credentials to the member, but verifiers keep
+    /// accepting those it already has until they expire, see [`CREDENTIAL_TTL`].
+    /// Expiry is what bounds the access of a removed member.
+    ///
+    /// Returns `false` if the identity is not a member.
+    async fn remove_member(&self, member: &IdentityIdentifier) -> Result<bool> {
+        if self.store.get(member.key_id(), MEMBER).await?.is_none() {
+            return Ok(false);
+        }
+        self.store.del(member.key_id(), MEMBER).await?;
+        self.store
+            .del(&member.to_string(), IdentityStateConst::ATTRIBUTES_KEY)
+            .await?;
+        update_index(&self.store, MEMBERS, |ids| ids.retain(|i| i != member)).await?;
+        Ok(true)
+    }
+
+    /// Consume a one-time token and return the attributes it grants.
+    async fn redeem_token(&self, token: &str) -> Result<Option<HashMap<String, String>>> {
+        let entry: TokenEntry = match self.store.get(token, TOKEN).await? {
+            Some(data) => minicbor::decode(&data)?,
+            None => return Ok(None),
+        };
+        self.store.del(token, TOKEN).await?;
+        let now = Timestamp::now().ok_or_else(|| ApiError::generic("invalid system time"))?;
+        if entry.expires <= u64::from(now) {
+            warn! {
+                target: "ockam_api::authenticator::direct::server",
+                "expired enrollment token"
+            }
+            return Ok(None);
+        }
+        Ok(Some(entry.attributes))
+    }
+
     async fn check_enroller<'a>(
         &mut self,
         req: &'a Request<'_>,
@@ -170,6 +324,25 @@ where
         &self,
         req: &'a Request<'_>,
         member: &IdentityIdentifier,
+    ) -> Result<Option<HashMap<String, String>>> {
+        if let Some(attrs) = self.member_attributes(member).await? {
+            return Ok(Some(attrs));
+        }
+        warn! {
+            target: "ockam_api::authenticator::direct::server",
+            member   = %member,
+            id       = %req.id(),
+            method   = ?req.method(),
+            path     = %req.path(),
+            body     = %req.has_body(),
+            "unauthorised member"
+        }
+        Ok(None)
+    }
+
+    async fn member_attributes(
+        &self,
+        member: &IdentityIdentifier,
     ) -> Result<Option<HashMap<String, String>>> {
         if let Some(data) = self.store.get(member.key_id(), MEMBER).await? {
             match minicbor::decode(&data) {
@@ -191,15 +364,6 @@ where
                 }
             }
         }
-        warn! {
-            target: "ockam_api::authenticator::direct::server",
-            member   = %member,
-            id       = %req.id(),
-            method   = ?req.method(),
-            path     = %req.path(),
-            body     = %req.has_body(),
-            "unauthorised member"
-        }
         Ok(None)
     }
 }
@@ -245,6 +409,85 @@ impl Client {
         }
     }
 
+    pub async fn update_member(
+        &mut self,
+        id: IdentityIdentifier,
+        attributes: HashMap<&str, &str>,
+    ) -> Result<()> {
+        let req = Request::put("/members").body(AddMember::new(id).with_attributes(attributes));
+        self.buf = self.request("update-member", "add_member", &req).await?;
+        assert_response_match(None, &self.buf);
+        let mut d = Decoder::new(&self.buf);
+        let res = response("update-member", &mut d)?;
+        if res.status() == Some(Status::Ok) {
+            Ok(())
+        } else {
+            Err(error("update-member", &res, &mut d))
+        }
+    }
+
+    pub async fn remove_member(&mut self, id: &IdentityIdentifier) -> Result<()> {
+        let req = Request::delete(format!("/members/{id}"));
+        self.buf = self.request("remove-member", None, &req).await?;
+        assert_response_match(None, &self.buf);
+        let mut d = Decoder::new(&self.buf);
+        let res = response("remove-member", &mut d)?;
+        if res.status() == Some(Status::Ok) {
+            Ok(())
+        } else {
+            Err(error("remove-member", &res, &mut d))
+        }
+    }
+
+    pub async fn list_members(&mut self) -> Result<Vec<MemberInfo<'_>>> {
+        let req = Request::get("/members");
+        self.buf = self.request("list-members", None, &req).await?;
+        assert_response_match("members", &self.buf);
+        let mut d = Decoder::new(&self.buf);
+        let res = response("list-members", &mut d)?;
+        if res.status() == Some(Status::Ok) {
+            Ok(d.decode()?)
+        } else {
+            Err(error("list-members", &res, &mut d))
+        }
+    }
+
+    /// Create a one-time token which enrolls its bearer with the given attributes.
+    pub async fn create_token(
+        &mut self,
+        attributes: HashMap<&str, &str>,
+        valid_for: Duration,
+    ) -> Result<String> {
+        let req =
+            Request::post("/tokens").body(CreateToken::new(valid_for).with_attributes(attributes));
+        self.buf = self
+            .request("create-token", "create_member_token", &req)
+            .await?;
+        assert_response_match("member_token", &self.buf);
+        let mut d = Decoder::new(&self.buf);
+        let res = response("create-token", &mut d)?;
+        if res.status() == Some(Status::Ok) {
+            let tkn: MemberToken = d.decode()?;
+            Ok(tkn.token().to_string())
+        } else {
+            Err(error("create-token", &res, &mut d))
+        }
+    }
+
+    /// Redeem a one-time token, enrolling this client's identity as a member.
+    pub async fn enroll(&mut self, token: &str) -> Result<()> {
+        let req = Request::post("/enroll").body(MemberToken::new(token));
+        self.buf = self.request("enroll", "member_token", &req).await?;
+        assert_response_match(None, &self.buf);
+        let mut d = Decoder::new(&self.buf);
+        let res = response("enroll", &mut d)?;
+        if res.status() == Some(Status::Ok) {
+            Ok(())
+        } else {
+            Err(error("enroll", &res, &mut d))
+        }
+    }
+
     pub async fn credential(&mut self) -> Result<Credential<'_>> {
         let req = Request::post("/credential");
         self.buf = self.request("new-credential", None, &req).await?;
@@ -3,6 +3,7 @@ use ockam_core::CowStr;
 use ockam_identity::IdentityIdentifier;
 use serde::{Deserialize, Serialize};
 use std::collections::HashMap;
+use std::time::Duration;
 
 #[cfg(feature = "tag")]
 use ockam_core::TypeTag;
@@ -46,3 +47,99 @@ impl<'a> AddMember<'a> {
 
 #[derive(Debug, Default, Deserialize, Serialize)]
 pub struct Enroller {}
+
+/// A member together with the attributes it has been enrolled with.
+#[derive(Debug, Decode, Encode, Serialize)]
+#[rustfmt::skip]
+#[cbor(map)]
+pub struct MemberInfo<'a> {
+    #[cfg(feature = "tag")]
+    #[serde(skip)]
+    #[n(0)] tag: TypeTag<4137560>,
+    #[n(1)] member: IdentityIdentifier,
+    #[b(2)] attributes: HashMap<CowStr<'a>, CowStr<'a>>,
+}
+
+impl<'a> MemberInfo<'a> {
+    pub fn new(member: IdentityIdentifier, attributes: HashMap<CowStr<'a>, CowStr<'a>>) -> Self {
+        MemberInfo {
+            #[cfg(feature = "tag")]
+            tag: TypeTag,
+            member,
+            attributes,
+        }
+    }
+
+    pub fn member(&self) -> &IdentityIdentifier {
+        &self.member
+    }
+
+    pub fn attributes(&self) -> &HashMap<CowStr<'_>, CowStr<'_>> {
+        &self.attributes
+    }
+}
+
+/// Request to create a one-time enrollment token.
+#[derive(Debug, Decode, Encode)]
+#[rustfmt::skip]
+#[cbor(map)]
+pub struct CreateToken<'a> {
+    #[cfg(feature = "tag")]
+    #[n(0)] tag: TypeTag<6650287>,
+    /// Attributes the redeeming identity will be enrolled with.
+    #[b(1)] attributes: HashMap<CowStr<'a>, CowStr<'a>>,
+    /// Validity of the token in seconds.
+    #[n(2)] valid_for: u64,
+}
+
+impl<'a> CreateToken<'a> {
+    pub fn new(valid_for: Duration) -> Self {
+        CreateToken {
+            #[cfg(feature = "tag")]
+            tag: TypeTag,
+            attributes: HashMap::new(),
+            valid_for: valid_for.as_secs(),
+        }
+    }
+
+    pub fn with_attributes<S: Into<CowStr<'a>>>(mut self, attributes: HashMap<S, S>) -> Self {
+        self.attributes = attributes
+            .into_iter()
+            .map(|(k, v)| (k.into(), v.into()))
+            .collect();
+        self
+    }
+
+    pub fn attributes(&self) -> &HashMap<CowStr<'_>, CowStr<'_>> {
+        &self.attributes
+    }
+
+    pub fn valid_for(&self) -> Duration {
+        Duration::from_secs(self.valid_for)
+    }
+}
+
+/// A one-time token which enrolls the identity redeeming it as a member.
+#[derive(Debug, Decode, Encode, Serialize)]
+#[rustfmt::skip]
+#[cbor(map)]
+pub struct MemberToken<'a> {
+    #[cfg(feature = "tag")]
+    #[serde(skip)]
+    #[n(0)] tag: TypeTag<9135802>,
+    #[b(1)] token: CowStr<'a>,
+}
+
+impl<'a> MemberToken<'a> {
+    pub fn new<S: Into<CowStr<'a>>>(token: S) -> Self {
+        MemberToken {
+            #[cfg(feature = "tag")]
+            tag: TypeTag,
+            token: token.into(),
+        }
+    }
+
+    pub fn token(&self) -> &str {
+        &self.token
+    }
+}
@@ -5,6 +5,7 @@ use ockam_core::compat::collections::VecDeque;
 use ockam_core::{CowStr, Result};
 use ockam_identity::{IdentityIdentifier, PublicIdentity};
 use ockam_multiaddr::{
+    pattern::{Rewriter, Rule},
     proto::{self, DnsAddr, Ip4, Ip6, Tcp},
     MultiAddr,
 };
@@ -16,6 +17,7 @@ use std::{
     net::{SocketAddr, SocketAddrV4, SocketAddrV6},
     str::FromStr,
 };
+use tracing::warn;
 
 #[derive(Debug, Default)]
 pub struct LookupMeta {
@@ -129,6 +131,85 @@ impl ConfigLookup {
         Some(m)
     }
 
+    /// Store a named address, referred to as `/alias/<name>`
+    pub fn set_alias(&mut self, name: &str, addr: MultiAddr) {
+        self.map
+            .insert(format!("/alias/{}", name), LookupValue::Alias(addr));
+    }
+
+    pub fn get_alias(&self, name: &str) -> Option<&MultiAddr> {
+        self.map
+            .get(&format!("/alias/{}", name))
+            .and_then(|value| match value {
+                LookupValue::Alias(addr) => Some(addr),
+                _ => None,
+            })
+    }
+
+    pub fn remove_alias(&mut self, name: &str) -> Option<LookupValue> {
+        self.map.remove(&format!("/alias/{}", name))
+    }
+
+    pub fn aliases(&self) -> impl Iterator<Item = (&str, &MultiAddr)> + '_ {
+        self.map.iter().filter_map(|(k, v)| match v {
+            LookupValue::Alias(addr) => Some((k.strip_prefix("/alias/").unwrap_or(k), addr)),
+            _ => None,
+        })
+    }
+
+    /// Whether `name` can be used as an alias name.
+    ///
+    /// Names are restricted to the characters of pattern capture names, so
+    /// that they are taken literally in the rules which expand aliases.
+    pub fn is_alias_name(name: &str) -> bool {
+        !name.is_empty()
+            && name
+                .chars()
+                .all(|c| c.is_alphanumeric() || c == '_' || c == '-')
+    }
+
+    /// Rewrite rules which replace every `/alias/<name>` with its address.
+    pub fn alias_rules(&self) -> impl Iterator<Item = Rule> + '_ {
+        self.aliases().filter_map(|(name, addr)| {
+            if !Self::is_alias_name(name) {
+                warn!(alias = %name, "ignoring alias with an invalid name");
+                return None;
+            }
+            match format!("/$a../alias/{name}/$b.. => /$a{addr}/$b").parse() {
+                Ok(rule) => Some(rule),
+                Err(e) => {
+                    warn!(alias = %name, %addr, err = %e, "ignoring alias without a valid rewrite rule");
+                    None
+                }
+            }
+        })
+    }
+
+    /// All rewrite rules, to expand aliases and then node names.
+    ///
+    /// As aliases are expanded until none is left, an alias may refer
+    /// to nodes and to other aliases.
+    pub fn rewriter(&self) -> Rewriter {
+        self.alias_rules().chain(self.node_rules()).collect()
+    }
+
+    /// Rewrite rules which replace every `/node/<name>` with the address
+    /// of that node, e.g. `/node/n1/service/echo` with
+    /// `/dnsaddr/localhost/tcp/6252/service/echo`.
+    pub fn node_rules(&self) -> impl Iterator<Item = Rule> + '_ {
+        self.map.keys().filter_map(|k| {
+            let name = k.strip_prefix("/node/")?;
+            let addr = self.node_address(&proto::Node::new(name))?;
+            match format!("/$a../node/{name}/$b.. => /$a{addr}/$b").parse() {
+                Ok(rule) => Some(rule),
+                Err(e) => {
+                    warn!(node = %name, %addr, err = %e, "ignoring node without a valid rewrite rule");
+                    None
+                }
+            }
+        })
+    }
+
     pub fn projects(&self) -> impl Iterator<Item = (String, ProjectLookup)> + '_ {
         self.map.iter().filter_map(|(k, v)| {
             if let LookupValue::Project(p) = v {
@@ -147,6 +228,7 @@ pub enum LookupValue {
     Address(InternetAddress),
     Space(SpaceLookup),
     Project(ProjectLookup),
+    Alias(MultiAddr),
 }
 
 /// An internet address abstraction (v6/v4/dns)
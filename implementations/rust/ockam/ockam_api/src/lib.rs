@@ -6,6 +6,7 @@ pub mod echoer;
 pub mod error;
 pub mod identity;
 pub mod nodes;
+pub mod oidc;
 pub mod okta;
 pub mod uppercase;
 pub mod vault;
@@ -34,6 +35,7 @@ impl DefaultAddress {
     pub const AUTHENTICATOR: &'static str = "authenticator";
     pub const VERIFIER: &'static str = "verifier";
     pub const OKTA_IDENTITY_PROVIDER: &'static str = "okta";
+    pub const OIDC_AUTHENTICATOR: &'static str = "oidc_authenticator";
 }
 
 pub mod actions {
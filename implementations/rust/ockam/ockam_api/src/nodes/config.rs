@@ -1,5 +1,6 @@
 use std::fmt::Formatter;
 use std::io::Write;
+use std::net::SocketAddr;
 use std::{
     fmt::Display,
     fs::File,
@@ -178,6 +179,8 @@ pub struct NodeStateConfig {
     pub identity: Option<Vec<u8>>,
     /// Identity was overridden
     pub identity_was_overridden: bool,
+    /// Address of the plain HTTP health endpoint
+    pub health_address: Option<SocketAddr>,
 }
 
 impl ConfigValues for NodeStateConfig {
@@ -1,7 +1,9 @@
 pub mod config;
 pub mod registry;
+pub mod routes;
 
 pub mod service;
+pub mod vault;
 
 pub mod models;
 
@@ -10,3 +12,4 @@ pub const NODEMANAGER_ADDR: &str = "_internal.nodemanager";
 
 /// The main node-manager service running on remote nodes
 pub use service::{IdentityOverride, NodeManager, NodeManagerWorker};
+pub use vault::NodeVault;
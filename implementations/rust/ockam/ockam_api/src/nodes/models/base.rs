@@ -4,6 +4,10 @@
 
 use minicbor::{Decode, Encode};
 use ockam_core::CowStr;
+use serde::Serialize;
+use std::fmt;
+
+use super::transport::TransportStatus;
 
 #[cfg(feature = "tag")]
 use ockam_core::TypeTag;
@@ -12,6 +16,30 @@ use ockam_core::TypeTag;
 
 ///////////////////-!  RESPONSE BODIES
 
+/// Request and response body for the log filter of a node
+///
+/// The filter uses the syntax of the `OCKAM_LOG` environment variable,
+/// e.g. `info` or `info,ockam_api=debug`.
+#[derive(Debug, Clone, Decode, Encode, Serialize)]
+#[rustfmt::skip]
+#[cbor(map)]
+pub struct LogLevel<'a> {
+    #[cfg(feature = "tag")]
+    #[serde(skip)]
+    #[n(0)] tag: TypeTag<2604419>,
+    #[b(1)] pub filter: CowStr<'a>,
+}
+
+impl<'a> LogLevel<'a> {
+    pub fn new(filter: impl Into<CowStr<'a>>) -> Self {
+        Self {
+            #[cfg(feature = "tag")]
+            tag: TypeTag,
+            filter: filter.into(),
+        }
+    }
+}
+
 /// Response body for a node status
 #[derive(Debug, Clone, Decode, Encode)]
 #[rustfmt::skip]
@@ -45,3 +73,93 @@ impl<'a> NodeStatus<'a> {
         }
     }
 }
+
+/// Response body for the health of a node
+///
+/// A node is ready when its credential, if it needs one, has not expired
+/// and all the sessions it keeps up are up.
+#[derive(Debug, Clone, Decode, Encode, Serialize)]
+#[rustfmt::skip]
+#[cbor(map)]
+pub struct NodeHealth<'a> {
+    #[cfg(feature = "tag")]
+    #[serde(skip)]
+    #[n(0)] tag: TypeTag<3957431>,
+    #[b(1)] pub node_name: CowStr<'a>,
+    #[n(2)] pub ready: bool,
+    #[n(3)] pub pid: i32,
+    #[n(4)] pub workers: u32,
+    #[b(5)] pub transports: Vec<TransportStatus<'a>>,
+    #[b(6)] pub secure_channel_listeners: Vec<CowStr<'a>>,
+    #[n(7)] pub credential: CredentialState,
+    /// Expiration of the credential, in seconds since the Unix epoch
+    #[n(8)] pub credential_expires_at: Option<u64>,
+    #[b(9)] pub sessions: Vec<SessionHealth<'a>>,
+}
+
+impl<'a> NodeHealth<'a> {
+    pub fn new(node_name: impl Into<CowStr<'a>>, pid: i32, workers: u32) -> Self {
+        Self {
+            #[cfg(feature = "tag")]
+            tag: TypeTag,
+            node_name: node_name.into(),
+            ready: true,
+            pid,
+            workers,
+            transports: vec![],
+            secure_channel_listeners: vec![],
+            credential: CredentialState::NotRequired,
+            credential_expires_at: None,
+            sessions: vec![],
+        }
+    }
+}
+
+/// State of the credential of a node
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Decode, Encode, Serialize)]
+#[rustfmt::skip]
+#[cbor(index_only)]
+#[serde(rename_all = "snake_case")]
+pub enum CredentialState {
+    /// The node has no authority to get a credential from
+    #[n(0)] NotRequired,
+    /// The node has not got a credential from its authority yet
+    #[n(1)] Missing,
+    #[n(2)] Valid,
+    #[n(3)] Expired,
+}
+
+impl fmt::Display for CredentialState {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(match self {
+            Self::NotRequired => "not required",
+            Self::Missing => "missing",
+            Self::Valid => "valid",
+            Self::Expired => "expired",
+        })
+    }
+}
+
+/// Status of a session kept up by a node
+#[derive(Debug, Clone, Decode, Encode, Serialize)]
+#[rustfmt::skip]
+#[cbor(map)]
+pub struct SessionHealth<'a> {
+    #[cfg(feature = "tag")]
+    #[serde(skip)]
+    #[n(0)] tag: TypeTag<8524613>,
+    /// Address the session checks by sending pings
+    #[b(1)] pub addr: CowStr<'a>,
+    #[b(2)] pub status: CowStr<'a>,
+}
+
+impl<'a> SessionHealth<'a> {
+    pub fn new(addr: impl Into<CowStr<'a>>, status: impl Into<CowStr<'a>>) -> Self {
+        Self {
+            #[cfg(feature = "tag")]
+            tag: TypeTag,
+            addr: addr.into(),
+            status: status.into(),
+        }
+    }
+}
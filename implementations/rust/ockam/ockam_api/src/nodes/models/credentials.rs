@@ -34,6 +34,8 @@ pub struct PresentCredentialRequest<'a> {
     #[n(0)] tag: TypeTag<3698687>,
     #[b(1)] pub route: Cow<'a, str>,
     #[n(2)] pub oneway: bool,
+    /// Attributes to disclose, all of them if not set.
+    #[b(3)] pub attributes: Option<Vec<Cow<'a, str>>>,
 }
 
 impl<'a> PresentCredentialRequest<'a> {
@@ -43,6 +45,15 @@ impl<'a> PresentCredentialRequest<'a> {
             tag: TypeTag,
             route: route.to_string().into(),
             oneway,
+            attributes: None,
         }
     }
+
+    pub fn with_attributes<S: Into<Cow<'a, str>>>(
+        mut self,
+        attributes: impl IntoIterator<Item = S>,
+    ) -> Self {
+        self.attributes = Some(attributes.into_iter().map(Into::into).collect());
+        self
+    }
 }
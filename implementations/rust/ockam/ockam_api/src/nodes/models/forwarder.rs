@@ -83,9 +83,25 @@ pub struct ForwarderInfo<'a> {
     #[b(1)] forwarding_route: CowStr<'a>,
     #[b(2)] remote_address: CowStr<'a>,
     #[b(3)] worker_address: CowStr<'a>,
+    #[b(4)] alias: Option<CowStr<'a>>,
 }
 
 impl<'a> ForwarderInfo<'a> {
+    pub fn new(
+        forwarding_route: impl Into<CowStr<'a>>,
+        remote_address: impl Into<CowStr<'a>>,
+        worker_address: impl Into<CowStr<'a>>,
+    ) -> Self {
+        Self {
+            #[cfg(feature = "tag")]
+            tag: Default::default(),
+            forwarding_route: forwarding_route.into(),
+            remote_address: remote_address.into(),
+            worker_address: worker_address.into(),
+            alias: None,
+        }
+    }
+
     pub fn forwarding_route(&'a self) -> &'a str {
         &self.forwarding_route
     }
@@ -93,16 +109,48 @@ impl<'a> ForwarderInfo<'a> {
     pub fn remote_address(&'a self) -> &'a str {
         &self.remote_address
     }
+
+    pub fn worker_address(&'a self) -> &'a str {
+        &self.worker_address
+    }
+
+    pub fn alias(&self) -> Option<&str> {
+        self.alias.as_deref()
+    }
+
+    pub fn with_alias(mut self, alias: Option<&str>) -> Self {
+        self.alias = alias.map(|a| a.to_string().into());
+        self
+    }
 }
 
 impl<'a> From<RemoteForwarderInfo> for ForwarderInfo<'a> {
     fn from(inner: RemoteForwarderInfo) -> Self {
+        Self::new(
+            inner.forwarding_route().to_string(),
+            inner.remote_address().to_string(),
+            inner.worker_address().to_string(),
+        )
+    }
+}
+
+/// Response body when listing the forwarders of a node
+#[derive(Debug, Clone, Decode, Encode, serde::Serialize)]
+#[rustfmt::skip]
+#[cbor(map)]
+pub struct ForwarderList<'a> {
+    #[cfg(feature = "tag")]
+    #[serde(skip)]
+    #[n(0)] tag: TypeTag<6126519>,
+    #[b(1)] pub list: Vec<ForwarderInfo<'a>>
+}
+
+impl<'a> ForwarderList<'a> {
+    pub fn new(list: Vec<ForwarderInfo<'a>>) -> Self {
         Self {
             #[cfg(feature = "tag")]
             tag: Default::default(),
-            forwarding_route: inner.forwarding_route().to_string().into(),
-            remote_address: inner.remote_address().to_string().into(),
-            worker_address: inner.worker_address().to_string().into(),
+            list,
         }
     }
 }
@@ -12,3 +12,4 @@ pub mod secure_channel;
 pub mod services;
 pub mod transport;
 pub mod vault;
+pub mod workers;
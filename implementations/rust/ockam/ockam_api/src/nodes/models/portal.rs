@@ -4,6 +4,7 @@ use std::net::SocketAddr;
 
 use minicbor::{Decode, Encode};
 use ockam_core::compat::borrow::Cow;
+use serde::Serialize;
 
 use ockam_core::CowStr;
 #[cfg(feature = "tag")]
@@ -127,11 +128,12 @@ impl<'a> CreateOutlet<'a> {
 }
 
 /// Response body when interacting with a portal endpoint
-#[derive(Clone, Debug, Decode, Encode)]
+#[derive(Clone, Debug, Decode, Encode, Serialize)]
 #[rustfmt::skip]
 #[cbor(map)]
 pub struct InletStatus<'a> {
     #[cfg(feature = "tag")]
+    #[serde(skip)]
     #[n(0)] tag: TypeTag<9302588>,
     #[b(1)] pub bind_addr: CowStr<'a>,
     #[b(2)] pub worker_addr: CowStr<'a>,
@@ -174,11 +176,12 @@ impl<'a> InletStatus<'a> {
 }
 
 /// Response body when interacting with a portal endpoint
-#[derive(Clone, Debug, Decode, Encode)]
+#[derive(Clone, Debug, Decode, Encode, Serialize)]
 #[rustfmt::skip]
 #[cbor(map)]
 pub struct OutletStatus<'a> {
     #[cfg(feature = "tag")]
+    #[serde(skip)]
     #[n(0)] tag: TypeTag<4012569>,
     #[b(1)] pub tcp_addr: CowStr<'a>,
     #[b(2)] pub worker_addr: CowStr<'a>,
@@ -217,11 +220,12 @@ impl<'a> OutletStatus<'a> {
 }
 
 /// Response body when returning a list of Inlets
-#[derive(Debug, Clone, Decode, Encode)]
+#[derive(Debug, Clone, Decode, Encode, Serialize)]
 #[rustfmt::skip]
 #[cbor(map)]
 pub struct InletList<'a> {
     #[cfg(feature = "tag")]
+    #[serde(skip)]
     #[n(0)] tag: TypeTag<8401504>,
     #[b(1)] pub list: Vec<InletStatus<'a>>
 }
@@ -237,11 +241,12 @@ impl<'a> InletList<'a> {
 }
 
 /// Response body when returning a list of Outlets
-#[derive(Debug, Clone, Decode, Encode)]
+#[derive(Debug, Clone, Decode, Encode, Serialize)]
 #[rustfmt::skip]
 #[cbor(map)]
 pub struct OutletList<'a> {
     #[cfg(feature = "tag")]
+    #[serde(skip)]
     #[n(0)] tag: TypeTag<8708916>,
     #[b(1)] pub list: Vec<OutletStatus<'a>>
 }
@@ -3,6 +3,7 @@ use std::time::Duration;
 use minicbor::{Decode, Encode};
 
 use crate::nodes::registry::SecureChannelInfo;
+use crate::session::Status;
 use ockam_core::compat::borrow::Cow;
 #[cfg(feature = "tag")]
 use ockam_core::TypeTag;
@@ -175,6 +176,7 @@ pub struct ShowSecureChannelResponse<'a> {
     #[b(1)] pub channel: Option<Cow<'a, str>>,
     #[b(2)] pub route: Option<Cow<'a, str>>,
     #[b(4)] pub authorized_identifiers: Option<Vec<CowStr<'a>>>,
+    #[b(5)] pub status: Option<CowStr<'a>>,
 }
 
 impl<'a> ShowSecureChannelResponse<'a> {
@@ -190,6 +192,13 @@ impl<'a> ShowSecureChannelResponse<'a> {
                         .map(|ids| ids.iter().map(|iid| iid.to_string().into()).collect())
                 })
                 .unwrap_or(None),
+            status: None,
         }
     }
+
+    /// Set the status of the session keeping the channel up.
+    pub fn with_status(mut self, status: Option<Status>) -> Self {
+        self.status = status.map(|s| s.to_string().into());
+        self
+    }
 }
@@ -1,7 +1,9 @@
 use std::path::Path;
 
+use crate::oidc::ClaimMapping;
 use minicbor::{Decode, Encode};
 use ockam_core::{CowBytes, CowStr};
+use serde::Serialize;
 
 #[cfg(feature = "tag")]
 use ockam_core::TypeTag;
@@ -243,11 +245,69 @@ impl<'a> StartOktaIdentityProviderRequest<'a> {
     }
 }
 
+/// Request body when instructing a node to start an OIDC authenticator service
 #[derive(Debug, Clone, Decode, Encode)]
 #[rustfmt::skip]
 #[cbor(map)]
+pub struct StartOidcAuthenticatorRequest<'a> {
+    #[cfg(feature = "tag")]
+    #[n(0)] tag: TypeTag<3069134>,
+    #[b(1)] addr: CowStr<'a>,
+    #[b(2)] issuer: CowStr<'a>,
+    #[b(3)] audience: CowStr<'a>,
+    /// Path to the provider's JSON Web Key Set.
+    #[b(4)] jwks: CowStr<'a>,
+    #[n(5)] mappings: Vec<ClaimMapping>,
+    #[b(6)] proj: CowBytes<'a>,
+}
+
+impl<'a> StartOidcAuthenticatorRequest<'a> {
+    pub fn new(
+        addr: impl Into<CowStr<'a>>,
+        issuer: impl Into<CowStr<'a>>,
+        audience: impl Into<CowStr<'a>>,
+        jwks: impl Into<CowStr<'a>>,
+        mappings: Vec<ClaimMapping>,
+        proj: impl Into<CowBytes<'a>>,
+    ) -> Self {
+        Self {
+            #[cfg(feature = "tag")]
+            tag: TypeTag,
+            addr: addr.into(),
+            issuer: issuer.into(),
+            audience: audience.into(),
+            jwks: jwks.into(),
+            mappings,
+            proj: proj.into(),
+        }
+    }
+
+    pub fn address(&'a self) -> &'a str {
+        &self.addr
+    }
+    pub fn issuer(&'a self) -> &'a str {
+        &self.issuer
+    }
+    pub fn audience(&'a self) -> &'a str {
+        &self.audience
+    }
+    pub fn jwks(&'a self) -> &'a str {
+        &self.jwks
+    }
+    pub fn mappings(&self) -> &[ClaimMapping] {
+        &self.mappings
+    }
+    pub fn project(&'a self) -> &'a [u8] {
+        &self.proj
+    }
+}
+
+#[derive(Debug, Clone, Decode, Encode, Serialize)]
+#[rustfmt::skip]
+#[cbor(map)]
 pub struct ServiceStatus<'a> {
     #[cfg(feature = "tag")]
+    #[serde(skip)]
     #[n(0)] tag: TypeTag<8542064>,
     #[b(2)] pub addr: CowStr<'a>,
     #[b(3)] pub service_type: CowStr<'a>,
@@ -265,11 +325,12 @@ impl<'a> ServiceStatus<'a> {
 }
 
 /// Response body for listing services
-#[derive(Debug, Clone, Decode, Encode)]
+#[derive(Debug, Clone, Decode, Encode, Serialize)]
 #[rustfmt::skip]
 #[cbor(map)]
 pub struct ServiceList<'a> {
     #[cfg(feature = "tag")]
+    #[serde(skip)]
     #[n(0)] tag: TypeTag<9587601>,
     #[b(1)] pub list: Vec<ServiceStatus<'a>>
 }
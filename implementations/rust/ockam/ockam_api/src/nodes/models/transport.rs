@@ -1,5 +1,6 @@
 use minicbor::{Decode, Encode};
 use ockam_core::CowStr;
+use serde::Serialize;
 use std::fmt::{self, Display};
 
 #[cfg(feature = "tag")]
@@ -61,7 +62,7 @@ impl<'a> DeleteTransport<'a> {
 /// Encode which type of transport is being requested
 // TODO: we have a TransportType in ockam_core.  Do we really want to
 // mirror this kind of type here?
-#[derive(Copy, Clone, Debug, Decode, Encode)]
+#[derive(Copy, Clone, Debug, Decode, Encode, Serialize)]
 #[rustfmt::skip]
 #[cbor(index_only)]
 pub enum TransportType {
@@ -84,7 +85,7 @@ impl Display for TransportType {
 }
 
 /// Encode which type of transport is being requested
-#[derive(Copy, Clone, Debug, Decode, Encode, PartialEq, Eq)]
+#[derive(Copy, Clone, Debug, Decode, Encode, PartialEq, Eq, Serialize)]
 #[rustfmt::skip]
 pub enum TransportMode {
     /// Listen on a set address
@@ -105,11 +106,12 @@ impl Display for TransportMode {
 ///////////////////-!  RESPONSE BODIES
 
 /// Response body when interacting with a transport
-#[derive(Debug, Clone, Decode, Encode)]
+#[derive(Debug, Clone, Decode, Encode, Serialize)]
 #[rustfmt::skip]
 #[cbor(map)]
 pub struct TransportStatus<'a> {
     #[cfg(feature = "tag")]
+    #[serde(skip)]
     #[n(0)] tag: TypeTag<1581592>,
     /// The type of transport to create
     #[n(2)] pub tt: TransportType,
@@ -143,11 +145,12 @@ impl<'a> TransportStatus<'a> {
 }
 
 /// Response body when interacting with a transport
-#[derive(Debug, Clone, Decode, Encode)]
+#[derive(Debug, Clone, Decode, Encode, Serialize)]
 #[rustfmt::skip]
 #[cbor(map)]
 pub struct TransportList<'a> {
     #[cfg(feature = "tag")]
+    #[serde(skip)]
     #[n(0)] tag: TypeTag<5212817>,
     #[b(1)] pub list: Vec<TransportStatus<'a>>
 }
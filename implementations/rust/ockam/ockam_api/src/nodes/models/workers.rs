@@ -0,0 +1,76 @@
+use minicbor::{Decode, Encode};
+use ockam_core::CowStr;
+use ockam_node::WorkerInfo;
+use serde::Serialize;
+
+#[cfg(feature = "tag")]
+use ockam_core::TypeTag;
+
+/// Description of an address registered on a node
+#[derive(Debug, Clone, Decode, Encode, Serialize)]
+#[rustfmt::skip]
+#[cbor(map)]
+pub struct WorkerStatus<'a> {
+    #[cfg(feature = "tag")]
+    #[serde(skip)]
+    #[n(0)] tag: TypeTag<4013887>,
+    #[b(1)] pub addr: CowStr<'a>,
+    #[b(2)] pub aliases: Vec<CowStr<'a>>,
+    #[b(3)] pub cluster: Option<CowStr<'a>>,
+    /// One of "worker", "processor" or "detached"
+    #[b(4)] pub worker_type: CowStr<'a>,
+    #[b(5)] pub access_control: CowStr<'a>,
+    #[n(6)] pub queued: u64,
+    #[n(7)] pub handled: u64,
+    /// Seconds since the Unix epoch
+    #[n(8)] pub started_at: Option<u64>,
+}
+
+impl<'a> From<WorkerInfo> for WorkerStatus<'a> {
+    fn from(info: WorkerInfo) -> Self {
+        let worker_type = if info.processor {
+            "processor"
+        } else if info.detached {
+            "detached"
+        } else {
+            "worker"
+        };
+        Self {
+            #[cfg(feature = "tag")]
+            tag: TypeTag,
+            addr: info.address.address().to_string().into(),
+            aliases: info
+                .aliases
+                .iter()
+                .map(|a| a.address().to_string().into())
+                .collect(),
+            cluster: info.cluster.map(CowStr::from),
+            worker_type: worker_type.into(),
+            access_control: info.access_control.into(),
+            queued: info.queued as u64,
+            handled: info.handled as u64,
+            started_at: info.started_at,
+        }
+    }
+}
+
+/// Response body for listing workers
+#[derive(Debug, Clone, Decode, Encode, Serialize)]
+#[rustfmt::skip]
+#[cbor(map)]
+pub struct WorkerList<'a> {
+    #[cfg(feature = "tag")]
+    #[serde(skip)]
+    #[n(0)] tag: TypeTag<7719425>,
+    #[b(1)] pub list: Vec<WorkerStatus<'a>>
+}
+
+impl<'a> WorkerList<'a> {
+    pub fn new(list: Vec<WorkerStatus<'a>>) -> Self {
+        Self {
+            #[cfg(feature = "tag")]
+            tag: TypeTag,
+            list,
+        }
+    }
+}
@@ -1,4 +1,7 @@
+use crate::nodes::models::forwarder::ForwarderInfo;
+use crate::nodes::models::secure_channel::CredentialExchangeMode;
 use crate::nodes::service::Alias;
+use crate::session::Key;
 use ockam_core::compat::collections::BTreeMap;
 use ockam_core::{Address, Route};
 use ockam_identity::IdentityIdentifier;
@@ -27,6 +30,26 @@ impl SecureChannelRegistry {
             .push(SecureChannelInfo::new(route, addr, authorized_identifiers))
     }
 
+    /// Remember how credentials were exchanged over the given channel.
+    pub fn set_credential_exchange_mode(&mut self, addr: &Address, mode: CredentialExchangeMode) {
+        if let Some(c) = self.channels.iter_mut().find(|x| x.addr() == addr) {
+            c.credential_exchange_mode = mode
+        }
+    }
+
+    /// Put back a channel which could not be replaced, so that it is
+    /// still listed until it is.
+    pub fn restore(&mut self, info: SecureChannelInfo) {
+        self.channels.push(info)
+    }
+
+    /// Remember the session keeping the given channel up.
+    pub fn set_session(&mut self, addr: &Address, key: Key) {
+        if let Some(c) = self.channels.iter_mut().find(|x| x.addr() == addr) {
+            c.session = Some(key)
+        }
+    }
+
     pub fn remove_by_addr(&mut self, addr: &Address) {
         self.channels.retain(|x| x.addr() != addr)
     }
@@ -43,6 +66,10 @@ pub struct SecureChannelInfo {
     // Local address of the created channel
     addr: Address,
     authorized_identifiers: Option<Vec<IdentityIdentifier>>,
+    // How our credential has been presented over the channel
+    credential_exchange_mode: CredentialExchangeMode,
+    // Session recovering the channel, if it was created through the API
+    session: Option<Key>,
 }
 
 impl SecureChannelInfo {
@@ -55,6 +82,8 @@ impl SecureChannelInfo {
             addr,
             route,
             authorized_identifiers,
+            credential_exchange_mode: CredentialExchangeMode::None,
+            session: None,
         }
     }
 
@@ -69,6 +98,14 @@ impl SecureChannelInfo {
     pub fn authorized_identifiers(&self) -> Option<&Vec<IdentityIdentifier>> {
         self.authorized_identifiers.as_ref()
     }
+
+    pub fn credential_exchange_mode(&self) -> CredentialExchangeMode {
+        self.credential_exchange_mode
+    }
+
+    pub fn session(&self) -> Option<Key> {
+        self.session
+    }
 }
 
 #[derive(Default)]
@@ -86,6 +123,9 @@ pub(crate) struct AuthenticatedServiceInfo {}
 #[derive(Default)]
 pub(crate) struct OktaIdentityProviderServiceInfo {}
 
+#[derive(Default)]
+pub(crate) struct OidcAuthenticatorServiceInfo {}
+
 #[derive(Default)]
 pub(crate) struct UppercaseServiceInfo {}
 
@@ -151,6 +191,7 @@ pub(crate) struct Registry {
     pub(crate) identity_services: BTreeMap<Address, IdentityServiceInfo>,
     pub(crate) authenticated_services: BTreeMap<Address, AuthenticatedServiceInfo>,
     pub(crate) okta_identity_provider_services: BTreeMap<Address, OktaIdentityProviderServiceInfo>,
+    pub(crate) oidc_authenticator_services: BTreeMap<Address, OidcAuthenticatorServiceInfo>,
     pub(crate) uppercase_services: BTreeMap<Address, UppercaseServiceInfo>,
     pub(crate) echoer_services: BTreeMap<Address, EchoerServiceInfo>,
     pub(crate) verifier_services: BTreeMap<Address, VerifierServiceInfo>,
@@ -161,4 +202,7 @@ pub(crate) struct Registry {
     // FIXME: wow this is a terrible way to store data
     pub(crate) inlets: BTreeMap<Alias, InletInfo>,
     pub(crate) outlets: BTreeMap<Alias, OutletInfo>,
+
+    /// Forwarders created by this node, by remote address.
+    pub(crate) forwarders: BTreeMap<String, ForwarderInfo<'static>>,
 }
@@ -0,0 +1,535 @@
+//! Declarative table of the node manager API
+//!
+//! Every endpoint served by the [`NodeManagerWorker`] is declared once
+//! with [`node_api`], together with the CDDL rules of its request and
+//! response bodies and its handler.  Requests are dispatched through the
+//! resulting [`ENDPOINTS`] and [`schema`] generates the CDDL describing
+//! the whole API from it.
+//!
+//! [`NodeManagerWorker`]: super::NodeManagerWorker
+
+use core::fmt::Write;
+use ockam_core::api::{Method, SCHEMA};
+
+/// CDDL rules of the node manager bodies
+pub const NODE_SCHEMA: &str = include_str!("schema.cddl");
+
+/// An endpoint of the node manager API.
+#[derive(Debug, Clone, Copy)]
+pub struct Endpoint {
+    pub route: Route,
+    pub method: Method,
+    /// Path pattern, where `{name}` segments match any value.
+    pub path: &'static str,
+    /// CDDL rule of the request body, if the endpoint expects one.
+    pub request: Option<&'static str>,
+    /// CDDL rule of the response body, if the endpoint returns one.
+    pub response: Option<&'static str>,
+    pub summary: &'static str,
+}
+
+impl Endpoint {
+    /// Return the values of the `{name}` segments if the endpoint matches.
+    pub fn matches<'a>(&self, method: Method, segments: &[&'a str]) -> Option<Vec<&'a str>> {
+        if self.method != method {
+            return None;
+        }
+        let pattern = self.path.trim_start_matches('/').split('/');
+        if pattern.clone().count() != segments.len() {
+            return None;
+        }
+        let mut params = Vec::new();
+        for (p, s) in pattern.zip(segments) {
+            if p.starts_with('{') && p.ends_with('}') {
+                params.push(*s)
+            } else if p != *s {
+                return None;
+            }
+        }
+        Some(params)
+    }
+
+    /// Name of the endpoint, used as prefix of its generated CDDL rules.
+    pub fn name(&self) -> String {
+        let mut name = String::new();
+        for (i, c) in format!("{:?}", self.route).chars().enumerate() {
+            if c.is_uppercase() {
+                if i > 0 {
+                    name.push('_')
+                }
+                name.extend(c.to_lowercase())
+            } else {
+                name.push(c)
+            }
+        }
+        name
+    }
+}
+
+/// Declare the endpoints of the node manager API and their handlers.
+///
+/// The table generates the [`Route`] enum, the [`ENDPOINTS`] list and a
+/// dispatch method calling the handler of a resolved route, so that an
+/// endpoint is declared exactly once.  Handlers are expressions evaluating
+/// to the encoded response, with the path parameters bound to the names
+/// listed between brackets.
+macro_rules! node_api {
+    (
+        impl $worker:ty {
+            async fn $dispatch:ident(&mut $this:ident, $ctx:ident, $req:ident, $dec:ident);
+        }
+        $(
+            $route:ident: $method:ident $path:literal, $request:expr => $response:expr, $summary:literal
+                => [$($param:ident),*] $handler:expr;
+        )*
+    ) => {
+        /// Identifies an endpoint of the node manager API.
+        #[derive(Debug, Clone, Copy, PartialEq, Eq)]
+        pub enum Route {
+            $(#[doc = $summary] $route,)*
+        }
+
+        /// All endpoints of the node manager API, in matching order.
+        pub const ENDPOINTS: &[$crate::nodes::routes::Endpoint] = &[$(
+            $crate::nodes::routes::Endpoint {
+                route: Route::$route,
+                method: ockam_core::api::Method::$method,
+                path: $path,
+                request: $request,
+                response: $response,
+                summary: $summary,
+            }
+        ),*];
+
+        impl $worker {
+            /// Call the handler of a route, `None` if the parameters don't match it.
+            async fn $dispatch(
+                &mut $this,
+                $ctx: &mut ockam::Context,
+                $req: &ockam_core::api::Request<'_>,
+                $dec: &mut minicbor::Decoder<'_>,
+                route: Route,
+                params: &[&str],
+            ) -> ockam_core::Result<Option<Vec<u8>>> {
+                let r = match (route, params) {
+                    $((Route::$route, [$($param),*]) => $handler,)*
+                    _ => return Ok(None),
+                };
+                Ok(Some(r))
+            }
+        }
+    };
+}
+
+pub(crate) use node_api;
+
+pub use super::service::{Route, ENDPOINTS};
+
+/// Find the endpoint of a request and the values of its path parameters.
+pub fn resolve<'a>(method: Method, segments: &[&'a str]) -> Option<(Route, Vec<&'a str>)> {
+    ENDPOINTS
+        .iter()
+        .find_map(|e| e.matches(method, segments).map(|params| (e.route, params)))
+}
+
+/// The CDDL of every endpoint body, generated from [`ENDPOINTS`].
+pub fn endpoints_schema() -> String {
+    let mut cddl = String::from(
+        ";;; Endpoints ;;;;;;;;;;;;;;;;;;;;;;;;;;;;;;;;;;;;;;;;;;;;;;;;;;;;;;;;;;;;;;\n",
+    );
+    for e in ENDPOINTS {
+        let name = e.name();
+        let _ = writeln!(cddl, "\n;; {} {}\n;; {}", e.method, e.path, e.summary);
+        // Bodies whose rule already carries the endpoint name need no alias.
+        for (suffix, rule) in [("request", e.request), ("response", e.response)] {
+            match rule {
+                Some(rule) if rule != format!("{name}_{suffix}") => {
+                    let _ = writeln!(cddl, "{name}_{suffix} = {rule}");
+                }
+                _ => {}
+            }
+        }
+    }
+    cddl
+}
+
+/// The complete CDDL of the node manager API: headers, bodies and endpoints.
+pub fn schema() -> String {
+    format!("{SCHEMA}\n{NODE_SCHEMA}\n{}", endpoints_schema())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::collections::BTreeMap;
+
+    fn defined_rules(cddl: &str) -> Vec<&str> {
+        cddl.lines()
+            .filter_map(|l| l.split_once('='))
+            .map(|(name, _)| name.trim())
+            .filter(|name| !name.is_empty() && !name.contains(char::is_whitespace))
+            .collect()
+    }
+
+    #[test]
+    fn every_body_rule_is_defined() {
+        let schema = schema();
+        let rules = defined_rules(&schema);
+        for e in ENDPOINTS {
+            for rule in [e.request, e.response].into_iter().flatten() {
+                assert!(
+                    rule == "any" || rules.contains(&rule),
+                    "{} {}: undefined rule {rule}",
+                    e.method,
+                    e.path
+                );
+            }
+        }
+    }
+
+    #[test]
+    fn rules_are_unique() {
+        let schema = schema();
+        let mut rules = defined_rules(&schema);
+        let count = rules.len();
+        rules.sort_unstable();
+        rules.dedup();
+        assert_eq!(rules.len(), count);
+    }
+
+    #[test]
+    fn resolve_routes() {
+        use Method::*;
+        assert_eq!(resolve(Get, &["node"]), Some((Route::GetNode, vec![])));
+        assert_eq!(
+            resolve(Get, &["policy", "tcp-outlet", "handle_message"]),
+            Some((Route::GetPolicy, vec!["tcp-outlet", "handle_message"]))
+        );
+        assert_eq!(
+            resolve(Get, &["p1", "addons"]),
+            Some((Route::ListAddons, vec!["p1"]))
+        );
+        assert_eq!(resolve(Patch, &["node"]), None);
+        assert_eq!(resolve(Get, &["node", "nope"]), None);
+        assert_eq!(
+            ENDPOINTS[3].name(),
+            "create_tcp_connection",
+            "rule prefixes are snake case route names"
+        );
+    }
+
+    /// A sample of every body rule used by [`ENDPOINTS`].
+    fn sample_bodies() -> BTreeMap<&'static str, Vec<u8>> {
+        use crate::cloud::enroll::enrollment_token::EnrollmentToken;
+        use crate::cloud::enroll::Token;
+        use crate::cloud::project::Project;
+        use crate::cloud::space::Space;
+        use crate::cloud::BareCloudRequestWrapper;
+        use crate::nodes::models::base::*;
+        use crate::nodes::models::credentials::*;
+        use crate::nodes::models::forwarder::*;
+        use crate::nodes::models::identity::*;
+        use crate::nodes::models::policy::*;
+        use crate::nodes::models::portal::*;
+        use crate::nodes::models::secure_channel::*;
+        use crate::nodes::models::services::*;
+        use crate::nodes::models::transport::*;
+        use crate::nodes::models::vault::*;
+        use crate::nodes::models::workers::*;
+        use crate::nodes::service::message::SendMessage;
+        use crate::oidc::ClaimMapping;
+        use ockam_abac::{Action, Expr};
+        use ockam_core::Address;
+        use ockam_identity::IdentityIdentifier;
+        use ockam_multiaddr::MultiAddr;
+        use ockam_node::WorkerInfo;
+        use std::path::Path;
+
+        fn cbor<T: minicbor::Encode<()>>(body: T) -> Vec<u8> {
+            minicbor::to_vec(body).unwrap()
+        }
+
+        let id = IdentityIdentifier::from_key_id("0123456789abcdef");
+        let addr: MultiAddr = "/ip4/127.0.0.1/tcp/4000/service/api".parse().unwrap();
+        let transport = || {
+            TransportStatus::new(
+                TransportType::Tcp,
+                TransportMode::Listen,
+                "127.0.0.1:4000",
+                "tid",
+            )
+        };
+        let worker = WorkerInfo {
+            address: "app".into(),
+            aliases: vec!["alias".into()],
+            cluster: Some("cluster".into()),
+            processor: false,
+            detached: false,
+            access_control: "AllowAll".into(),
+            queued: 1,
+            handled: 2,
+            started_at: Some(1_000),
+        };
+        let mut health = NodeHealth::new("n1", 42, 10);
+        health.transports.push(transport());
+        health.secure_channel_listeners.push("api".into());
+        health.credential = CredentialState::Valid;
+        health.credential_expires_at = Some(1_000);
+        health.sessions.push(SessionHealth::new("sc", "up"));
+        let mut inlet = CreateInlet::to_node(
+            "127.0.0.1:5000".parse().unwrap(),
+            addr.clone(),
+            Some(true),
+            Some(id.clone()),
+        );
+        inlet.set_alias("inlet");
+        let inlet_status =
+            || InletStatus::new("127.0.0.1:5000", "inlet", "inlet", None, "/service/o");
+        let outlet_status = || OutletStatus::new("127.0.0.1:6000", "outlet", "outlet", None);
+        let forwarder =
+            || ForwarderInfo::new("0#forward", "forward", "worker").with_alias(Some("f"));
+        let space = || Space {
+            #[cfg(feature = "tag")]
+            tag: Default::default(),
+            id: "s1".into(),
+            name: "space".into(),
+            users: vec!["alice@example.com".into()],
+        };
+        let project = || Project {
+            id: "p1".into(),
+            name: "default".into(),
+            space_name: "space".into(),
+            services: vec!["echo".into()],
+            access_route: "/dnsaddr/example.com/tcp/4000".into(),
+            users: vec!["alice@example.com".into()],
+            space_id: "s1".into(),
+            identity: Some(id.clone()),
+            ..Default::default()
+        };
+        let mut show = ShowSecureChannelResponse::new(None);
+        show.channel = Some("sc".into());
+        show.route = Some("0#sc".into());
+        show.authorized_identifiers = Some(vec![id.to_string().into()]);
+        show.status = Some("up".into());
+        let project_bytes = b"project".as_slice();
+
+        BTreeMap::from([
+            (
+                "node_status",
+                cbor(NodeStatus::new("n1", "Running", 10, 42, 1)),
+            ),
+            ("worker_list", cbor(WorkerList::new(vec![worker.into()]))),
+            (
+                "transport_list",
+                cbor(TransportList::new(vec![transport()])),
+            ),
+            ("transport_status", cbor(transport())),
+            (
+                "create_transport",
+                cbor(CreateTransport::new(
+                    TransportType::Tcp,
+                    TransportMode::Connect,
+                    "peer",
+                )),
+            ),
+            ("delete_transport", cbor(DeleteTransport::new("tid", true))),
+            (
+                "create_vault_request",
+                cbor(CreateVaultRequest::new(Some("vault.json"))),
+            ),
+            (
+                "create_identity_response",
+                cbor(CreateIdentityResponse::new(id.to_string())),
+            ),
+            (
+                "short_identity_response",
+                cbor(ShortIdentityResponse::new(id.to_string())),
+            ),
+            (
+                "long_identity_response",
+                cbor(LongIdentityResponse::new(vec![1, 2, 3])),
+            ),
+            (
+                "get_credential_request",
+                cbor(GetCredentialRequest::new(true)),
+            ),
+            (
+                "present_credential_request",
+                cbor(PresentCredentialRequest::new(&addr, false).with_attributes(["role"])),
+            ),
+            ("addresses", cbor(vec!["sc".to_string()])),
+            (
+                "create_secure_channel_request",
+                cbor(CreateSecureChannelRequest::new(
+                    &addr,
+                    Some(vec![id.clone()]),
+                    CredentialExchangeMode::Mutual,
+                )),
+            ),
+            (
+                "create_secure_channel_response",
+                cbor(CreateSecureChannelResponse::new(&Address::from_string(
+                    "sc",
+                ))),
+            ),
+            (
+                "delete_secure_channel_request",
+                cbor(DeleteSecureChannelRequest::new(&Address::from_string("sc"))),
+            ),
+            (
+                "delete_secure_channel_response",
+                cbor(DeleteSecureChannelResponse::new(Some(
+                    Address::from_string("sc"),
+                ))),
+            ),
+            (
+                "show_secure_channel_request",
+                cbor(ShowSecureChannelRequest::new(&Address::from_string("sc"))),
+            ),
+            ("show_secure_channel_response", cbor(show)),
+            (
+                "create_secure_channel_listener_request",
+                cbor(CreateSecureChannelListenerRequest::new(
+                    &Address::from_string("api"),
+                    Some(vec![id.clone()]),
+                )),
+            ),
+            (
+                "start_vault_service_request",
+                cbor(StartVaultServiceRequest::new("vault")),
+            ),
+            (
+                "start_identity_service_request",
+                cbor(StartIdentityServiceRequest::new("identity")),
+            ),
+            (
+                "start_authenticated_service_request",
+                cbor(StartAuthenticatedServiceRequest::new("authenticated")),
+            ),
+            (
+                "start_uppercase_service_request",
+                cbor(StartUppercaseServiceRequest::new("uppercase")),
+            ),
+            (
+                "start_echoer_service_request",
+                cbor(StartEchoerServiceRequest::new("echo")),
+            ),
+            (
+                "start_authenticator_request",
+                cbor(StartAuthenticatorRequest::new(
+                    "authenticator",
+                    Path::new("members"),
+                    project_bytes,
+                )),
+            ),
+            (
+                "start_verifier_service_request",
+                cbor(StartVerifierService::new("verifier")),
+            ),
+            (
+                "start_credentials_service_request",
+                cbor(StartCredentialsService::new("credentials", true)),
+            ),
+            (
+                "start_okta_identity_provider_request",
+                cbor(StartOktaIdentityProviderRequest::new(
+                    "okta",
+                    "https://okta.example.com",
+                    "cert",
+                    vec!["email"],
+                    project_bytes,
+                )),
+            ),
+            (
+                "start_oidc_authenticator_request",
+                cbor(StartOidcAuthenticatorRequest::new(
+                    "oidc",
+                    "https://issuer.example.com",
+                    "ockam",
+                    "jwks.json",
+                    vec![ClaimMapping::new("email", "email")],
+                    project_bytes,
+                )),
+            ),
+            (
+                "service_list",
+                cbor(ServiceList::new(vec![ServiceStatus::new("echo", "echoer")])),
+            ),
+            (
+                "forwarder_list",
+                cbor(ForwarderList::new(vec![forwarder()])),
+            ),
+            ("forwarder_info", cbor(forwarder())),
+            (
+                "create_forwarder",
+                cbor(CreateForwarder::at_node(
+                    addr.clone(),
+                    Some("f".into()),
+                    true,
+                    Some(id.clone()),
+                )),
+            ),
+            ("create_inlet", cbor(inlet)),
+            (
+                "create_outlet",
+                cbor(CreateOutlet::new(
+                    "127.0.0.1:6000",
+                    "outlet",
+                    Some("outlet".into()),
+                    Some(false),
+                )),
+            ),
+            ("inlet_status", cbor(inlet_status())),
+            ("outlet_status", cbor(outlet_status())),
+            ("inlet_list", cbor(InletList::new(vec![inlet_status()]))),
+            ("outlet_list", cbor(OutletList::new(vec![outlet_status()]))),
+            ("log_level", cbor(LogLevel::new("info,ockam_api=debug"))),
+            ("node_health", cbor(health)),
+            ("policy", cbor(Policy::new(Expr::Bool(true)))),
+            (
+                "policy_list",
+                cbor(PolicyList::new(vec![(
+                    Action::new("handle_message"),
+                    Expr::Bool(true),
+                )])),
+            ),
+            ("cloud_request", cbor(BareCloudRequestWrapper::bare(&addr))),
+            ("space", cbor(space())),
+            ("spaces", cbor(vec![space()])),
+            ("project", cbor(project())),
+            ("projects", cbor(vec![project()])),
+            (
+                "enrollment_token",
+                cbor(EnrollmentToken::new(Token::new("token"))),
+            ),
+            (
+                "send_message",
+                cbor(SendMessage::new(&addr, b"hello".as_slice())),
+            ),
+        ])
+    }
+
+    #[test]
+    fn bodies_match_schema() {
+        use cddl_cat::validate_cbor_bytes;
+
+        let schema = schema();
+        let samples = sample_bodies();
+        for e in ENDPOINTS {
+            let name = e.name();
+            for (suffix, rule) in [("request", e.request), ("response", e.response)] {
+                let rule = match rule {
+                    Some("any") | None => continue,
+                    Some(rule) => rule,
+                };
+                let cbor = samples
+                    .get(rule)
+                    .unwrap_or_else(|| panic!("no sample body for rule {rule}"));
+                // Validate against the endpoint alias, which resolves to the rule.
+                if let Err(err) = validate_cbor_bytes(&format!("{name}_{suffix}"), &schema, cbor) {
+                    panic!("{} {}: {rule}: {err}", e.method, e.path)
+                }
+            }
+        }
+    }
+}
@@ -2,9 +2,10 @@
 
 use minicbor::Decoder;
 
+use crate::nodes::NodeVault;
 use ockam::compat::asynchronous::RwLock;
 use ockam::{Address, Context, ForwardingService, Result, Routed, TcpTransport, Worker};
-use ockam_core::api::{Error, Method, Request, Response, ResponseBuilder, Status};
+use ockam_core::api::{Error, Request, Response, ResponseBuilder, Status};
 use ockam_core::compat::{
     boxed::Box,
     string::String,
@@ -17,15 +18,18 @@ use ockam_multiaddr::proto::{Project, Secure};
 use ockam_multiaddr::{MultiAddr, Protocol};
 use ockam_node::tokio;
 use ockam_node::tokio::task::JoinHandle;
-use ockam_vault::storage::FileStorage;
-use ockam_vault::Vault;
+use ockam_transport_udp::UdpTransport;
+use ockam_transport_websocket::WebSocketTransport;
+use ockam_vault::storage::StorageKey;
 use std::collections::BTreeMap;
 use std::error::Error as _;
+use std::net::SocketAddr;
 use std::path::PathBuf;
 use std::time::Duration;
 
 use super::models::secure_channel::CredentialExchangeMode;
 use super::registry::Registry;
+use super::routes;
 use crate::config::cli::AuthoritiesConfig;
 use crate::config::lookup::ProjectLookup;
 use crate::error::ApiError;
@@ -41,13 +45,18 @@ pub mod message;
 
 mod credentials;
 mod forwarder;
+mod health;
 mod identity;
+mod logs;
 mod policy;
 mod portals;
 mod secure_channel;
 mod services;
 mod transport;
 mod vault;
+mod workers;
+
+pub use logs::LogFilter;
 
 const TARGET: &str = "ockam_api::nodemanager::service";
 
@@ -107,8 +116,8 @@ pub struct NodeManager {
     pub(crate) controller_identity_id: IdentityIdentifier,
     skip_defaults: bool,
     enable_credential_checks: bool,
-    vault: Option<Vault>,
-    identity: Option<Identity<Vault>>,
+    vault: Option<NodeVault>,
+    identity: Option<Identity<NodeVault>>,
     project_id: Option<String>,
     projects: Arc<BTreeMap<String, ProjectLookup>>,
     authorities: Option<Authorities>,
@@ -117,16 +126,23 @@ pub struct NodeManager {
     sessions: Arc<Mutex<Sessions>>,
     medic: JoinHandle<Result<(), ockam_core::Error>>,
     policies: LmdbStorage,
+    log_filter: Option<Arc<dyn LogFilter>>,
+    health_address: Option<SocketAddr>,
+    vault_key: Option<StorageKey>,
 }
 
 pub struct NodeManagerWorker {
     node_manager: Arc<RwLock<NodeManager>>,
+    credential_refresher: Option<JoinHandle<()>>,
+    health_server: Option<JoinHandle<()>>,
 }
 
 impl NodeManagerWorker {
     pub fn new(node_manager: NodeManager) -> Self {
         NodeManagerWorker {
             node_manager: Arc::new(RwLock::new(node_manager)),
+            credential_refresher: None,
+            health_server: None,
         }
     }
 
@@ -141,13 +157,13 @@ pub struct IdentityOverride {
 }
 
 impl NodeManager {
-    pub(crate) fn identity(&self) -> Result<&Identity<Vault>> {
+    pub(crate) fn identity(&self) -> Result<&Identity<NodeVault>> {
         self.identity
             .as_ref()
             .ok_or_else(|| ApiError::generic("Identity doesn't exist"))
     }
 
-    pub(crate) fn vault(&self) -> Result<&Vault> {
+    pub(crate) fn vault(&self) -> Result<&NodeVault> {
         self.vault
             .as_ref()
             .ok_or_else(|| ApiError::generic("Vault doesn't exist"))
@@ -174,6 +190,12 @@ pub struct NodeManagerGeneralOptions {
     enable_credential_checks: bool,
     // Should be passed only when creating fresh node and we want it to get default root Identity
     identity_override: Option<IdentityOverride>,
+    // Should be passed only when the node runs in its own process and owns its logging
+    log_filter: Option<Arc<dyn LogFilter>>,
+    // Kept in the node state, so that a restarted node serves its health again
+    health_address: Option<SocketAddr>,
+    // Unlocks the encrypted vaults of the node
+    vault_key: Option<StorageKey>,
 }
 
 impl NodeManagerGeneralOptions {
@@ -183,6 +205,8 @@ impl NodeManagerGeneralOptions {
         skip_defaults: bool,
         enable_credential_checks: bool,
         identity_override: Option<IdentityOverride>,
+        log_filter: Option<Arc<dyn LogFilter>>,
+        health_address: Option<SocketAddr>,
     ) -> Self {
         Self {
             node_name,
@@ -190,8 +214,17 @@ impl NodeManagerGeneralOptions {
             skip_defaults,
             enable_credential_checks,
             identity_override,
+            log_filter,
+            health_address,
+            vault_key: None,
         }
     }
+
+    /// Unlock the encrypted vaults of the node with the given key
+    pub fn with_vault_key(mut self, key: Option<StorageKey>) -> Self {
+        self.vault_key = key;
+        self
+    }
 }
 
 pub struct NodeManagerProjectsOptions<'a> {
@@ -298,10 +331,7 @@ impl NodeManager {
         let vault_path = state.read().vault_path.clone();
         let vault = match vault_path {
             Some(vault_path) => {
-                let vault_storage = FileStorage::create(vault_path).await?;
-                let vault = Vault::new(Some(Arc::new(vault_storage)));
-
-                Some(vault)
+                Some(NodeVault::open(vault_path, general_options.vault_key.clone()).await?)
             }
             None => None,
         };
@@ -328,6 +358,20 @@ impl NodeManager {
             ));
         }
 
+        let health_address = match general_options.health_address {
+            Some(addr) => {
+                state.write().health_address = Some(addr);
+                state.persist_config_updates().map_err(map_anyhow_err)?;
+                Some(addr)
+            }
+            None => state.read().health_address,
+        };
+
+        // Besides TCP, the MultiAddrs given to this node may route through
+        // UDP and WebSocket connections, see `multiaddr_to_route`.
+        UdpTransport::create(ctx).await?;
+        WebSocketTransport::create(ctx).await?;
+
         let medic = Medic::new();
         let sessions = medic.sessions();
 
@@ -354,6 +398,9 @@ impl NodeManager {
             },
             sessions,
             policies: policies_storage,
+            log_filter: general_options.log_filter,
+            health_address,
+            vault_key: general_options.vault_key,
         };
 
         if !general_options.skip_defaults {
@@ -510,255 +557,283 @@ impl NodeManagerWorker {
             "request"
         }
 
-        use Method::*;
         let path = req.path();
         let path_segments = req.path_segments::<5>();
         let method = match req.method() {
             Some(m) => m,
-            None => todo!(),
-        };
-
-        let r = match (method, path_segments.as_slice()) {
-            // ==*== Basic node information ==*==
-            // TODO: create, delete, destroy remote nodes
-            (Get, ["node"]) => {
-                let node_manager = self.node_manager.read().await;
-                Response::ok(req.id())
-                    .body(NodeStatus::new(
-                        &node_manager.node_name,
-                        "Running",
-                        ctx.list_workers().await?.len() as u32,
-                        std::process::id() as i32,
-                        node_manager.transports.len() as u32,
-                    ))
-                    .to_vec()?
-            }
-
-            // ==*== Tcp Connection ==*==
-            // TODO: Get all tcp connections
-            (Get, ["node", "tcp", "connection"]) => {
-                let node_manager = self.node_manager.read().await;
-                self.get_tcp_con_or_list(req, &node_manager.transports, TransportMode::Connect)
-                    .to_vec()?
-            }
-            (Post, ["node", "tcp", "connection"]) => {
-                self.add_transport(req, dec).await?.to_vec()?
-            }
-            (Delete, ["node", "tcp", "connection"]) => {
-                self.delete_transport(req, dec).await?.to_vec()?
-            }
-
-            // ==*== Tcp Listeners ==*==
-            (Get, ["node", "tcp", "listener"]) => {
-                let node_manager = self.node_manager.read().await;
-                self.get_tcp_con_or_list(
-                    req,
-                    &node_manager.transports.clone(),
-                    TransportMode::Listen,
-                )
-                .to_vec()?
-            }
-            (Post, ["node", "tcp", "listener"]) => self.add_transport(req, dec).await?.to_vec()?,
-            (Delete, ["node", "tcp", "listener"]) => {
-                self.delete_transport(req, dec).await?.to_vec()?
-            }
-
-            // ==*== Vault ==*==
-            (Post, ["node", "vault"]) => self.create_vault(req, dec).await?.to_vec()?,
-
-            // ==*== Identity ==*==
-            (Post, ["node", "identity"]) => self.create_identity(ctx, req).await?.to_vec()?,
-            (Post, ["node", "identity", "actions", "show", "short"]) => {
-                self.short_identity(req).await?.to_vec()?
-            }
-            (Post, ["node", "identity", "actions", "show", "long"]) => {
-                self.long_identity(req).await?.to_vec()?
-            }
-
-            // ==*== Credentials ==*==
-            (Post, ["node", "credentials", "actions", "get"]) => {
-                self.get_credential(req, dec).await?.to_vec()?
-            }
-            (Post, ["node", "credentials", "actions", "present"]) => {
-                self.present_credential(req, dec).await?.to_vec()?
-            }
-
-            // ==*== Secure channels ==*==
-            // TODO: Change to RequestBuilder format
-            (Get, ["node", "secure_channel"]) => {
-                let node_manager = self.node_manager.read().await;
-                self.list_secure_channels(req, &node_manager.registry)
-                    .to_vec()?
-            }
-            (Get, ["node", "secure_channel_listener"]) => {
-                let node_manager = self.node_manager.read().await;
-                self.list_secure_channel_listener(req, &node_manager.registry)
-                    .to_vec()?
-            }
-            (Post, ["node", "secure_channel"]) => {
-                self.create_secure_channel(req, dec).await?.to_vec()?
-            }
-            (Delete, ["node", "secure_channel"]) => {
-                self.delete_secure_channel(req, dec).await?.to_vec()?
-            }
-            (Get, ["node", "show_secure_channel"]) => {
-                self.show_secure_channel(req, dec).await?.to_vec()?
-            }
-            (Post, ["node", "secure_channel_listener"]) => self
-                .create_secure_channel_listener(req, dec)
-                .await?
-                .to_vec()?,
-
-            // ==*== Services ==*==
-            (Post, ["node", "services", "vault"]) => {
-                self.start_vault_service(ctx, req, dec).await?.to_vec()?
-            }
-            (Post, ["node", "services", "identity"]) => {
-                self.start_identity_service(ctx, req, dec).await?.to_vec()?
-            }
-            (Post, ["node", "services", "authenticated"]) => self
-                .start_authenticated_service(ctx, req, dec)
-                .await?
-                .to_vec()?,
-            (Post, ["node", "services", "uppercase"]) => self
-                .start_uppercase_service(ctx, req, dec)
-                .await?
-                .to_vec()?,
-            (Post, ["node", "services", "echo"]) => {
-                self.start_echoer_service(ctx, req, dec).await?.to_vec()?
-            }
-            (Post, ["node", "services", "authenticator"]) => self
-                .start_authenticator_service(ctx, req, dec)
-                .await?
-                .to_vec()?,
-            (Post, ["node", "services", "verifier"]) => {
-                self.start_verifier_service(ctx, req, dec).await?.to_vec()?
-            }
-            (Post, ["node", "services", "credentials"]) => self
-                .start_credentials_service(ctx, req, dec)
-                .await?
-                .to_vec()?,
-            (Post, ["node", "services", "okta_identity_provider"]) => self
-                .start_okta_identity_provider_service(ctx, req, dec)
-                .await?
-                .to_vec()?,
-            (Get, ["node", "services"]) => {
-                let node_manager = self.node_manager.read().await;
-                self.list_services(req, &node_manager.registry).to_vec()?
-            }
-
-            // ==*== Forwarder commands ==*==
-            (Post, ["node", "forwarder"]) => self.create_forwarder(ctx, req.id(), dec).await?,
-
-            // ==*== Inlets & Outlets ==*==
-            (Get, ["node", "inlet"]) => {
-                let node_manager = self.node_manager.read().await;
-                self.get_inlets(req, &node_manager.registry).to_vec()?
-            }
-            (Get, ["node", "outlet"]) => {
-                let node_manager = self.node_manager.read().await;
-                self.get_outlets(req, &node_manager.registry).to_vec()?
-            }
-            (Post, ["node", "inlet"]) => self.create_inlet(req, dec).await?.to_vec()?,
-            (Post, ["node", "outlet"]) => self.create_outlet(req, dec).await?.to_vec()?,
-            (Delete, ["node", "portal"]) => todo!(),
-
-            (Post, ["policy", resource, action]) => self
-                .node_manager
-                .read()
-                .await
-                .add_policy(resource, action, req, dec)
-                .await?
-                .to_vec()?,
-            (Get, ["policy", resource]) => self
-                .node_manager
-                .read()
-                .await
-                .list_policies(req, resource)
-                .await?
-                .to_vec()?,
-            (Get, ["policy", resource, action]) => self
-                .node_manager
-                .read()
-                .await
-                .get_policy(req, resource, action)
-                .await?
-                .either(ResponseBuilder::to_vec, ResponseBuilder::to_vec)?,
-            (Delete, ["policy", resource, action]) => self
-                .node_manager
-                .read()
-                .await
-                .del_policy(req, resource, action)
-                .await?
-                .to_vec()?,
-
-            // ==*== Spaces ==*==
-            (Post, ["v0", "spaces"]) => self.create_space(ctx, dec).await?,
-            (Get, ["v0", "spaces"]) => self.list_spaces(ctx, dec).await?,
-            (Get, ["v0", "spaces", id]) => self.get_space(ctx, dec, id).await?,
-            (Delete, ["v0", "spaces", id]) => self.delete_space(ctx, dec, id).await?,
-
-            // ==*== Project' enrollers ==*==
-            (Post, ["v0", "project-enrollers", project_id]) => {
-                self.add_project_enroller(ctx, dec, project_id).await?
-            }
-            (Get, ["v0", "project-enrollers", project_id]) => {
-                self.list_project_enrollers(ctx, dec, project_id).await?
-            }
-            (Delete, ["v0", "project-enrollers", project_id, identity_id]) => {
-                self.delete_project_enroller(ctx, dec, project_id, identity_id)
-                    .await?
-            }
-
-            // ==*== Projects ==*==
-            (Post, ["v0", "projects", space_id]) => self.create_project(ctx, dec, space_id).await?,
-            (Get, ["v0", "projects"]) => self.list_projects(ctx, dec).await?,
-            (Get, ["v0", "projects", project_id]) => self.get_project(ctx, dec, project_id).await?,
-            (Delete, ["v0", "projects", space_id, project_id]) => {
-                self.delete_project(ctx, dec, space_id, project_id).await?
+            None => {
+                return Ok(Response::bad_request(req.id())
+                    .body("Missing request method")
+                    .to_vec()?)
             }
+        };
 
-            // ==*== Enroll ==*==
-            (Post, ["v0", "enroll", "auth0"]) => self.enroll_auth0(ctx, dec).await?,
-            (Get, ["v0", "enroll", "token"]) => self.generate_enrollment_token(ctx, dec).await?,
-            (Put, ["v0", "enroll", "token"]) => {
-                self.authenticate_enrollment_token(ctx, dec).await?
+        let r = match routes::resolve(method, path_segments.as_slice()) {
+            Some((route, params)) => self.dispatch(ctx, req, dec, route, &params).await?,
+            None => None,
+        };
+        match r {
+            Some(r) => Ok(r),
+            // ==*== Catch-all for Unimplemented APIs ==*==
+            None => {
+                warn!(%method, %path, "Called invalid endpoint");
+                Ok(Response::bad_request(req.id())
+                    .body(format!("Invalid endpoint: {}", path))
+                    .to_vec()?)
             }
+        }
+    }
 
-            // ==*== Subscriptions ==*==
-            (Post, ["subscription"]) => self.activate_subscription(ctx, dec).await?,
-            (Get, ["subscription", id]) => self.get_subscription(ctx, dec, id).await?,
-            (Get, ["subscription"]) => self.list_subscriptions(ctx, dec).await?,
-            (Put, ["subscription", id, "contact_info"]) => {
-                self.update_subscription_contact_info(ctx, dec, id).await?
-            }
-            (Put, ["subscription", id, "space_id"]) => {
-                self.update_subscription_space(ctx, dec, id).await?
-            }
-            (Put, ["subscription", id, "unsubscribe"]) => self.unsubscribe(ctx, dec, id).await?,
+    async fn get_node(&self, ctx: &Context, req: &Request<'_>) -> Result<Vec<u8>> {
+        let node_manager = self.node_manager.read().await;
+        let status = NodeStatus::new(
+            &node_manager.node_name,
+            "Running",
+            ctx.list_workers().await?.len() as u32,
+            std::process::id() as i32,
+            node_manager.transports.len() as u32,
+        );
+        Ok(Response::ok(req.id()).body(status).to_vec()?)
+    }
+}
 
-            // ==*== Addons ==*==
-            (Get, [project_id, "addons"]) => self.list_addons(ctx, dec, project_id).await?,
-            (Put, [project_id, "addons", addon_id]) => {
-                self.configure_addon(ctx, dec, project_id, addon_id).await?
-            }
-            (Delete, [project_id, "addons", addon_id]) => {
-                self.disable_addon(ctx, dec, project_id, addon_id).await?
-            }
+routes::node_api! {
+    impl NodeManagerWorker {
+        async fn dispatch(&mut self, ctx, req, dec);
+    }
 
-            // ==*== Messages ==*==
-            (Post, ["v0", "message"]) => self.send_message(ctx, req, dec).await?,
+    // ==*== Basic node information ==*==
+    // TODO: create, delete, destroy remote nodes
+    GetNode: Get "/node", None => Some("node_status"), "Show the status of the node"
+        => [] self.get_node(ctx, req).await?;
+    ListWorkers: Get "/node/workers", None => Some("worker_list"), "List the workers of the node"
+        => [] self.list_workers(ctx, req).await?.to_vec()?;
+
+    // ==*== Tcp Connection ==*==
+    // TODO: Get all tcp connections
+    ListTcpConnections: Get "/node/tcp/connection", None => Some("transport_list"), "List TCP connections"
+        => [] {
+            let node_manager = self.node_manager.read().await;
+            self.get_tcp_con_or_list(req, &node_manager.transports, TransportMode::Connect)
+                .to_vec()?
+        };
+    CreateTcpConnection: Post "/node/tcp/connection", Some("create_transport") => Some("transport_status"), "Create a TCP connection"
+        => [] self.add_transport(req, dec).await?.to_vec()?;
+    DeleteTcpConnection: Delete "/node/tcp/connection", Some("delete_transport") => None, "Delete a TCP connection"
+        => [] self.delete_transport(req, dec).await?.to_vec()?;
+
+    // ==*== Tcp Listeners ==*==
+    ListTcpListeners: Get "/node/tcp/listener", None => Some("transport_list"), "List TCP listeners"
+        => [] {
+            let node_manager = self.node_manager.read().await;
+            self.get_tcp_con_or_list(req, &node_manager.transports, TransportMode::Listen)
+                .to_vec()?
+        };
+    CreateTcpListener: Post "/node/tcp/listener", Some("create_transport") => Some("transport_status"), "Create a TCP listener"
+        => [] self.add_transport(req, dec).await?.to_vec()?;
+    DeleteTcpListener: Delete "/node/tcp/listener", Some("delete_transport") => None, "Delete a TCP listener"
+        => [] self.delete_transport(req, dec).await?.to_vec()?;
+
+    // ==*== Vault ==*==
+    CreateVault: Post "/node/vault", Some("create_vault_request") => None, "Create the vault of the node"
+        => [] self.create_vault(req, dec).await?.to_vec()?;
+
+    // ==*== Identity ==*==
+    CreateIdentity: Post "/node/identity", None => Some("create_identity_response"), "Create the identity of the node"
+        => [] self.create_identity(ctx, req).await?.to_vec()?;
+    ShowIdentityShort: Post "/node/identity/actions/show/short", None => Some("short_identity_response"), "Show the identifier of the node identity"
+        => [] self.short_identity(req).await?.to_vec()?;
+    ShowIdentityLong: Post "/node/identity/actions/show/long", None => Some("long_identity_response"), "Show the change history of the node identity"
+        => [] self.long_identity(req).await?.to_vec()?;
+
+    // ==*== Credentials ==*==
+    GetCredential: Post "/node/credentials/actions/get", Some("get_credential_request") => None, "Get a credential from the project authority"
+        => [] self.get_credential(req, dec).await?.to_vec()?;
+    PresentCredential: Post "/node/credentials/actions/present", Some("present_credential_request") => None, "Present the node credential to a peer"
+        => [] self.present_credential(req, dec).await?.to_vec()?;
+
+    // ==*== Secure channels ==*==
+    // TODO: Change to RequestBuilder format
+    ListSecureChannels: Get "/node/secure_channel", None => Some("addresses"), "List secure channels"
+        => [] {
+            let node_manager = self.node_manager.read().await;
+            self.list_secure_channels(req, &node_manager.registry).to_vec()?
+        };
+    ListSecureChannelListeners: Get "/node/secure_channel_listener", None => Some("addresses"), "List secure channel listeners"
+        => [] {
+            let node_manager = self.node_manager.read().await;
+            self.list_secure_channel_listener(req, &node_manager.registry).to_vec()?
+        };
+    CreateSecureChannel: Post "/node/secure_channel", Some("create_secure_channel_request") => Some("create_secure_channel_response"), "Create a secure channel"
+        => [] self.create_secure_channel(req, dec).await?.to_vec()?;
+    DeleteSecureChannel: Delete "/node/secure_channel", Some("delete_secure_channel_request") => Some("delete_secure_channel_response"), "Delete a secure channel"
+        => [] self.delete_secure_channel(req, dec).await?.to_vec()?;
+    ShowSecureChannel: Get "/node/show_secure_channel", Some("show_secure_channel_request") => Some("show_secure_channel_response"), "Show a secure channel"
+        => [] self.show_secure_channel(req, dec).await?.to_vec()?;
+    CreateSecureChannelListener: Post "/node/secure_channel_listener", Some("create_secure_channel_listener_request") => None, "Create a secure channel listener"
+        => [] self.create_secure_channel_listener(req, dec).await?.to_vec()?;
+
+    // ==*== Services ==*==
+    StartVaultService: Post "/node/services/vault", Some("start_vault_service_request") => None, "Start a vault service"
+        => [] self.start_vault_service(ctx, req, dec).await?.to_vec()?;
+    StartIdentityService: Post "/node/services/identity", Some("start_identity_service_request") => None, "Start an identity service"
+        => [] self.start_identity_service(ctx, req, dec).await?.to_vec()?;
+    StartAuthenticatedService: Post "/node/services/authenticated", Some("start_authenticated_service_request") => None, "Start an authenticated service"
+        => [] self.start_authenticated_service(ctx, req, dec).await?.to_vec()?;
+    StartUppercaseService: Post "/node/services/uppercase", Some("start_uppercase_service_request") => None, "Start an uppercase service"
+        => [] self.start_uppercase_service(ctx, req, dec).await?.to_vec()?;
+    StartEchoerService: Post "/node/services/echo", Some("start_echoer_service_request") => None, "Start an echoer service"
+        => [] self.start_echoer_service(ctx, req, dec).await?.to_vec()?;
+    StartAuthenticatorService: Post "/node/services/authenticator", Some("start_authenticator_request") => None, "Start an authenticator service"
+        => [] self.start_authenticator_service(ctx, req, dec).await?.to_vec()?;
+    StartVerifierService: Post "/node/services/verifier", Some("start_verifier_service_request") => None, "Start a verifier service"
+        => [] self.start_verifier_service(ctx, req, dec).await?.to_vec()?;
+    StartCredentialsService: Post "/node/services/credentials", Some("start_credentials_service_request") => None, "Start a credentials service"
+        => [] self.start_credentials_service(ctx, req, dec).await?.to_vec()?;
+    StartOktaIdentityProviderService: Post "/node/services/okta_identity_provider", Some("start_okta_identity_provider_request") => None, "Start an Okta identity provider service"
+        => [] self.start_okta_identity_provider_service(ctx, req, dec).await?.to_vec()?;
+    StartOidcAuthenticatorService: Post "/node/services/oidc_authenticator", Some("start_oidc_authenticator_request") => None, "Start an OIDC authenticator service"
+        => [] self.start_oidc_authenticator_service(ctx, req, dec).await?.to_vec()?;
+    ListServices: Get "/node/services", None => Some("service_list"), "List services"
+        => [] {
+            let node_manager = self.node_manager.read().await;
+            self.list_services(req, &node_manager.registry).to_vec()?
+        };
 
-            // ==*== Catch-all for Unimplemented APIs ==*==
-            _ => {
-                warn!(%method, %path, "Called invalid endpoint");
-                Response::bad_request(req.id())
-                    .body(format!("Invalid endpoint: {}", path))
-                    .to_vec()?
-            }
+    // ==*== Forwarder commands ==*==
+    ListForwarders: Get "/node/forwarder", None => Some("forwarder_list"), "List the forwarders created by the node"
+        => [] {
+            let node_manager = self.node_manager.read().await;
+            self.list_forwarders(req, &node_manager.registry).to_vec()?
         };
-        Ok(r)
-    }
+    CreateForwarder: Post "/node/forwarder", Some("create_forwarder") => Some("forwarder_info"), "Create a forwarder"
+        => [] self.create_forwarder(ctx, req.id(), dec).await?;
+
+    // ==*== Inlets & Outlets ==*==
+    ListInlets: Get "/node/inlet", None => Some("inlet_list"), "List TCP inlets"
+        => [] {
+            let node_manager = self.node_manager.read().await;
+            self.get_inlets(req, &node_manager.registry).to_vec()?
+        };
+    ListOutlets: Get "/node/outlet", None => Some("outlet_list"), "List TCP outlets"
+        => [] {
+            let node_manager = self.node_manager.read().await;
+            self.get_outlets(req, &node_manager.registry).to_vec()?
+        };
+    CreateInlet: Post "/node/inlet", Some("create_inlet") => Some("inlet_status"), "Create a TCP inlet"
+        => [] self.create_inlet(req, dec).await?.to_vec()?;
+    CreateOutlet: Post "/node/outlet", Some("create_outlet") => Some("outlet_status"), "Create a TCP outlet"
+        => [] self.create_outlet(req, dec).await?.to_vec()?;
+    DeletePortal: Delete "/node/portal", None => None, "Delete a portal (not implemented)"
+        => [] Response::not_implemented(req.id()).to_vec()?;
+
+    // ==*== Logs ==*==
+    GetLogLevel: Get "/node/log_level", None => Some("log_level"), "Show the log filter of the node"
+        => [] self.get_log_level(req).await?;
+    SetLogLevel: Put "/node/log_level", Some("log_level") => Some("log_level"), "Change the log filter of the node"
+        => [] self.set_log_level(req, dec).await?;
+
+    // ==*== Health ==*==
+    GetNodeHealth: Get "/node/health", None => Some("node_health"), "Show the health of the node"
+        => [] self.get_node_health(ctx, req).await?.to_vec()?;
+
+    // ==*== Policies ==*==
+    AddPolicy: Post "/policy/{resource}/{action}", Some("policy") => None, "Set the policy of a resource action"
+        => [resource, action] self
+            .node_manager
+            .read()
+            .await
+            .add_policy(resource, action, req, dec)
+            .await?
+            .to_vec()?;
+    ListPolicies: Get "/policy/{resource}", None => Some("policy_list"), "List the policies of a resource"
+        => [resource] self
+            .node_manager
+            .read()
+            .await
+            .list_policies(req, resource)
+            .await?
+            .to_vec()?;
+    GetPolicy: Get "/policy/{resource}/{action}", None => Some("policy"), "Get the policy of a resource action"
+        => [resource, action] self
+            .node_manager
+            .read()
+            .await
+            .get_policy(req, resource, action)
+            .await?
+            .either(ResponseBuilder::to_vec, ResponseBuilder::to_vec)?;
+    DeletePolicy: Delete "/policy/{resource}/{action}", None => None, "Delete the policy of a resource action"
+        => [resource, action] self
+            .node_manager
+            .read()
+            .await
+            .del_policy(req, resource, action)
+            .await?
+            .to_vec()?;
+
+    // ==*== Spaces ==*==
+    CreateSpace: Post "/v0/spaces", Some("cloud_request") => Some("space"), "Create a space"
+        => [] self.create_space(ctx, dec).await?;
+    ListSpaces: Get "/v0/spaces", Some("cloud_request") => Some("spaces"), "List spaces"
+        => [] self.list_spaces(ctx, dec).await?;
+    GetSpace: Get "/v0/spaces/{id}", Some("cloud_request") => Some("space"), "Show a space"
+        => [id] self.get_space(ctx, dec, id).await?;
+    DeleteSpace: Delete "/v0/spaces/{id}", Some("cloud_request") => None, "Delete a space"
+        => [id] self.delete_space(ctx, dec, id).await?;
+
+    // ==*== Project' enrollers ==*==
+    AddProjectEnroller: Post "/v0/project-enrollers/{project_id}", Some("cloud_request") => Some("any"), "Add an enroller to a project"
+        => [project_id] self.add_project_enroller(ctx, dec, project_id).await?;
+    ListProjectEnrollers: Get "/v0/project-enrollers/{project_id}", Some("cloud_request") => Some("any"), "List the enrollers of a project"
+        => [project_id] self.list_project_enrollers(ctx, dec, project_id).await?;
+    DeleteProjectEnroller: Delete "/v0/project-enrollers/{project_id}/{identity_id}", Some("cloud_request") => None, "Remove an enroller from a project"
+        => [project_id, identity_id] self
+            .delete_project_enroller(ctx, dec, project_id, identity_id)
+            .await?;
+
+    // ==*== Projects ==*==
+    CreateProject: Post "/v0/projects/{space_id}", Some("cloud_request") => Some("project"), "Create a project"
+        => [space_id] self.create_project(ctx, dec, space_id).await?;
+    ListProjects: Get "/v0/projects", Some("cloud_request") => Some("projects"), "List projects"
+        => [] self.list_projects(ctx, dec).await?;
+    GetProject: Get "/v0/projects/{project_id}", Some("cloud_request") => Some("project"), "Show a project"
+        => [project_id] self.get_project(ctx, dec, project_id).await?;
+    DeleteProject: Delete "/v0/projects/{space_id}/{project_id}", Some("cloud_request") => None, "Delete a project"
+        => [space_id, project_id] self.delete_project(ctx, dec, space_id, project_id).await?;
+
+    // ==*== Enroll ==*==
+    EnrollAuth0: Post "/v0/enroll/auth0", Some("cloud_request") => None, "Enroll with an Auth0 token"
+        => [] self.enroll_auth0(ctx, dec).await?;
+    GenerateEnrollmentToken: Get "/v0/enroll/token", Some("cloud_request") => Some("enrollment_token"), "Generate an enrollment token"
+        => [] self.generate_enrollment_token(ctx, dec).await?;
+    AuthenticateEnrollmentToken: Put "/v0/enroll/token", Some("cloud_request") => None, "Enroll with an enrollment token"
+        => [] self.authenticate_enrollment_token(ctx, dec).await?;
+
+    // ==*== Subscriptions ==*==
+    ActivateSubscription: Post "/subscription", Some("cloud_request") => Some("any"), "Activate a subscription"
+        => [] self.activate_subscription(ctx, dec).await?;
+    GetSubscription: Get "/subscription/{id}", Some("cloud_request") => Some("any"), "Show a subscription"
+        => [id] self.get_subscription(ctx, dec, id).await?;
+    ListSubscriptions: Get "/subscription", Some("cloud_request") => Some("any"), "List subscriptions"
+        => [] self.list_subscriptions(ctx, dec).await?;
+    UpdateSubscriptionContactInfo: Put "/subscription/{id}/contact_info", Some("cloud_request") => Some("any"), "Update the contact information of a subscription"
+        => [id] self.update_subscription_contact_info(ctx, dec, id).await?;
+    UpdateSubscriptionSpace: Put "/subscription/{id}/space_id", Some("cloud_request") => Some("any"), "Move a subscription to another space"
+        => [id] self.update_subscription_space(ctx, dec, id).await?;
+    Unsubscribe: Put "/subscription/{id}/unsubscribe", Some("cloud_request") => Some("any"), "Cancel a subscription"
+        => [id] self.unsubscribe(ctx, dec, id).await?;
+
+    // ==*== Addons ==*==
+    ListAddons: Get "/{project_id}/addons", Some("cloud_request") => Some("any"), "List the addons of a project"
+        => [project_id] self.list_addons(ctx, dec, project_id).await?;
+    ConfigureAddon: Put "/{project_id}/addons/{addon_id}", Some("cloud_request") => None, "Configure an addon of a project"
+        => [project_id, addon_id] self.configure_addon(ctx, dec, project_id, addon_id).await?;
+    DisableAddon: Delete "/{project_id}/addons/{addon_id}", Some("cloud_request") => None, "Disable an addon of a project"
+        => [project_id, addon_id] self.disable_addon(ctx, dec, project_id, addon_id).await?;
+
+    // ==*== Messages ==*==
+    SendMessage: Post "/v0/message", Some("send_message") => Some("any"), "Send a message and return the reply"
+        => [] self.send_message(ctx, req, dec).await?;
 }
 
 #[ockam::worker]
@@ -772,10 +847,36 @@ impl Worker for NodeManagerWorker {
             node_manger.initialize_defaults(ctx).await?;
         }
 
+        // Credentials obtained from an authority need to be renewed before they expire
+        if node_manger.authorities().is_ok() {
+            let node_manager = self.node_manager.clone();
+            self.credential_refresher = Some(tokio::spawn(Self::refresh_credentials(node_manager)));
+        }
+
+        if let Some(addr) = node_manger.health_address {
+            let listener = tokio::net::TcpListener::bind(addr).await.map_err(|e| {
+                ApiError::message(format!("failed to listen on {addr} for health checks: {e}"))
+            })?;
+            info!(%addr, "Serving health checks");
+            let node_manager = self.node_manager.clone();
+            let ctx = ctx.async_try_clone().await?;
+            self.health_server = Some(tokio::spawn(Self::serve_health(
+                listener,
+                node_manager,
+                ctx,
+            )));
+        }
+
         Ok(())
     }
 
     async fn shutdown(&mut self, _: &mut Self::Context) -> Result<()> {
+        if let Some(refresher) = &self.credential_refresher {
+            refresher.abort();
+        }
+        if let Some(server) = &self.health_server {
+            server.abort();
+        }
         let node_manager = self.node_manager.read().await;
         node_manager.medic.abort();
         Ok(())
@@ -830,8 +931,17 @@ pub(crate) mod tests {
 
     impl NodeManager {
         pub(crate) async fn test_create(ctx: &Context) -> Result<Route> {
-            let node_dir = tempfile::tempdir().unwrap();
             let node_manager = "manager";
+            let node_manager_worker = NodeManagerWorker::new(Self::test_new(ctx).await?);
+
+            // Initialize node_man worker and return its route
+            ctx.start_worker(node_manager, node_manager_worker).await?;
+            Ok(route![node_manager])
+        }
+
+        /// A node manager with an identity, that isn't started yet
+        pub(crate) async fn test_new(ctx: &Context) -> Result<Self> {
+            let node_dir = tempfile::tempdir().unwrap();
             let transport = TcpTransport::create(ctx).await?;
             let node_address = transport.listen("127.0.0.1:0").await?;
             let mut node_man = NodeManager::create(
@@ -842,6 +952,8 @@ pub(crate) mod tests {
                     true,
                     false,
                     None,
+                    None,
+                    None,
                 ),
                 NodeManagerProjectsOptions::new(None, None, Default::default()),
                 NodeManagerTransportOptions::new(
@@ -858,12 +970,7 @@ pub(crate) mod tests {
             // Initialize identity
             node_man.create_vault_impl(None, false).await?;
             node_man.create_identity_impl(ctx, false).await?;
-
-            let node_manager_worker = NodeManagerWorker::new(node_man);
-
-            // Initialize node_man worker and return its route
-            ctx.start_worker(node_manager, node_manager_worker).await?;
-            Ok(route![node_manager])
+            Ok(node_man)
         }
     }
 }
@@ -1,28 +1,130 @@
 use crate::authenticator::direct::Client;
 use crate::error::ApiError;
+use crate::lmdb::LmdbStorage;
 use crate::multiaddr_to_route;
 use crate::nodes::models::credentials::{GetCredentialRequest, PresentCredentialRequest};
+use crate::nodes::models::secure_channel::CredentialExchangeMode;
 use crate::nodes::service::map_multiaddr_err;
 use crate::nodes::NodeManager;
+use crate::nodes::NodeVault;
 use crate::DefaultAddress;
 use minicbor::Decoder;
+use ockam::compat::asynchronous::RwLock;
 use ockam::Result;
 use ockam_core::api::{Request, Response, ResponseBuilder};
-use ockam_core::{route, AsyncTryClone};
+use ockam_core::compat::sync::Arc;
+use ockam_core::{route, Address, AsyncTryClone, Route};
+use ockam_identity::credential::{Credential, CredentialData, Timestamp, Unverified};
+use ockam_identity::{Identity, IdentityIdentifier, PublicIdentity, TrustMultiIdentifiersPolicy};
 use ockam_multiaddr::MultiAddr;
+use ockam_node::tokio;
 use std::str::FromStr;
+use std::time::Duration;
 
 use super::NodeManagerWorker;
 
+/// How often to look for a credential to refresh, while the node has none.
+const CREDENTIAL_CHECK_INTERVAL: Duration = Duration::from_secs(60);
+
+/// How long to wait before retrying a failed credential refresh.
+const CREDENTIAL_RETRY_INTERVAL: Duration = Duration::from_secs(10);
+
+/// How long to wait for the secure channel to the authority.
+const AUTHORITY_TIMEOUT: Duration = Duration::from_secs(120);
+
+/// Time from `now` until a credential valid from `created` to `expires` should be refreshed.
+///
+/// Credentials are refreshed once 80% of their validity has elapsed, which leaves
+/// enough time to present the new one before peers drop the attributes of the old one.
+fn refresh_delay(created: u64, expires: u64, now: u64) -> Duration {
+    let refresh_at = expires - expires.saturating_sub(created) / 5;
+    Duration::from_secs(refresh_at.saturating_sub(now))
+}
+
+/// What getting and presenting a credential needs from the node manager
+///
+/// It is taken from the node manager under a short lock, so that the node
+/// manager isn't locked while talking to the authority and to the peers.
+pub(super) struct CredentialRequester {
+    identity: Identity<NodeVault>,
+    authority: Route,
+    authority_id: IdentityIdentifier,
+    authorities: Vec<PublicIdentity>,
+    authenticated_storage: LmdbStorage,
+}
+
+impl CredentialRequester {
+    /// Get a new credential from the authority, over a secure channel to it
+    pub(super) async fn fetch(&self) -> Result<Credential<'static>> {
+        debug!("Create secure channel to project authority");
+        let sc = self
+            .identity
+            .create_secure_channel_extended(
+                self.authority.clone(),
+                TrustMultiIdentifiersPolicy::new(vec![self.authority_id.clone()]),
+                &self.authenticated_storage,
+                AUTHORITY_TIMEOUT,
+            )
+            .await?;
+        debug!("Created secure channel to project authority");
+
+        let credential = self.request(&sc).await;
+        if let Err(e) = self.identity.stop_secure_channel(&sc).await {
+            debug!(%sc, err = %e, "Failed to stop the secure channel to the authority")
+        }
+        credential
+    }
+
+    async fn request(&self, sc: &Address) -> Result<Credential<'static>> {
+        let mut client = Client::new(
+            route![sc.clone(), DefaultAddress::AUTHENTICATOR],
+            self.identity.ctx(),
+        )
+        .await?;
+        let credential = client.credential().await?.to_owned();
+        debug!("Got credential");
+
+        self.identity
+            .verify_self_credential(&credential, self.authorities.iter())
+            .await?;
+        debug!("Verified self credential");
+        Ok(credential)
+    }
+
+    /// Present the node's credential over the given secure channels
+    ///
+    /// A channel the credential can't be presented on is only logged, so
+    /// that the other channels still get it.
+    pub(super) async fn present(&self, channels: &[(Address, CredentialExchangeMode)]) {
+        for (addr, mode) in channels {
+            let route = route![addr.clone(), DefaultAddress::CREDENTIAL_SERVICE];
+            let res = match mode {
+                CredentialExchangeMode::None => continue,
+                CredentialExchangeMode::Oneway => self.identity.present_credential(route).await,
+                CredentialExchangeMode::Mutual => {
+                    self.identity
+                        .present_credential_mutual(
+                            route,
+                            &self.authorities,
+                            &self.authenticated_storage,
+                        )
+                        .await
+                }
+            };
+            match res {
+                Ok(()) => debug!(%addr, "Presented refreshed credential"),
+                Err(e) => warn!(%addr, err = %e, "Failed to present refreshed credential"),
+            }
+        }
+    }
+}
+
 impl NodeManager {
-    pub(super) async fn get_credential_impl(&mut self, overwrite: bool) -> Result<()> {
+    /// What is needed to get a credential from the first known authority
+    pub(super) async fn credential_requester(&self) -> Result<CredentialRequester> {
         debug!("Credential check: looking for identity");
         let identity = self.identity()?.async_try_clone().await?;
 
-        if identity.credential().await.is_some() && !overwrite {
-            return Err(ApiError::generic("credential already exists"));
-        }
-
         debug!("Credential check: looking for authorities...");
         let authorities = self.authorities()?;
 
@@ -34,8 +136,6 @@ impl NodeManager {
 
         debug!("Getting credential from : {}", authority.addr);
 
-        let allowed = vec![authority.identity.identifier().clone()];
-
         let route = match multiaddr_to_route(&authority.addr) {
             Some(route) => route,
             None => {
@@ -44,41 +144,110 @@ impl NodeManager {
             }
         };
 
-        debug!("Create secure channel to project authority");
-        let sc = self
-            .create_secure_channel_internal(&identity, route, Some(allowed), None)
-            .await?;
-        debug!("Created secure channel to project authority");
+        Ok(CredentialRequester {
+            identity,
+            authority: route,
+            authority_id: authority.identity.identifier().clone(),
+            authorities: authorities.public_identities(),
+            authenticated_storage: self.authenticated_storage.clone(),
+        })
+    }
 
-        // Borrow checker issues...
-        let authorities = self.authorities()?;
+    pub(super) async fn get_credential_impl(&mut self, overwrite: bool) -> Result<()> {
+        if self.identity()?.credential().await.is_some() && !overwrite {
+            return Err(ApiError::generic("credential already exists"));
+        }
+        let credential = self.credential_requester().await?.fetch().await?;
+        self.identity()?.set_credential(Some(credential)).await;
+        Ok(())
+    }
 
-        let mut client =
-            Client::new(route![sc, DefaultAddress::AUTHENTICATOR], identity.ctx()).await?;
-        let credential = client.credential().await?;
-        debug!("Got credential");
+    /// Time until the node's credential should be refreshed, if it has one
+    /// and an authority to get a new one from.
+    async fn credential_refresh_delay(&self) -> Option<Duration> {
+        if self.authorities().map_or(true, |a| a.as_ref().is_empty()) {
+            return None;
+        }
+        let identity = self.identity().ok()?;
+        let credential = identity.credential().await?;
+        let data = CredentialData::<Unverified>::try_from(&credential).ok()?;
+        Some(refresh_delay(
+            data.unverified_created_at().into(),
+            data.unverified_expires_at().into(),
+            Timestamp::now()?.into(),
+        ))
+    }
 
-        identity
-            .verify_self_credential(&credential, authorities.public_identities().iter())
-            .await?;
-        debug!("Verified self credential");
+    /// The secure channels the node's credential has been presented on
+    fn credential_channels(&self) -> Vec<(Address, CredentialExchangeMode)> {
+        self.registry
+            .secure_channels
+            .list()
+            .iter()
+            .map(|c| (c.addr().clone(), c.credential_exchange_mode()))
+            .collect()
+    }
+}
 
-        identity.set_credential(Some(credential.to_owned())).await;
+impl NodeManagerWorker {
+    /// Keep the node's credential from expiring, for as long as the node runs.
+    pub(super) async fn refresh_credentials(node_manager: Arc<RwLock<NodeManager>>) {
+        loop {
+            let delay = node_manager.read().await.credential_refresh_delay().await;
+            match delay {
+                Some(delay) => tokio::time::sleep(delay).await,
+                None => {
+                    tokio::time::sleep(CREDENTIAL_CHECK_INTERVAL).await;
+                    continue;
+                }
+            }
+            if let Err(e) = Self::refresh_credential(&node_manager).await {
+                warn!(err = %e, "Failed to refresh credential");
+                tokio::time::sleep(CREDENTIAL_RETRY_INTERVAL).await;
+            }
+        }
+    }
 
+    /// Get a new credential from the authority and present it again over every
+    /// secure channel the previous credential has been presented on.
+    ///
+    /// The node manager is only locked to read what this needs and to store
+    /// the new credential, not while talking to the authority or the peers.
+    async fn refresh_credential(node_manager: &RwLock<NodeManager>) -> Result<()> {
+        let requester = node_manager.read().await.credential_requester().await?;
+        let credential = requester.fetch().await?;
+        let channels = {
+            let node_manager = node_manager.write().await;
+            node_manager
+                .identity()?
+                .set_credential(Some(credential))
+                .await;
+            node_manager.credential_channels()
+        };
+        debug!("Refreshed credential");
+        requester.present(&channels).await;
         Ok(())
     }
-}
 
-impl NodeManagerWorker {
     pub(super) async fn get_credential(
         &mut self,
         req: &Request<'_>,
         dec: &mut Decoder<'_>,
     ) -> Result<ResponseBuilder> {
-        let mut node_manager = self.node_manager.write().await;
         let request: GetCredentialRequest = dec.decode()?;
-
-        node_manager.get_credential_impl(request.overwrite).await?;
+        let requester = {
+            let node_manager = self.node_manager.read().await;
+            if node_manager.identity()?.credential().await.is_some() && !request.overwrite {
+                return Err(ApiError::generic("credential already exists"));
+            }
+            node_manager.credential_requester().await?
+        };
+        let credential = requester.fetch().await?;
+        let node_manager = self.node_manager.write().await;
+        node_manager
+            .identity()?
+            .set_credential(Some(credential))
+            .await;
 
         let response = Response::ok(req.id());
         Ok(response)
@@ -100,19 +269,158 @@ impl NodeManagerWorker {
 
         let identity = node_manager.identity()?;
 
-        if request.oneway {
-            identity.present_credential(route).await?;
-        } else {
-            identity
-                .present_credential_mutual(
-                    route,
-                    &node_manager.authorities()?.public_identities(),
-                    &node_manager.authenticated_storage,
-                )
-                .await?;
+        let attributes: Option<Vec<&str>> = request
+            .attributes
+            .as_ref()
+            .map(|attrs| attrs.iter().map(|a| a.as_ref()).collect());
+        match (request.oneway, attributes) {
+            (true, None) => identity.present_credential(route).await?,
+            (true, Some(attributes)) => {
+                identity
+                    .present_credential_disclosing(route, &attributes)
+                    .await?
+            }
+            (false, None) => {
+                identity
+                    .present_credential_mutual(
+                        route,
+                        &node_manager.authorities()?.public_identities(),
+                        &node_manager.authenticated_storage,
+                    )
+                    .await?
+            }
+            (false, Some(attributes)) => {
+                identity
+                    .present_credential_mutual_disclosing(
+                        route,
+                        &node_manager.authorities()?.public_identities(),
+                        &node_manager.authenticated_storage,
+                        &attributes,
+                    )
+                    .await?
+            }
         }
 
         let response = Response::ok(req.id());
         Ok(response)
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use ockam::{Context, Routed, Worker};
+    use ockam_identity::authenticated_storage::mem::InMemoryStorage;
+    use ockam_identity::{IdentitySecureChannelLocalInfo, TrustEveryonePolicy};
+    use ockam_vault::Vault;
+
+    /// How long the credentials of `ShortLivedIssuer` are valid for
+    const CREDENTIAL_TTL: Duration = Duration::from_secs(2);
+
+    /// An authority issuing credentials that expire quickly
+    struct ShortLivedIssuer(Identity<Vault>);
+
+    #[ockam::worker]
+    impl Worker for ShortLivedIssuer {
+        type Context = Context;
+        type Message = Vec<u8>;
+
+        async fn handle_message(&mut self, ctx: &mut Context, m: Routed<Vec<u8>>) -> Result<()> {
+            let info = IdentitySecureChannelLocalInfo::find_info(m.local_message())?;
+            let req: Request = Decoder::new(m.as_body()).decode()?;
+            let credential = Credential::builder(info.their_identity_id().clone())
+                .with_attribute("role", b"member")
+                .valid_for(CREDENTIAL_TTL);
+            let credential = self.0.issue_credential(credential).await?;
+            let res = Response::ok(req.id()).body(credential).to_vec()?;
+            ctx.send(m.return_route(), res).await
+        }
+    }
+
+    #[ockam_macros::test]
+    async fn refreshed_credential_is_presented_again(ctx: &mut Context) -> Result<()> {
+        let dir = tempfile::tempdir().unwrap();
+        let storage = LmdbStorage::new(dir.path().join("authenticated_storage.lmdb")).await?;
+
+        let authority = Identity::create(ctx, &Vault::create()).await?;
+        authority
+            .create_secure_channel_listener("authority", TrustEveryonePolicy, &storage)
+            .await?;
+        let issuer = ShortLivedIssuer(authority.async_try_clone().await?);
+        ctx.start_worker(DefaultAddress::AUTHENTICATOR, issuer)
+            .await?;
+
+        // A peer of the node, which keeps the attributes of the node's credential
+        let peer_storage = InMemoryStorage::new();
+        let peer = Identity::create(ctx, &Vault::create()).await?;
+        peer.create_secure_channel_listener("peer", TrustEveryonePolicy, &peer_storage)
+            .await?;
+        peer.start_credentials_exchange_worker(
+            vec![authority.to_public().await?],
+            DefaultAddress::CREDENTIAL_SERVICE,
+            false,
+            peer_storage.clone(),
+        )
+        .await?;
+
+        let node = Identity::create(ctx, &NodeVault::Software(Vault::create())).await?;
+        let requester = CredentialRequester {
+            identity: node.async_try_clone().await?,
+            authority: route!["authority"],
+            authority_id: authority.identifier().clone(),
+            authorities: vec![authority.to_public().await?],
+            authenticated_storage: storage.clone(),
+        };
+        let sc = node
+            .create_secure_channel("peer", TrustEveryonePolicy, &storage)
+            .await?;
+        let channels = [(sc, CredentialExchangeMode::Oneway)];
+        let node_public = node.to_public().await?;
+
+        node.set_credential(Some(requester.fetch().await?)).await;
+        requester.present(&channels).await;
+        assert!(node_public.get_attributes(&peer_storage).await?.is_some());
+
+        // Once the credential expires, the peer drops the node's attributes
+        tokio::time::sleep(CREDENTIAL_TTL + Duration::from_secs(1)).await;
+        assert!(node_public.get_attributes(&peer_storage).await?.is_none());
+
+        // and gets them back when the refreshed credential is presented
+        node.set_credential(Some(requester.fetch().await?)).await;
+        requester.present(&channels).await;
+        let attributes = node_public.get_attributes(&peer_storage).await?;
+        assert_eq!(
+            attributes.unwrap().get("role").map(Vec::as_slice),
+            Some(b"member".as_slice())
+        );
+
+        ctx.stop().await
+    }
+
+    #[ockam_macros::test]
+    async fn no_refresh_without_authority(ctx: &mut Context) -> Result<()> {
+        let node_manager = NodeManager::test_new(ctx).await?;
+        let authority = Identity::create(ctx, &Vault::create()).await?;
+        let identity = node_manager.identity()?;
+        let credential = Credential::builder(identity.identifier().clone())
+            .with_attribute("role", b"member")
+            .valid_for(CREDENTIAL_TTL);
+        let credential = authority.issue_credential(credential).await?;
+        identity.set_credential(Some(credential)).await;
+
+        // The node holds a credential, but has no authority to refresh it from
+        assert!(node_manager.credential_refresh_delay().await.is_none());
+
+        ctx.stop().await
+    }
+
+    #[test]
+    fn refresh_before_expiry() {
+        let delay = refresh_delay(1000, 2000, 1000);
+        assert_eq!(delay, Duration::from_secs(800));
+        let delay = refresh_delay(1000, 2000, 1500);
+        assert_eq!(delay, Duration::from_secs(300));
+        let delay = refresh_delay(1000, 2000, 1900);
+        assert_eq!(delay, Duration::ZERO);
+    }
+}
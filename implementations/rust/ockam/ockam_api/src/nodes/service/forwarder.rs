@@ -1,11 +1,11 @@
-use std::sync::Arc;
+use std::sync::{Arc, Mutex};
 
 use minicbor::Decoder;
 
 use ockam::compat::asynchronous::RwLock;
 use ockam::remote::RemoteForwarder;
 use ockam::Result;
-use ockam_core::api::{Id, Response, Status};
+use ockam_core::api::{Id, Request, Response, ResponseBuilder, Status};
 use ockam_core::AsyncTryClone;
 use ockam_identity::IdentityIdentifier;
 use ockam_multiaddr::MultiAddr;
@@ -13,9 +13,10 @@ use ockam_node::tokio::time::timeout;
 use ockam_node::Context;
 
 use crate::error::ApiError;
-use crate::nodes::models::forwarder::{CreateForwarder, ForwarderInfo};
+use crate::nodes::models::forwarder::{CreateForwarder, ForwarderInfo, ForwarderList};
+use crate::nodes::registry::Registry;
 use crate::session::util;
-use crate::session::{Replacer, Session};
+use crate::session::{Data, Replacer, Session, TARGET};
 use crate::{multiaddr_to_route, try_multiaddr_to_addr};
 
 use super::{NodeManager, NodeManagerWorker};
@@ -47,31 +48,53 @@ impl NodeManagerWorker {
             } else {
                 RemoteForwarder::create(ctx, route).await
             }
+        } else if let Some(alias) = req.alias() {
+            RemoteForwarder::create_static(ctx, route, alias).await
         } else {
-            let f = if let Some(alias) = req.alias() {
-                RemoteForwarder::create_static(ctx, route, alias).await
+            RemoteForwarder::create(ctx, route).await
+        };
+
+        // The forwarder is recreated when the secure channel it was created
+        // with goes down, or when the channel it is routed through is replaced.
+        // `remote` is its registry key, kept up to date by the session replacer.
+        let parent = node_manager.parent_session(req.address());
+        let mut remote = None;
+        if forwarder.is_ok() && (!sec_chan.is_empty() || parent.is_some()) {
+            let ctx = Arc::new(ctx.async_try_clone().await?);
+            let key = Arc::new(Mutex::new(String::new()));
+            let mut s = if sec_chan.is_empty() {
+                Session::new(req.address().clone())
             } else {
-                RemoteForwarder::create(ctx, route).await
+                Session::new(sec_chan.clone())
             };
-            if f.is_ok() && !sec_chan.is_empty() {
-                let ctx = Arc::new(ctx.async_try_clone().await?);
-                let repl = replacer(
-                    manager,
-                    ctx,
-                    req.address().clone(),
-                    req.alias().map(|a| a.to_string()),
-                    req.authorized(),
-                );
-                let mut s = Session::new(sec_chan);
-                s.set_replacer(repl);
-                node_manager.sessions.lock().unwrap().add(s);
+            s.data().put(TARGET, req.address().clone());
+            if let Some(p) = parent {
+                s.set_parent(p)
             }
-            f
-        };
+            let repl = replacer(
+                manager,
+                ctx,
+                s.data(),
+                req.alias().map(|a| a.to_string()),
+                req.authorized(),
+                req.at_rust_node(),
+                key.clone(),
+            );
+            s.set_replacer(repl);
+            node_manager.sessions.lock().unwrap().add(s);
+            remote = Some(key);
+        }
 
         match forwarder {
             Ok(info) => {
-                let b = ForwarderInfo::from(info);
+                let b = ForwarderInfo::from(info).with_alias(req.alias());
+                if let Some(remote) = &remote {
+                    *remote.lock().unwrap() = b.remote_address().to_string();
+                }
+                node_manager
+                    .registry
+                    .forwarders
+                    .insert(b.remote_address().to_string(), b.clone());
                 debug!(
                     forwarding_route = %b.forwarding_route(),
                     remote_address = %b.remote_address(),
@@ -89,41 +112,76 @@ impl NodeManagerWorker {
     }
 }
 
+impl NodeManagerWorker {
+    pub(super) fn list_forwarders<'a>(
+        &self,
+        req: &Request<'a>,
+        registry: &'a Registry,
+    ) -> ResponseBuilder<ForwarderList<'a>> {
+        Response::ok(req.id()).body(ForwarderList::new(
+            registry.forwarders.values().cloned().collect(),
+        ))
+    }
+}
+
 /// Create a session replacer.
 ///
 /// This returns a function that accepts the previous ping address (e.g.
 /// the secure channel worker address) and constructs the whole route
-/// again.
+/// again. Forwarders routed through the secure channel of another session
+/// have no channel of their own, and their ping address is their target.
 fn replacer(
     manager: Arc<RwLock<NodeManager>>,
     ctx: Arc<Context>,
-    addr: MultiAddr,
+    data: Data,
     alias: Option<String>,
     auth: Option<IdentityIdentifier>,
+    at_rust_node: bool,
+    remote: Arc<Mutex<String>>,
 ) -> Replacer {
     Box::new(move |prev| {
         let ctx = ctx.clone();
-        let addr = addr.clone();
+        let data = data.clone();
         let alias = alias.clone();
         let auth = auth.clone();
         let manager = manager.clone();
+        let remote = remote.clone();
         Box::pin(async move {
+            let addr = data
+                .get::<MultiAddr>(TARGET)
+                .ok_or_else(|| ApiError::generic("missing forwarder target"))?;
             debug!(%prev, %addr, "creating new remote forwarder");
             let f = async {
-                let prev = try_multiaddr_to_addr(&prev)?;
-                let mut this = manager.write().await;
-                let _ = this.delete_secure_channel(&prev).await;
-                let timeout = Some(util::MAX_CONNECT_TIME);
-                let (sec, rest) = this.connect(&addr, auth, timeout).await?;
+                let (sec, rest) = {
+                    let mut this = manager.write().await;
+                    if let Ok(prev) = try_multiaddr_to_addr(&prev) {
+                        if this.registry.secure_channels.get_by_addr(&prev).is_some()
+                            && this.parent_session(&addr).is_none()
+                        {
+                            let _ = this.delete_secure_channel(&prev).await;
+                        }
+                    }
+                    let timeout = Some(util::MAX_CONNECT_TIME);
+                    this.connect(&addr, auth, timeout).await?
+                };
+                // The forwarder is created without holding the node manager
                 let a = sec.clone().try_with(&rest)?;
                 let r = multiaddr_to_route(&a)
                     .ok_or_else(|| ApiError::message(format!("invalid multiaddr: {a}")))?;
-                if let Some(alias) = &alias {
-                    RemoteForwarder::create_static(&ctx, r, alias).await?;
-                } else {
-                    RemoteForwarder::create(&ctx, r).await?;
-                }
-                Ok(sec)
+                let info = match &alias {
+                    Some(alias) if at_rust_node => {
+                        RemoteForwarder::create_static_without_heartbeats(&ctx, r, alias).await?
+                    }
+                    Some(alias) => RemoteForwarder::create_static(&ctx, r, alias).await?,
+                    None => RemoteForwarder::create(&ctx, r).await?,
+                };
+                let info = ForwarderInfo::from(info).with_alias(alias.as_deref());
+                let mut this = manager.write().await;
+                let mut remote = remote.lock().unwrap();
+                this.registry.forwarders.remove(&*remote);
+                *remote = info.remote_address().to_string();
+                this.registry.forwarders.insert(remote.clone(), info);
+                Ok(if sec.is_empty() { a } else { sec })
             };
             match timeout(util::MAX_RECOVERY_TIME, f).await {
                 Err(_) => {
@@ -0,0 +1,281 @@
+use ockam::compat::asynchronous::RwLock;
+use ockam::{Context, Result};
+use ockam_core::api::{Request, Response, ResponseBuilder};
+use ockam_core::compat::sync::Arc;
+use ockam_identity::credential::{CredentialData, Timestamp, Unverified};
+use ockam_node::tokio;
+use ockam_node::tokio::io::{AsyncReadExt, AsyncWriteExt};
+use ockam_node::tokio::net::{TcpListener, TcpStream};
+use ockam_node::tokio::time::timeout;
+use std::time::Duration;
+
+use crate::nodes::models::base::{CredentialState, NodeHealth, SessionHealth};
+use crate::nodes::models::transport::TransportStatus;
+use crate::session::Status;
+
+use super::{NodeManager, NodeManagerWorker};
+
+/// How long a health check client has to send its request and read the response
+const HTTP_TIMEOUT: Duration = Duration::from_secs(5);
+
+/// Size of the largest request accepted by the health endpoint
+const MAX_REQUEST_SIZE: usize = 8 * 1024;
+
+impl NodeManager {
+    pub(super) async fn health(&self, ctx: &Context) -> Result<NodeHealth<'static>> {
+        let workers = ctx.list_workers().await?.len() as u32;
+        let mut health =
+            NodeHealth::new(self.node_name.clone(), std::process::id() as i32, workers);
+
+        health.transports = self
+            .transports
+            .iter()
+            .map(|(tid, (tt, tm, addr))| TransportStatus::new(*tt, *tm, addr.clone(), tid.clone()))
+            .collect();
+        health.secure_channel_listeners = self
+            .registry
+            .secure_channel_listeners
+            .keys()
+            .map(|addr| addr.to_string().into())
+            .collect();
+
+        let authorities = self.authorities().map(|a| a.as_ref().len()).unwrap_or(0);
+        if authorities > 0 {
+            let credential = match self.identity() {
+                Ok(identity) => identity.credential().await,
+                Err(_) => None,
+            };
+            let expires = credential.as_ref().and_then(|c| {
+                let data = CredentialData::<Unverified>::try_from(c).ok()?;
+                Some(u64::from(data.unverified_expires_at()))
+            });
+            let now = Timestamp::now().map(u64::from).unwrap_or_default();
+            health.credential = match expires {
+                None => CredentialState::Missing,
+                Some(t) if t <= now => CredentialState::Expired,
+                Some(_) => CredentialState::Valid,
+            };
+            health.credential_expires_at = expires;
+        }
+
+        let sessions = self.sessions.lock().unwrap();
+        health.sessions = sessions
+            .iter()
+            .map(|(_, s)| SessionHealth::new(s.ping_address().to_string(), s.status().to_string()))
+            .collect();
+
+        // A node that didn't get a credential yet may not need one, while an
+        // expired credential is refused by the peers the node talks to.
+        health.ready = health.credential != CredentialState::Expired
+            && sessions.iter().all(|(_, s)| s.status() == Status::Up);
+        Ok(health)
+    }
+}
+
+impl NodeManagerWorker {
+    pub(super) async fn get_node_health(
+        &self,
+        ctx: &Context,
+        req: &Request<'_>,
+    ) -> Result<ResponseBuilder<NodeHealth<'static>>> {
+        let node_manager = self.node_manager.read().await;
+        let health = node_manager.health(ctx).await?;
+        Ok(Response::ok(req.id()).body(health))
+    }
+
+    /// Answer the health checks of orchestrators, over plain HTTP
+    ///
+    /// `GET /health` answers `200 OK` for as long as the node runs, without
+    /// waiting for the node manager, which sessions being recovered may hold
+    /// for a while. `GET /ready` answers `200 OK` when the node is ready or
+    /// `503 Service Unavailable` otherwise, with the health of the node as
+    /// its JSON body.
+    pub(super) async fn serve_health(
+        listener: TcpListener,
+        node_manager: Arc<RwLock<NodeManager>>,
+        ctx: Context,
+    ) {
+        let ctx = Arc::new(ctx);
+        loop {
+            let stream = match listener.accept().await {
+                Ok((stream, _)) => stream,
+                Err(e) => {
+                    warn!(err = %e, "Failed to accept a health check connection");
+                    tokio::time::sleep(Duration::from_millis(100)).await;
+                    continue;
+                }
+            };
+            // A slow client must not delay the probes of the others
+            tokio::spawn(answer_probe(stream, node_manager.clone(), ctx.clone()));
+        }
+    }
+}
+
+async fn answer_probe(
+    mut stream: TcpStream,
+    node_manager: Arc<RwLock<NodeManager>>,
+    ctx: Arc<Context>,
+) {
+    let request = match timeout(HTTP_TIMEOUT, read_request(&mut stream)).await {
+        Ok(Ok(r)) => r,
+        _ => return,
+    };
+    let response = match parse_probe(&request) {
+        Err(status) => http_response(status, None),
+        Ok(Probe::Liveness) => http_response("200 OK", None),
+        Ok(Probe::Readiness) => {
+            // The node manager may be busy, but not for longer than the client waits
+            let health = timeout(HTTP_TIMEOUT, async {
+                node_manager.read().await.health(&ctx).await
+            })
+            .await;
+            match health {
+                Ok(Ok(h)) => {
+                    let status = if h.ready {
+                        "200 OK"
+                    } else {
+                        "503 Service Unavailable"
+                    };
+                    http_response(status, serde_json::to_string(&h).ok())
+                }
+                Ok(Err(e)) => {
+                    warn!(err = %e, "Failed to get the health of the node");
+                    http_response("500 Internal Server Error", None)
+                }
+                Err(_) => {
+                    warn!("Timed out getting the health of the node");
+                    http_response("503 Service Unavailable", None)
+                }
+            }
+        }
+    };
+    let _ = timeout(HTTP_TIMEOUT, stream.write_all(response.as_bytes())).await;
+}
+
+/// Read the request line and headers of an HTTP request
+async fn read_request(stream: &mut TcpStream) -> std::io::Result<String> {
+    let mut buf = Vec::new();
+    let mut chunk = [0; 1024];
+    while !buf.windows(4).any(|w| w == b"\r\n\r\n") && buf.len() < MAX_REQUEST_SIZE {
+        let n = stream.read(&mut chunk).await?;
+        if n == 0 {
+            break;
+        }
+        buf.extend_from_slice(&chunk[..n]);
+    }
+    Ok(String::from_utf8_lossy(&buf).into_owned())
+}
+
+#[derive(Debug, PartialEq, Eq)]
+enum Probe {
+    Liveness,
+    Readiness,
+}
+
+/// The probe asked for by an HTTP request, or the status to refuse it with
+fn parse_probe(request: &str) -> Result<Probe, &'static str> {
+    let mut words = request.split_whitespace();
+    if words.next() != Some("GET") {
+        return Err("405 Method Not Allowed");
+    }
+    let path = words.next().unwrap_or_default();
+    match path.split('?').next() {
+        Some("/health") => Ok(Probe::Liveness),
+        Some("/ready") => Ok(Probe::Readiness),
+        _ => Err("404 Not Found"),
+    }
+}
+
+fn http_response(status: &str, body: Option<String>) -> String {
+    let body = body.unwrap_or_default();
+    format!(
+        "HTTP/1.1 {status}\r\nContent-Type: application/json\r\nContent-Length: {}\r\nConnection: close\r\n\r\n{body}",
+        body.len()
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::session::Session;
+    use minicbor::Decoder;
+    use ockam_core::api::Status as ApiStatus;
+    use ockam_core::route;
+    use ockam_core::AsyncTryClone;
+
+    /// Send a probe to the health server and return the status line of the response
+    async fn probe(addr: std::net::SocketAddr, path: &str) -> String {
+        let mut stream = TcpStream::connect(addr).await.unwrap();
+        let request = format!("GET {path} HTTP/1.1\r\nHost: localhost\r\n\r\n");
+        stream.write_all(request.as_bytes()).await.unwrap();
+        let mut response = String::new();
+        stream.read_to_string(&mut response).await.unwrap();
+        response.lines().next().unwrap_or_default().to_string()
+    }
+
+    #[ockam_macros::test]
+    async fn serve_probes(ctx: &mut Context) -> Result<()> {
+        let node_manager = NodeManager::test_new(ctx).await?;
+        let sessions = node_manager.sessions.clone();
+        let mut worker = NodeManagerWorker::new(node_manager);
+        let node_manager = worker.get().clone();
+        ctx.start_worker("manager", worker).await?;
+
+        let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let addr = listener.local_addr().unwrap();
+        let server_ctx = ctx.async_try_clone().await?;
+        tokio::spawn(NodeManagerWorker::serve_health(
+            listener,
+            node_manager.clone(),
+            server_ctx,
+        ));
+
+        // An idle client doesn't hold the other probes back
+        let _idle = TcpStream::connect(addr).await.unwrap();
+        let ready = timeout(Duration::from_secs(1), probe(addr, "/ready")).await;
+        assert_eq!(ready.unwrap(), "HTTP/1.1 200 OK");
+
+        let mut session = Session::new("/service/nowhere".parse().unwrap());
+        session.set_status(Status::Down);
+        sessions.lock().unwrap().add(session);
+        assert_eq!(probe(addr, "/health").await, "HTTP/1.1 200 OK");
+        assert_eq!(
+            probe(addr, "/ready").await,
+            "HTTP/1.1 503 Service Unavailable"
+        );
+        assert_eq!(probe(addr, "/other").await, "HTTP/1.1 404 Not Found");
+
+        // The node stays alive while the node manager is busy
+        let busy = node_manager.write().await;
+        let live = timeout(Duration::from_secs(1), probe(addr, "/health")).await;
+        assert_eq!(live.unwrap(), "HTTP/1.1 200 OK");
+        drop(busy);
+
+        // The same health is served by the node API
+        let req = Request::get("/node/health");
+        let res =
+            ockam_node::api::request(ctx, "node_health", None, route!["manager"], req).await?;
+        let mut dec = Decoder::new(&res);
+        let header: Response = dec.decode()?;
+        assert_eq!(header.status(), Some(ApiStatus::Ok));
+        let health: NodeHealth = dec.decode()?;
+        assert!(!health.ready);
+        assert_eq!(health.sessions.len(), 1);
+        assert_eq!(health.sessions[0].status, "down");
+
+        ctx.stop().await
+    }
+
+    #[test]
+    fn probes() {
+        let get = |path: &str| format!("GET {path} HTTP/1.1\r\nHost: localhost\r\n\r\n");
+        assert_eq!(parse_probe(&get("/health")), Ok(Probe::Liveness));
+        assert_eq!(parse_probe(&get("/ready?verbose")), Ok(Probe::Readiness));
+        assert_eq!(parse_probe(&get("/node")), Err("404 Not Found"));
+        assert_eq!(
+            parse_probe("POST /health HTTP/1.1\r\n\r\n"),
+            Err("405 Method Not Allowed")
+        );
+        assert_eq!(parse_probe(""), Err("405 Method Not Allowed"));
+    }
+}
@@ -0,0 +1,61 @@
+use minicbor::Decoder;
+use ockam_core::api::{self, Error, Request, Response};
+use ockam_core::Result;
+
+use crate::nodes::models::base::LogLevel;
+
+use super::NodeManagerWorker;
+
+/// Access to the log filter of the process running a node
+///
+/// Logging is set up by the program embedding the node manager, which
+/// hands it an implementation of this trait to change the filter at runtime.
+pub trait LogFilter: Send + Sync + 'static {
+    /// The current filter, e.g. `info,ockam_api=debug`
+    fn get(&self) -> String;
+
+    /// Replace the filter, or return why it is invalid
+    fn set(&self, filter: &str) -> std::result::Result<(), String>;
+}
+
+impl NodeManagerWorker {
+    pub(super) async fn get_log_level(&self, req: &Request<'_>) -> Result<Vec<u8>> {
+        let node_manager = self.node_manager.read().await;
+        match &node_manager.log_filter {
+            Some(f) => Ok(Response::ok(req.id())
+                .body(LogLevel::new(f.get()))
+                .to_vec()?),
+            None => not_available(req),
+        }
+    }
+
+    pub(super) async fn set_log_level(
+        &self,
+        req: &Request<'_>,
+        dec: &mut Decoder<'_>,
+    ) -> Result<Vec<u8>> {
+        let body: LogLevel = dec.decode()?;
+        let node_manager = self.node_manager.read().await;
+        let f = match &node_manager.log_filter {
+            Some(f) => f,
+            None => return not_available(req),
+        };
+        match f.set(&body.filter) {
+            Ok(()) => {
+                info!(filter = %body.filter, "Log filter changed");
+                Ok(Response::ok(req.id())
+                    .body(LogLevel::new(f.get()))
+                    .to_vec()?)
+            }
+            Err(e) => Ok(api::bad_request(req, &e).to_vec()?),
+        }
+    }
+}
+
+fn not_available(req: &Request<'_>) -> Result<Vec<u8>> {
+    let mut e = Error::new(req.path()).with_message("the log filter of this node can't be changed");
+    if let Some(m) = req.method() {
+        e = e.with_method(m)
+    }
+    Ok(Response::not_implemented(req.id()).body(e).to_vec()?)
+}
@@ -4,7 +4,7 @@ use crate::nodes::models::portal::{
 };
 use crate::nodes::registry::{InletInfo, OutletInfo, Registry};
 use crate::nodes::service::random_alias;
-use crate::session::{util, Data, Replacer, Session};
+use crate::session::{util, Data, Replacer, Session, TARGET};
 use crate::{actions, resources};
 use crate::{multiaddr_to_route, try_multiaddr_to_addr};
 use minicbor::Decoder;
@@ -15,7 +15,7 @@ use ockam::{Address, Result};
 use ockam_abac::expr::{and, eq, ident, str};
 use ockam_abac::{Action, Env, PolicyAccessControl, PolicyStorage, Resource};
 use ockam_core::api::{Request, Response, ResponseBuilder};
-use ockam_core::{AccessControl, AllowAll};
+use ockam_core::{AccessControl, AllowAll, AsyncTryClone};
 use ockam_identity::IdentityIdentifier;
 use ockam_multiaddr::proto::{Project, Secure, Service};
 use ockam_multiaddr::{MultiAddr, Protocol};
@@ -218,6 +218,20 @@ impl NodeManagerWorker {
                     );
                     s.set_replacer(repl);
                     node_manager.sessions.lock().unwrap().add(s);
+                } else if let Some(parent) = node_manager.parent_session(req.outlet_addr()) {
+                    let mut s = Session::new(req.outlet_addr().clone());
+                    s.data().put(INLET_WORKER, worker_addr.clone());
+                    s.data().put(TARGET, req.outlet_addr().clone());
+                    s.set_parent(parent);
+                    let repl = dependent_replacer(
+                        manager,
+                        s.data(),
+                        alias.clone(),
+                        listen_addr.clone(),
+                        access_control.clone(),
+                    );
+                    s.set_replacer(repl);
+                    node_manager.sessions.lock().unwrap().add(s);
                 }
 
                 Response::ok(rid).body(InletStatus::new(
@@ -413,6 +427,50 @@ fn replacer(
     })
 }
 
+/// Create a session replacer for an inlet routed through the secure channel
+/// of another session.
+///
+/// The returned function recreates the inlet with the session target, which
+/// has been updated to go through the replacement of that channel.
+fn dependent_replacer(
+    manager: Arc<RwLock<NodeManager>>,
+    data: Data,
+    alias: String,
+    bind: String,
+    access: Arc<dyn AccessControl>,
+) -> Replacer {
+    Box::new(move |prev| {
+        let alias = alias.clone();
+        let bind = bind.clone();
+        let manager = manager.clone();
+        let access = access.clone();
+        let data = data.clone();
+        Box::pin(async move {
+            let addr = data
+                .get::<MultiAddr>(TARGET)
+                .ok_or_else(|| ApiError::generic("missing inlet target"))?;
+            debug!(%prev, %addr, "creating new tcp inlet");
+            let r = multiaddr_to_route(&addr)
+                .ok_or_else(|| ApiError::message(format!("invalid multiaddr: {addr}")))?;
+            // The node manager is only locked to update its registry
+            let tcp = manager.read().await.tcp_transport.async_try_clone().await?;
+            if let Some(wa) = data.get::<Address>(INLET_WORKER) {
+                let _ = tcp.stop_inlet(wa).await;
+            }
+            let opts = InletOptions::new(bind.clone(), r.clone(), access);
+            let wa = tcp.create_inlet_extended(opts).await?.0;
+            manager
+                .write()
+                .await
+                .registry
+                .inlets
+                .insert(alias, InletInfo::new(&bind, Some(&wa), &r));
+            data.put(INLET_WORKER, wa);
+            Ok(addr)
+        })
+    })
+}
+
 fn without_outlet_address(mut addr: MultiAddr) -> MultiAddr {
     if let Some(p) = addr.last() {
         if let Some(a) = p.cast::<Service>() {
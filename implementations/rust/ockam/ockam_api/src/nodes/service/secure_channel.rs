@@ -9,15 +9,20 @@ use crate::nodes::models::secure_channel::{
 };
 use crate::nodes::registry::Registry;
 use crate::nodes::NodeManager;
-use crate::DefaultAddress;
+use crate::nodes::NodeVault;
+use crate::session::util;
+use crate::session::{self, Data, Key, Replacer, Session, TARGET};
+use crate::{multiaddr_to_route, try_address_to_multiaddr, try_multiaddr_to_addr, DefaultAddress};
 use minicbor::Decoder;
+use ockam::compat::asynchronous::RwLock;
 use ockam::identity::TrustEveryonePolicy;
 use ockam::{Address, Result, Route};
 use ockam_core::api::{Request, Response, ResponseBuilder};
+use ockam_core::compat::sync::Arc;
 use ockam_core::{route, AsyncTryClone};
 use ockam_identity::{Identity, IdentityIdentifier, TrustMultiIdentifiersPolicy};
 use ockam_multiaddr::MultiAddr;
-use ockam_vault::Vault;
+use ockam_node::tokio::time::timeout;
 
 impl NodeManager {
     async fn get_credential_if_needed(&mut self) -> Result<()> {
@@ -37,7 +42,7 @@ impl NodeManager {
 
     pub(crate) async fn create_secure_channel_internal(
         &mut self,
-        identity: &Identity<Vault>,
+        identity: &Identity<NodeVault>,
         sc_route: Route,
         authorized_identifiers: Option<Vec<IdentityIdentifier>>,
         timeout: Option<Duration>,
@@ -130,6 +135,10 @@ impl NodeManager {
             }
         }
 
+        self.registry
+            .secure_channels
+            .set_credential_exchange_mode(&sc_addr, actual_exchange_mode);
+
         // Return secure channel address
         Ok(sc_addr)
     }
@@ -174,12 +183,38 @@ impl NodeManager {
         Ok(())
     }
 
+    /// The session of the secure channel which `addr` is routed through, if any.
+    pub(super) fn parent_session(&self, addr: &MultiAddr) -> Option<Key> {
+        let first = try_multiaddr_to_addr(&addr.split(1).0).ok()?;
+        self.registry.secure_channels.get_by_addr(&first)?.session()
+    }
+
+    /// The status of the session keeping the secure channel at `addr` up.
+    ///
+    /// Channels created for an inlet or a forwarder are covered by the
+    /// session of the inlet or forwarder, which is routed through them.
+    pub(super) fn secure_channel_status(&self, addr: &Address) -> Option<session::Status> {
+        let sessions = self.sessions.lock().unwrap();
+        let info = self.registry.secure_channels.get_by_addr(addr)?;
+        if let Some(k) = info.session() {
+            return sessions.session(&k).map(|s| s.status());
+        }
+        let ma = try_address_to_multiaddr(addr).ok()?;
+        let status = sessions
+            .iter()
+            .find(|(_, s)| util::strip_prefix(s.ping_address(), &ma).is_some())
+            .map(|(_, s)| s.status());
+        status
+    }
+
     pub(super) async fn delete_secure_channel(&mut self, addr: &Address) -> Result<()> {
         debug!(%addr, "deleting secure channel");
         let identity = self.identity()?;
-        identity.stop_secure_channel(addr).await?;
+        // The channel is forgotten even if it was already stopped, so
+        // that it is not reused for its route.
+        let res = identity.stop_secure_channel(addr).await;
         self.registry.secure_channels.remove_by_addr(addr);
-        Ok(())
+        res
     }
 }
 
@@ -218,6 +253,7 @@ impl NodeManagerWorker {
         req: &Request<'_>,
         dec: &mut Decoder<'_>,
     ) -> Result<ResponseBuilder<CreateSecureChannelResponse<'a>>> {
+        let manager = self.node_manager.clone();
         let mut node_manager = self.node_manager.write().await;
         let CreateSecureChannelRequest {
             addr,
@@ -249,12 +285,33 @@ impl NodeManagerWorker {
         let channel = node_manager
             .create_secure_channel_impl(
                 route,
-                authorized_identifiers,
+                authorized_identifiers.clone(),
                 credential_exchange_mode,
                 timeout,
             )
             .await?;
 
+        // Keep the channel up, unless an existing session already does.
+        if node_manager.secure_channel_status(&channel).is_none() {
+            let mut s = Session::new(try_address_to_multiaddr(&channel)?);
+            s.data().put(TARGET, addr.clone());
+            if let Some(parent) = node_manager.parent_session(&addr) {
+                s.set_parent(parent)
+            }
+            let repl = replacer(
+                manager,
+                s.data(),
+                authorized_identifiers,
+                credential_exchange_mode,
+            );
+            s.set_replacer(repl);
+            let key = node_manager.sessions.lock().unwrap().add(s);
+            node_manager
+                .registry
+                .secure_channels
+                .set_session(&channel, key);
+        }
+
         let response = Response::ok(req.id()).body(CreateSecureChannelResponse::new(&channel));
 
         Ok(response)
@@ -269,9 +326,17 @@ impl NodeManagerWorker {
         let addr = Address::from(body.channel.as_ref());
         info!(%addr, "Handling request to delete secure channel");
         let mut node_manager = self.node_manager.write().await;
+        let session = node_manager
+            .registry
+            .secure_channels
+            .get_by_addr(&addr)
+            .and_then(|c| c.session());
         let res = match node_manager.delete_secure_channel(&addr).await {
             Ok(()) => {
                 trace!(%addr, "Removed secure channel");
+                if let Some(k) = session {
+                    node_manager.sessions.lock().unwrap().remove(&k);
+                }
                 Some(addr)
             }
             Err(err) => {
@@ -298,8 +363,9 @@ impl NodeManagerWorker {
             .registry
             .secure_channels
             .get_by_addr(&sc_address);
+        let status = node_manager.secure_channel_status(&sc_address);
 
-        Ok(Response::ok(req.id()).body(ShowSecureChannelResponse::new(info)))
+        Ok(Response::ok(req.id()).body(ShowSecureChannelResponse::new(info).with_status(status)))
     }
 
     pub(super) async fn create_secure_channel_listener(
@@ -340,3 +406,63 @@ impl NodeManagerWorker {
         Ok(response)
     }
 }
+
+/// Create a session replacer.
+///
+/// This returns a function that accepts the previous secure channel
+/// address, deletes that channel and creates a new one to the session
+/// target.
+fn replacer(
+    manager: Arc<RwLock<NodeManager>>,
+    data: Data,
+    auth: Option<Vec<IdentityIdentifier>>,
+    mode: CredentialExchangeMode,
+) -> Replacer {
+    Box::new(move |prev| {
+        let manager = manager.clone();
+        let data = data.clone();
+        let auth = auth.clone();
+        Box::pin(async move {
+            let addr = data
+                .get::<MultiAddr>(TARGET)
+                .ok_or_else(|| ApiError::generic("missing secure channel target"))?;
+            debug!(%prev, %addr, "creating new secure channel");
+            let f = async {
+                let prev = try_multiaddr_to_addr(&prev)?;
+                let route = multiaddr_to_route(&addr)
+                    .ok_or_else(|| ApiError::message(format!("invalid multiaddr: {addr}")))?;
+                let mut this = manager.write().await;
+                let old = this.registry.secure_channels.get_by_addr(&prev).cloned();
+                let _ = this.delete_secure_channel(&prev).await;
+                let timeout = Some(util::MAX_CONNECT_TIME);
+                let sc = match this
+                    .create_secure_channel_impl(route, auth, mode, timeout)
+                    .await
+                {
+                    Ok(sc) => sc,
+                    Err(e) => {
+                        if let Some(old) = old {
+                            this.registry.secure_channels.restore(old)
+                        }
+                        return Err(e);
+                    }
+                };
+                if let Some(k) = old.and_then(|c| c.session()) {
+                    this.registry.secure_channels.set_session(&sc, k)
+                }
+                try_address_to_multiaddr(&sc)
+            };
+            match timeout(util::MAX_RECOVERY_TIME, f).await {
+                Err(_) => {
+                    warn!(%addr, "timeout creating new secure channel");
+                    Err(ApiError::generic("timeout"))
+                }
+                Ok(Err(e)) => {
+                    warn!(%addr, err = %e, "error creating new secure channel");
+                    Err(e)
+                }
+                Ok(Ok(a)) => Ok(a),
+            }
+        })
+    })
+}
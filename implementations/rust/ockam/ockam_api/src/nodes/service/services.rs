@@ -5,11 +5,13 @@ use crate::identity::IdentityService;
 use crate::nodes::models::services::{
     ServiceList, ServiceStatus, StartAuthenticatedServiceRequest, StartAuthenticatorRequest,
     StartCredentialsService, StartEchoerServiceRequest, StartIdentityServiceRequest,
-    StartOktaIdentityProviderRequest, StartUppercaseServiceRequest, StartVaultServiceRequest,
-    StartVerifierService,
+    StartOidcAuthenticatorRequest, StartOktaIdentityProviderRequest, StartUppercaseServiceRequest,
+    StartVaultServiceRequest, StartVerifierService,
 };
 use crate::nodes::registry::{CredentialsServiceInfo, Registry, VerifierServiceInfo};
 use crate::nodes::NodeManager;
+use crate::oidc::jwt::{Jwks, JwtValidator};
+use crate::oidc::ClaimMapping;
 use crate::uppercase::Uppercase;
 use crate::vault::VaultService;
 use minicbor::Decoder;
@@ -74,6 +76,9 @@ impl NodeManager {
 
         let authorities = self.authorities()?;
 
+        identity
+            .register_schema(crate::authenticator::project_member_schema())
+            .await;
         identity
             .start_credentials_exchange_worker(
                 authorities.public_identities(),
@@ -172,6 +177,34 @@ impl NodeManager {
         Ok(())
     }
 
+    pub(super) async fn start_oidc_authenticator_service_impl(
+        &mut self,
+        ctx: &Context,
+        addr: Address,
+        validator: JwtValidator,
+        mappings: Vec<ClaimMapping>,
+        proj: &[u8],
+    ) -> Result<()> {
+        use crate::nodes::registry::OidcAuthenticatorServiceInfo;
+        if self
+            .registry
+            .oidc_authenticator_services
+            .contains_key(&addr)
+        {
+            return Err(ApiError::generic(
+                "OIDC authenticator service already started",
+            ));
+        }
+        let db = self.authenticated_storage.async_try_clone().await?;
+        let id = self.identity()?.async_try_clone().await?;
+        let au = crate::oidc::Server::new(proj.to_vec(), db, id, validator, mappings);
+        ctx.start_worker(addr.clone(), au).await?;
+        self.registry
+            .oidc_authenticator_services
+            .insert(addr, OidcAuthenticatorServiceInfo::default());
+        Ok(())
+    }
+
     pub(super) async fn start_okta_identity_provider_service_impl(
         &mut self,
         ctx: &Context,
@@ -293,6 +326,29 @@ impl NodeManagerWorker {
         Ok(Response::ok(req.id()))
     }
 
+    pub(super) async fn start_oidc_authenticator_service(
+        &mut self,
+        ctx: &Context,
+        req: &Request<'_>,
+        dec: &mut Decoder<'_>,
+    ) -> Result<ResponseBuilder> {
+        let mut node_manager = self.node_manager.write().await;
+        let body: StartOidcAuthenticatorRequest = dec.decode()?;
+        let addr: Address = body.address().into();
+        let jwks = Jwks::from_file(body.jwks())?;
+        let validator = JwtValidator::new(jwks, body.issuer(), body.audience());
+        node_manager
+            .start_oidc_authenticator_service_impl(
+                ctx,
+                addr,
+                validator,
+                body.mappings().to_vec(),
+                body.project(),
+            )
+            .await?;
+        Ok(Response::ok(req.id()))
+    }
+
     pub(super) async fn start_okta_identity_provider_service<'a>(
         &mut self,
         ctx: &Context,
@@ -398,6 +454,16 @@ impl NodeManagerWorker {
             .credentials_services
             .keys()
             .for_each(|addr| list.push(ServiceStatus::new(addr.address(), "credentials")));
+        registry
+            .oidc_authenticator_services
+            .keys()
+            .for_each(|addr| list.push(ServiceStatus::new(addr.address(), "oidc_authenticator")));
+        registry
+            .okta_identity_provider_services
+            .keys()
+            .for_each(|addr| {
+                list.push(ServiceStatus::new(addr.address(), "okta_identity_provider"))
+            });
 
         #[cfg(feature = "direct-authenticator")]
         registry
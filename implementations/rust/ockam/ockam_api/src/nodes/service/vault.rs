@@ -1,14 +1,11 @@
 use super::{map_anyhow_err, NodeManagerWorker};
 use crate::nodes::models::vault::CreateVaultRequest;
-use crate::nodes::NodeManager;
+use crate::nodes::{NodeManager, NodeVault};
 use minicbor::Decoder;
-use ockam::vault::storage::FileStorage;
-use ockam::vault::Vault;
 use ockam::Result;
 use ockam_core::api::{Request, Response, ResponseBuilder};
 use ockam_core::errcode::{Kind, Origin};
 use std::path::{Path, PathBuf};
-use std::sync::Arc;
 
 impl NodeManager {
     pub fn default_vault_path(node_dir: &Path) -> PathBuf {
@@ -35,8 +32,7 @@ impl NodeManager {
 
         let path = path.unwrap_or_else(|| Self::default_vault_path(&self.node_dir));
 
-        let vault_storage = FileStorage::create(path.clone()).await?;
-        let vault = Vault::new(Some(Arc::new(vault_storage)));
+        let vault = NodeVault::open(path.clone(), self.vault_key.clone()).await?;
 
         let state = self.config.state();
         state.write().vault_path = Some(path);
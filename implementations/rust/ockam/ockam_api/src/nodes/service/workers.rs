@@ -0,0 +1,21 @@
+use crate::nodes::models::workers::{WorkerList, WorkerStatus};
+use ockam::{Context, Result};
+use ockam_core::api::{Request, Response, ResponseBuilder};
+
+use super::NodeManagerWorker;
+
+impl NodeManagerWorker {
+    pub(super) async fn list_workers(
+        &self,
+        ctx: &Context,
+        req: &Request<'_>,
+    ) -> Result<ResponseBuilder<WorkerList<'static>>> {
+        let list = ctx
+            .list_worker_info()
+            .await?
+            .into_iter()
+            .map(WorkerStatus::from)
+            .collect();
+        Ok(Response::ok(req.id()).body(WorkerList::new(list)))
+    }
+}
@@ -0,0 +1,223 @@
+//! The vault of a node, keeping its secrets in software or on a PKCS#11 token.
+
+use crate::error::ApiError;
+use ockam::vault::storage::{FileStorage, StorageKey};
+use ockam_core::vault::{
+    AsymmetricVault, Buffer, Hasher, KeyId, PublicKey, SecretAttributes, SecretKey, SecretVault,
+    Signature, Signer, SmallBuffer, SymmetricVault, Verifier,
+};
+use ockam_core::{async_trait, compat::boxed::Box, Result};
+use ockam_vault::Vault;
+use ockam_vault_pkcs11::Pkcs11Vault;
+use serde::{Deserialize, Serialize};
+use std::path::{Path, PathBuf};
+use std::sync::Arc;
+
+/// Environment variable holding the user PIN of PKCS#11 tokens.
+pub const PKCS11_PIN_ENV: &str = "OCKAM_PKCS11_PIN";
+
+/// A PKCS#11 token, such as a hardware security module, holding the secrets of a vault.
+///
+/// It is written in place of the storage file of a software vault. The user PIN
+/// is not written, but read from [`PKCS11_PIN_ENV`] when the vault is opened.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct HsmConfig {
+    /// Path to the PKCS#11 module
+    pub module: PathBuf,
+    /// Label of the token
+    pub token: String,
+}
+
+#[derive(Serialize, Deserialize)]
+#[serde(deny_unknown_fields)]
+struct HsmFile {
+    pkcs11: HsmConfig,
+}
+
+impl HsmConfig {
+    pub fn new(module: PathBuf, token: String) -> Self {
+        Self { module, token }
+    }
+
+    /// Read the token written at `path`, if the file describes one.
+    pub fn read(path: &Path) -> Option<Self> {
+        let data = std::fs::read(path).ok()?;
+        serde_json::from_slice::<HsmFile>(&data)
+            .ok()
+            .map(|f| f.pkcs11)
+    }
+
+    pub fn write(&self, path: &Path) -> Result<()> {
+        let file = HsmFile {
+            pkcs11: self.clone(),
+        };
+        let data = serde_json::to_vec_pretty(&file).map_err(ApiError::wrap)?;
+        std::fs::write(path, data).map_err(|e| ApiError::generic(&e.to_string()))
+    }
+
+    /// Log into the token with the PIN of [`PKCS11_PIN_ENV`].
+    pub fn open(&self) -> Result<Pkcs11Vault> {
+        let pin = std::env::var(PKCS11_PIN_ENV).map_err(|_| {
+            ApiError::message(format!(
+                "Set {} to the PIN of the PKCS#11 token {}",
+                PKCS11_PIN_ENV, self.token
+            ))
+        })?;
+        Pkcs11Vault::open(&self.module, &self.token, &pin)
+    }
+}
+
+/// The vault of a node.
+#[derive(Clone)]
+pub enum NodeVault {
+    Software(Vault),
+    Pkcs11(Pkcs11Vault),
+}
+
+impl NodeVault {
+    /// Open the vault written at `path`.
+    ///
+    /// The file either describes a PKCS#11 token, see [`HsmConfig`], or is
+    /// the storage of a software vault, unlocked with `key` if it is encrypted.
+    pub async fn open(path: PathBuf, key: Option<StorageKey>) -> Result<Self> {
+        match HsmConfig::read(&path) {
+            Some(hsm) => Ok(NodeVault::Pkcs11(hsm.open()?)),
+            None => {
+                let storage = FileStorage::create_with_key(path, key).await?;
+                Ok(NodeVault::Software(Vault::new(Some(Arc::new(storage)))))
+            }
+        }
+    }
+}
+
+/// Call the same method on the vault of either kind.
+macro_rules! delegate {
+    ($self:ident.$method:ident($($arg:expr),*)) => {
+        match $self {
+            NodeVault::Software(v) => v.$method($($arg),*).await,
+            NodeVault::Pkcs11(v) => v.$method($($arg),*).await,
+        }
+    };
+}
+
+#[async_trait]
+impl SecretVault for NodeVault {
+    async fn secret_generate(&self, attributes: SecretAttributes) -> Result<KeyId> {
+        delegate!(self.secret_generate(attributes))
+    }
+
+    async fn secret_import(&self, secret: &[u8], attributes: SecretAttributes) -> Result<KeyId> {
+        delegate!(self.secret_import(secret, attributes))
+    }
+
+    async fn secret_export(&self, key_id: &KeyId) -> Result<SecretKey> {
+        delegate!(self.secret_export(key_id))
+    }
+
+    async fn secret_attributes_get(&self, key_id: &KeyId) -> Result<SecretAttributes> {
+        delegate!(self.secret_attributes_get(key_id))
+    }
+
+    async fn secret_public_key_get(&self, key_id: &KeyId) -> Result<PublicKey> {
+        delegate!(self.secret_public_key_get(key_id))
+    }
+
+    async fn secret_destroy(&self, key_id: KeyId) -> Result<()> {
+        delegate!(self.secret_destroy(key_id))
+    }
+}
+
+#[async_trait]
+impl Signer for NodeVault {
+    async fn sign(&self, key_id: &KeyId, data: &[u8]) -> Result<Signature> {
+        delegate!(self.sign(key_id, data))
+    }
+}
+
+#[async_trait]
+impl Verifier for NodeVault {
+    async fn verify(
+        &self,
+        signature: &Signature,
+        public_key: &PublicKey,
+        data: &[u8],
+    ) -> Result<bool> {
+        delegate!(self.verify(signature, public_key, data))
+    }
+}
+
+#[async_trait]
+impl Hasher for NodeVault {
+    async fn sha256(&self, data: &[u8]) -> Result<[u8; 32]> {
+        delegate!(self.sha256(data))
+    }
+
+    async fn hkdf_sha256(
+        &self,
+        salt: &KeyId,
+        info: &[u8],
+        ikm: Option<&KeyId>,
+        output_attributes: SmallBuffer<SecretAttributes>,
+    ) -> Result<SmallBuffer<KeyId>> {
+        delegate!(self.hkdf_sha256(salt, info, ikm, output_attributes))
+    }
+}
+
+#[async_trait]
+impl AsymmetricVault for NodeVault {
+    async fn ec_diffie_hellman(
+        &self,
+        secret: &KeyId,
+        peer_public_key: &PublicKey,
+    ) -> Result<KeyId> {
+        delegate!(self.ec_diffie_hellman(secret, peer_public_key))
+    }
+
+    async fn compute_key_id_for_public_key(&self, public_key: &PublicKey) -> Result<KeyId> {
+        delegate!(self.compute_key_id_for_public_key(public_key))
+    }
+}
+
+#[async_trait]
+impl SymmetricVault for NodeVault {
+    async fn aead_aes_gcm_encrypt(
+        &self,
+        key_id: &KeyId,
+        plaintext: &[u8],
+        nonce: &[u8],
+        aad: &[u8],
+    ) -> Result<Buffer<u8>> {
+        delegate!(self.aead_aes_gcm_encrypt(key_id, plaintext, nonce, aad))
+    }
+
+    async fn aead_aes_gcm_decrypt(
+        &self,
+        key_id: &KeyId,
+        cipher_text: &[u8],
+        nonce: &[u8],
+        aad: &[u8],
+    ) -> Result<Buffer<u8>> {
+        delegate!(self.aead_aes_gcm_decrypt(key_id, cipher_text, nonce, aad))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn hsm_config_is_told_apart_from_vault_storage() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("vault.json");
+
+        let hsm = HsmConfig::new("/usr/lib/softhsm/libsofthsm2.so".into(), "ockam".into());
+        hsm.write(&path).unwrap();
+        let read = HsmConfig::read(&path).unwrap();
+        assert_eq!(read.module, hsm.module);
+        assert_eq!(read.token, hsm.token);
+
+        std::fs::write(&path, br#"{"entries":{},"next_id":0}"#).unwrap();
+        assert!(HsmConfig::read(&path).is_none());
+    }
+}
@@ -0,0 +1,221 @@
+//! Offline verification of JSON Web Tokens against a JSON Web Key Set.
+
+use crate::error::ApiError;
+use core::time::Duration;
+use ockam_core::Result;
+use ring::signature::{self, RsaPublicKeyComponents, UnparsedPublicKey};
+use serde::Deserialize;
+use serde_json::{Map, Value};
+use std::path::Path;
+use std::time::SystemTime;
+
+/// A JSON Web Key Set (RFC 7517).
+#[derive(Debug, Clone, Deserialize)]
+pub struct Jwks {
+    keys: Vec<Jwk>,
+}
+
+/// A single public key of a [`Jwks`].
+///
+/// RSA (`RS256`), P-256 (`ES256`) and Ed25519 (`EdDSA`) keys are supported.
+#[derive(Debug, Clone, Deserialize)]
+pub struct Jwk {
+    kty: String,
+    kid: Option<String>,
+    alg: Option<String>,
+    crv: Option<String>,
+    n: Option<String>,
+    e: Option<String>,
+    x: Option<String>,
+    y: Option<String>,
+}
+
+impl Jwks {
+    pub fn from_json(json: &str) -> Result<Self> {
+        serde_json::from_str(json).map_err(|e| ApiError::generic(&format!("invalid JWKS: {e}")))
+    }
+
+    pub fn from_file<P: AsRef<Path>>(path: P) -> Result<Self> {
+        let json = std::fs::read_to_string(path.as_ref())
+            .map_err(|e| ApiError::generic(&format!("failed to read {:?}: {e}", path.as_ref())))?;
+        Self::from_json(&json)
+    }
+
+    pub fn keys(&self) -> &[Jwk] {
+        &self.keys
+    }
+
+    /// Find a key which may verify tokens signed with `alg` and, if given, has the key id `kid`.
+    fn find(&self, kid: Option<&str>, alg: &str) -> Option<&Jwk> {
+        self.keys.iter().find(|k| {
+            k.kty == kty(alg)
+                && k.alg.as_deref().map(|a| a == alg).unwrap_or(true)
+                && (kid.is_none() || k.kid.as_deref() == kid)
+        })
+    }
+}
+
+impl Jwk {
+    pub fn kid(&self) -> Option<&str> {
+        self.kid.as_deref()
+    }
+
+    fn verify(&self, alg: &str, msg: &[u8], sig: &[u8]) -> Result<()> {
+        let ok = match alg {
+            "RS256" => {
+                let n = decode(
+                    self.n
+                        .as_deref()
+                        .ok_or_else(|| invalid("RSA key without `n`"))?,
+                )?;
+                let e = decode(
+                    self.e
+                        .as_deref()
+                        .ok_or_else(|| invalid("RSA key without `e`"))?,
+                )?;
+                RsaPublicKeyComponents { n, e }
+                    .verify(&signature::RSA_PKCS1_2048_8192_SHA256, msg, sig)
+                    .is_ok()
+            }
+            "ES256" => {
+                if self.crv.as_deref() != Some("P-256") {
+                    return Err(invalid("ES256 requires a P-256 key"));
+                }
+                let x = decode(
+                    self.x
+                        .as_deref()
+                        .ok_or_else(|| invalid("EC key without `x`"))?,
+                )?;
+                let y = decode(
+                    self.y
+                        .as_deref()
+                        .ok_or_else(|| invalid("EC key without `y`"))?,
+                )?;
+                let mut point = vec![0x04];
+                point.extend_from_slice(&x);
+                point.extend_from_slice(&y);
+                UnparsedPublicKey::new(&signature::ECDSA_P256_SHA256_FIXED, point)
+                    .verify(msg, sig)
+                    .is_ok()
+            }
+            "EdDSA" => {
+                if self.crv.as_deref() != Some("Ed25519") {
+                    return Err(invalid("EdDSA requires an Ed25519 key"));
+                }
+                let x = decode(
+                    self.x
+                        .as_deref()
+                        .ok_or_else(|| invalid("OKP key without `x`"))?,
+                )?;
+                UnparsedPublicKey::new(&signature::ED25519, x)
+                    .verify(msg, sig)
+                    .is_ok()
+            }
+            other => return Err(invalid(&format!("unsupported algorithm {other}"))),
+        };
+        if ok {
+            Ok(())
+        } else {
+            Err(invalid("invalid signature"))
+        }
+    }
+}
+
+/// Validates tokens of a single issuer for a single audience.
+#[derive(Debug, Clone)]
+pub struct JwtValidator {
+    jwks: Jwks,
+    issuer: String,
+    audience: String,
+    leeway: Duration,
+}
+
+impl JwtValidator {
+    pub fn new(jwks: Jwks, issuer: impl Into<String>, audience: impl Into<String>) -> Self {
+        JwtValidator {
+            jwks,
+            issuer: issuer.into(),
+            audience: audience.into(),
+            leeway: Duration::from_secs(60),
+        }
+    }
+
+    /// Tolerated clock difference when checking `exp` and `nbf` (default: 60s).
+    pub fn with_leeway(mut self, leeway: Duration) -> Self {
+        self.leeway = leeway;
+        self
+    }
+
+    /// Verify the token's signature and registered claims and return all its claims.
+    pub fn validate(&self, token: &str) -> Result<Map<String, Value>> {
+        let mut parts = token.split('.');
+        let (header, payload, sig) = match (parts.next(), parts.next(), parts.next(), parts.next())
+        {
+            (Some(h), Some(p), Some(s), None) => (h, p, s),
+            _ => return Err(invalid("malformed token")),
+        };
+        let signed = &token[..header.len() + 1 + payload.len()];
+
+        let header: Map<String, Value> = parse(&decode(header)?)?;
+        let alg = header
+            .get("alg")
+            .and_then(Value::as_str)
+            .ok_or_else(|| invalid("missing `alg`"))?;
+        let kid = header.get("kid").and_then(Value::as_str);
+        let key = self
+            .jwks
+            .find(kid, alg)
+            .ok_or_else(|| invalid("no matching key"))?;
+        key.verify(alg, signed.as_bytes(), &decode(sig)?)?;
+
+        let claims: Map<String, Value> = parse(&decode(payload)?)?;
+        if claims.get("iss").and_then(Value::as_str) != Some(&self.issuer) {
+            return Err(invalid("unexpected issuer"));
+        }
+        let aud_ok = match claims.get("aud") {
+            Some(Value::String(a)) => a == &self.audience,
+            Some(Value::Array(a)) => a.iter().any(|a| a.as_str() == Some(&self.audience)),
+            _ => false,
+        };
+        if !aud_ok {
+            return Err(invalid("unexpected audience"));
+        }
+        let now = SystemTime::now()
+            .duration_since(SystemTime::UNIX_EPOCH)
+            .map_err(|_| ApiError::generic("invalid system time"))?
+            .as_secs();
+        let leeway = self.leeway.as_secs();
+        match claims.get("exp").and_then(Value::as_u64) {
+            Some(exp) if now <= exp.saturating_add(leeway) => {}
+            Some(_) => return Err(invalid("token expired")),
+            None => return Err(invalid("missing `exp`")),
+        }
+        if let Some(nbf) = claims.get("nbf").and_then(Value::as_u64) {
+            if now.saturating_add(leeway) < nbf {
+                return Err(invalid("token not yet valid"));
+            }
+        }
+        Ok(claims)
+    }
+}
+
+fn kty(alg: &str) -> &'static str {
+    match alg {
+        "RS256" => "RSA",
+        "ES256" => "EC",
+        "EdDSA" => "OKP",
+        _ => "",
+    }
+}
+
+fn decode(s: &str) -> Result<Vec<u8>> {
+    base64::decode_config(s, base64::URL_SAFE_NO_PAD).map_err(|_| invalid("invalid base64"))
+}
+
+fn parse(data: &[u8]) -> Result<Map<String, Value>> {
+    serde_json::from_slice(data).map_err(|_| invalid("invalid JSON"))
+}
+
+fn invalid(msg: &str) -> ockam_core::Error {
+    ApiError::generic(&format!("invalid JWT: {msg}"))
+}
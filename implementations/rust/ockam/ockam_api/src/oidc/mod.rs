@@ -0,0 +1,229 @@
+//! An authenticator which trusts JWTs of an OpenID Connect provider.
+//!
+//! Tokens are verified offline against the provider's JSON Web Key Set and
+//! their claims are mapped to credential attributes.
+
+pub mod jwt;
+pub mod types;
+
+use crate::authenticator::{
+    project_member_schema, put_member, CREDENTIAL_TTL, PROJECT_ID, PROJECT_MEMBER_SCHEMA,
+};
+use core::str;
+use minicbor::{Decode, Decoder, Encode};
+use ockam_core::api::{self, Method, Request, Response};
+use ockam_core::{self, Result, Routed, Worker};
+use ockam_identity::authenticated_storage::AuthenticatedStorage;
+use ockam_identity::credential::Credential;
+use ockam_identity::{Identity, IdentityIdentifier, IdentitySecureChannelLocalInfo, IdentityVault};
+use ockam_node::Context;
+use serde::{Deserialize, Serialize};
+use serde_json::{Map, Value};
+use std::collections::HashMap;
+use tracing::{trace, warn};
+
+use self::jwt::JwtValidator;
+use self::types::AuthenticateOidcToken;
+
+/// Decode the body of a request, answering with a bad request if it is malformed.
+macro_rules! decode_body {
+    ($req:expr, $dec:expr) => {
+        match $dec.decode() {
+            Ok(body) => body,
+            Err(e) => {
+                let msg = format!("invalid request body: {}", e);
+                return Ok(api::bad_request($req, &msg).to_vec()?);
+            }
+        }
+    };
+}
+
+/// Maps a token claim to a credential attribute.
+#[derive(Debug, Clone, Encode, Decode, Serialize, Deserialize, PartialEq, Eq)]
+#[rustfmt::skip]
+#[cbor(map)]
+pub struct ClaimMapping {
+    /// Name of the claim. Nested claims are separated by dots, e.g. `realm.roles`.
+    #[n(1)] pub claim: String,
+    /// Name of the attribute the claim value is stored under.
+    #[n(2)] pub attribute: String,
+    /// Reject tokens without this claim.
+    #[serde(default)]
+    #[n(3)] pub required: bool,
+}
+
+impl ClaimMapping {
+    pub fn new(claim: impl Into<String>, attribute: impl Into<String>) -> Self {
+        ClaimMapping {
+            claim: claim.into(),
+            attribute: attribute.into(),
+            required: false,
+        }
+    }
+
+    pub fn required(mut self) -> Self {
+        self.required = true;
+        self
+    }
+
+    /// Look up the claim in the given token claims.
+    ///
+    /// Strings are taken verbatim, numbers and booleans are formatted and
+    /// arrays of those are joined with commas.
+    fn extract(&self, claims: &Map<String, Value>) -> Option<String> {
+        let mut path = self.claim.split('.');
+        let first = claims.get(path.next()?)?;
+        let value = path.try_fold(first, |v, k| v.get(k))?;
+        match value {
+            Value::Array(vs) => {
+                let vs: Option<Vec<String>> = vs.iter().map(scalar).collect();
+                vs.map(|vs| vs.join(","))
+            }
+            v => scalar(v),
+        }
+    }
+}
+
+fn scalar(v: &Value) -> Option<String> {
+    match v {
+        Value::String(s) => Some(s.clone()),
+        Value::Bool(b) => Some(b.to_string()),
+        Value::Number(n) => Some(n.to_string()),
+        _ => None,
+    }
+}
+
+pub struct Server<S, V: IdentityVault> {
+    project: Vec<u8>,
+    store: S,
+    ident: Identity<V>,
+    validator: JwtValidator,
+    mappings: Vec<ClaimMapping>,
+}
+
+#[ockam_core::worker]
+impl<S, V> Worker for Server<S, V>
+where
+    S: AuthenticatedStorage,
+    V: IdentityVault,
+{
+    type Context = Context;
+    type Message = Vec<u8>;
+
+    async fn initialize(&mut self, _: &mut Context) -> Result<()> {
+        self.ident.register_schema(project_member_schema()).await;
+        Ok(())
+    }
+
+    async fn handle_message(&mut self, c: &mut Context, m: Routed<Self::Message>) -> Result<()> {
+        if let Ok(i) = IdentitySecureChannelLocalInfo::find_info(m.local_message()) {
+            let r = self.on_request(i.their_identity_id(), m.as_body()).await?;
+            c.send(m.return_route(), r).await
+        } else {
+            let mut dec = Decoder::new(m.as_body());
+            let req: Request = dec.decode()?;
+            let res = api::forbidden(&req, "secure channel required").to_vec()?;
+            c.send(m.return_route(), res).await
+        }
+    }
+}
+
+impl<S, V> Server<S, V>
+where
+    S: AuthenticatedStorage,
+    V: IdentityVault,
+{
+    pub fn new(
+        project: Vec<u8>,
+        store: S,
+        identity: Identity<V>,
+        validator: JwtValidator,
+        mappings: Vec<ClaimMapping>,
+    ) -> Self {
+        Server {
+            project,
+            store,
+            ident: identity,
+            validator,
+            mappings,
+        }
+    }
+
+    async fn on_request(&mut self, from: &IdentityIdentifier, data: &[u8]) -> Result<Vec<u8>> {
+        let mut dec = Decoder::new(data);
+        let req: Request = dec.decode()?;
+
+        trace! {
+            target: "ockam_api::oidc::server",
+            from   = %from,
+            id     = %req.id(),
+            method = ?req.method(),
+            path   = %req.path(),
+            body   = %req.has_body(),
+            "request"
+        }
+
+        let res = match req.method() {
+            Some(Method::Post) => match req.path_segments::<2>().as_slice() {
+                // Add a member with the token's attributes, as the direct authenticator does.
+                ["enroll"] => {
+                    let tkn: AuthenticateOidcToken = decode_body!(&req, dec);
+                    match self.attributes(tkn.token()) {
+                        Ok(attrs) => {
+                            put_member(&self.store, from, minicbor::to_vec(attrs)?).await?;
+                            Response::ok(req.id()).to_vec()?
+                        }
+                        Err(error) => api::forbidden(&req, &error.to_string()).to_vec()?,
+                    }
+                }
+                // Exchange the token for a credential.
+                ["credential"] => {
+                    let tkn: AuthenticateOidcToken = decode_body!(&req, dec);
+                    let attrs = match self.attributes(tkn.token()) {
+                        Ok(attrs) => attrs,
+                        Err(error) => return Ok(api::forbidden(&req, &error.to_string()).to_vec()?),
+                    };
+                    let crd = attrs
+                        .iter()
+                        .fold(
+                            Credential::builder(from.clone())
+                                .with_schema(PROJECT_MEMBER_SCHEMA)
+                                .valid_for(CREDENTIAL_TTL),
+                            |crd, (a, v)| crd.with_attribute(a, v.as_bytes()),
+                        )
+                        .with_attribute(PROJECT_ID, &self.project);
+                    if let Err(error) = self.ident.validate_credential(&crd).await {
+                        return Ok(api::bad_request(&req, &error.to_string()).to_vec()?);
+                    }
+                    let crd = self.ident.issue_credential(crd).await?;
+                    Response::ok(req.id()).body(crd).to_vec()?
+                }
+                _ => api::unknown_path(&req).to_vec()?,
+            },
+            _ => api::invalid_method(&req).to_vec()?,
+        };
+        Ok(res)
+    }
+
+    /// Validate the token and map its claims to attributes.
+    fn attributes(&self, token: &str) -> Result<HashMap<String, String>> {
+        let claims = self.validator.validate(token).map_err(|e| {
+            warn!(target: "ockam_api::oidc::server", error = %e, "token rejected");
+            e
+        })?;
+        let mut attrs = HashMap::new();
+        for m in &self.mappings {
+            match m.extract(&claims) {
+                Some(v) => {
+                    attrs.insert(m.attribute.clone(), v);
+                }
+                None if m.required => {
+                    let msg = format!("missing claim `{}`", m.claim);
+                    return Err(crate::error::ApiError::generic(&msg));
+                }
+                None => {}
+            }
+        }
+        Ok(attrs)
+    }
+}
@@ -0,0 +1,29 @@
+use minicbor::{Decode, Encode};
+use ockam_core::CowStr;
+
+#[cfg(feature = "tag")]
+use ockam_core::TypeTag;
+
+/// A JWT issued by an OpenID Connect provider, e.g. an ID token.
+#[derive(Debug, Decode, Encode)]
+#[rustfmt::skip]
+#[cbor(map)]
+pub struct AuthenticateOidcToken<'a> {
+    #[cfg(feature = "tag")]
+    #[n(0)] tag: TypeTag<5523619>,
+    #[b(1)] token: CowStr<'a>,
+}
+
+impl<'a> AuthenticateOidcToken<'a> {
+    pub fn new<S: Into<CowStr<'a>>>(token: S) -> Self {
+        AuthenticateOidcToken {
+            #[cfg(feature = "tag")]
+            tag: TypeTag,
+            token: token.into(),
+        }
+    }
+
+    pub fn token(&self) -> &str {
+        &self.token
+    }
+}
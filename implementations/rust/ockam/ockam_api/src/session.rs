@@ -12,14 +12,20 @@ use ockam_node::tokio::sync::mpsc;
 use ockam_node::tokio::task::JoinSet;
 use ockam_node::tokio::time::{timeout, Duration};
 use ockam_node::Context;
-use sessions::{Key, Ping, Status};
+use sessions::Ping;
 use tracing as log;
 
-pub use sessions::{Data, Replacer, Session, Sessions};
+pub use sessions::{Data, Key, Replacer, Session, Sessions, Status};
 
 const MAX_FAILURES: usize = 3;
 const DELAY: Duration = Duration::from_secs(3);
 
+/// Session data holding the address a dependent session is routed to.
+///
+/// When the parent session is replaced, the parent's old address in this
+/// value is substituted with the new one before the dependent is replaced.
+pub const TARGET: &str = "target";
+
 #[derive(Debug)]
 pub struct Medic {
     delay: Duration,
@@ -67,7 +73,12 @@ impl Medic {
             log::debug!("check sessions");
             {
                 let mut sessions = self.sessions.lock().unwrap();
+                let mut down = Vec::new();
                 for (&key, session) in sessions.iter_mut() {
+                    if session.parent().is_some() {
+                        // Dependent sessions follow the health of their parent.
+                        continue;
+                    }
                     if session.pings().len() < MAX_FAILURES {
                         let m = Message::new(session.key());
                         session.add_ping(m.ping);
@@ -107,6 +118,7 @@ impl Medic {
                                 session.set_status(Status::Down);
                                 log::info!(%key, "replacing session");
                                 self.replacements.spawn(async move { (key, f.await) });
+                                down.push(key);
                             }
                             Status::Down => {
                                 log::warn!(%key, "session is down");
@@ -114,6 +126,14 @@ impl Medic {
                         }
                     }
                 }
+                while let Some(k) = down.pop() {
+                    for d in sessions.dependents(&k) {
+                        if let Some(s) = sessions.session_mut(&d) {
+                            s.set_status(Status::Down);
+                            down.push(d)
+                        }
+                    }
+                }
             }
 
             let _ = timeout(self.delay, self.get_results(&mut rx)).await;
@@ -145,9 +165,28 @@ impl Medic {
                         let mut sessions = self.sessions.lock().unwrap();
                         if let Some(s) = sessions.session_mut(&k) {
                             log::info!(key = %k, addr = %a, "replacement is up");
+                            let prev = s.ping_address().clone();
                             s.set_status(Status::Up);
-                            s.set_ping_address(a);
+                            s.set_ping_address(a.clone());
                             s.clear_pings();
+                            // Sessions routed through this one are rebuilt
+                            // on top of the replacement, in order.
+                            for d in sessions.dependents(&k) {
+                                if let Some(s) = sessions.session_mut(&d) {
+                                    if let Some(t) = s.data().get::<MultiAddr>(TARGET) {
+                                        if let Some(rest) = util::strip_prefix(&t, &prev) {
+                                            match a.clone().try_with(&rest) {
+                                                Ok(t) => s.data().put(TARGET, t),
+                                                Err(e) => log::error!(key = %d, err = %e, "invalid target address"),
+                                            }
+                                        }
+                                    }
+                                    s.set_status(Status::Down);
+                                    let f = s.replacement(s.ping_address().clone());
+                                    log::info!(key = %d, parent = %k, "replacing dependent session");
+                                    self.replacements.spawn(async move { (d, f.await) });
+                                }
+                            }
                         }
                     }
                 },
@@ -216,3 +255,98 @@ impl Worker for Collector {
         Ok(())
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use ockam_core::AsyncTryClone;
+
+    /// A replacer that records which session it replaced, and the target the
+    /// session had when it was replaced
+    fn recorder(
+        name: &'static str,
+        data: Data,
+        log: Arc<Mutex<Vec<(&'static str, MultiAddr)>>>,
+        new: MultiAddr,
+    ) -> Replacer {
+        Box::new(move |prev| {
+            let target = data.get::<MultiAddr>(TARGET).unwrap_or(prev);
+            log.lock().unwrap().push((name, target));
+            let new = new.clone();
+            Box::pin(async move { Ok(new) })
+        })
+    }
+
+    #[ockam_macros::test]
+    async fn dependents_are_replaced_after_their_parent(ctx: &mut Context) -> Result<(), Error> {
+        let medic = Medic {
+            delay: Duration::from_millis(100),
+            ..Medic::new()
+        };
+        let sessions = medic.sessions();
+        let log = Arc::new(Mutex::new(Vec::new()));
+
+        // Nothing answers the pings sent to the parent, so it goes down
+        let old: MultiAddr = "/service/old".parse()?;
+        let new: MultiAddr = "/service/new".parse()?;
+        let outlet: MultiAddr = "/service/outlet".parse()?;
+        let mut parent = Session::new(old.clone());
+        parent.set_replacer(recorder("parent", parent.data(), log.clone(), new.clone()));
+        let mut dependent = Session::new(old.clone().try_with(&outlet)?);
+        dependent.data().put(TARGET, old.clone().try_with(&outlet)?);
+        dependent.set_parent(parent.key());
+        dependent.set_replacer(recorder(
+            "dependent",
+            dependent.data(),
+            log.clone(),
+            new.clone(),
+        ));
+        let (p, d) = {
+            let mut sessions = sessions.lock().unwrap();
+            (sessions.add(parent), sessions.add(dependent))
+        };
+
+        tokio::spawn(medic.start(ctx.async_try_clone().await?));
+        timeout(Duration::from_secs(5), async {
+            while log.lock().unwrap().len() < 2 {
+                tokio::time::sleep(Duration::from_millis(50)).await
+            }
+        })
+        .await
+        .expect("sessions are replaced");
+
+        // The dependent is rebuilt on top of the replacement of its parent
+        assert_eq!(
+            *log.lock().unwrap(),
+            [
+                ("parent", old),
+                ("dependent", new.clone().try_with(&outlet)?)
+            ]
+        );
+        {
+            let sessions = sessions.lock().unwrap();
+            assert_eq!(sessions.session(&p).unwrap().status(), Status::Up);
+            assert_eq!(sessions.session(&d).unwrap().status(), Status::Up);
+        }
+
+        ctx.stop().await
+    }
+
+    #[test]
+    fn removing_a_parent_removes_its_dependents() {
+        let addr: MultiAddr = "/service/a".parse().unwrap();
+        let mut sessions = Sessions::new();
+        let p = sessions.add(Session::new(addr.clone()));
+        let mut child = Session::new(addr.clone());
+        child.set_parent(p);
+        let c = sessions.add(child);
+        let mut grandchild = Session::new(addr.clone());
+        grandchild.set_parent(c);
+        sessions.add(grandchild);
+        let other = sessions.add(Session::new(addr));
+
+        assert!(sessions.remove(&p).is_some());
+        let left: Vec<_> = sessions.iter().map(|(k, _)| *k).collect();
+        assert_eq!(left, [other]);
+    }
+}
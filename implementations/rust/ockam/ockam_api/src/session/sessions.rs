@@ -26,6 +26,7 @@ pub struct Session {
     status: Status,
     replace: Replacer,
     pings: Vec<Ping>,
+    parent: Option<Key>,
 }
 
 #[derive(Debug, Clone)]
@@ -44,6 +45,7 @@ impl fmt::Debug for Session {
             .field("addr", &self.addr)
             .field("status", &self.status)
             .field("pings", &self.pings)
+            .field("parent", &self.parent)
             .finish()
     }
 }
@@ -67,7 +69,24 @@ impl Sessions {
         k
     }
 
-    #[allow(unused)]
+    /// Remove session `k`, along with the sessions that depend on it.
+    ///
+    /// Dependent sessions are routed through their parent, so they can not
+    /// be recovered once it is gone.
+    pub fn remove(&mut self, k: &Key) -> Option<Session> {
+        let s = self.map.remove(k)?;
+        for d in self.dependents(k) {
+            log::debug! {
+                target: "ockam_api::session",
+                key    = %d,
+                parent = %k,
+                "removing dependent session"
+            }
+            self.remove(&d);
+        }
+        Some(s)
+    }
+
     pub fn session(&self, k: &Key) -> Option<&Session> {
         self.map.get(k)
     }
@@ -76,7 +95,6 @@ impl Sessions {
         self.map.get_mut(k)
     }
 
-    #[allow(unused)]
     pub fn iter(&self) -> impl Iterator<Item = (&Key, &Session)> + '_ {
         self.map.iter()
     }
@@ -84,6 +102,15 @@ impl Sessions {
     pub fn iter_mut(&mut self) -> impl Iterator<Item = (&Key, &mut Session)> + '_ {
         self.map.iter_mut()
     }
+
+    /// The sessions which depend on session `k`.
+    pub fn dependents(&self, k: &Key) -> Vec<Key> {
+        self.map
+            .iter()
+            .filter(|(_, s)| s.parent.as_ref() == Some(k))
+            .map(|(k, _)| *k)
+            .collect()
+    }
 }
 
 impl Session {
@@ -95,6 +122,7 @@ impl Session {
             status: Status::Up,
             replace: Box::new(move |r| Box::pin(async move { Ok(r) })),
             pings: Vec::new(),
+            parent: None,
         }
     }
 
@@ -141,6 +169,28 @@ impl Session {
     pub fn clear_pings(&mut self) {
         self.pings.clear()
     }
+
+    /// The session this session is routed through, if any.
+    ///
+    /// A dependent session is not pinged. It is replaced after its parent
+    /// has been replaced, once its `TARGET` data has been updated to go
+    /// through the new parent address.
+    pub fn parent(&self) -> Option<Key> {
+        self.parent
+    }
+
+    pub fn set_parent(&mut self, k: Key) {
+        self.parent = Some(k)
+    }
+}
+
+impl fmt::Display for Status {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Status::Up => f.write_str("up"),
+            Status::Down => f.write_str("down"),
+        }
+    }
 }
 
 impl Data {
@@ -13,3 +13,30 @@ pub(crate) fn starts_with_host_tcp_secure(addr: &MultiAddr) -> Option<usize> {
         None
     }
 }
+
+/// If `addr` begins with all the protocols of `prefix`, return the remaining ones.
+pub(crate) fn strip_prefix(addr: &MultiAddr, prefix: &MultiAddr) -> Option<MultiAddr> {
+    let n = prefix.iter().count();
+    if n == 0 || n > addr.iter().count() {
+        return None;
+    }
+    addr.iter()
+        .zip(prefix.iter())
+        .all(|(a, p)| a.code() == p.code() && a.data() == p.data())
+        .then(|| addr.split(n).1)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use core::str::FromStr;
+
+    #[test]
+    fn strip_prefix_matches_whole_protocols() {
+        let addr = MultiAddr::from_str("/service/abc/service/outlet").unwrap();
+        let rest = strip_prefix(&addr, &MultiAddr::from_str("/service/abc").unwrap());
+        assert_eq!(rest.unwrap().to_string(), "/service/outlet");
+        assert!(strip_prefix(&addr, &MultiAddr::from_str("/service/ab").unwrap()).is_none());
+        assert!(strip_prefix(&addr, &MultiAddr::default()).is_none());
+    }
+}
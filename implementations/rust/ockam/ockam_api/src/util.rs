@@ -1,82 +1,91 @@
-use crate::config::lookup::{ConfigLookup, InternetAddress, LookupMeta};
+use crate::config::lookup::{ConfigLookup, LookupMeta};
 use crate::error::ApiError;
 use anyhow::anyhow;
+use core::iter::Peekable;
 use core::str::FromStr;
 use ockam::{Address, Error, TCP};
 use ockam_core::{Route, LOCAL};
-use ockam_multiaddr::proto::{DnsAddr, Ip4, Ip6, Node, Project, Secure, Service, Space, Tcp};
-use ockam_multiaddr::{MultiAddr, Protocol};
+use ockam_multiaddr::proto::{
+    Alias, Ble, DnsAddr, Ip4, Ip6, Node, Project, Secure, Service, Space, Tcp, Udp, Unix, Ws, Wss,
+};
+use ockam_multiaddr::{MultiAddr, ProtoIter, Protocol};
+use ockam_transport_udp::UDP;
+use ockam_transport_websocket::WS;
 use std::net::{SocketAddrV4, SocketAddrV6};
 
 /// Go through a multiaddr and remove all instances of
-/// `/node/<whatever>` out of it and replaces it with a fully
-/// qualified address to the target
+/// `/alias/<whatever>` and `/node/<whatever>` out of it and replaces
+/// them with a fully qualified address to the target
 pub fn clean_multiaddr(
     input: &MultiAddr,
     lookup: &ConfigLookup,
 ) -> Option<(MultiAddr, LookupMeta)> {
-    let mut new_ma = MultiAddr::default();
+    let new_ma = lookup.rewriter().rewrite(input).ok()?;
     let mut lookup_meta = LookupMeta::default();
 
-    let it = input.iter().peekable();
-    for p in it {
+    for p in new_ma.iter() {
         match p.code() {
             Node::CODE => {
                 let alias = p.cast::<Node>()?;
-                let addr = lookup
-                    .get_node(&alias)
-                    .expect("provided invalid substitution route");
-
-                match addr {
-                    InternetAddress::Dns(dns, _) => new_ma.push_back(DnsAddr::new(dns)).ok()?,
-                    InternetAddress::V4(v4) => new_ma.push_back(Ip4(*v4.ip())).ok()?,
-                    InternetAddress::V6(v6) => new_ma.push_back(Ip6(*v6.ip())).ok()?,
-                }
-
-                new_ma.push_back(Tcp(addr.port())).ok()?;
+                error!(target: "ockam_api", node = &*alias, "unknown node");
+                return None;
+            }
+            Alias::CODE => {
+                let alias = p.cast::<Alias>()?;
+                error!(target: "ockam_api", alias = &*alias, "unknown alias");
+                return None;
             }
             Project::CODE => {
                 // Parse project name from the MultiAddr.
                 let alias = p.cast::<Project>()?;
                 // Store it in the lookup meta, so we can later
                 // retrieve it from either the config or the cloud.
-                lookup_meta.project.push_back(alias.to_string());
                 // No substitution done here. It will be done later by `clean_projects_multiaddr`.
-                new_ma.push_back_value(&p).ok()?
+                lookup_meta.project.push_back(alias.to_string());
             }
             Space::CODE => panic!("/space/ substitutions are not supported yet!"),
-            _ => new_ma.push_back_value(&p).ok()?,
+            _ => {}
         }
     }
 
     Some((new_ma, lookup_meta))
 }
 
+/// The prefix of the first protocol in `ma` which nodes have no transport for.
+///
+/// These protocols can be written in a multi-address, but not routed over.
+fn missing_transport(ma: &MultiAddr) -> Option<&'static str> {
+    ma.iter().find_map(|p| match p.code() {
+        Wss::CODE => Some(Wss::PREFIX),
+        Unix::CODE => Some(Unix::PREFIX),
+        Ble::CODE => Some(Ble::PREFIX),
+        _ => None,
+    })
+}
+
 /// Try to convert a multi-address to an Ockam route.
 pub fn multiaddr_to_route(ma: &MultiAddr) -> Option<Route> {
+    if let Some(proto) = missing_transport(ma) {
+        error!(target: "ockam_api", addr = %ma, "no transport for /{proto}");
+        return None;
+    }
     let mut rb = Route::new();
     let mut it = ma.iter().peekable();
     while let Some(p) = it.next() {
         match p.code() {
             Ip4::CODE => {
                 let ip4 = p.cast::<Ip4>()?;
-                let tcp = it.next()?.cast::<Tcp>()?;
-                let add = Address::new(TCP, SocketAddrV4::new(*ip4, *tcp).to_string());
-                rb = rb.append(add)
+                rb = rb.append(host_to_addr(&ip4.to_string(), &mut it)?)
             }
             Ip6::CODE => {
                 let ip6 = p.cast::<Ip6>()?;
-                let tcp = it.next()?.cast::<Tcp>()?;
-                let add = Address::new(TCP, SocketAddrV6::new(*ip6, *tcp, 0, 0).to_string());
-                rb = rb.append(add)
+                rb = rb.append(host_to_addr(&format!("[{}]", *ip6), &mut it)?)
             }
             DnsAddr::CODE => {
                 let host = p.cast::<DnsAddr>()?;
                 if let Some(p) = it.peek() {
-                    if p.code() == Tcp::CODE {
-                        let tcp = p.cast::<Tcp>()?;
-                        rb = rb.append(Address::new(TCP, format!("{}:{}", &*host, *tcp)));
-                        let _ = it.next();
+                    if p.code() == Tcp::CODE || p.code() == Udp::CODE {
+                        rb = rb.append(host_to_addr(&host, &mut it)?);
                         continue;
                     }
                 }
@@ -105,7 +114,47 @@ pub fn multiaddr_to_route(ma: &MultiAddr) -> Option<Route> {
     Some(rb.into())
 }
 
+/// Convert a host followed by its transport protocols to an Ockam Address.
+///
+/// The host is followed by either a UDP port, or by a TCP port and then
+/// optionally by a WebSocket path, e.g. `/ip4/10.0.0.1/tcp/80/ws/%2F`.
+fn host_to_addr(host: &str, it: &mut Peekable<ProtoIter>) -> Option<Address> {
+    let p = it.next()?;
+    match p.code() {
+        Udp::CODE => {
+            let udp = p.cast::<Udp>()?;
+            Some(Address::new(UDP, format!("{host}:{}", *udp)))
+        }
+        Tcp::CODE => {
+            let tcp = p.cast::<Tcp>()?;
+            let addr = format!("{host}:{}", *tcp);
+            match it.peek().map(|p| p.code()) {
+                Some(Ws::CODE) => {
+                    let p = it.next()?;
+                    let path = p.cast::<Ws>()?;
+                    // The WebSocket transport always connects to the root path.
+                    if !path.is_empty() && &*path != "/" {
+                        error!(target: "ockam_api", path = &*path, "unsupported websocket path");
+                        return None;
+                    }
+                    Some(Address::new(WS, addr))
+                }
+                _ => Some(Address::new(TCP, addr)),
+            }
+        }
+        other => {
+            error!(target: "ockam_api", code = %other, "expected a tcp or udp port");
+            None
+        }
+    }
+}
+
 pub fn try_multiaddr_to_route(ma: &MultiAddr) -> Result<Route, Error> {
+    if let Some(proto) = missing_transport(ma) {
+        return Err(ApiError::message(format!(
+            "no transport for /{proto} in {ma}"
+        )));
+    }
     multiaddr_to_route(ma)
         .ok_or_else(|| ApiError::message(format!("could not convert {ma} to route")))
 }
@@ -115,15 +164,17 @@ pub fn multiaddr_to_addr(ma: &MultiAddr) -> Option<Address> {
     let mut it = ma.iter().peekable();
     let p = it.next()?;
     match p.code() {
+        Ip4::CODE => {
+            let ip4 = p.cast::<Ip4>()?;
+            host_to_addr(&ip4.to_string(), &mut it)
+        }
+        Ip6::CODE => {
+            let ip6 = p.cast::<Ip6>()?;
+            host_to_addr(&format!("[{}]", *ip6), &mut it)
+        }
         DnsAddr::CODE => {
             let host = p.cast::<DnsAddr>()?;
-            if let Some(p) = it.peek() {
-                if p.code() == Tcp::CODE {
-                    let tcp = p.cast::<Tcp>()?;
-                    return Some(Address::new(TCP, format!("{}:{}", &*host, *tcp)));
-                }
-            }
-            None
+            host_to_addr(&host, &mut it)
         }
         Service::CODE => {
             let local = p.cast::<Service>()?;
@@ -152,20 +203,21 @@ pub fn try_address_to_multiaddr(a: &Address) -> Result<MultiAddr, Error> {
     let mut ma = MultiAddr::default();
     match a.transport_type() {
         TCP => {
-            if let Ok(sa) = SocketAddrV4::from_str(a.address()) {
-                ma.push_back(Ip4::new(*sa.ip()))?;
-                ma.push_back(Tcp::new(sa.port()))?
-            } else if let Ok(sa) = SocketAddrV6::from_str(a.address()) {
-                ma.push_back(Ip6::new(*sa.ip()))?;
-                ma.push_back(Tcp::new(sa.port()))?
-            } else if let Some((host, port)) = a.address().split_once(':') {
-                ma.push_back(DnsAddr::new(host))?;
-                let n = u16::from_str(port).map_err(ApiError::wrap)?;
-                ma.push_back(Tcp::new(n))?
-            } else {
-                ma.push_back(DnsAddr::new(a.address()))?
+            if let Some(port) = push_host(&mut ma, a.address())? {
+                ma.push_back(Tcp::new(port))?
             }
         }
+        UDP => {
+            let port = push_host(&mut ma, a.address())?
+                .ok_or_else(|| ApiError::message(format!("missing udp port: {a}")))?;
+            ma.push_back(Udp::new(port))?
+        }
+        WS => {
+            let port = push_host(&mut ma, a.address())?
+                .ok_or_else(|| ApiError::message(format!("missing websocket port: {a}")))?;
+            ma.push_back(Tcp::new(port))?;
+            ma.push_back(Ws::new("/"))?
+        }
         LOCAL => ma.push_back(Service::new(a.address()))?,
         other => {
             error!(target: "ockam_api", transport = %other, "unsupported transport type");
@@ -177,6 +229,24 @@ pub fn try_address_to_multiaddr(a: &Address) -> Result<MultiAddr, Error> {
     Ok(ma)
 }
 
+/// Push the host of a `host[:port]` string and return the port, if any.
+fn push_host(ma: &mut MultiAddr, s: &str) -> Result<Option<u16>, Error> {
+    if let Ok(sa) = SocketAddrV4::from_str(s) {
+        ma.push_back(Ip4::new(*sa.ip()))?;
+        Ok(Some(sa.port()))
+    } else if let Ok(sa) = SocketAddrV6::from_str(s) {
+        ma.push_back(Ip6::new(*sa.ip()))?;
+        Ok(Some(sa.port()))
+    } else if let Some((host, port)) = s.split_once(':') {
+        ma.push_back(DnsAddr::new(host))?;
+        let n = u16::from_str(port).map_err(ApiError::wrap)?;
+        Ok(Some(n))
+    } else {
+        ma.push_back(DnsAddr::new(s))?;
+        Ok(None)
+    }
+}
+
 /// Try to convert an Ockam Address into a MultiAddr.
 pub fn addr_to_multiaddr<T: Into<Address>>(a: T) -> Option<MultiAddr> {
     let r: Route = Route::from(a);
@@ -250,4 +320,60 @@ fn clean_multiaddr_simple() {
 
     let new_route = multiaddr_to_route(&new_addr).unwrap();
     println!("{:#?}", new_route);
+
+    let addr: MultiAddr = "/node/hub/service/forward_to_n1/node/n1".parse().unwrap();
+    assert!(clean_multiaddr(&addr, &lookup).is_none());
+}
+
+#[test]
+fn clean_multiaddr_alias() {
+    use std::net::{Ipv4Addr, SocketAddr, SocketAddrV4};
+
+    let mut lookup = ConfigLookup::new();
+    lookup.set_node(
+        "hub",
+        SocketAddr::V4(SocketAddrV4::new(Ipv4Addr::new(127, 0, 0, 1), 666)).into(),
+    );
+    let fwd: MultiAddr = "/node/hub/service/forward_to_db".parse().unwrap();
+    lookup.set_alias("db", fwd);
+    let api: MultiAddr = "/alias/db/secure/api".parse().unwrap();
+    lookup.set_alias("db-api", api);
+
+    let addr: MultiAddr = "/alias/db-api/service/outlet".parse().unwrap();
+    let (new_addr, _) = clean_multiaddr(&addr, &lookup).unwrap();
+    let expected: MultiAddr =
+        "/ip4/127.0.0.1/tcp/666/service/forward_to_db/secure/api/service/outlet"
+            .parse()
+            .unwrap();
+    assert_eq!(new_addr, expected);
+
+    let addr: MultiAddr = "/alias/nope".parse().unwrap();
+    assert!(clean_multiaddr(&addr, &lookup).is_none());
+}
+
+#[test]
+fn multiaddr_to_route_transports() {
+    let route = |s: &str| multiaddr_to_route(&s.parse().unwrap());
+    let r = route("/ip4/127.0.0.1/udp/4000/service/echo").unwrap();
+    assert_eq!(r.next().unwrap(), &Address::new(UDP, "127.0.0.1:4000"));
+    let r = route("/dnsaddr/localhost/tcp/80/ws/%2F/service/echo").unwrap();
+    assert_eq!(r.next().unwrap(), &Address::new(WS, "localhost:80"));
+    let r = route("/ip6/::1/tcp/4000").unwrap();
+    assert_eq!(r.next().unwrap(), &Address::new(TCP, "[::1]:4000"));
+    assert!(route("/ip4/127.0.0.1/tcp/443/ws/%2Fchat").is_none());
+    assert!(route("/ip4/127.0.0.1/tcp/443/wss/%2F").is_none());
+    assert!(route("/ble/ockam_ble_1/service/echo").is_none());
+    let err = try_multiaddr_to_route(&"/unix/%2Ftmp%2Fockam.sock".parse().unwrap());
+    assert!(err
+        .unwrap_err()
+        .to_string()
+        .contains("no transport for /unix"));
+
+    for s in [
+        "/ip4/127.0.0.1/udp/4000/service/echo",
+        "/ip4/127.0.0.1/tcp/80/ws/%2F",
+    ] {
+        let ma: MultiAddr = s.parse().unwrap();
+        assert_eq!(route_to_multiaddr(&route(s).unwrap()), Some(ma));
+    }
 }
@@ -6,28 +6,26 @@ use minicbor::encode::Write;
 use minicbor::{Decoder, Encode};
 use models::*;
 use ockam_core::api::{Error, Id, Method, Request, Response, Status};
-use ockam_core::vault::{
-    AsymmetricVault, Hasher, KeyId, SecretVault, Signature, Signer, SymmetricVault, Verifier,
-};
+use ockam_core::vault::{KeyId, Signature};
 use ockam_core::CowStr;
 use ockam_core::{Result, Routed, Worker};
+use ockam_identity::IdentityVault;
 use ockam_node::Context;
-use ockam_vault::Vault;
 use tracing::trace;
 
 /// Vault Service Worker
-pub struct VaultService {
-    vault: Vault,
+pub struct VaultService<V: IdentityVault> {
+    vault: V,
 }
 
-impl VaultService {
+impl<V: IdentityVault> VaultService<V> {
     /// Constructor
-    pub fn new(vault: Vault) -> Self {
+    pub fn new(vault: V) -> Self {
         Self { vault }
     }
 }
 
-impl VaultService {
+impl<V: IdentityVault> VaultService<V> {
     fn response_for_bad_request<W>(req: &Request, msg: &str, enc: W) -> Result<()>
     where
         W: Write<Error = Infallible>,
@@ -342,7 +340,7 @@ impl VaultService {
 }
 
 #[ockam_core::worker]
-impl Worker for VaultService {
+impl<V: IdentityVault> Worker for VaultService<V> {
     type Message = Vec<u8>;
     type Context = Context;
 
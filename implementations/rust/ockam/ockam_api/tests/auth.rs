@@ -1,4 +1,5 @@
 use std::collections::HashMap;
+use std::time::Duration;
 
 use ockam::authenticated_storage::AuthenticatedStorage;
 use ockam::identity::authenticated_storage::mem::InMemoryStorage;
@@ -7,6 +8,7 @@ use ockam::route;
 use ockam::vault::Vault;
 use ockam_api::authenticator::direct;
 use ockam_api::authenticator::direct::types::Enroller;
+use ockam_core::api::{Request, Response, Status};
 use ockam_core::Result;
 use ockam_identity::{IdentityIdentifier, PublicIdentity, TrustEveryonePolicy};
 use ockam_node::Context;
@@ -140,3 +142,157 @@ async fn update_member_format(ctx: &mut Context) -> Result<()> {
 
     ctx.stop().await
 }
+
+#[ockam_macros::test]
+async fn enrollment_tokens_and_members(ctx: &mut Context) -> Result<()> {
+    // Create the authority:
+    let enroller = Identity::create(ctx, &Vault::create()).await?;
+    let mut tmpf = NamedTempFile::new().unwrap();
+    let enrollers = [(enroller.identifier().clone(), Enroller::default())];
+    serde_json::to_writer(&mut tmpf, &HashMap::from(enrollers)).unwrap();
+    let store = InMemoryStorage::new();
+    let authority = {
+        let a = Identity::create(ctx, &Vault::create()).await?;
+        a.create_secure_channel_listener("api", TrustEveryonePolicy, &InMemoryStorage::new())
+            .await?;
+        let exported = a.export().await?;
+        let auth = direct::Server::new(b"project42".to_vec(), store, tmpf.path(), a);
+        ctx.start_worker("auth", auth).await?;
+        exported
+    };
+    let pkey = PublicIdentity::import(&authority, &Vault::create()).await?;
+
+    let e2a = enroller
+        .create_secure_channel("api", TrustEveryonePolicy, &InMemoryStorage::new())
+        .await?;
+    let mut e = direct::Client::new(route![e2a, "auth"], ctx).await?;
+
+    // Create a token presetting the member's attributes:
+    let token = e
+        .create_token(HashMap::from([("role", "member")]), Duration::from_secs(60))
+        .await?;
+
+    // Redeem it from a new identity:
+    let member = Identity::create(ctx, &Vault::create()).await?;
+    let m2a = member
+        .create_secure_channel("api", TrustEveryonePolicy, &InMemoryStorage::new())
+        .await?;
+    let mut m = direct::Client::new(route![m2a, "auth"], ctx).await?;
+    m.enroll(&token).await?;
+    let cred = m.credential().await?;
+    let data = pkey
+        .verify_credential(&cred, member.identifier(), &Vault::create())
+        .await?;
+    assert_eq!(Some(b"member".as_slice()), data.attributes().get("role"));
+
+    // Tokens can only be used once:
+    assert!(m.enroll(&token).await.is_err());
+
+    // Expired tokens are rejected:
+    let expired = e.create_token(HashMap::new(), Duration::ZERO).await?;
+    assert!(m.enroll(&expired).await.is_err());
+
+    // Validities that overflow the expiry time are rejected:
+    assert!(e.create_token(HashMap::new(), Duration::MAX).await.is_err());
+    assert!(e.list_members().await.is_ok());
+
+    // Members cannot manage other members:
+    assert!(m.list_members().await.is_err());
+
+    // The enroller sees the member and can update it:
+    let members = e.list_members().await?;
+    assert_eq!(1, members.len());
+    assert_eq!(member.identifier(), members[0].member());
+    e.update_member(
+        member.identifier().clone(),
+        HashMap::from([("role", "admin")]),
+    )
+    .await?;
+    let cred = m.credential().await?;
+    let data = pkey
+        .verify_credential(&cred, member.identifier(), &Vault::create())
+        .await?;
+    assert_eq!(Some(b"admin".as_slice()), data.attributes().get("role"));
+
+    // A removed member gets no more credentials:
+    e.remove_member(member.identifier()).await?;
+    assert!(m.credential().await.is_err());
+    assert!(e.list_members().await?.is_empty());
+    assert!(e.remove_member(member.identifier()).await.is_err());
+
+    ctx.stop().await
+}
+
+#[ockam_macros::test]
+async fn removed_member_credential_expires(ctx: &mut Context) -> Result<()> {
+    let enroller = Identity::create(ctx, &Vault::create()).await?;
+    let mut tmpf = NamedTempFile::new().unwrap();
+    let enrollers = [(enroller.identifier().clone(), Enroller::default())];
+    serde_json::to_writer(&mut tmpf, &HashMap::from(enrollers)).unwrap();
+    let authority = {
+        let a = Identity::create(ctx, &Vault::create()).await?;
+        a.create_secure_channel_listener("api", TrustEveryonePolicy, &InMemoryStorage::new())
+            .await?;
+        let exported = a.export().await?;
+        let store = InMemoryStorage::new();
+        let auth = direct::Server::new(b"project42".to_vec(), store, tmpf.path(), a)
+            .with_credential_ttl(Duration::from_secs(1));
+        ctx.start_worker("auth", auth).await?;
+        exported
+    };
+    let pkey = PublicIdentity::import(&authority, &Vault::create()).await?;
+
+    let e2a = enroller
+        .create_secure_channel("api", TrustEveryonePolicy, &InMemoryStorage::new())
+        .await?;
+    let mut e = direct::Client::new(route![e2a, "auth"], ctx).await?;
+    let member = Identity::create(ctx, &Vault::create()).await?;
+    e.add_member(member.identifier().clone(), HashMap::new())
+        .await?;
+
+    let m2a = member
+        .create_secure_channel("api", TrustEveryonePolicy, &InMemoryStorage::new())
+        .await?;
+    let mut m = direct::Client::new(route![m2a, "auth"], ctx).await?;
+    let cred = m.credential().await?;
+    e.remove_member(member.identifier()).await?;
+
+    // The credential issued before the removal is valid until it expires
+    pkey.verify_credential(&cred, member.identifier(), &Vault::create())
+        .await?;
+    ctx.sleep(Duration::from_secs(2)).await;
+    assert!(pkey
+        .verify_credential(&cred, member.identifier(), &Vault::create())
+        .await
+        .is_err());
+
+    ctx.stop().await
+}
+
+#[ockam_macros::test]
+async fn malformed_body_is_a_bad_request(ctx: &mut Context) -> Result<()> {
+    let mut tmpf = NamedTempFile::new().unwrap();
+    serde_json::to_writer(&mut tmpf, &HashMap::<IdentityIdentifier, Enroller>::new()).unwrap();
+    let a = Identity::create(ctx, &Vault::create()).await?;
+    a.create_secure_channel_listener("api", TrustEveryonePolicy, &InMemoryStorage::new())
+        .await?;
+    let auth = direct::Server::new(
+        b"project42".to_vec(),
+        InMemoryStorage::new(),
+        tmpf.path(),
+        a,
+    );
+    ctx.start_worker("auth", auth).await?;
+
+    let member = Identity::create(ctx, &Vault::create()).await?;
+    let m2a = member
+        .create_secure_channel("api", TrustEveryonePolicy, &InMemoryStorage::new())
+        .await?;
+    let req = Request::post("/enroll").body(42u8).to_vec()?;
+    ctx.send(route![m2a, "auth"], req).await?;
+    let res = ctx.receive::<Vec<u8>>().await?.take().body();
+    let res: Response = minicbor::Decoder::new(&res).decode()?;
+    assert_eq!(Some(Status::BadRequest), res.status());
+
+    ctx.stop().await
+}
@@ -0,0 +1,241 @@
+use std::collections::HashMap;
+use std::time::{Duration, SystemTime};
+
+use minicbor::Decoder;
+use ockam::identity::authenticated_storage::mem::InMemoryStorage;
+use ockam::identity::credential::Credential;
+use ockam::identity::Identity;
+use ockam::route;
+use ockam::vault::Vault;
+use ockam_api::authenticator::direct::{self, types::Enroller};
+use ockam_api::authenticator::CREDENTIAL_TTL;
+use ockam_api::oidc::jwt::{Jwks, JwtValidator};
+use ockam_api::oidc::types::AuthenticateOidcToken;
+use ockam_api::oidc::{self, ClaimMapping};
+use ockam_core::api::{Request, Response, Status};
+use ockam_core::Result;
+use ockam_identity::{PublicIdentity, TrustEveryonePolicy};
+use ockam_node::Context;
+use ring::rand::SystemRandom;
+use ring::signature::{Ed25519KeyPair, RsaKeyPair, RSA_PKCS1_SHA256};
+use serde_json::{json, Value};
+use tempfile::NamedTempFile;
+
+const JWKS: &str = include_str!("data/oidc/jwks.json");
+const RSA_KEY: &str = include_str!("data/oidc/rsa.pem");
+const ED25519_KEY: &str = include_str!("data/oidc/ed25519.pem");
+
+const ISSUER: &str = "https://idp.example.com";
+const AUDIENCE: &str = "ockam";
+
+fn pem(s: &str) -> Vec<u8> {
+    let b64: String = s.lines().filter(|l| !l.starts_with("-----")).collect();
+    base64::decode(b64).unwrap()
+}
+
+fn b64(data: &[u8]) -> String {
+    base64::encode_config(data, base64::URL_SAFE_NO_PAD)
+}
+
+fn now() -> u64 {
+    SystemTime::now()
+        .duration_since(SystemTime::UNIX_EPOCH)
+        .unwrap()
+        .as_secs()
+}
+
+/// Create a token signed with one of the keys of the mock JWKS.
+fn token(alg: &str, claims: Value) -> String {
+    let kid = if alg == "RS256" { "rsa-1" } else { "ed-1" };
+    let header = json!({ "alg": alg, "typ": "JWT", "kid": kid });
+    let signed = format!(
+        "{}.{}",
+        b64(header.to_string().as_bytes()),
+        b64(claims.to_string().as_bytes())
+    );
+    let sig = match alg {
+        "RS256" => {
+            let key = RsaKeyPair::from_pkcs8(&pem(RSA_KEY)).unwrap();
+            let mut sig = vec![0; key.public_modulus_len()];
+            key.sign(
+                &RSA_PKCS1_SHA256,
+                &SystemRandom::new(),
+                signed.as_bytes(),
+                &mut sig,
+            )
+            .unwrap();
+            sig
+        }
+        _ => {
+            let key = Ed25519KeyPair::from_pkcs8_maybe_unchecked(&pem(ED25519_KEY)).unwrap();
+            key.sign(signed.as_bytes()).as_ref().to_vec()
+        }
+    };
+    format!("{signed}.{}", b64(&sig))
+}
+
+fn claims() -> Value {
+    json!({
+        "iss": ISSUER,
+        "aud": [AUDIENCE, "other"],
+        "sub": "alice",
+        "exp": now() + 60,
+        "email": "alice@example.com",
+        "realm": { "groups": ["admins", "devs"] }
+    })
+}
+
+fn validator() -> JwtValidator {
+    JwtValidator::new(Jwks::from_json(JWKS).unwrap(), ISSUER, AUDIENCE)
+}
+
+#[test]
+fn validate_tokens() {
+    let v = validator();
+
+    for alg in ["RS256", "EdDSA"] {
+        let claims = v.validate(&token(alg, claims())).unwrap();
+        assert_eq!(Some("alice"), claims.get("sub").and_then(Value::as_str));
+    }
+
+    // Tampered payload:
+    let t = token("EdDSA", claims());
+    let mut parts: Vec<&str> = t.split('.').collect();
+    let mut forged = claims();
+    forged["sub"] = json!("mallory");
+    let forged = b64(forged.to_string().as_bytes());
+    parts[1] = &forged;
+    assert!(v.validate(&parts.join(".")).is_err());
+
+    // Wrong issuer, audience or expired:
+    let mut c = claims();
+    c["iss"] = json!("https://evil.example.com");
+    assert!(v.validate(&token("RS256", c)).is_err());
+    let mut c = claims();
+    c["aud"] = json!("other");
+    assert!(v.validate(&token("RS256", c)).is_err());
+    let mut c = claims();
+    c["exp"] = json!(now() - 3600);
+    assert!(v.validate(&token("RS256", c)).is_err());
+
+    // Not yet valid, unless within leeway:
+    let mut c = claims();
+    c["nbf"] = json!(now() + 30);
+    let t = token("EdDSA", c);
+    assert!(v.validate(&t).is_ok());
+    assert!(v.clone().with_leeway(Duration::ZERO).validate(&t).is_err());
+}
+
+#[ockam_macros::test]
+async fn credential_from_token(ctx: &mut Context) -> Result<()> {
+    // Create the authenticator:
+    let authority = {
+        let a = Identity::create(ctx, &Vault::create()).await?;
+        a.create_secure_channel_listener("api", TrustEveryonePolicy, &InMemoryStorage::new())
+            .await?;
+        let exported = a.export().await?;
+        let mappings = vec![
+            ClaimMapping::new("email", "email").required(),
+            ClaimMapping::new("realm.groups", "groups"),
+        ];
+        let auth = oidc::Server::new(
+            b"project42".to_vec(),
+            InMemoryStorage::new(),
+            a,
+            validator(),
+            mappings,
+        );
+        ctx.start_worker("oidc", auth).await?;
+        exported
+    };
+
+    let member = Identity::create(ctx, &Vault::create()).await?;
+    let m2a = member
+        .create_secure_channel("api", TrustEveryonePolicy, &InMemoryStorage::new())
+        .await?;
+
+    // Exchange a token for a credential:
+    let req =
+        Request::post("/credential").body(AuthenticateOidcToken::new(token("RS256", claims())));
+    let res: Vec<u8> = ctx
+        .send_and_receive(route![m2a.clone(), "oidc"], req.to_vec()?)
+        .await?;
+    let mut dec = Decoder::new(&res);
+    let hdr: Response = dec.decode()?;
+    assert_eq!(Some(Status::Ok), hdr.status());
+    let cred: Credential = dec.decode()?;
+
+    let pkey = PublicIdentity::import(&authority, &Vault::create()).await?;
+    let data = pkey
+        .verify_credential(&cred, member.identifier(), &Vault::create())
+        .await?;
+    let attrs = data.attributes();
+    assert_eq!(Some(b"project42".as_slice()), attrs.get("project_id"));
+    assert_eq!(Some(b"alice@example.com".as_slice()), attrs.get("email"));
+    assert_eq!(Some(b"admins,devs".as_slice()), attrs.get("groups"));
+    let validity = u64::from(data.expires_at()) - u64::from(data.created_at());
+    assert_eq!(CREDENTIAL_TTL.as_secs(), validity);
+
+    // A malformed request is answered:
+    let req = Request::post("/credential").body("not a token");
+    let res: Vec<u8> = ctx
+        .send_and_receive(route![m2a.clone(), "oidc"], req.to_vec()?)
+        .await?;
+    let hdr: Response = Decoder::new(&res).decode()?;
+    assert_eq!(Some(Status::BadRequest), hdr.status());
+
+    // A token without a required claim is rejected:
+    let mut c = claims();
+    c.as_object_mut().unwrap().remove("email");
+    let req = Request::post("/credential").body(AuthenticateOidcToken::new(token("EdDSA", c)));
+    let res: Vec<u8> = ctx
+        .send_and_receive(route![m2a, "oidc"], req.to_vec()?)
+        .await?;
+    let hdr: Response = Decoder::new(&res).decode()?;
+    assert_eq!(Some(Status::Forbidden), hdr.status());
+
+    ctx.stop().await
+}
+
+#[ockam_macros::test]
+async fn enrolled_members_are_listed(ctx: &mut Context) -> Result<()> {
+    // Create both authenticators over the same storage:
+    let enroller = Identity::create(ctx, &Vault::create()).await?;
+    let mut tmpf = NamedTempFile::new().unwrap();
+    let enrollers = [(enroller.identifier().clone(), Enroller::default())];
+    serde_json::to_writer(&mut tmpf, &HashMap::from(enrollers)).unwrap();
+    let store = InMemoryStorage::new();
+    {
+        let a = Identity::create(ctx, &Vault::create()).await?;
+        a.create_secure_channel_listener("api", TrustEveryonePolicy, &InMemoryStorage::new())
+            .await?;
+        let auth = direct::Server::new(b"project42".to_vec(), store.clone(), tmpf.path(), a);
+        ctx.start_worker("auth", auth).await?;
+        let a = Identity::create(ctx, &Vault::create()).await?;
+        let auth = oidc::Server::new(b"project42".to_vec(), store, a, validator(), vec![]);
+        ctx.start_worker("oidc", auth).await?;
+    }
+
+    // Enroll with a token:
+    let member = Identity::create(ctx, &Vault::create()).await?;
+    let m2a = member
+        .create_secure_channel("api", TrustEveryonePolicy, &InMemoryStorage::new())
+        .await?;
+    let req = Request::post("/enroll").body(AuthenticateOidcToken::new(token("RS256", claims())));
+    let res: Vec<u8> = ctx
+        .send_and_receive(route![m2a, "oidc"], req.to_vec()?)
+        .await?;
+    let hdr: Response = Decoder::new(&res).decode()?;
+    assert_eq!(Some(Status::Ok), hdr.status());
+
+    // The enroller sees the new member:
+    let e2a = enroller
+        .create_secure_channel("api", TrustEveryonePolicy, &InMemoryStorage::new())
+        .await?;
+    let mut e = direct::Client::new(route![e2a, "auth"], ctx).await?;
+    let members = e.list_members().await?;
+    assert_eq!(1, members.len());
+    assert_eq!(member.identifier(), members[0].member());
+
+    ctx.stop().await
+}
@@ -0,0 +1,74 @@
+use anyhow::anyhow;
+use clap::Args;
+
+use ockam_api::config::lookup::ConfigLookup;
+use ockam_multiaddr::proto::Alias;
+use ockam_multiaddr::{MultiAddr, Protocol};
+use serde::Serialize;
+
+use crate::alias::HELP_DETAIL;
+use crate::util::exitcode;
+use crate::util::output::Output;
+use crate::{help, CommandGlobalOpts, Error};
+
+/// Add an alias, or replace an existing one
+#[derive(Clone, Debug, Args)]
+#[command(arg_required_else_help = true, after_long_help = help::template(HELP_DETAIL))]
+pub struct AddCommand {
+    /// Name of the alias, made of letters, digits, `_` and `-`
+    name: String,
+
+    /// Address the alias stands for
+    address: MultiAddr,
+}
+
+impl AddCommand {
+    pub fn run(self, options: CommandGlobalOpts) {
+        if let Err(e) = run_impl(options, self) {
+            eprintln!("{}", e);
+            crate::util::exit(e.code());
+        }
+    }
+}
+
+fn run_impl(opts: CommandGlobalOpts, cmd: AddCommand) -> crate::Result<()> {
+    let alias = format!("/{}/{}", Alias::PREFIX, cmd.name);
+    let alias = match alias.parse::<MultiAddr>() {
+        Ok(a) if ConfigLookup::is_alias_name(&cmd.name) && a.iter().count() == 1 => a,
+        _ => {
+            let message = anyhow!("Invalid alias name {:?}", cmd.name);
+            return Err(Error::new(exitcode::USAGE, message));
+        }
+    };
+
+    // Reject aliases which would expand forever.
+    let mut lookup = opts.config.lookup();
+    lookup.set_alias(&cmd.name, cmd.address.clone());
+    if lookup.rewriter().rewrite(&alias).is_err() {
+        let message = anyhow!("Alias {} refers to itself", cmd.name);
+        return Err(Error::new(exitcode::DATAERR, message));
+    }
+
+    opts.config.set_alias(&cmd.name, cmd.address.clone());
+    opts.config.persist_config_updates()?;
+    opts.print(&AddedAlias {
+        name: cmd.name,
+        alias: alias.to_string(),
+        address: cmd.address.to_string(),
+    })?;
+    Ok(())
+}
+
+/// Result of adding an alias
+#[derive(Debug, Serialize)]
+struct AddedAlias {
+    name: String,
+    alias: String,
+    address: String,
+}
+
+impl Output for AddedAlias {
+    fn output(&self) -> anyhow::Result<String> {
+        Ok(format!("{} => {}", self.alias, self.address))
+    }
+}
@@ -0,0 +1,59 @@
+use clap::Args;
+use cli_table::{Cell, Style, Table};
+use serde::Serialize;
+
+use crate::alias::HELP_DETAIL;
+use crate::util::output::Output;
+use crate::{help, CommandGlobalOpts};
+
+/// List all aliases
+#[derive(Clone, Debug, Args)]
+#[command(after_long_help = help::template(HELP_DETAIL))]
+pub struct ListCommand {}
+
+impl ListCommand {
+    pub fn run(self, options: CommandGlobalOpts) {
+        if let Err(e) = run_impl(options) {
+            eprintln!("{}", e);
+            crate::util::exit(e.code());
+        }
+    }
+}
+
+fn run_impl(opts: CommandGlobalOpts) -> crate::Result<()> {
+    let lookup = opts.config.lookup();
+    let aliases = lookup
+        .aliases()
+        .map(|(n, a)| ListedAlias {
+            name: n.to_string(),
+            address: a.to_string(),
+        })
+        .collect::<Vec<_>>();
+    opts.print(&aliases)?;
+    Ok(())
+}
+
+/// An alias and the address it stands for
+#[derive(Debug, Serialize)]
+struct ListedAlias {
+    name: String,
+    address: String,
+}
+
+impl Output for Vec<ListedAlias> {
+    fn output(&self) -> anyhow::Result<String> {
+        if self.is_empty() {
+            return Ok("No aliases found".to_string());
+        }
+        let rows = self
+            .iter()
+            .map(|a| [a.name.as_str().cell(), a.address.as_str().cell()])
+            .collect::<Vec<_>>();
+        let table = rows
+            .table()
+            .title(["Alias".cell().bold(true), "Address".cell().bold(true)])
+            .display()?
+            .to_string();
+        Ok(table)
+    }
+}
@@ -0,0 +1,69 @@
+mod add;
+mod list;
+mod remove;
+
+use add::AddCommand;
+use list::ListCommand;
+use remove::RemoveCommand;
+
+use crate::{help, CommandGlobalOpts};
+use clap::{Args, Subcommand};
+
+const HELP_DETAIL: &str = "\
+About:
+    Give names to addresses that are used often.
+
+    An alias is stored in the configuration and can be used wherever an address is
+    accepted, as `/alias/<name>`, followed by any other protocols. Aliases can refer
+    to nodes and to other aliases, which are resolved when the alias is used.
+
+Examples:
+```sh
+    # Name the address of a database outlet reached through a forwarder
+    $ ockam alias add prod-db /node/relay/service/forward_to_db/secure/api/service/outlet
+
+    # Use the alias
+    $ ockam tcp-inlet create --from 127.0.0.1:5432 --to /alias/prod-db
+
+    # Show all aliases
+    $ ockam alias list
+
+    # Delete the alias
+    $ ockam alias remove prod-db
+```
+
+    Completion scripts generated with `ockam completion` complete the aliases that
+    exist when completing.
+";
+
+/// Manage address aliases
+#[derive(Clone, Debug, Args)]
+#[command(
+    arg_required_else_help = true,
+    subcommand_required = true,
+    after_long_help = help::template(HELP_DETAIL)
+)]
+pub struct AliasCommand {
+    #[command(subcommand)]
+    subcommand: AliasSubcommand,
+}
+
+#[derive(Clone, Debug, Subcommand)]
+pub enum AliasSubcommand {
+    #[command(display_order = 800)]
+    Add(AddCommand),
+    #[command(display_order = 800)]
+    List(ListCommand),
+    #[command(display_order = 800)]
+    Remove(RemoveCommand),
+}
+
+impl AliasCommand {
+    pub fn run(self, options: CommandGlobalOpts) {
+        match self.subcommand {
+            AliasSubcommand::Add(c) => c.run(options),
+            AliasSubcommand::List(c) => c.run(options),
+            AliasSubcommand::Remove(c) => c.run(options),
+        }
+    }
+}
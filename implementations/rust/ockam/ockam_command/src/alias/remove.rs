@@ -0,0 +1,48 @@
+use anyhow::anyhow;
+use clap::Args;
+use serde::Serialize;
+
+use crate::alias::HELP_DETAIL;
+use crate::util::exitcode;
+use crate::util::output::Output;
+use crate::{help, CommandGlobalOpts, Error};
+
+/// Remove an alias
+#[derive(Clone, Debug, Args)]
+#[command(arg_required_else_help = true, after_long_help = help::template(HELP_DETAIL))]
+pub struct RemoveCommand {
+    /// Name of the alias
+    #[arg(id = "NAME")]
+    name: String,
+}
+
+impl RemoveCommand {
+    pub fn run(self, options: CommandGlobalOpts) {
+        if let Err(e) = run_impl(options, self) {
+            eprintln!("{}", e);
+            crate::util::exit(e.code());
+        }
+    }
+}
+
+fn run_impl(opts: CommandGlobalOpts, cmd: RemoveCommand) -> crate::Result<()> {
+    if !opts.config.remove_alias(&cmd.name) {
+        let message = anyhow!("Alias {} not known", cmd.name);
+        return Err(Error::new(exitcode::DATAERR, message));
+    }
+    opts.config.persist_config_updates()?;
+    opts.print(&RemovedAlias { name: cmd.name })?;
+    Ok(())
+}
+
+/// Result of removing an alias
+#[derive(Debug, Serialize)]
+struct RemovedAlias {
+    name: String,
+}
+
+impl Output for RemovedAlias {
+    fn output(&self) -> anyhow::Result<String> {
+        Ok(format!("Removed alias {}", self.name))
+    }
+}
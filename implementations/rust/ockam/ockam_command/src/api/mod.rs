@@ -0,0 +1,29 @@
+use clap::{Args, Subcommand};
+
+use crate::{help, CommandGlobalOpts};
+
+mod schema;
+
+const HELP_DETAIL: &str = "";
+
+/// Inspect the node API
+#[derive(Clone, Debug, Args)]
+#[command(after_long_help = help::template(HELP_DETAIL))]
+pub struct ApiCommand {
+    #[command(subcommand)]
+    pub subcommand: ApiSubcommand,
+}
+
+#[derive(Clone, Debug, Subcommand)]
+pub enum ApiSubcommand {
+    #[command(display_order = 800)]
+    Schema(schema::SchemaCommand),
+}
+
+impl ApiCommand {
+    pub fn run(self, options: CommandGlobalOpts) {
+        match self.subcommand {
+            ApiSubcommand::Schema(c) => c.run(options),
+        }
+    }
+}
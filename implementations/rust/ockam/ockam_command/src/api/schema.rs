@@ -0,0 +1,39 @@
+use clap::Args;
+
+use ockam_api::nodes::routes;
+
+use crate::help;
+use crate::CommandGlobalOpts;
+
+const HELP_DETAIL: &str = "\
+About:
+    Print the CDDL schema of the node API.
+
+    The schema describes the request and response headers, as well as the
+    body of every endpoint, named after the endpoint with a `_request` or
+    `_response` suffix.
+
+```sh
+    # Dump the schema to a file
+    $ ockam api schema > ockam.cddl
+```
+";
+
+/// Print the CDDL schema of the node API
+#[derive(Clone, Debug, Args)]
+#[command(after_long_help = help::template(HELP_DETAIL))]
+pub struct SchemaCommand {
+    /// Only print the endpoint rules
+    #[arg(long)]
+    endpoints: bool,
+}
+
+impl SchemaCommand {
+    pub fn run(self, _options: CommandGlobalOpts) {
+        if self.endpoints {
+            print!("{}", routes::endpoints_schema());
+        } else {
+            print!("{}", routes::schema());
+        }
+    }
+}
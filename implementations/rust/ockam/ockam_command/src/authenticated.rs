@@ -1,11 +1,13 @@
-use crate::help;
-use crate::util::embedded_node;
+use crate::util::output::Output;
+use crate::util::{embedded_node, tcp_transport};
+use crate::{help, CommandGlobalOpts};
 use anyhow::{anyhow, Result};
 use clap::builder::NonEmptyStringValueParser;
 use clap::{Args, Subcommand};
-use ockam::{Context, TcpTransport};
+use ockam::Context;
 use ockam_api::auth;
 use ockam_multiaddr::MultiAddr;
+use serde::Serialize;
 
 const HELP_DETAIL: &str = "";
 
@@ -47,20 +49,27 @@ pub enum AuthenticatedSubcommand {
 }
 
 impl AuthenticatedCommand {
-    pub fn run(self) {
-        if let Err(e) = embedded_node(run_impl, self.subcommand) {
+    pub fn run(self, options: CommandGlobalOpts) {
+        if let Err(e) = embedded_node(run_impl, (options, self.subcommand)) {
             eprintln!("Ockam node failed: {:?}", e,);
         }
     }
 }
 
-async fn run_impl(ctx: Context, cmd: AuthenticatedSubcommand) -> crate::Result<()> {
-    TcpTransport::create(&ctx).await?;
+async fn run_impl(
+    ctx: Context,
+    (opts, cmd): (CommandGlobalOpts, AuthenticatedSubcommand),
+) -> crate::Result<()> {
+    tcp_transport(&ctx).await?;
     match &cmd {
         AuthenticatedSubcommand::Get { addr, id, key } => {
             let mut c = client(addr, &ctx).await?;
             let val = c.get(id, key).await?;
-            println!("{val:?}")
+            opts.print(&AttributeValue {
+                id: id.clone(),
+                key: key.clone(),
+                value: val.map(|v| String::from_utf8_lossy(v).into_owned()),
+            })?
         }
         AuthenticatedSubcommand::Del { addr, id, key } => {
             let mut c = client(addr, &ctx).await?;
@@ -71,6 +80,23 @@ async fn run_impl(ctx: Context, cmd: AuthenticatedSubcommand) -> crate::Result<(
     Ok(())
 }
 
+/// The value of an attribute of a subject
+#[derive(Debug, Serialize)]
+struct AttributeValue {
+    id: String,
+    key: String,
+    value: Option<String>,
+}
+
+impl Output for AttributeValue {
+    fn output(&self) -> Result<String> {
+        match &self.value {
+            Some(v) => Ok(v.clone()),
+            None => Ok(format!("Attribute {} is not set", self.key)),
+        }
+    }
+}
+
 async fn client(addr: &MultiAddr, ctx: &Context) -> Result<auth::Client> {
     let to = ockam_api::multiaddr_to_route(addr)
         .ok_or_else(|| anyhow!("failed to parse address: {addr}"))?;
@@ -0,0 +1,111 @@
+use std::collections::HashMap;
+use std::net::SocketAddr;
+use std::str::FromStr;
+
+use anyhow::{anyhow, Context as _};
+use clap::Args;
+use ockam::identity::{IdentityIdentifier, PublicIdentity};
+use ockam::Context;
+use ockam_api::authenticator::direct::types::Enroller;
+use ockam_vault::Vault;
+
+use crate::node::{is_node_up, print_query_status, spawn_background_node};
+use crate::service::start::start_authenticator_service;
+use crate::util::{exitcode, node_rpc, tcp_transport, RpcBuilder};
+use crate::{help, node, CommandGlobalOpts};
+
+const HELP_DETAIL: &str = "\
+About:
+    Creates a node running a direct authenticator service. The node gets an
+    identity of its own which members must trust as their authority. The
+    default identity is authorised to enroll members.
+";
+
+/// Create a self-hosted authority node
+#[derive(Clone, Debug, Args)]
+#[command(after_long_help = help::template(HELP_DETAIL))]
+pub struct CreateCommand {
+    /// Name of the authority node
+    #[arg(default_value = "authority")]
+    node_name: String,
+
+    /// TCP listener address
+    #[arg(long, short, id = "SOCKET_ADDRESS", default_value = "127.0.0.1:0")]
+    tcp_listener_address: String,
+
+    /// Project identifier included in issued credentials [default: the node name]
+    #[arg(long)]
+    project: Option<String>,
+
+    /// Additional identities authorised to enroll members
+    #[arg(long = "enroller", value_name = "IDENTIFIER")]
+    enrollers: Vec<IdentityIdentifier>,
+}
+
+impl CreateCommand {
+    pub fn run(self, options: CommandGlobalOpts) {
+        node_rpc(rpc, (options, self));
+    }
+}
+
+async fn rpc(ctx: Context, (opts, cmd): (CommandGlobalOpts, CreateCommand)) -> crate::Result<()> {
+    run_impl(&ctx, &opts, cmd).await
+}
+
+async fn run_impl(
+    ctx: &Context,
+    opts: &CommandGlobalOpts,
+    cmd: CreateCommand,
+) -> crate::Result<()> {
+    let node_name = &cmd.node_name;
+    let cfg = &opts.config;
+
+    // The authority uses an identity of its own rather than the default one
+    let node = node::CreateCommand {
+        node_name: node_name.clone(),
+        tcp_listener_address: cmd.tcp_listener_address.clone(),
+        no_shared_identity: true,
+        ..Default::default()
+    }
+    .overwrite_addr()?;
+    let addr = SocketAddr::from_str(&node.tcp_listener_address)?;
+    spawn_background_node(ctx, opts, &node, addr).await?;
+
+    // The default identity may enroll members
+    let default_identity = cfg
+        .get_default_identity()
+        .context("Default identity was not found")?;
+    let default_identity = PublicIdentity::import(&default_identity, &Vault::default()).await?;
+    let enrollers: HashMap<IdentityIdentifier, Enroller> =
+        std::iter::once(default_identity.identifier().clone())
+            .chain(cmd.enrollers.iter().cloned())
+            .map(|id| (id, Enroller::default()))
+            .collect();
+    let enrollers_path = cfg.get_node_dir(node_name)?.join("enrollers.json");
+    std::fs::write(&enrollers_path, serde_json::to_string(&enrollers)?)?;
+
+    let tcp = tcp_transport(ctx).await?;
+    let mut rpc = RpcBuilder::new(ctx, opts, node_name).tcp(&tcp)?.build();
+    if !is_node_up(&mut rpc, true).await? {
+        return Err(crate::Error::new(
+            exitcode::UNAVAILABLE,
+            anyhow!("node {node_name} did not start"),
+        ));
+    }
+
+    // The state of the node is printed once it runs the authenticator
+    let project = cmd.project.as_deref().unwrap_or(node_name);
+    start_authenticator_service(
+        ctx,
+        opts,
+        node_name,
+        "authenticator",
+        &enrollers_path,
+        project,
+        Some(&tcp),
+    )
+    .await?;
+    let port = cfg.get_node_port(node_name)?;
+    print_query_status(&mut rpc, port, node_name, false).await?;
+    Ok(())
+}
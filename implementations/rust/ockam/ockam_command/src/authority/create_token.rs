@@ -0,0 +1,47 @@
+use std::time::Duration;
+
+use clap::Args;
+use ockam::Context;
+use ockam_api::authenticator::direct::types::MemberToken;
+
+use crate::authority::util::{authenticator_addr, parse_attributes, AuthorityOpts};
+use crate::node::util::{delete_embedded_node, start_embedded_node};
+use crate::util::{api, node_rpc, RpcBuilder};
+use crate::CommandGlobalOpts;
+
+/// Create a one-time enrollment token
+#[derive(Clone, Debug, Args)]
+pub struct CreateTokenCommand {
+    #[command(flatten)]
+    authority: AuthorityOpts,
+
+    /// Attributes in `key=value` format the redeeming identity is enrolled with
+    #[arg(short, long = "attribute", value_name = "ATTRIBUTE")]
+    attributes: Vec<String>,
+
+    /// Number of seconds the token can be redeemed for
+    #[arg(long, value_name = "SECONDS", default_value_t = 600)]
+    expires_in: u64,
+}
+
+impl CreateTokenCommand {
+    pub fn run(self, options: CommandGlobalOpts) {
+        node_rpc(rpc, (options, self));
+    }
+}
+
+async fn rpc(
+    ctx: Context,
+    (opts, cmd): (CommandGlobalOpts, CreateTokenCommand),
+) -> crate::Result<()> {
+    let attributes = parse_attributes(&cmd.attributes)?;
+    let node_name = start_embedded_node(&ctx, &opts.config).await?;
+    let to = authenticator_addr(&ctx, &opts, &node_name, &cmd.authority).await?;
+    let mut rpc = RpcBuilder::new(&ctx, &opts, &node_name).to(&to)?.build();
+    let valid_for = Duration::from_secs(cmd.expires_in);
+    rpc.request(api::authority::create_token(&attributes, valid_for))
+        .await?;
+    rpc.parse_and_print_response::<MemberToken>()?;
+    delete_embedded_node(&opts.config, &node_name).await;
+    Ok(())
+}
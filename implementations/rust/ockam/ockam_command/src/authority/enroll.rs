@@ -0,0 +1,52 @@
+use clap::Args;
+use ockam::Context;
+use serde::Serialize;
+
+use crate::authority::util::{authenticator_addr, AuthorityOpts};
+use crate::node::util::{delete_embedded_node, start_embedded_node};
+use crate::util::output::Output;
+use crate::util::{api, node_rpc, RpcBuilder};
+use crate::CommandGlobalOpts;
+
+/// Redeem an enrollment token to become a member of an authority
+#[derive(Clone, Debug, Args)]
+#[command(arg_required_else_help = true)]
+pub struct EnrollCommand {
+    #[command(flatten)]
+    authority: AuthorityOpts,
+
+    /// The one-time token handed out by an enroller
+    #[arg(long)]
+    token: String,
+}
+
+impl EnrollCommand {
+    pub fn run(self, options: CommandGlobalOpts) {
+        node_rpc(rpc, (options, self));
+    }
+}
+
+async fn rpc(ctx: Context, (opts, cmd): (CommandGlobalOpts, EnrollCommand)) -> crate::Result<()> {
+    let node_name = start_embedded_node(&ctx, &opts.config).await?;
+    let to = authenticator_addr(&ctx, &opts, &node_name, &cmd.authority).await?;
+    let mut rpc = RpcBuilder::new(&ctx, &opts, &node_name).to(&to)?.build();
+    rpc.request(api::authority::enroll(&cmd.token)).await?;
+    rpc.is_ok()?;
+    delete_embedded_node(&opts.config, &node_name).await;
+    opts.print(&Enrolled {
+        authority: cmd.authority.at.to_string(),
+    })?;
+    Ok(())
+}
+
+/// Result of enrolling with an authority
+#[derive(Debug, Serialize)]
+struct Enrolled {
+    authority: String,
+}
+
+impl Output for Enrolled {
+    fn output(&self) -> anyhow::Result<String> {
+        Ok(format!("Enrolled with the authority at {}", self.authority))
+    }
+}
@@ -0,0 +1,34 @@
+use clap::Args;
+use ockam::Context;
+use ockam_api::authenticator::direct::types::MemberInfo;
+
+use crate::authority::util::{authenticator_addr, AuthorityOpts};
+use crate::node::util::{delete_embedded_node, start_embedded_node};
+use crate::util::{api, node_rpc, RpcBuilder};
+use crate::CommandGlobalOpts;
+
+/// List the members of an authority
+#[derive(Clone, Debug, Args)]
+pub struct ListMembersCommand {
+    #[command(flatten)]
+    authority: AuthorityOpts,
+}
+
+impl ListMembersCommand {
+    pub fn run(self, options: CommandGlobalOpts) {
+        node_rpc(rpc, (options, self));
+    }
+}
+
+async fn rpc(
+    ctx: Context,
+    (opts, cmd): (CommandGlobalOpts, ListMembersCommand),
+) -> crate::Result<()> {
+    let node_name = start_embedded_node(&ctx, &opts.config).await?;
+    let to = authenticator_addr(&ctx, &opts, &node_name, &cmd.authority).await?;
+    let mut rpc = RpcBuilder::new(&ctx, &opts, &node_name).to(&to)?.build();
+    rpc.request(api::authority::list_members()).await?;
+    rpc.parse_and_print_response::<Vec<MemberInfo>>()?;
+    delete_embedded_node(&opts.config, &node_name).await;
+    Ok(())
+}
@@ -0,0 +1,79 @@
+mod create;
+mod create_token;
+mod enroll;
+mod list_members;
+mod remove_member;
+mod update_member;
+mod util;
+
+pub(crate) use create::CreateCommand;
+pub(crate) use create_token::CreateTokenCommand;
+pub(crate) use enroll::EnrollCommand;
+pub(crate) use list_members::ListMembersCommand;
+pub(crate) use remove_member::RemoveMemberCommand;
+pub(crate) use update_member::UpdateMemberCommand;
+
+use crate::help;
+use crate::CommandGlobalOpts;
+use clap::{Args, Subcommand};
+
+const HELP_DETAIL: &str = "\
+About:
+    An authority is a node which issues membership credentials without relying
+    on Ockam Orchestrator. Enrollers, by default the identity which created the
+    authority, manage its members directly or hand out one-time enrollment
+    tokens which identities redeem to become members.
+
+    Removing a member makes the authority stop issuing credentials to it. The
+    credentials the member already has are accepted until they expire, so their
+    expiry bounds its access. The authority issues credentials valid for 10
+    minutes, which nodes refresh.
+
+Examples:
+
+```sh
+    # Create an authority node
+    $ ockam authority create
+
+    # Create an enrollment token for a member with the role `member`
+    $ ockam authority create-token --attribute role=member
+
+    # Redeem the token
+    $ ockam authority enroll --token 8d4f...
+```
+";
+
+/// Manage self-hosted authorities
+#[derive(Clone, Debug, Args)]
+#[command(
+    after_long_help = help::template(HELP_DETAIL),
+    arg_required_else_help = true,
+    subcommand_required = true
+)]
+pub struct AuthorityCommand {
+    #[command(subcommand)]
+    subcommand: AuthoritySubcommand,
+}
+
+#[derive(Clone, Debug, Subcommand)]
+pub enum AuthoritySubcommand {
+    Create(CreateCommand),
+    CreateToken(CreateTokenCommand),
+    Enroll(EnrollCommand),
+    ListMembers(ListMembersCommand),
+    UpdateMember(UpdateMemberCommand),
+    RemoveMember(RemoveMemberCommand),
+}
+
+impl AuthorityCommand {
+    pub fn run(self, options: CommandGlobalOpts) {
+        match self.subcommand {
+            AuthoritySubcommand::Create(c) => c.run(options),
+            AuthoritySubcommand::CreateToken(c) => c.run(options),
+            AuthoritySubcommand::Enroll(c) => c.run(options),
+            AuthoritySubcommand::ListMembers(c) => c.run(options),
+            AuthoritySubcommand::UpdateMember(c) => c.run(options),
+            AuthoritySubcommand::RemoveMember(c) => c.run(options),
+        }
+    }
+}
@@ -0,0 +1,60 @@
+use clap::Args;
+use ockam::identity::IdentityIdentifier;
+use ockam::Context;
+use serde::Serialize;
+
+use crate::authority::util::{authenticator_addr, AuthorityOpts};
+use crate::node::util::{delete_embedded_node, start_embedded_node};
+use crate::util::output::Output;
+use crate::util::{api, node_rpc, RpcBuilder};
+use crate::CommandGlobalOpts;
+
+/// Remove a member from an authority, revoking its membership
+///
+/// The member can no longer get credentials from the authority. The credentials
+/// it already has are short-lived, and stay valid until they expire, within
+/// 10 minutes.
+#[derive(Clone, Debug, Args)]
+#[command(arg_required_else_help = true)]
+pub struct RemoveMemberCommand {
+    /// Identifier of the member
+    member: IdentityIdentifier,
+
+    #[command(flatten)]
+    authority: AuthorityOpts,
+}
+
+impl RemoveMemberCommand {
+    pub fn run(self, options: CommandGlobalOpts) {
+        node_rpc(rpc, (options, self));
+    }
+}
+
+async fn rpc(
+    ctx: Context,
+    (opts, cmd): (CommandGlobalOpts, RemoveMemberCommand),
+) -> crate::Result<()> {
+    let node_name = start_embedded_node(&ctx, &opts.config).await?;
+    let to = authenticator_addr(&ctx, &opts, &node_name, &cmd.authority).await?;
+    let mut rpc = RpcBuilder::new(&ctx, &opts, &node_name).to(&to)?.build();
+    rpc.request(api::authority::remove_member(&cmd.member))
+        .await?;
+    rpc.is_ok()?;
+    delete_embedded_node(&opts.config, &node_name).await;
+    opts.print(&RemovedMember {
+        member: cmd.member.to_string(),
+    })?;
+    Ok(())
+}
+
+/// Result of removing a member
+#[derive(Debug, Serialize)]
+struct RemovedMember {
+    member: String,
+}
+
+impl Output for RemovedMember {
+    fn output(&self) -> anyhow::Result<String> {
+        Ok(format!("Removed member {}", self.member))
+    }
+}
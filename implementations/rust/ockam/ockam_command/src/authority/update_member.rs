@@ -0,0 +1,67 @@
+use clap::Args;
+use ockam::identity::IdentityIdentifier;
+use ockam::Context;
+use serde::Serialize;
+use std::collections::BTreeMap;
+
+use crate::authority::util::{authenticator_addr, parse_attributes, AuthorityOpts};
+use crate::node::util::{delete_embedded_node, start_embedded_node};
+use crate::util::output::Output;
+use crate::util::{api, node_rpc, RpcBuilder};
+use crate::CommandGlobalOpts;
+
+/// Replace the attributes of a member
+#[derive(Clone, Debug, Args)]
+#[command(arg_required_else_help = true)]
+pub struct UpdateMemberCommand {
+    /// Identifier of the member
+    member: IdentityIdentifier,
+
+    #[command(flatten)]
+    authority: AuthorityOpts,
+
+    /// Attributes in `key=value` format to be attached to the member
+    #[arg(short, long = "attribute", value_name = "ATTRIBUTE")]
+    attributes: Vec<String>,
+}
+
+impl UpdateMemberCommand {
+    pub fn run(self, options: CommandGlobalOpts) {
+        node_rpc(rpc, (options, self));
+    }
+}
+
+async fn rpc(
+    ctx: Context,
+    (opts, cmd): (CommandGlobalOpts, UpdateMemberCommand),
+) -> crate::Result<()> {
+    let attributes = parse_attributes(&cmd.attributes)?;
+    let node_name = start_embedded_node(&ctx, &opts.config).await?;
+    let to = authenticator_addr(&ctx, &opts, &node_name, &cmd.authority).await?;
+    let mut rpc = RpcBuilder::new(&ctx, &opts, &node_name).to(&to)?.build();
+    rpc.request(api::authority::update_member(
+        cmd.member.clone(),
+        &attributes,
+    ))
+    .await?;
+    rpc.is_ok()?;
+    delete_embedded_node(&opts.config, &node_name).await;
+    opts.print(&UpdatedMember {
+        member: cmd.member.to_string(),
+        attributes: attributes.into_iter().collect(),
+    })?;
+    Ok(())
+}
+
+/// Result of updating the attributes of a member
+#[derive(Debug, Serialize)]
+struct UpdatedMember {
+    member: String,
+    attributes: BTreeMap<String, String>,
+}
+
+impl Output for UpdatedMember {
+    fn output(&self) -> anyhow::Result<String> {
+        Ok(format!("Updated member {}", self.member))
+    }
+}
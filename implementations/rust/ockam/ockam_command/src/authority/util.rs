@@ -0,0 +1,69 @@
+use std::collections::HashMap;
+
+use anyhow::{anyhow, Context as _};
+use clap::Args;
+use ockam::identity::IdentityIdentifier;
+use ockam::Context;
+use ockam_api::clean_multiaddr;
+use ockam_api::nodes::models::secure_channel::{
+    CreateSecureChannelResponse, CredentialExchangeMode,
+};
+use ockam_multiaddr::proto::Service;
+use ockam_multiaddr::MultiAddr;
+use tracing::debug;
+
+use crate::util::{api, RpcBuilder};
+use crate::CommandGlobalOpts;
+
+/// Address of the authority's secure channel listener.
+#[derive(Clone, Debug, Args)]
+pub struct AuthorityOpts {
+    /// Route to the secure channel listener of the authority node
+    #[arg(
+        long,
+        value_name = "ROUTE",
+        default_value = "/node/authority/service/api"
+    )]
+    pub at: MultiAddr,
+
+    /// Identifier the authority is expected to present
+    #[arg(long, value_name = "IDENTIFIER")]
+    pub authority: Option<IdentityIdentifier>,
+}
+
+/// Open a secure channel from `node_name` to the authority and
+/// return the address of its authenticator service through it.
+pub async fn authenticator_addr(
+    ctx: &Context,
+    opts: &CommandGlobalOpts,
+    node_name: &str,
+    authority: &AuthorityOpts,
+) -> crate::Result<MultiAddr> {
+    let (at, _) = clean_multiaddr(&authority.at, &opts.config.lookup())
+        .context(format!("Could not convert {} into route", &authority.at))?;
+    debug!(%at, "establishing secure channel to authority");
+    let mut rpc = RpcBuilder::new(ctx, opts, node_name).build();
+    rpc.request(api::create_secure_channel(
+        &at,
+        authority.authority.clone().map(|a| vec![a]),
+        CredentialExchangeMode::None,
+    ))
+    .await?;
+    let res = rpc.parse_response::<CreateSecureChannelResponse>()?;
+    let mut addr = res.addr()?;
+    addr.push_back(Service::new("authenticator"))
+        .map_err(anyhow::Error::from)?;
+    Ok(addr)
+}
+
+/// Parse attributes given in `key=value` format.
+pub fn parse_attributes(attrs: &[String]) -> crate::Result<HashMap<String, String>> {
+    let mut attributes = HashMap::new();
+    for attr in attrs {
+        let (key, value) = attr
+            .split_once('=')
+            .ok_or_else(|| anyhow!("attribute `{attr}` is not in `key=value` format"))?;
+        attributes.insert(key.to_string(), value.to_string());
+    }
+    Ok(attributes)
+}
@@ -1,7 +1,6 @@
-use crate::{help, OckamCommand};
-use clap::{Args, CommandFactory};
+use crate::{help, CommandGlobalOpts, OckamCommand};
+use clap::{Args, CommandFactory, ValueEnum};
 use clap_complete::{generate, Shell};
-use std::io;
 
 const HELP_DETAIL: &str = "\
 About:
@@ -24,24 +23,140 @@ About:
     # FISH
     $ ockam completion --shell fish > ~/.config/fish/completions/ockam.fish
 ```
+
+    The scripts for bash, zsh and fish also complete the names of the nodes and
+    aliases that exist when completing: in addresses starting with `/node/` or
+    `/alias/`, after `--node` and `--at`, for the commands taking a node name and
+    for `ockam alias remove`.
 ";
 
+/// Complete the names of nodes and aliases with `ockam completion --names`
+const BASH_NAMES: &str = r#"
+_ockam_names() {
+    local cur="${COMP_WORDS[COMP_CWORD]}" prev="${COMP_WORDS[COMP_CWORD-1]}" kind=""
+    case "${cur}" in
+        /alias/*|/node/*) kind=addresses ;;
+        *)
+            case "${prev}" in
+                --node|-n|--at) kind=nodes ;;
+            esac
+            if [[ ${COMP_CWORD} -eq 3 ]]; then
+                case "${COMP_WORDS[1]} ${COMP_WORDS[2]}" in
+                    "alias remove") kind=aliases ;;
+                    "node show"|"node delete"|"node start"|"node stop"|"node logs"|"node inspect"|"node log-level") kind=nodes ;;
+                esac
+            fi
+            ;;
+    esac
+    if [[ -n "${kind}" ]]; then
+        COMPREPLY=( $(compgen -W "$("${COMP_WORDS[0]}" completion --names "${kind}" 2>/dev/null)" -- "${cur}") )
+        return 0
+    fi
+    _ockam "$@"
+}
+
+complete -F _ockam_names -o bashdefault -o default ockam
+"#;
+
+/// Inserted before the final call of the zsh script, see [`BASH_NAMES`]
+const ZSH_NAMES: &str = r#"functions[_ockam_clap]=$functions[_ockam]
+_ockam() {
+    local kind
+    case $PREFIX in
+        /alias/*|/node/*) kind=addresses ;;
+        *)
+            case $words[CURRENT-1] in
+                --node|-n|--at) kind=nodes ;;
+            esac
+            if (( CURRENT == 4 )); then
+                case "$words[2] $words[3]" in
+                    "alias remove") kind=aliases ;;
+                    "node "(show|delete|start|stop|logs|inspect|log-level)) kind=nodes ;;
+                esac
+            fi
+            ;;
+    esac
+    if [[ -n $kind ]]; then
+        compadd -- ${(f)"$($words[1] completion --names $kind 2>/dev/null)"}
+        return
+    fi
+    _ockam_clap "$@"
+}
+
+"#;
+
+/// See [`BASH_NAMES`]
+const FISH_NAMES: &str = r#"
+complete -c ockam -f -n 'string match -q -r "^/(alias|node)/" -- (commandline -ct)' -a '(ockam completion --names addresses)'
+complete -c ockam -l node -s n -x -a '(ockam completion --names nodes)'
+complete -c ockam -l at -x -a '(ockam completion --names nodes)'
+complete -c ockam -f -n '__fish_seen_subcommand_from node; and __fish_seen_subcommand_from show delete start stop logs inspect log-level' -a '(ockam completion --names nodes)'
+complete -c ockam -f -n '__fish_seen_subcommand_from alias; and __fish_seen_subcommand_from remove' -a '(ockam completion --names aliases)'
+"#;
+
 /// Generate shell completion scripts
 #[derive(Clone, Debug, Args)]
 #[command(arg_required_else_help = true, after_long_help = help::template(HELP_DETAIL))]
 pub struct CompletionCommand {
     /// The type of shell (bash, zsh, fish)
-    #[arg(display_order = 900, long, short)]
-    shell: Shell,
+    #[arg(display_order = 900, long, short, required_unless_present = "names")]
+    shell: Option<Shell>,
+
+    /// Print the existing names of the given kind, for the completion scripts
+    #[arg(long, hide = true, value_enum)]
+    names: Option<Names>,
+}
+
+#[derive(Clone, Copy, Debug, ValueEnum)]
+enum Names {
+    Aliases,
+    Nodes,
+    /// `/alias/<name>` and `/node/<name>` addresses
+    Addresses,
 }
 
 impl CompletionCommand {
-    pub fn run(self) {
-        generate(
-            self.shell,
-            &mut OckamCommand::command(),
-            "ockam",
-            &mut io::stdout(),
-        )
+    pub fn run(self, options: CommandGlobalOpts) {
+        if let Some(kind) = self.names {
+            for name in names(&options, kind) {
+                println!("{name}");
+            }
+            return;
+        }
+        let shell = match self.shell {
+            Some(shell) => shell,
+            None => return,
+        };
+        let mut script = Vec::new();
+        generate(shell, &mut OckamCommand::command(), "ockam", &mut script);
+        let mut script = String::from_utf8_lossy(&script).into_owned();
+        match shell {
+            Shell::Bash => script.push_str(BASH_NAMES),
+            Shell::Zsh => {
+                let end = script.rfind("_ockam \"$@\"").unwrap_or(script.len());
+                script.insert_str(end, ZSH_NAMES)
+            }
+            Shell::Fish => script.push_str(FISH_NAMES),
+            _ => {}
+        }
+        print!("{script}")
+    }
+}
+
+/// The names of the aliases or nodes of the configuration
+fn names(opts: &CommandGlobalOpts, kind: Names) -> Vec<String> {
+    let aliases = || -> Vec<String> {
+        let lookup = opts.config.lookup();
+        lookup.aliases().map(|(name, _)| name.to_string()).collect()
+    };
+    let nodes = || -> Vec<String> { opts.config.inner().nodes.keys().cloned().collect() };
+    match kind {
+        Names::Aliases => aliases(),
+        Names::Nodes => nodes(),
+        Names::Addresses => aliases()
+            .into_iter()
+            .map(|a| format!("/alias/{a}"))
+            .chain(nodes().into_iter().map(|n| format!("/node/{n}")))
+            .collect(),
     }
 }
@@ -0,0 +1,44 @@
+use clap::Args;
+
+use crate::compose::{ComposeFile, HELP_DETAIL};
+use crate::node::util::delete_node;
+use crate::node::DeletedNodes;
+use crate::{help, CommandGlobalOpts};
+
+/// Delete all the nodes of a topology
+#[derive(Clone, Debug, Args)]
+#[command(after_long_help = help::template(HELP_DETAIL))]
+pub struct DownCommand {
+    #[command(flatten)]
+    compose: ComposeFile,
+
+    /// Kill the nodes instead of asking them to stop
+    #[arg(long)]
+    force: bool,
+}
+
+impl DownCommand {
+    pub fn run(self, options: CommandGlobalOpts) {
+        if let Err(e) = run_impl(options, self) {
+            eprintln!("{}", e);
+            crate::util::exit(e.code());
+        }
+    }
+}
+
+fn run_impl(opts: CommandGlobalOpts, cmd: DownCommand) -> crate::Result<()> {
+    let spec = cmd.compose.read()?;
+    // Nodes are deleted in the reverse order to which they were started,
+    // so that no node loses a node it depends on while still running.
+    let mut nodes = vec![];
+    for name in spec.names().rev() {
+        if opts.config.get_node(name).is_err() {
+            continue;
+        }
+        delete_node(&opts, name, cmd.force);
+        nodes.push(name.to_string());
+    }
+    opts.config.persist_config_updates()?;
+    opts.print(&DeletedNodes { nodes })?;
+    Ok(())
+}
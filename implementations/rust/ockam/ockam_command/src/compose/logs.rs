@@ -0,0 +1,57 @@
+use clap::Args;
+
+use crate::compose::{ComposeFile, HELP_DETAIL};
+use crate::node::read_log;
+use crate::{help, CommandGlobalOpts};
+
+/// Show the logs of all the nodes of a topology
+#[derive(Clone, Debug, Args)]
+#[command(after_long_help = help::template(HELP_DETAIL))]
+pub struct LogsCommand {
+    #[command(flatten)]
+    compose: ComposeFile,
+
+    /// Number of lines to show
+    #[arg(long, short = 'n', default_value_t = 50)]
+    lines: usize,
+
+    /// Show the standard error of the nodes instead of their log
+    #[arg(long)]
+    stderr: bool,
+}
+
+impl LogsCommand {
+    pub fn run(self, options: CommandGlobalOpts) {
+        if let Err(e) = run_impl(options, self) {
+            eprintln!("{}", e);
+            crate::util::exit(e.code());
+        }
+    }
+}
+
+fn run_impl(opts: CommandGlobalOpts, cmd: LogsCommand) -> crate::Result<()> {
+    let spec = cmd.compose.read()?;
+    let width = spec.names().map(str::len).max().unwrap_or_default();
+
+    let mut lines = vec![];
+    for name in spec.names() {
+        let (log, stderr) = match opts.config.node_log_paths(name) {
+            Some(paths) => paths,
+            None => continue,
+        };
+        let path = if cmd.stderr { stderr } else { log };
+        lines.extend(read_log(&path)?.into_iter().map(|l| (name, l)));
+    }
+    // Merge the logs of all nodes in time order. The sort is stable, so
+    // lines logged by one node at the same instant keep their order.
+    lines.sort_by_key(|(_, l)| l.timestamp);
+
+    let skip = lines.len().saturating_sub(cmd.lines);
+    for (name, l) in lines.into_iter().skip(skip) {
+        println!(
+            "{name:width$} | {}",
+            l.format(&opts.global_args.output_format)
+        );
+    }
+    Ok(())
+}
@@ -0,0 +1,231 @@
+use anyhow::{anyhow, Context as _};
+use clap::{Args, Subcommand};
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeSet;
+use std::path::{Path, PathBuf};
+
+use down::DownCommand;
+use logs::LogsCommand;
+use status::StatusCommand;
+use up::UpCommand;
+
+use ockam::identity::IdentityIdentifier;
+use ockam_multiaddr::MultiAddr;
+
+use crate::node::{InletSpec, NodeSpec};
+use crate::{help, CommandGlobalOpts};
+
+mod down;
+mod logs;
+mod status;
+mod up;
+
+const HELP_DETAIL: &str = "\
+About:
+    Run a topology of several local nodes described in a single file.
+
+    The file lists the nodes to run, each one with the same settings as accepted by
+    `ockam node apply`. Nodes are started in the order in which they are listed, and
+    are then configured in that same order, so a node can refer to any node listed
+    before it, for instance to connect to it, create an inlet to one of its outlets or
+    register a forwarder on it.
+
+    Secure channels are listed in their own section. Each one is created by its `from`
+    node, once that node is configured, to the secure channel listener at the end of
+    its `to` route, and is only created again if the node has no channel to that route.
+    The inlets of a channel go through it: their `to` route starts at the other end of
+    the channel.
+
+    The file is read as TOML if its extension is `.toml`, and as YAML otherwise.
+
+```yaml
+    nodes:
+      - name: relay
+      - name: server
+        outlets:
+          - from: db
+            to: 127.0.0.1:5432
+        forwarders:
+          - name: server
+            at: /node/relay
+      - name: client
+    channels:
+      - from: client
+        to: /node/relay/service/forward_to_server/service/api
+        inlets:
+          - from: 127.0.0.1:7000
+            to: /service/db
+```
+
+Examples:
+```sh
+    # Start all the nodes of ockam-compose.yaml and configure them
+    $ ockam compose up
+
+    # Show the state of each node
+    $ ockam compose status
+
+    # Show the last lines logged by every node
+    $ ockam compose logs
+
+    # Delete all the nodes
+    $ ockam compose down
+```
+";
+
+/// Run a topology of local nodes
+#[derive(Clone, Debug, Args)]
+#[command(
+    arg_required_else_help = true,
+    subcommand_required = true,
+    after_long_help = help::template(HELP_DETAIL)
+)]
+pub struct ComposeCommand {
+    #[command(subcommand)]
+    subcommand: ComposeSubcommand,
+}
+
+#[derive(Clone, Debug, Subcommand)]
+pub enum ComposeSubcommand {
+    #[command(display_order = 800)]
+    Up(UpCommand),
+    #[command(display_order = 800)]
+    Down(DownCommand),
+    #[command(display_order = 800)]
+    Status(StatusCommand),
+    #[command(display_order = 800)]
+    Logs(LogsCommand),
+}
+
+impl ComposeCommand {
+    pub fn run(self, options: CommandGlobalOpts) {
+        match self.subcommand {
+            ComposeSubcommand::Up(c) => c.run(options),
+            ComposeSubcommand::Down(c) => c.run(options),
+            ComposeSubcommand::Status(c) => c.run(options),
+            ComposeSubcommand::Logs(c) => c.run(options),
+        }
+    }
+}
+
+/// Location of the topology file, shared by all subcommands
+#[derive(Clone, Debug, Args)]
+pub struct ComposeFile {
+    /// Path to the topology, in YAML or TOML
+    #[arg(
+        short,
+        long = "file",
+        id = "FILE",
+        default_value = "ockam-compose.yaml"
+    )]
+    file: PathBuf,
+}
+
+impl ComposeFile {
+    fn read(&self) -> anyhow::Result<ComposeSpec> {
+        ComposeSpec::read(&self.file)
+    }
+}
+
+/// A set of nodes, in start order, and the secure channels between them
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct ComposeSpec {
+    pub(crate) nodes: Vec<NodeSpec>,
+
+    #[serde(default)]
+    pub(crate) channels: Vec<ChannelSpec>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct ChannelSpec {
+    /// Node creating the channel.
+    pub(crate) from: String,
+    /// Route to the secure channel listener.
+    pub(crate) to: MultiAddr,
+    pub(crate) authorized: Option<Vec<IdentityIdentifier>>,
+    /// Inlets of the `from` node, with routes starting at the listener.
+    #[serde(default)]
+    pub(crate) inlets: Vec<InletSpec>,
+}
+
+impl ComposeSpec {
+    fn read<P: AsRef<Path>>(path: P) -> anyhow::Result<Self> {
+        let path = path.as_ref();
+        let s =
+            std::fs::read_to_string(path).with_context(|| anyhow!("failed to read {:?}", path))?;
+        Self::parse(&s, path.extension().map_or(false, |e| e == "toml"))
+            .with_context(|| anyhow!("invalid topology {:?}", path))
+    }
+
+    fn parse(s: &str, toml: bool) -> anyhow::Result<Self> {
+        let spec: ComposeSpec = if toml {
+            toml::from_str(s)?
+        } else {
+            serde_yaml::from_str(s)?
+        };
+        let mut names = BTreeSet::new();
+        for n in &spec.nodes {
+            let name = n
+                .name
+                .as_deref()
+                .ok_or_else(|| anyhow!("every node must have a name"))?;
+            if !names.insert(name) {
+                return Err(anyhow!("node {name} is listed more than once"));
+            }
+        }
+        if let Some(c) = spec.channels.iter().find(|c| !names.contains(&*c.from)) {
+            return Err(anyhow!(
+                "channel to {} is from unknown node {}",
+                c.to,
+                c.from
+            ));
+        }
+        Ok(spec)
+    }
+
+    /// The node names, in start order
+    fn names(&self) -> impl DoubleEndedIterator<Item = &str> {
+        self.nodes.iter().filter_map(|n| n.name.as_deref())
+    }
+
+    /// The channels created by the node `name`
+    fn channels_from<'a>(&'a self, name: &'a str) -> impl Iterator<Item = &'a ChannelSpec> {
+        self.channels.iter().filter(move |c| c.from == name)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn nodes_are_named_once() {
+        let spec = ComposeSpec::parse("nodes:\n  - name: a\n  - name: b\n", false).unwrap();
+        assert_eq!(spec.names().collect::<Vec<_>>(), ["a", "b"]);
+        assert!(ComposeSpec::parse("nodes:\n  - name: a\n  - {}\n", false).is_err());
+        assert!(ComposeSpec::parse("nodes:\n  - name: a\n  - name: a\n", false).is_err());
+        assert!(ComposeSpec::parse("[[nodes]]\nname = \"a\"\n", true).is_ok());
+    }
+
+    #[test]
+    fn channels_are_from_listed_nodes() {
+        let yaml = r#"
+nodes:
+  - name: a
+  - name: b
+channels:
+  - from: b
+    to: /node/a/service/api
+    inlets:
+      - from: 127.0.0.1:7000
+        to: /service/db
+"#;
+        let spec = ComposeSpec::parse(yaml, false).unwrap();
+        assert_eq!(spec.channels_from("b").count(), 1);
+        assert_eq!(spec.channels_from("a").count(), 0);
+        assert_eq!(spec.channels[0].inlets[0].to.to_string(), "/service/db");
+        assert!(ComposeSpec::parse(&yaml.replace("from: b", "from: c"), false).is_err());
+    }
+}
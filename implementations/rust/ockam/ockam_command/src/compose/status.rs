@@ -0,0 +1,125 @@
+use clap::Args;
+use cli_table::{Cell, CellStruct, Style, Table};
+use serde::Serialize;
+
+use ockam::Context;
+use ockam_api::nodes::models::forwarder::ForwarderList;
+use ockam_api::nodes::models::portal::{InletList, OutletList};
+
+use crate::compose::{ComposeFile, HELP_DETAIL};
+use crate::node::is_node_up;
+use crate::util::output::Output;
+use crate::util::{api, node_rpc, tcp_transport, Rpc, RpcBuilder};
+use crate::{help, CommandGlobalOpts};
+
+/// Show the state of all the nodes of a topology
+#[derive(Clone, Debug, Args)]
+#[command(after_long_help = help::template(HELP_DETAIL))]
+pub struct StatusCommand {
+    #[command(flatten)]
+    compose: ComposeFile,
+}
+
+impl StatusCommand {
+    pub fn run(self, options: CommandGlobalOpts) {
+        node_rpc(run_impl, (options, self))
+    }
+}
+
+/// State of one node of the topology
+#[derive(Debug, Serialize)]
+struct NodeStatus {
+    name: String,
+    status: &'static str,
+    pid: Option<i32>,
+    port: Option<u16>,
+    inlets: Option<usize>,
+    outlets: Option<usize>,
+    forwarders: Option<usize>,
+}
+
+async fn run_impl(
+    ctx: Context,
+    (opts, cmd): (CommandGlobalOpts, StatusCommand),
+) -> crate::Result<()> {
+    let spec = cmd.compose.read()?;
+    let tcp = tcp_transport(&ctx).await?;
+
+    let mut nodes = vec![];
+    for name in spec.names() {
+        let mut status = NodeStatus {
+            name: name.to_string(),
+            status: "Missing",
+            pid: None,
+            port: None,
+            inlets: None,
+            outlets: None,
+            forwarders: None,
+        };
+        if let Ok(node) = opts.config.get_node(name) {
+            status.pid = node.pid();
+            status.port = Some(node.port());
+            let mut rpc = RpcBuilder::new(&ctx, &opts, name).tcp(&tcp)?.build();
+            if is_node_up(&mut rpc, false).await? {
+                status.status = "Up";
+                let [inlets, outlets, forwarders] = count_portals(&rpc).await?;
+                status.inlets = Some(inlets);
+                status.outlets = Some(outlets);
+                status.forwarders = Some(forwarders);
+            } else {
+                status.status = "Down";
+            }
+        }
+        nodes.push(status);
+    }
+    opts.print(&nodes)?;
+    Ok(())
+}
+
+/// Number of inlets, outlets and forwarders of a node
+async fn count_portals(rpc: &Rpc<'_>) -> anyhow::Result<[usize; 3]> {
+    let mut rpc = rpc.clone();
+    rpc.request(api::list_inlets()).await?;
+    let inlets = rpc.parse_response::<InletList>()?.list.len();
+    let mut rpc = rpc.clone();
+    rpc.request(api::list_outlets()).await?;
+    let outlets = rpc.parse_response::<OutletList>()?.list.len();
+    let mut rpc = rpc.clone();
+    rpc.request(api::list_forwarders()).await?;
+    let forwarders = rpc.parse_response::<ForwarderList>()?.list.len();
+    Ok([inlets, outlets, forwarders])
+}
+
+impl Output for Vec<NodeStatus> {
+    fn output(&self) -> anyhow::Result<String> {
+        fn opt<T: ToString>(v: Option<T>) -> CellStruct {
+            v.map_or_else(|| "-".to_string(), |v| v.to_string()).cell()
+        }
+        let mut rows = vec![];
+        for n in self {
+            rows.push([
+                (&n.name).cell(),
+                n.status.cell(),
+                opt(n.pid),
+                opt(n.port),
+                opt(n.inlets),
+                opt(n.outlets),
+                opt(n.forwarders),
+            ]);
+        }
+        let table = rows
+            .table()
+            .title([
+                "Node".cell().bold(true),
+                "Status".cell().bold(true),
+                "PID".cell().bold(true),
+                "API Port".cell().bold(true),
+                "Inlets".cell().bold(true),
+                "Outlets".cell().bold(true),
+                "Forwarders".cell().bold(true),
+            ])
+            .display()?
+            .to_string();
+        Ok(table)
+    }
+}
@@ -0,0 +1,190 @@
+use anyhow::anyhow;
+use clap::Args;
+use serde::Serialize;
+
+use ockam::{route, Context, TcpTransport};
+use ockam_api::nodes::models::secure_channel::{
+    CreateSecureChannelResponse, CredentialExchangeMode, ShowSecureChannelResponse,
+};
+use ockam_api::{multiaddr_to_route, route_to_multiaddr};
+use ockam_core::Address;
+use ockam_multiaddr::MultiAddr;
+
+use crate::compose::{ChannelSpec, ComposeFile, HELP_DETAIL};
+use crate::node::{
+    apply_spec, create_node, plan_new_node, start_node, summary, ApplyReport, InletSpec, NodeSpec,
+};
+use crate::util::output::Output;
+use crate::util::{api, exitcode, node_rpc, resolve_names, tcp_transport, Rpc, RpcBuilder};
+use crate::{help, CommandGlobalOpts};
+
+/// Start and configure all the nodes of a topology
+#[derive(Clone, Debug, Args)]
+#[command(after_long_help = help::template(HELP_DETAIL))]
+pub struct UpCommand {
+    #[command(flatten)]
+    compose: ComposeFile,
+
+    /// Only print the changes that would be made
+    #[arg(long)]
+    dry_run: bool,
+}
+
+impl UpCommand {
+    pub fn run(self, options: CommandGlobalOpts) {
+        node_rpc(run_impl, (options, self))
+    }
+}
+
+async fn run_impl(ctx: Context, (opts, cmd): (CommandGlobalOpts, UpCommand)) -> crate::Result<()> {
+    let spec = cmd.compose.read()?;
+    let tcp = tcp_transport(&ctx).await?;
+
+    // All nodes are started before any of them is configured, since
+    // their configurations can refer to each other.
+    let mut reports = vec![];
+    for node in &spec.nodes {
+        let name = node.name.as_deref().unwrap_or_default();
+        let mut report = ApplyReport::new(name, cmd.dry_run);
+        if opts.config.get_node(name).is_err() {
+            opts.progress(format_args!("+ node {name}"));
+            report.created = true;
+            if !cmd.dry_run {
+                create_node(&ctx, &opts, &tcp, name, node).await?;
+            }
+            reports.push(report);
+            continue;
+        }
+        start_node(&ctx, &opts, &tcp, &mut report).await?;
+        reports.push(report);
+    }
+
+    for (node, report) in spec.nodes.iter().zip(reports.iter_mut()) {
+        opts.progress(format_args!("== {}", report.node));
+        if report.created && cmd.dry_run {
+            plan_new_node(&opts, node, report).await?;
+        } else {
+            apply_spec(&ctx, &opts, &tcp, node, report).await?;
+        }
+        for channel in spec.channels_from(node.name.as_deref().unwrap_or_default()) {
+            apply_channel(&ctx, &opts, &tcp, channel, report).await?;
+        }
+    }
+
+    let topology = Topology {
+        dry_run: cmd.dry_run,
+        nodes: reports,
+    };
+    opts.print(&topology)?;
+    if topology.nodes.iter().any(|r| !r.conflicts.is_empty()) {
+        return Err(crate::Error::new(
+            exitcode::DATAERR,
+            anyhow!("the nodes do not match {:?}", cmd.compose.file),
+        ));
+    }
+    Ok(())
+}
+
+/// Create a channel, unless the node already has one to the same route, then its inlets
+async fn apply_channel(
+    ctx: &Context,
+    opts: &CommandGlobalOpts,
+    tcp: &TcpTransport,
+    channel: &ChannelSpec,
+    report: &mut ApplyReport,
+) -> crate::Result<()> {
+    // Without the node, planning only lists the changes
+    let rpc = if report.created && report.dry_run {
+        None
+    } else {
+        Some(RpcBuilder::new(ctx, opts, &report.node).tcp(tcp)?.build())
+    };
+    // The nodes of the route may only be created by this run
+    let to = match resolve_names(&channel.to, &opts.config.lookup()) {
+        Ok(to) => Some(to),
+        Err(_) if report.dry_run => None,
+        Err(e) => return Err(e.into()),
+    };
+    let existing = match (&rpc, &to) {
+        (Some(rpc), Some(to)) => find_channel(rpc, to).await?,
+        _ => None,
+    };
+    let addr = match existing {
+        Some(addr) => addr,
+        None => {
+            let what = format!("secure-channel {}", channel.to);
+            opts.progress(format_args!("+ {what}"));
+            report.changes.push(what);
+            match (rpc, to) {
+                (Some(mut rpc), Some(to)) if !report.dry_run => {
+                    let req = api::create_secure_channel(
+                        &to,
+                        channel.authorized.clone(),
+                        CredentialExchangeMode::Mutual,
+                    );
+                    rpc.request(req).await?;
+                    let res = rpc.parse_response::<CreateSecureChannelResponse>()?;
+                    Address::from_string(res.addr.as_ref())
+                }
+                // The inlets are only listed, so their route doesn't matter
+                _ => Address::from_string("secure_channel"),
+            }
+        }
+    };
+
+    let start = route_to_multiaddr(&route![addr.clone()])
+        .ok_or_else(|| anyhow!("invalid secure channel address {addr}"))?;
+    let mut inlets = vec![];
+    for i in &channel.inlets {
+        let mut to = start.clone();
+        to.try_extend(i.to.iter())?;
+        inlets.push(InletSpec { to, ..i.clone() });
+    }
+    let spec = NodeSpec {
+        name: Some(report.node.clone()),
+        inlets,
+        ..Default::default()
+    };
+    if report.created && report.dry_run {
+        plan_new_node(opts, &spec, report).await?;
+    } else {
+        apply_spec(ctx, opts, tcp, &spec, report).await?;
+    }
+    Ok(())
+}
+
+/// The address of a secure channel of the node to the listener at `to`
+async fn find_channel(rpc: &Rpc<'_>, to: &MultiAddr) -> anyhow::Result<Option<Address>> {
+    let route = multiaddr_to_route(to).map(|r| r.to_string());
+    let mut list = rpc.clone();
+    list.request(api::list_secure_channels()).await?;
+    for addr in list.parse_response::<Vec<String>>()? {
+        let addr = Address::from_string(addr);
+        let mut show = rpc.clone();
+        show.request(api::show_secure_channel(&addr)).await?;
+        let res = show.parse_response::<ShowSecureChannelResponse>()?;
+        if res.route.map(|r| r.to_string()) == route {
+            return Ok(Some(addr));
+        }
+    }
+    Ok(None)
+}
+
+/// What `compose up` changed on the nodes of a topology
+#[derive(Debug, Serialize)]
+struct Topology {
+    dry_run: bool,
+    nodes: Vec<ApplyReport>,
+}
+
+impl Output for Topology {
+    fn output(&self) -> anyhow::Result<String> {
+        let changes = self.nodes.iter().map(|r| r.changes.len()).sum();
+        let conflicts = self.nodes.iter().map(|r| r.conflicts.len()).sum();
+        Ok(format!(
+            "{} node(s), {}",
+            self.nodes.len(),
+            summary(changes, conflicts, self.dry_run)
+        ))
+    }
+}
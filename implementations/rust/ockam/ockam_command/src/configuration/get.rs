@@ -1,3 +1,4 @@
+use crate::configuration::NodeAddress;
 use crate::{util::exitcode, CommandGlobalOpts};
 use anyhow::anyhow;
 use clap::Args;
@@ -12,7 +13,7 @@ impl GetCommand {
     pub fn run(self, options: CommandGlobalOpts) {
         if let Err(e) = run_impl(options, self) {
             eprintln!("{}", e);
-            std::process::exit(e.code());
+            crate::util::exit(e.code());
         }
     }
 }
@@ -21,7 +22,10 @@ fn run_impl(options: CommandGlobalOpts, cmd: GetCommand) -> crate::Result<()> {
     let lookup = options.config.lookup();
     match lookup.get_node(&cmd.alias) {
         Some(addr) => {
-            println!("Node: {}\nAddress: {}", cmd.alias, addr);
+            options.print(&NodeAddress {
+                node: cmd.alias,
+                address: addr.to_string(),
+            })?;
             Ok(())
         }
         None => Err(crate::error::Error::new(
@@ -1,7 +1,9 @@
 use anyhow::anyhow;
 use clap::Args;
+use serde::Serialize;
 
 use crate::exitcode::UNAVAILABLE;
+use crate::util::output::Output;
 use crate::CommandGlobalOpts;
 
 #[derive(Clone, Debug, Args)]
@@ -11,7 +13,7 @@ impl GetDefaultNodeCommand {
     pub fn run(self, options: CommandGlobalOpts) {
         if let Err(e) = run_impl(options) {
             eprintln!("{}", e);
-            std::process::exit(e.code());
+            crate::util::exit(e.code());
         }
     }
 }
@@ -19,7 +21,7 @@ impl GetDefaultNodeCommand {
 fn run_impl(opts: CommandGlobalOpts) -> crate::Result<()> {
     match opts.config.get_default_node() {
         Some(name) => {
-            println!("Current Default Node: {}", name);
+            opts.print(&DefaultNode { node: name })?;
             Ok(())
         }
         None => Err(crate::error::Error::new(
@@ -28,3 +30,15 @@ fn run_impl(opts: CommandGlobalOpts) -> crate::Result<()> {
         )),
     }
 }
+
+/// The node used when a command is not given one
+#[derive(Debug, Serialize)]
+struct DefaultNode {
+    node: String,
+}
+
+impl Output for DefaultNode {
+    fn output(&self) -> anyhow::Result<String> {
+        Ok(format!("Current Default Node: {}", self.node))
+    }
+}
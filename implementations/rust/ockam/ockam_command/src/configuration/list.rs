@@ -1,3 +1,4 @@
+use crate::configuration::NodeAddress;
 use crate::CommandGlobalOpts;
 use clap::Args;
 use ockam_api::config::lookup::LookupValue;
@@ -9,14 +10,22 @@ impl ListCommand {
     pub fn run(self, options: CommandGlobalOpts) {
         let lookup = options.config.lookup();
 
+        let mut nodes = vec![];
         for (alias, value) in &lookup.map {
             // Currently we only have this one type of lookup but we
             // need to be ready for more values.  Remove this "allow"
             // in the future
             #[allow(irrefutable_let_patterns)]
             if let LookupValue::Address(addr) = value {
-                println!("Node:    {}\nAddress: {}\n", alias, addr);
+                nodes.push(NodeAddress {
+                    node: alias.to_string(),
+                    address: addr.to_string(),
+                });
             }
         }
+        if let Err(e) = options.print(&nodes) {
+            eprintln!("{}", e);
+            crate::util::exit(crate::util::exitcode::SOFTWARE);
+        }
     }
 }
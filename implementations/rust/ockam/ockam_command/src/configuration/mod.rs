@@ -11,8 +11,10 @@ use set::SetCommand;
 use set_default_node::SetDefaultNodeCommand;
 
 use crate::help;
+use crate::util::output::Output;
 use crate::CommandGlobalOpts;
 use clap::{Args, Subcommand};
+use serde::Serialize;
 
 const HELP_DETAIL: &str = "";
 
@@ -43,3 +45,26 @@ impl ConfigurationCommand {
         }
     }
 }
+
+/// The address a node name resolves to
+#[derive(Debug, Serialize)]
+struct NodeAddress {
+    node: String,
+    address: String,
+}
+
+impl Output for NodeAddress {
+    fn output(&self) -> anyhow::Result<String> {
+        Ok(format!("Node: {}\nAddress: {}", self.node, self.address))
+    }
+}
+
+impl Output for Vec<NodeAddress> {
+    fn output(&self) -> anyhow::Result<String> {
+        let nodes: Vec<_> = self
+            .iter()
+            .map(|n| format!("Node:    {}\nAddress: {}\n", n.node, n.address))
+            .collect();
+        Ok(nodes.join("\n"))
+    }
+}
@@ -16,7 +16,7 @@ impl SetCommand {
     pub fn run(self, options: CommandGlobalOpts) {
         if let Err(e) = run_impl(options, self) {
             eprintln!("{}", e);
-            std::process::exit(e.code());
+            crate::util::exit(e.code());
         }
     }
 }
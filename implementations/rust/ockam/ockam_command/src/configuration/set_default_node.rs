@@ -11,7 +11,7 @@ impl SetDefaultNodeCommand {
     pub fn run(self, options: CommandGlobalOpts) {
         if let Err(e) = run_impl(&self.name, &options) {
             eprintln!("{}", e);
-            std::process::exit(e.code());
+            crate::util::exit(e.code());
         }
     }
 }
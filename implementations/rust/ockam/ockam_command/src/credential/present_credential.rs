@@ -5,7 +5,7 @@ use ockam_multiaddr::MultiAddr;
 
 use crate::node::NodeOpts;
 use crate::util::api::{self};
-use crate::util::{node_rpc, Rpc};
+use crate::util::{node_rpc, resolve_aliases, Rpc};
 use crate::CommandGlobalOpts;
 
 #[derive(Clone, Debug, Args)]
@@ -18,6 +18,14 @@ pub struct PresentCredentialCommand {
 
     #[arg(short, long)]
     pub oneway: bool,
+
+    /// Only disclose the given attributes.
+    ///
+    /// They are not derived from the verifier's policy, so list every attribute
+    /// it checks. In a mutual exchange the other party still decides what its
+    /// own credential discloses.
+    #[arg(long = "attribute", value_name = "NAME")]
+    pub attributes: Vec<String>,
 }
 
 impl PresentCredentialCommand {
@@ -38,8 +46,13 @@ async fn run_impl(
     opts: CommandGlobalOpts,
     cmd: PresentCredentialCommand,
 ) -> crate::Result<()> {
+    let to = resolve_aliases(&cmd.to, &opts.config.lookup())?;
     let mut rpc = Rpc::background(ctx, &opts, &cmd.node_opts.api_node)?;
-    rpc.request(api::credentials::present_credential(&cmd.to, cmd.oneway))
-        .await?;
+    rpc.request(api::credentials::present_credential(
+        &to,
+        cmd.oneway,
+        &cmd.attributes,
+    ))
+    .await?;
     Ok(())
 }
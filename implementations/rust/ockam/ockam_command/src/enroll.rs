@@ -6,6 +6,7 @@ use std::io::stdin;
 
 use colorful::Colorful;
 use reqwest::StatusCode;
+use serde::Serialize;
 use tokio::time::{sleep, Duration};
 use tokio_retry::{strategy::ExponentialBackoff, Retry};
 use tracing::{debug, info};
@@ -51,12 +52,30 @@ async fn run_impl(ctx: &Context, opts: CommandGlobalOpts, cmd: EnrollCommand) ->
 
     let cloud_opts = cmd.cloud_opts.clone();
     let space = default_space(ctx, &opts, &cloud_opts, &node_name).await?;
-    default_project(ctx, &opts, &cloud_opts, &node_name, &space).await?;
+    let project = default_project(ctx, &opts, &cloud_opts, &node_name, &space).await?;
     delete_embedded_node(&opts.config, &node_name).await;
 
+    opts.print(&Enrolled { space, project })?;
     Ok(())
 }
 
+/// The default space and project of an enrolled identity
+#[derive(Debug, Serialize)]
+struct Enrolled<'a> {
+    space: Space<'a>,
+    project: Project<'a>,
+}
+
+impl Output for Enrolled<'_> {
+    fn output(&self) -> anyhow::Result<String> {
+        Ok(format!(
+            "\n{}\n{}",
+            self.space.output()?,
+            self.project.output()?
+        ))
+    }
+}
+
 async fn enroll(
     ctx: &Context,
     opts: &CommandGlobalOpts,
@@ -99,13 +118,12 @@ async fn default_space<'a>(
             name: crate::space::random_name(),
             admins: vec![],
         };
-        println!(
+        opts.progress(format!(
             "\n{}",
             "Creating a trial space for you (everything in it will be deleted in 15 days) ..."
                 .light_magenta()
-        );
-        println!(
-            "{}",
+        ));
+        opts.progress(
             "To learn more about production ready spaces in Ockam Orchestrator, contact us at: hello@ockam.io".light_magenta()
         );
 
@@ -122,7 +140,6 @@ async fn default_space<'a>(
             .to_owned()
     };
     config::set_space(&opts.config, &default_space)?;
-    println!("\n{}", default_space.output()?);
     Ok(default_space)
 }
 
@@ -164,7 +181,6 @@ async fn default_project<'a>(
     };
     let project =
         check_project_readiness(ctx, opts, cloud_opts, node_name, None, default_project).await?;
-    println!("{}", project.output()?);
     Ok(project)
 }
 
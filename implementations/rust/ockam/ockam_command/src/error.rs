@@ -53,7 +53,15 @@ impl From<ockam::Error> for Error {
 
 impl From<anyhow::Error> for Error {
     fn from(e: anyhow::Error) -> Self {
-        Error::new(exitcode::SOFTWARE, e)
+        // Keep the exit code of an error that went through an `anyhow::Result`
+        let e = match e.downcast::<Error>() {
+            Ok(e) => return e,
+            Err(e) => e,
+        };
+        match e.downcast::<ConfigError>() {
+            Ok(e) => e.into(),
+            Err(e) => Error::new(exitcode::SOFTWARE, e),
+        }
     }
 }
 
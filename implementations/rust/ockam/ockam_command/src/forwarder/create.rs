@@ -4,15 +4,17 @@ use ockam::identity::IdentityIdentifier;
 use ockam_multiaddr::proto::Project;
 use rand::prelude::random;
 
-use ockam::{Context, TcpTransport};
+use ockam::Context;
 use ockam_api::is_local_node;
 use ockam_api::nodes::models::forwarder::{CreateForwarder, ForwarderInfo};
 use ockam_core::api::Request;
-use ockam_multiaddr::{proto::Node, MultiAddr, Protocol};
+use ockam_multiaddr::{MultiAddr, Protocol};
 
 use crate::forwarder::HELP_DETAIL;
 use crate::util::output::Output;
-use crate::util::{extract_address_value, node_rpc, RpcBuilder};
+use crate::util::{
+    extract_address_value, node_rpc, resolve_aliases, resolve_names, tcp_transport, RpcBuilder,
+};
 use crate::Result;
 use crate::{help, CommandGlobalOpts};
 
@@ -47,45 +49,10 @@ impl CreateCommand {
 }
 
 async fn rpc(ctx: Context, (opts, cmd): (CommandGlobalOpts, CreateCommand)) -> Result<()> {
-    let tcp = TcpTransport::create(&ctx).await?;
+    let tcp = tcp_transport(&ctx).await?;
     let api_node = extract_address_value(&cmd.to)?;
-    let at_rust_node = is_local_node(&cmd.at).context("Argument --at is not valid")?;
-
-    let lookup = opts.config.lookup();
-
-    let mut ma = MultiAddr::default();
-
-    for proto in cmd.at.iter() {
-        match proto.code() {
-            Node::CODE => {
-                let alias = proto
-                    .cast::<Node>()
-                    .ok_or_else(|| anyhow!("invalid node address protocol"))?;
-                let addr = lookup
-                    .node_address(&alias)
-                    .ok_or_else(|| anyhow!("no address for node {}", &*alias))?;
-                ma.try_extend(&addr)?
-            }
-            _ => ma.push_back_value(&proto)?,
-        }
-    }
-
-    let req = {
-        let alias = if at_rust_node {
-            format!("forward_to_{}", cmd.forwarder_name)
-        } else {
-            cmd.forwarder_name.clone()
-        };
-        let body = if cmd.at.matches(0, &[Project::CODE.into()]) {
-            if cmd.authorized.is_some() {
-                return Err(anyhow!("--authorized can not be used with project addresses").into());
-            }
-            CreateForwarder::at_project(ma, Some(alias))
-        } else {
-            CreateForwarder::at_node(ma, Some(alias), at_rust_node, cmd.authorized)
-        };
-        Request::post("/node/forwarder").body(body)
-    };
+    let body = make_api_body(&opts, &cmd.forwarder_name, &cmd.at, cmd.authorized)?;
+    let req = Request::post("/node/forwarder").body(body);
 
     let mut rpc = RpcBuilder::new(&ctx, &opts, &api_node).tcp(&tcp)?.build();
     rpc.request(req).await?;
@@ -94,6 +61,42 @@ async fn rpc(ctx: Context, (opts, cmd): (CommandGlobalOpts, CreateCommand)) -> R
     Ok(())
 }
 
+/// Construct the body of a request creating forwarder `name` at `at`
+///
+/// `/alias` and `/node` protocols in `at` are resolved to the address
+/// they stand for.
+pub(crate) fn make_api_body(
+    opts: &CommandGlobalOpts,
+    name: &str,
+    at: &MultiAddr,
+    authorized: Option<IdentityIdentifier>,
+) -> Result<CreateForwarder<'static>> {
+    let lookup = opts.config.lookup();
+    let at = &resolve_aliases(at, &lookup)?;
+    let at_rust_node = is_local_node(at).context("Argument --at is not valid")?;
+
+    let ma = resolve_names(at, &lookup)?;
+
+    let alias = if at_rust_node {
+        format!("forward_to_{name}")
+    } else {
+        name.to_string()
+    };
+    if at.matches(0, &[Project::CODE.into()]) {
+        if authorized.is_some() {
+            return Err(anyhow!("--authorized can not be used with project addresses").into());
+        }
+        Ok(CreateForwarder::at_project(ma, Some(alias)))
+    } else {
+        Ok(CreateForwarder::at_node(
+            ma,
+            Some(alias),
+            at_rust_node,
+            authorized,
+        ))
+    }
+}
+
 impl Output for ForwarderInfo<'_> {
     fn output(&self) -> anyhow::Result<String> {
         Ok(format!("/service/{}", self.remote_address()))
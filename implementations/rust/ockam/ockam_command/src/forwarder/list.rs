@@ -0,0 +1,33 @@
+use clap::Args;
+
+use ockam::Context;
+use ockam_api::nodes::models::forwarder::ForwarderList;
+
+use crate::forwarder::HELP_DETAIL;
+use crate::node::NodeOpts;
+use crate::util::{api, extract_address_value, node_rpc, tcp_transport, RpcBuilder};
+use crate::Result;
+use crate::{help, CommandGlobalOpts};
+
+/// List Forwarders created by a node
+#[derive(Clone, Debug, Args)]
+#[command(after_long_help = help::template(HELP_DETAIL))]
+pub struct ListCommand {
+    #[command(flatten)]
+    node_opts: NodeOpts,
+}
+
+impl ListCommand {
+    pub fn run(self, options: CommandGlobalOpts) {
+        node_rpc(rpc, (options, self));
+    }
+}
+
+async fn rpc(ctx: Context, (opts, cmd): (CommandGlobalOpts, ListCommand)) -> Result<()> {
+    let tcp = tcp_transport(&ctx).await?;
+    let node_name = extract_address_value(&cmd.node_opts.api_node)?;
+    let mut rpc = RpcBuilder::new(&ctx, &opts, &node_name).tcp(&tcp)?.build();
+    rpc.request(api::list_forwarders()).await?;
+    rpc.parse_and_print_response::<ForwarderList>()?;
+    Ok(())
+}
@@ -1,10 +1,12 @@
 use clap::{Args, Subcommand};
 
-pub(crate) use create::CreateCommand;
+pub(crate) use create::{make_api_body, CreateCommand};
+use list::ListCommand;
 
 use crate::{help, CommandGlobalOpts};
 
 mod create;
+mod list;
 
 const HELP_DETAIL: &str = "\
 About:
@@ -23,6 +25,9 @@ About:
 
     # Send a message to the uppercase service on blue via its forwarder on green
     $ ockam message send hello --to /node/green/service/forward_to_blue/service/uppercase
+
+    # List the forwarders created by blue
+    $ ockam forwarder list --node blue
 ```
 
     This can be very useful in establishing communication between applications
@@ -62,12 +67,14 @@ pub struct ForwarderCommand {
 #[derive(Clone, Debug, Subcommand)]
 pub enum ForwarderSubCommand {
     Create(CreateCommand),
+    List(ListCommand),
 }
 
 impl ForwarderCommand {
     pub fn run(self, opts: CommandGlobalOpts) {
         match self.subcommand {
             ForwarderSubCommand::Create(c) => c.run(opts),
+            ForwarderSubCommand::List(c) => c.run(opts),
         }
     }
 }
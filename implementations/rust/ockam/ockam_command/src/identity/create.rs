@@ -1,8 +1,12 @@
 use crate::help;
 use crate::node::NodeOpts;
+use crate::util::exitcode;
 use crate::util::{node_rpc, Rpc};
+use crate::vault::util::{open_vault, vault_path};
 use crate::CommandGlobalOpts;
+use anyhow::anyhow;
 use clap::Args;
+use ockam::identity::Identity;
 use ockam::Context;
 use ockam_api::nodes::models::identity::CreateIdentityResponse;
 use ockam_core::api::Request;
@@ -12,6 +16,15 @@ use ockam_core::api::Request;
 pub struct CreateCommand {
     #[command(flatten)]
     node_opts: NodeOpts,
+
+    /// Create the default identity in the vault with this name, instead of on a node.
+    /// Its keys stay on the PKCS#11 token of vaults created with `--hsm-module`
+    #[arg(long, value_name = "NAME")]
+    vault: Option<String>,
+
+    /// Replace the existing default identity
+    #[arg(long, requires = "vault")]
+    force: bool,
 }
 
 impl CreateCommand {
@@ -24,10 +37,47 @@ async fn run_impl(
     ctx: Context,
     (options, cmd): (CommandGlobalOpts, CreateCommand),
 ) -> crate::Result<()> {
+    if let Some(vault) = &cmd.vault {
+        return create_default_identity(&ctx, &options, vault, cmd.force).await;
+    }
     let mut rpc = Rpc::background(&ctx, &options, &cmd.node_opts.api_node)?;
     let request = Request::post("/node/identity");
     rpc.request(request).await?;
-    let res = rpc.parse_response::<CreateIdentityResponse>()?;
-    println!("Identity {} created!", res.identity_id);
+    rpc.parse_and_print_response::<CreateIdentityResponse>()?;
+    Ok(())
+}
+
+/// Create the default identity, used by the nodes created next, in a named vault
+async fn create_default_identity(
+    ctx: &Context,
+    opts: &CommandGlobalOpts,
+    vault_name: &str,
+    force: bool,
+) -> crate::Result<()> {
+    let cfg = &opts.config;
+    if cfg.get_default_identity().is_some() && !force {
+        return Err(crate::error::Error::new(
+            exitcode::CANTCREAT,
+            anyhow!("A default identity already exists. Use --force to replace it"),
+        ));
+    }
+    let path = vault_path(vault_name);
+    if !path.exists() {
+        return Err(crate::error::Error::new(
+            exitcode::CONFIG,
+            anyhow!("Vault with name {vault_name} was not found"),
+        ));
+    }
+
+    let vault = open_vault(path.clone()).await?;
+    let identity = Identity::create(ctx, &vault).await?;
+
+    cfg.set_default_vault_path(Some(path));
+    cfg.set_default_identity(Some(identity.export().await?));
+    cfg.persist_config_updates()?;
+
+    opts.print(&CreateIdentityResponse::new(
+        identity.identifier().to_string(),
+    ))?;
     Ok(())
 }
@@ -0,0 +1,115 @@
+use crate::util::node_rpc;
+use crate::util::output::Output;
+use crate::vault::util::{open_vault, read_passphrase};
+use crate::CommandGlobalOpts;
+use anyhow::{anyhow, Context as _};
+use clap::Args;
+use ockam::identity::Identity;
+use ockam::Context;
+use ockam_vault::storage::{SealedData, StorageKey};
+use serde::{Deserialize, Serialize};
+use std::path::PathBuf;
+
+/// Additional authenticated data binding the encrypted bundle to its format.
+pub(super) const BUNDLE_AAD: &[u8] = b"ockam identity bundle v1";
+
+/// On-disk format of an identity exported with its secrets.
+#[derive(Serialize, Deserialize)]
+pub(super) struct EncryptedBundle {
+    pub(super) version: u8,
+    #[serde(flatten)]
+    pub(super) sealed: SealedData,
+}
+
+/// Export an identity, optionally with its secret keys
+#[derive(Clone, Debug, Args)]
+pub struct ExportCommand {
+    /// Export the identity of this node instead of the default identity
+    #[arg(long)]
+    node: Option<String>,
+
+    /// Include the secret keys, encrypted with a passphrase read from the terminal
+    #[arg(long)]
+    with_secrets: bool,
+
+    /// Encrypt the secret keys with a key derived from the contents of this file
+    #[arg(long, requires = "with_secrets")]
+    key_file: Option<PathBuf>,
+
+    /// Write the export to this file instead of standard output
+    #[arg(long)]
+    file: Option<PathBuf>,
+}
+
+impl ExportCommand {
+    pub fn run(self, options: CommandGlobalOpts) {
+        node_rpc(run_impl, (options, self))
+    }
+}
+
+async fn run_impl(
+    ctx: Context,
+    (opts, cmd): (CommandGlobalOpts, ExportCommand),
+) -> crate::Result<()> {
+    let (vault_path, exported) = match &cmd.node {
+        Some(name) => {
+            let node = opts.config.node(name)?;
+            let state = node.state().read();
+            (
+                state.vault_path.clone().context("Node has no vault")?,
+                state.identity.clone().context("Node has no identity")?,
+            )
+        }
+        None => (
+            opts.config
+                .get_default_vault_path()
+                .context("Default vault was not found")?,
+            opts.config
+                .get_default_identity()
+                .context("Default identity was not found")?,
+        ),
+    };
+
+    let output = if cmd.with_secrets {
+        let vault = open_vault(vault_path).await?;
+        let identity = Identity::import(&ctx, &exported, &vault).await?;
+        let bundle = identity.export_with_secrets().await?.encode()?;
+
+        let key = match cmd.key_file {
+            Some(path) => StorageKey::KeyFile(path),
+            None => read_passphrase("Bundle passphrase", true)?,
+        };
+        Exported::Bundle(EncryptedBundle {
+            version: 1,
+            sealed: SealedData::seal(&key, &bundle, BUNDLE_AAD)?,
+        })
+    } else {
+        Exported::Identity {
+            identity: hex::encode(exported),
+        }
+    };
+
+    match cmd.file {
+        Some(path) => std::fs::write(&path, output.output()?)
+            .map_err(|e| anyhow!("Failed to write {}: {e}", path.display()))?,
+        None => opts.print(&output)?,
+    }
+    Ok(())
+}
+
+/// An exported identity, as hex, or as an encrypted bundle when it has its secrets
+#[derive(Serialize)]
+#[serde(untagged)]
+enum Exported {
+    Identity { identity: String },
+    Bundle(EncryptedBundle),
+}
+
+impl Output for Exported {
+    fn output(&self) -> anyhow::Result<String> {
+        match self {
+            Exported::Identity { identity } => Ok(identity.clone()),
+            Exported::Bundle(b) => Ok(serde_json::to_string_pretty(b)?),
+        }
+    }
+}
@@ -0,0 +1,97 @@
+use super::export::{EncryptedBundle, BUNDLE_AAD};
+use crate::util::exitcode;
+use crate::util::node_rpc;
+use crate::util::output::Output;
+use crate::vault::util::{open_vault, read_passphrase};
+use crate::CommandGlobalOpts;
+use anyhow::anyhow;
+use clap::Args;
+use ockam::identity::{Identity, IdentityBundle};
+use ockam::Context;
+use ockam_api::config::cli;
+use ockam_vault::storage::StorageKey;
+use serde::Serialize;
+use std::path::PathBuf;
+
+/// Restore an identity and its secret keys as the default identity
+#[derive(Clone, Debug, Args)]
+pub struct ImportCommand {
+    /// File produced by `ockam identity export --with-secrets`
+    file: PathBuf,
+
+    /// Decrypt the bundle with a key derived from the contents of this file
+    #[arg(long)]
+    key_file: Option<PathBuf>,
+
+    /// Replace the existing default identity
+    #[arg(long)]
+    force: bool,
+}
+
+impl ImportCommand {
+    pub fn run(self, options: CommandGlobalOpts) {
+        node_rpc(run_impl, (options, self))
+    }
+}
+
+async fn run_impl(
+    ctx: Context,
+    (opts, cmd): (CommandGlobalOpts, ImportCommand),
+) -> crate::Result<()> {
+    let cfg = &opts.config;
+    if cfg.get_default_identity().is_some() && !cmd.force {
+        return Err(crate::error::Error::new(
+            exitcode::CANTCREAT,
+            anyhow!("A default identity already exists. Use --force to replace it"),
+        ));
+    }
+
+    let contents = std::fs::read(&cmd.file)?;
+    let encrypted: EncryptedBundle = serde_json::from_slice(&contents)
+        .map_err(|e| anyhow!("{} is not an identity bundle: {e}", cmd.file.display()))?;
+    if encrypted.version != 1 {
+        return Err(anyhow!("Unsupported bundle version {}", encrypted.version).into());
+    }
+    let key = match cmd.key_file {
+        Some(path) => StorageKey::KeyFile(path),
+        None => read_passphrase("Bundle passphrase", false)?,
+    };
+    let bundle = encrypted
+        .sealed
+        .open(&key, BUNDLE_AAD)
+        .map_err(|_| anyhow!("Failed to decrypt the bundle. Is the passphrase correct?"))?;
+    let bundle = IdentityBundle::decode(&bundle)?;
+
+    let vault_path = cfg.get_default_vault_path().unwrap_or_else(|| {
+        let path = cli::OckamConfig::directories()
+            .config_dir()
+            .join("default_vault.json");
+        cfg.set_default_vault_path(Some(path.clone()));
+        path
+    });
+    if let Some(dir) = vault_path.parent() {
+        tokio::fs::create_dir_all(dir).await?;
+    }
+    let vault = open_vault(vault_path).await?;
+    let identity = Identity::import_with_secrets(&ctx, &bundle, &vault).await?;
+
+    cfg.set_default_identity(Some(bundle.identity().to_vec()));
+    cfg.persist_config_updates()?;
+
+    opts.print(&ImportedIdentity {
+        identifier: identity.identifier().to_string(),
+    })?;
+    Ok(())
+}
+
+/// Result of importing an identity
+#[derive(Debug, Serialize)]
+struct ImportedIdentity {
+    identifier: String,
+}
+
+impl Output for ImportedIdentity {
+    fn output(&self) -> anyhow::Result<String> {
+        Ok(format!("Identity {} imported!", self.identifier))
+    }
+}
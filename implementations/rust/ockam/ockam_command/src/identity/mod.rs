@@ -1,7 +1,11 @@
 mod create;
+mod export;
+mod import;
 mod show;
 
 pub(crate) use create::CreateCommand;
+pub(crate) use export::ExportCommand;
+pub(crate) use import::ImportCommand;
 pub(crate) use show::ShowCommand;
 
 use crate::CommandGlobalOpts;
@@ -20,6 +24,10 @@ pub enum IdentitySubcommand {
     Create(CreateCommand),
     /// Print short existing identity, `--full` for long identity
     Show(ShowCommand),
+    /// Export an identity, `--with-secrets` to include its keys in an encrypted bundle
+    Export(ExportCommand),
+    /// Restore an identity exported with `--with-secrets`
+    Import(ImportCommand),
 }
 
 impl IdentityCommand {
@@ -27,6 +35,8 @@ impl IdentityCommand {
         match self.subcommand {
             IdentitySubcommand::Create(c) => c.run(options),
             IdentitySubcommand::Show(c) => c.run(options),
+            IdentitySubcommand::Export(c) => c.run(options),
+            IdentitySubcommand::Import(c) => c.run(options),
         }
     }
 }
@@ -2,8 +2,12 @@
 //! credential management, and authorization policy enforcement — at scale.
 
 mod admin;
+mod alias;
+mod api;
 mod authenticated;
+mod authority;
 mod completion;
+mod compose;
 mod configuration;
 mod credential;
 mod enroll;
@@ -18,6 +22,7 @@ mod project;
 mod reset;
 mod secure_channel;
 mod service;
+mod shell;
 mod space;
 mod subscription;
 mod tcp;
@@ -29,6 +34,7 @@ mod version;
 
 use anyhow::Context;
 use authenticated::AuthenticatedCommand;
+use authority::AuthorityCommand;
 use completion::CompletionCommand;
 use configuration::ConfigurationCommand;
 use credential::CredentialCommand;
@@ -44,17 +50,22 @@ use reset::ResetCommand;
 use secure_channel::{listener::SecureChannelListenerCommand, SecureChannelCommand};
 use service::ServiceCommand;
 use space::SpaceCommand;
+use std::io::Write;
 use std::path::PathBuf;
 use tcp::{
     connection::TcpConnectionCommand, inlet::TcpInletCommand, listener::TcpListenerCommand,
     outlet::TcpOutletCommand,
 };
-use util::{exitcode, exitcode::ExitCode, setup_logging, OckamConfig};
+use util::{exitcode, exitcode::ExitCode, output::Output, setup_logging, OckamConfig};
 use vault::VaultCommand;
 use version::Version;
 
 use crate::admin::AdminCommand;
+use crate::alias::AliasCommand;
+use crate::api::ApiCommand;
+use crate::compose::ComposeCommand;
 use crate::node::util::run::CommandSection;
+use crate::shell::ShellCommand;
 use crate::subscription::SubscriptionCommand;
 use clap::{ArgAction, Args, Parser, Subcommand, ValueEnum};
 use upgrade::check_if_an_upgrade_is_available;
@@ -141,6 +152,23 @@ Examples:
     The two sides authenticated and authorized each other's known, cryptographically
     provable identifiers. In later examples we'll see how we can build granular,
     attribute-based access control with authorization policies.
+
+Output:
+    Commands print their results as text meant to be read by people. With
+    `--output json` they print them as JSON instead, which scripts can rely on.
+
+Exit Codes:
+    0   The command succeeded
+    64  The command was used incorrectly: unknown arguments or invalid values
+    65  The input data was incorrect, or a node rejected the request
+    69  A node or service is not running, or doesn't support the request
+    70  An internal error occurred
+    71  A node process could not be started or stopped
+    73  A file, a node or a transport could not be created
+    74  A file, or the state of a node, could not be read or written
+    76  A node sent a response that could not be understood
+    77  A node refused the request because it is not authorized
+    78  The configuration is missing or invalid
 ";
 
 #[derive(Debug, Parser)]
@@ -195,13 +223,7 @@ pub struct GlobalArgs {
     no_color: bool,
 
     /// Output format
-    #[arg(
-        hide = help::hide(),
-        global = true,
-        long = "output",
-        value_enum,
-        default_value = "plain"
-    )]
+    #[arg(global = true, long = "output", value_enum, default_value = "plain")]
     output_format: OutputFormat,
 
     // if test_argument_parser is true, command arguments are checked
@@ -259,6 +281,31 @@ impl CommandGlobalOpts {
             config,
         }
     }
+
+    /// Print the result of a command in the requested output format
+    pub fn print<T>(&self, v: &T) -> anyhow::Result<()>
+    where
+        T: Output + serde::Serialize,
+    {
+        let o = match self.global_args.output_format {
+            OutputFormat::Plain => v.output().context("Failed to format the output")?,
+            OutputFormat::Json => {
+                serde_json::to_string_pretty(v).context("Failed to serialize the output")?
+            }
+        };
+        // A closed pipe (e.g. `| head`) is not an error of the command
+        match writeln!(std::io::stdout(), "{}", o) {
+            Err(e) if e.kind() == std::io::ErrorKind::BrokenPipe => Ok(()),
+            r => r.context("Failed to write the output"),
+        }
+    }
+
+    /// Print the progress of a command, which is left out of the JSON output
+    pub fn progress(&self, msg: impl std::fmt::Display) {
+        if self.global_args.output_format == OutputFormat::Plain {
+            println!("{}", msg);
+        }
+    }
 }
 
 #[derive(Debug, Subcommand)]
@@ -271,6 +318,8 @@ pub enum OckamSubcommand {
     Project(ProjectCommand),
     #[command(display_order = 803)]
     Reset(ResetCommand),
+    #[command(display_order = 804)]
+    Authority(AuthorityCommand),
 
     #[command(display_order = 811)]
     Node(NodeCommand),
@@ -294,6 +343,12 @@ pub enum OckamSubcommand {
     Message(MessageCommand),
     #[command(display_order = 821)]
     Policy(PolicyCommand),
+    #[command(display_order = 822)]
+    Compose(ComposeCommand),
+    #[command(display_order = 823)]
+    Alias(AliasCommand),
+    #[command(display_order = 824)]
+    Shell(ShellCommand),
 
     #[command(display_order = 900)]
     Completion(CompletionCommand),
@@ -305,6 +360,7 @@ pub enum OckamSubcommand {
     Vault(VaultCommand),
     Subscription(SubscriptionCommand),
     Admin(AdminCommand),
+    Api(ApiCommand),
 }
 
 pub fn run() {
@@ -312,13 +368,23 @@ pub fn run() {
         .map(replace_hyphen_with_stdin)
         .collect::<Vec<_>>();
     let args = input.clone();
-    let command: OckamCommand = OckamCommand::parse_from(input);
+    let command = match OckamCommand::try_parse_from(input) {
+        Ok(c) => c,
+        // Help and version requests are not errors
+        Err(e) if !e.use_stderr() => e.exit(),
+        Err(e) => {
+            let _ = e.print();
+            std::process::exit(exitcode::USAGE);
+        }
+    };
 
     if !command.global_args.test_argument_parser {
         check_if_an_upgrade_is_available();
     }
 
-    if !command.global_args.quiet {
+    let child_process =
+        matches!(&command.subcommand, OckamSubcommand::Node(c) if c.is_child_process());
+    if !command.global_args.quiet && !child_process {
         setup_logging(command.global_args.verbose, command.global_args.no_color);
         tracing::debug!("{}", Version::short());
         tracing::debug!("Parsed {:?}", &command);
@@ -349,7 +415,8 @@ impl OckamCommand {
         }
 
         match self.subcommand {
-            OckamSubcommand::Authenticated(c) => c.run(),
+            OckamSubcommand::Authenticated(c) => c.run(options),
+            OckamSubcommand::Authority(c) => c.run(options),
             OckamSubcommand::Configuration(c) => c.run(options),
             OckamSubcommand::Enroll(c) => c.run(options),
             OckamSubcommand::Forwarder(c) => c.run(options),
@@ -367,23 +434,39 @@ impl OckamCommand {
             OckamSubcommand::SecureChannel(c) => c.run(options),
             OckamSubcommand::SecureChannelListener(c) => c.run(options),
             OckamSubcommand::Service(c) => c.run(options),
-            OckamSubcommand::Completion(c) => c.run(),
+            OckamSubcommand::Completion(c) => c.run(options),
             OckamSubcommand::Credential(c) => c.run(options),
             OckamSubcommand::Subscription(c) => c.run(options),
             OckamSubcommand::Reset(c) => c.run(options),
             OckamSubcommand::Admin(c) => c.run(options),
+            OckamSubcommand::Api(c) => c.run(options),
+            OckamSubcommand::Compose(c) => c.run(options),
+            OckamSubcommand::Alias(c) => c.run(options),
+            OckamSubcommand::Shell(c) => c.run(options),
         }
     }
 }
 
 fn replace_hyphen_with_stdin(s: String) -> String {
     use std::io;
-    if s.contains("/-") {
+    if s.contains("/-") || s.contains("-/") {
         let mut buffer = String::new();
         io::stdin()
             .read_line(&mut buffer)
             .expect("could not read from standard input");
-        let args_from_stdin = buffer
+        replace_hyphen_with(s, &buffer)
+    } else {
+        s
+    }
+}
+
+/// Replace the `/-` or `-/` placeholder of an argument with the given input
+///
+/// The input is normalized to a sequence of `/`-separated segments, so that
+/// the output of a command can be spliced into the address of another one.
+pub(crate) fn replace_hyphen_with(s: String, input: &str) -> String {
+    if s.contains("/-") {
+        let args_from_stdin = input
             .trim()
             .split('/')
             .filter(|&s| !s.is_empty())
@@ -391,12 +474,7 @@ fn replace_hyphen_with_stdin(s: String) -> String {
 
         s.replace("/-", &args_from_stdin)
     } else if s.contains("-/") {
-        let mut buffer = String::new();
-        io::stdin()
-            .read_line(&mut buffer)
-            .expect("could not read from standard input");
-
-        let args_from_stdin = buffer
+        let args_from_stdin = input
             .trim()
             .split('/')
             .filter(|&s| !s.is_empty())
@@ -2,7 +2,7 @@ use crate::{help, CommandGlobalOpts};
 use clap::{Args, Subcommand};
 pub use send::SendCommand;
 
-mod send;
+pub(crate) mod send;
 
 const HELP_DETAIL: &str = "\
 About:
@@ -78,6 +78,16 @@ Examples:
     $ ockam secure-channel create --from /node/n1 --to /node/n2/service/api \\
         | ockam message send hello --from /node/n1 --to -/service/uppercase
     HELLO
+
+    # Trace a message across nodes; each hop logs an `ockam.hop` span
+    # with the trace id of the returned traceparent. The spans are exported
+    # to the OpenTelemetry collector at OTEL_EXPORTER_OTLP_ENDPOINT, if set
+    $ ockam message send hello --from /node/n1 --to /node/n2/service/uppercase --trace \\
+        --output json
+    {
+      \"message\": \"HELLO\",
+      \"traceparent\": \"00-4bf92f3577b34da6a3ce929d0e0e4736-00f067aa0ba902b7-01\"
+    }
 ```
 ";
 
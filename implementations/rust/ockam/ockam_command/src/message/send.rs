@@ -1,16 +1,21 @@
 use anyhow::Context as _;
 use clap::Args;
 
-use ockam::{Context, TcpTransport};
+use ockam::opentelemetry::{continue_trace, span_trace_context};
+use ockam::Context;
 use ockam_api::clean_multiaddr;
 use ockam_api::nodes::models::secure_channel::CredentialExchangeMode;
 use ockam_api::nodes::service::message::SendMessage;
 use ockam_core::api::{Request, RequestBuilder};
+use ockam_core::TraceContext;
 use ockam_multiaddr::MultiAddr;
+use serde::Serialize;
+use tracing::{info, info_span};
 
 use crate::node::util::{delete_embedded_node, start_embedded_node};
 use crate::util::api::CloudOpts;
-use crate::util::{extract_address_value, node_rpc, RpcBuilder};
+use crate::util::output::Output;
+use crate::util::{extract_address_value, node_rpc, tcp_transport, RpcBuilder};
 use crate::Result;
 use crate::{help, message::HELP_DETAIL, CommandGlobalOpts};
 
@@ -32,6 +37,14 @@ pub struct SendCommand {
 
     pub message: String,
 
+    /// Start a new trace for the message, shown with `--output json`
+    #[arg(long, conflicts_with = "traceparent")]
+    pub trace: bool,
+
+    /// Continue an existing trace, given as a W3C traceparent value
+    #[arg(long, value_name = "TRACEPARENT", value_parser = parse_traceparent)]
+    pub traceparent: Option<TraceContext>,
+
     #[command(flatten)]
     cloud_opts: CloudOpts,
 }
@@ -51,7 +64,7 @@ async fn rpc(mut ctx: Context, (opts, cmd): (CommandGlobalOpts, SendCommand)) ->
         // Setup environment depending on whether we are sending the message from an embedded node or a background node
         let (api_node, tcp) = if let Some(node) = &cmd.from {
             let api_node = extract_address_value(node)?;
-            let tcp = TcpTransport::create(ctx).await?;
+            let tcp = tcp_transport(ctx).await?;
             (api_node, Some(tcp))
         } else {
             let api_node = start_embedded_node(ctx, &opts.config).await?;
@@ -71,16 +84,34 @@ async fn rpc(mut ctx: Context, (opts, cmd): (CommandGlobalOpts, SendCommand)) ->
         .await?;
         let to = crate::project::util::clean_projects_multiaddr(to, projects_sc)?;
 
+        // Attach a trace context, which is propagated along the message route.
+        // The span of this command is its root when spans are exported.
+        let span = info_span!("ockam.message.send", to = %cmd.to);
+        let tracing_context = match cmd.traceparent {
+            Some(parent) => Some(continue_trace(&span, &parent).unwrap_or_else(|| parent.child())),
+            None if cmd.trace => {
+                Some(span_trace_context(&span).unwrap_or_else(TraceContext::new_root))
+            }
+            None => None,
+        };
+        if let Some(tc) = tracing_context {
+            info!(traceparent = %tc, "sending a traced message");
+            ctx.set_tracing_context(Some(tc));
+        }
+
         // Send request
         let mut rpc = RpcBuilder::new(ctx, opts, &api_node)
             .tcp(tcp.as_ref())?
             .build();
         rpc.request(req(&to, &cmd.message)).await?;
         let res = rpc.parse_response::<Vec<u8>>()?;
-        println!(
-            "{}",
-            String::from_utf8(res).context("Received content is not a valid utf8 string")?
-        );
+        let reply = Reply {
+            message: String::from_utf8(res)
+                .context("Received content is not a valid utf8 string")?,
+            traceparent: tracing_context.map(|tc| tc.to_traceparent()),
+        };
+        drop(span);
+        opts.print(&reply)?;
 
         // only delete node in case 'from' is empty and embedded node was started before
         if cmd.from.is_none() {
@@ -92,6 +123,25 @@ async fn rpc(mut ctx: Context, (opts, cmd): (CommandGlobalOpts, SendCommand)) ->
     go(&mut ctx, &opts, cmd).await
 }
 
+/// The reply to a message
+#[derive(Debug, Serialize)]
+struct Reply {
+    message: String,
+    /// The trace of the message, if it was traced
+    #[serde(skip_serializing_if = "Option::is_none")]
+    traceparent: Option<String>,
+}
+
+impl Output for Reply {
+    fn output(&self) -> anyhow::Result<String> {
+        Ok(self.message.clone())
+    }
+}
+
+fn parse_traceparent(value: &str) -> std::result::Result<TraceContext, String> {
+    TraceContext::from_traceparent(value).ok_or_else(|| "invalid traceparent".to_string())
+}
+
 pub(crate) fn req<'a>(to: &'a MultiAddr, message: &'a str) -> RequestBuilder<'a, SendMessage<'a>> {
     Request::post("v0/message").body(SendMessage::new(to, message.as_bytes()))
 }
@@ -0,0 +1,875 @@
+use anyhow::{anyhow, Context as _};
+use clap::Args;
+use minicbor::Encode;
+use serde::{Deserialize, Serialize};
+use std::net::{SocketAddr, ToSocketAddrs};
+use std::path::{Path, PathBuf};
+use std::str::FromStr;
+
+use ockam::identity::IdentityIdentifier;
+use ockam::{Address, Context, TcpTransport};
+use ockam_abac::{Action, Expr, Resource};
+use ockam_api::nodes::models::forwarder::ForwarderList;
+use ockam_api::nodes::models::identity::ShortIdentityResponse;
+use ockam_api::nodes::models::policy::Policy;
+use ockam_api::nodes::models::portal::{CreateInlet, CreateOutlet, InletList, OutletList};
+use ockam_api::nodes::models::secure_channel::CreateSecureChannelListenerRequest;
+use ockam_api::nodes::models::services::ServiceList;
+use ockam_api::nodes::models::transport::{
+    CreateTransport, TransportList, TransportMode, TransportType,
+};
+use ockam_api::nodes::models::vault::CreateVaultRequest;
+use ockam_core::api::{Request, RequestBuilder, Status};
+use ockam_multiaddr::proto::Project;
+use ockam_multiaddr::{MultiAddr, Protocol};
+
+use crate::node::{is_node_up, restart_background_node, spawn_background_node, CreateCommand};
+use crate::service::config::{SecureChannelListenerConfig, ServiceConfigs};
+use crate::util::output::Output;
+use crate::util::{api, exitcode, node_rpc, resolve_names, tcp_transport, Rpc, RpcBuilder};
+use crate::{forwarder, help, CommandGlobalOpts};
+
+const HELP_DETAIL: &str = "\
+About:
+    Converge a node to the state described in a configuration file.
+
+    The file lists the transports, secure channel listeners, portals, forwarders,
+    policies and services that the node should run. Every entry that is missing
+    on the node is created, entries that already exist are left untouched, so
+    applying the same file twice has no effect. Entries that exist with different
+    settings are reported as conflicts, as they can't be changed in place.
+    Removing an entry from the file does not remove it from the node.
+
+    If the node doesn't exist yet, it is created first, and if it is stopped, it
+    is started again. A node created with a `vault` entry starts without default
+    services, and its identity is created in that vault.
+
+    The file is read as TOML if its extension is `.toml`, and as YAML otherwise.
+
+```yaml
+    name: n1
+    tcp_listeners:
+      - 127.0.0.1:6001
+    secure_channel_listeners:
+      - address: listener
+    outlets:
+      - from: db
+        to: 127.0.0.1:5432
+    inlets:
+      - from: 127.0.0.1:7000
+        to: /node/n2/service/db
+    forwarders:
+      - name: n1
+        at: /node/relay
+    policies:
+      - resource: tcp-outlet
+        action: handle_message
+        expression: (= subject.component \"db\")
+    services:
+      verifier: {}
+```
+
+Examples:
+```sh
+    # Show the changes that would be made to the node
+    $ ockam node apply -f n1.yaml --dry-run
+
+    # Apply them
+    $ ockam node apply -f n1.yaml
+```
+";
+
+/// Apply a declarative configuration to a node
+#[derive(Clone, Debug, Args)]
+#[command(arg_required_else_help = true, after_long_help = help::template(HELP_DETAIL))]
+pub struct ApplyCommand {
+    /// Path to the node configuration, in YAML or TOML
+    #[arg(short, long = "file", id = "FILE")]
+    file: PathBuf,
+
+    /// Name of the node, overriding the name given in the file
+    #[arg(long)]
+    node: Option<String>,
+
+    /// Only print the changes that would be made
+    #[arg(long)]
+    dry_run: bool,
+}
+
+impl ApplyCommand {
+    pub fn run(self, options: CommandGlobalOpts) {
+        node_rpc(run_impl, (options, self))
+    }
+}
+
+/// Declarative configuration of a node
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct NodeSpec {
+    pub(crate) name: Option<String>,
+
+    /// Address of the API listener, when the node is created.
+    pub(crate) tcp_listener_address: Option<String>,
+
+    /// Address of the plain HTTP health endpoint, when the node is created.
+    pub(crate) health_address: Option<SocketAddr>,
+
+    pub(crate) vault: Option<VaultSpec>,
+    pub(crate) identity: Option<IdentitySpec>,
+
+    #[serde(default)]
+    pub(crate) tcp_listeners: Vec<String>,
+
+    #[serde(default)]
+    pub(crate) tcp_connections: Vec<String>,
+
+    #[serde(default)]
+    pub(crate) secure_channel_listeners: Vec<SecureChannelListenerConfig>,
+
+    #[serde(default)]
+    pub(crate) outlets: Vec<OutletSpec>,
+
+    #[serde(default)]
+    pub(crate) inlets: Vec<InletSpec>,
+
+    #[serde(default)]
+    pub(crate) forwarders: Vec<ForwarderSpec>,
+
+    #[serde(default)]
+    pub(crate) policies: Vec<PolicySpec>,
+
+    pub(crate) services: Option<ServiceConfigs>,
+}
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct VaultSpec {
+    /// Storage of the vault, or the default storage of the node if not set.
+    pub(crate) path: Option<PathBuf>,
+}
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct IdentitySpec {
+    /// Expected identifier of the node.
+    pub(crate) identifier: Option<IdentityIdentifier>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct OutletSpec {
+    /// Address of the outlet worker.
+    pub(crate) from: String,
+    /// TCP address to forward the traffic to.
+    pub(crate) to: SocketAddr,
+    pub(crate) alias: Option<String>,
+    pub(crate) check_credential: Option<bool>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct InletSpec {
+    /// TCP address to listen on.
+    pub(crate) from: SocketAddr,
+    /// Route to the outlet.
+    pub(crate) to: MultiAddr,
+    pub(crate) alias: Option<String>,
+    pub(crate) authorized: Option<IdentityIdentifier>,
+    pub(crate) check_credential: Option<bool>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct ForwarderSpec {
+    pub(crate) name: String,
+    /// Route to the node at which to create the forwarder.
+    pub(crate) at: MultiAddr,
+    pub(crate) authorized: Option<IdentityIdentifier>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct PolicySpec {
+    pub(crate) resource: String,
+    pub(crate) action: String,
+    pub(crate) expression: String,
+}
+
+impl NodeSpec {
+    pub(crate) fn read<P: AsRef<Path>>(path: P) -> anyhow::Result<Self> {
+        let path = path.as_ref();
+        let s =
+            std::fs::read_to_string(path).with_context(|| anyhow!("failed to read {:?}", path))?;
+        Self::parse(&s, path.extension().map_or(false, |e| e == "toml"))
+            .with_context(|| anyhow!("invalid node configuration {:?}", path))
+    }
+
+    fn parse(s: &str, toml: bool) -> anyhow::Result<Self> {
+        if toml {
+            Ok(toml::from_str(s)?)
+        } else {
+            Ok(serde_yaml::from_str(s)?)
+        }
+    }
+}
+
+async fn run_impl(
+    ctx: Context,
+    (opts, cmd): (CommandGlobalOpts, ApplyCommand),
+) -> crate::Result<()> {
+    let spec = NodeSpec::read(&cmd.file)?;
+    let node_name = cmd
+        .node
+        .clone()
+        .or_else(|| spec.name.clone())
+        .ok_or_else(|| anyhow!("the node name must be given in the file or with --node"))?;
+
+    let tcp = tcp_transport(&ctx).await?;
+    let mut report = ApplyReport::new(&node_name, cmd.dry_run);
+    if opts.config.get_node(&node_name).is_err() {
+        report.created = true;
+        opts.progress(format_args!("+ node {node_name}"));
+        if cmd.dry_run {
+            plan_new_node(&opts, &spec, &mut report).await?;
+        } else {
+            create_node(&ctx, &opts, &tcp, &node_name, &spec).await?;
+            apply_spec(&ctx, &opts, &tcp, &spec, &mut report).await?;
+        }
+    } else {
+        start_node(&ctx, &opts, &tcp, &mut report).await?;
+        apply_spec(&ctx, &opts, &tcp, &spec, &mut report).await?;
+    }
+    opts.print(&report)?;
+    if !report.conflicts.is_empty() {
+        return Err(crate::Error::new(
+            exitcode::DATAERR,
+            anyhow!("node {node_name} does not match {:?}", cmd.file),
+        ));
+    }
+    Ok(())
+}
+
+/// What `node apply` changed on a node, or would change on a dry run
+#[derive(Debug, Serialize)]
+pub(crate) struct ApplyReport {
+    pub(crate) node: String,
+    pub(crate) dry_run: bool,
+    /// The node didn't exist and was created
+    pub(crate) created: bool,
+    /// The node wasn't running and was started
+    pub(crate) started: bool,
+    pub(crate) changes: Vec<String>,
+    pub(crate) conflicts: Vec<Conflict>,
+}
+
+/// A setting of the spec that differs from the node and can't be changed in place
+#[derive(Debug, Serialize)]
+pub(crate) struct Conflict {
+    what: String,
+    reason: String,
+}
+
+impl ApplyReport {
+    pub(crate) fn new(node: &str, dry_run: bool) -> Self {
+        Self {
+            node: node.to_string(),
+            dry_run,
+            created: false,
+            started: false,
+            changes: vec![],
+            conflicts: vec![],
+        }
+    }
+}
+
+/// Summary of the changes, as they are printed while being applied
+pub(crate) fn summary(changes: usize, conflicts: usize, dry_run: bool) -> String {
+    let verb = if dry_run { "to apply" } else { "applied" };
+    format!("{changes} change(s) {verb}, {conflicts} conflict(s)")
+}
+
+impl Output for ApplyReport {
+    fn output(&self) -> anyhow::Result<String> {
+        Ok(summary(
+            self.changes.len(),
+            self.conflicts.len(),
+            self.dry_run,
+        ))
+    }
+}
+
+/// Converge a running node to `spec`
+///
+/// The changes and the conflicts found are added to `report`.
+pub(crate) async fn apply_spec(
+    ctx: &Context,
+    opts: &CommandGlobalOpts,
+    tcp: &TcpTransport,
+    spec: &NodeSpec,
+    report: &mut ApplyReport,
+) -> crate::Result<()> {
+    let rpc = RpcBuilder::new(ctx, opts, &report.node).tcp(tcp)?.build();
+    let mut applier = Applier {
+        opts,
+        rpc: Some(rpc),
+        dry_run: report.dry_run,
+        report,
+    };
+    applier.run(spec).await?;
+    Ok(())
+}
+
+/// List the changes that creating the node described by `spec` would make
+///
+/// The node is taken to be empty, as it doesn't exist yet.
+pub(crate) async fn plan_new_node(
+    opts: &CommandGlobalOpts,
+    spec: &NodeSpec,
+    report: &mut ApplyReport,
+) -> crate::Result<()> {
+    let mut applier = Applier {
+        opts,
+        rpc: None,
+        dry_run: true,
+        report,
+    };
+    applier.run(spec).await?;
+    Ok(())
+}
+
+/// Create the node described by `spec` and wait until it is up
+pub(crate) async fn create_node(
+    ctx: &Context,
+    opts: &CommandGlobalOpts,
+    tcp: &TcpTransport,
+    node_name: &str,
+    spec: &NodeSpec,
+) -> crate::Result<()> {
+    let mut cmd = CreateCommand {
+        node_name: node_name.to_string(),
+        health_address: spec.health_address,
+        // The vault and identity of the spec are created by `apply_spec`
+        skip_defaults: spec.vault.is_some(),
+        ..Default::default()
+    };
+    if let Some(addr) = &spec.tcp_listener_address {
+        cmd.tcp_listener_address = addr.clone();
+    }
+    let cmd = cmd.overwrite_addr()?;
+    let addr = SocketAddr::from_str(&cmd.tcp_listener_address)?;
+    spawn_background_node(ctx, opts, &cmd, addr).await?;
+
+    let mut rpc = RpcBuilder::new(ctx, opts, node_name).tcp(tcp)?.build();
+    if !is_node_up(&mut rpc, true).await? {
+        return Err(anyhow!("node {node_name} did not start").into());
+    }
+    Ok(())
+}
+
+/// Start the existing node of `report` if it isn't running, and wait until it is up
+///
+/// On a dry run, the node is only reported as started.
+pub(crate) async fn start_node(
+    ctx: &Context,
+    opts: &CommandGlobalOpts,
+    tcp: &TcpTransport,
+    report: &mut ApplyReport,
+) -> crate::Result<()> {
+    let node_name = report.node.clone();
+    let mut rpc = RpcBuilder::new(ctx, opts, &node_name).tcp(tcp)?.build();
+    if is_node_up(&mut rpc, false).await? {
+        return Ok(());
+    }
+    opts.progress(format_args!("+ start node {node_name}"));
+    report.started = true;
+    if !report.dry_run {
+        restart_background_node(opts, &node_name)?;
+        if !is_node_up(&mut rpc, true).await? {
+            return Err(crate::Error::new(
+                exitcode::UNAVAILABLE,
+                anyhow!("node {node_name} did not start"),
+            ));
+        }
+    }
+    Ok(())
+}
+
+/// The socket addresses of `addr`, which can use a host name
+fn resolve_socket_addr(addr: &str) -> Vec<SocketAddr> {
+    addr.to_socket_addrs()
+        .map(|addrs| addrs.collect())
+        .unwrap_or_default()
+}
+
+/// Whether two socket addresses, possibly given with host names, are the same
+fn same_socket_addr(a: &str, b: &str) -> bool {
+    if a == b {
+        return true;
+    }
+    let b = resolve_socket_addr(b);
+    resolve_socket_addr(a).iter().any(|a| b.contains(a))
+}
+
+/// Compares the spec with the node and sends the missing changes
+///
+/// Without an `rpc`, the node doesn't exist yet and is taken to be empty.
+struct Applier<'a> {
+    opts: &'a CommandGlobalOpts,
+    rpc: Option<Rpc<'a>>,
+    dry_run: bool,
+    report: &'a mut ApplyReport,
+}
+
+impl<'a> Applier<'a> {
+    async fn run(&mut self, spec: &NodeSpec) -> anyhow::Result<()> {
+        self.identity(spec).await?;
+        self.tcp_listeners(spec).await?;
+        self.tcp_connections(spec).await?;
+        self.secure_channel_listeners(spec).await?;
+        self.outlets(spec).await?;
+        self.inlets(spec).await?;
+        self.forwarders(spec).await?;
+        self.policies(spec).await?;
+        self.services(spec).await
+    }
+
+    /// Send a change to the node, unless this is a dry run
+    async fn apply<T>(&mut self, what: String, req: RequestBuilder<'_, T>) -> anyhow::Result<()>
+    where
+        T: Encode<()>,
+    {
+        self.opts.progress(format_args!("+ {what}"));
+        self.report.changes.push(what.clone());
+        if self.dry_run {
+            return Ok(());
+        }
+        let mut rpc = self
+            .rpc
+            .clone()
+            .ok_or_else(|| anyhow!("node {} is not running", self.report.node))?;
+        rpc.request(req).await?;
+        let (res, dec) = rpc.check_response()?;
+        match res.status() {
+            Some(Status::Ok) => Ok(()),
+            _ => Err(anyhow!(
+                "failed to apply {what}: {}",
+                rpc.parse_err_msg(res, dec)
+            )),
+        }
+    }
+
+    fn conflict(&mut self, what: String, reason: &str) {
+        eprintln!("! {what}: {reason}");
+        self.report.conflicts.push(Conflict {
+            what,
+            reason: reason.to_string(),
+        });
+    }
+
+    async fn identity(&mut self, spec: &NodeSpec) -> anyhow::Result<()> {
+        let current = match self.rpc.clone() {
+            Some(mut rpc) => {
+                rpc.request(api::short_identity()).await?;
+                rpc.parse_response::<ShortIdentityResponse>()
+                    .ok()
+                    .map(|r| r.identity_id.to_string())
+            }
+            // A new node gets the default identity, unless the spec has a vault
+            None if spec.vault.is_none() => return Ok(()),
+            None => None,
+        };
+        match current {
+            Some(id) => {
+                let expected = spec.identity.as_ref().and_then(|i| i.identifier.as_ref());
+                match expected {
+                    Some(e) if e.to_string() != id => {
+                        self.conflict(format!("identity {e}"), &format!("node identity is {id}"))
+                    }
+                    _ => {}
+                }
+                self.vault(spec);
+            }
+            None => {
+                let path = spec
+                    .vault
+                    .as_ref()
+                    .and_then(|v| v.path.as_ref())
+                    .map(|p| p.to_string_lossy().to_string());
+                let what = format!("vault {}", path.as_deref().unwrap_or("(default)"));
+                let req = Request::post("/node/vault").body(CreateVaultRequest::new(path));
+                self.apply(what, req).await?;
+                let req = Request::post("/node/identity");
+                self.apply("identity".to_string(), req).await?;
+            }
+        }
+        Ok(())
+    }
+
+    /// Report a conflict if the node doesn't keep its secrets in the vault of the spec
+    fn vault(&mut self, spec: &NodeSpec) {
+        let expected = match spec.vault.as_ref().and_then(|v| v.path.as_ref()) {
+            Some(path) => path,
+            None => return,
+        };
+        let current = self
+            .opts
+            .config
+            .node(&self.report.node)
+            .ok()
+            .and_then(|n| n.state().read().vault_path.clone());
+        let canonical = |p: &Path| std::fs::canonicalize(p).unwrap_or_else(|_| p.to_path_buf());
+        if current.as_deref().map(canonical) != Some(canonical(expected)) {
+            let reason = match current {
+                Some(path) => format!("node vault is {}", path.display()),
+                None => "node has no vault".to_string(),
+            };
+            self.conflict(format!("vault {}", expected.display()), &reason)
+        }
+    }
+
+    async fn transports(&mut self, path: &str) -> anyhow::Result<Vec<String>> {
+        let mut rpc = match self.rpc.clone() {
+            Some(rpc) => rpc,
+            None => return Ok(vec![]),
+        };
+        rpc.request(Request::get(path)).await?;
+        let list = rpc.parse_response::<TransportList>()?;
+        Ok(list.list.iter().map(|t| t.payload.to_string()).collect())
+    }
+
+    async fn tcp_listeners(&mut self, spec: &NodeSpec) -> anyhow::Result<()> {
+        let current = self.transports("/node/tcp/listener").await?;
+        for addr in spec
+            .tcp_listeners
+            .iter()
+            .filter(|a| !current.iter().any(|c| same_socket_addr(a, c)))
+        {
+            // Nodes only bind to numeric addresses
+            let bind = resolve_socket_addr(addr)
+                .first()
+                .map(ToString::to_string)
+                .ok_or_else(|| anyhow!("invalid tcp-listener {addr}"))?;
+            let body = CreateTransport::new(TransportType::Tcp, TransportMode::Listen, bind);
+            let req = Request::post("/node/tcp/listener").body(body);
+            self.apply(format!("tcp-listener {addr}"), req).await?;
+        }
+        Ok(())
+    }
+
+    async fn tcp_connections(&mut self, spec: &NodeSpec) -> anyhow::Result<()> {
+        let current = self.transports("/node/tcp/connection").await?;
+        for addr in spec
+            .tcp_connections
+            .iter()
+            .filter(|a| !current.iter().any(|c| same_socket_addr(a, c)))
+        {
+            let body = CreateTransport::new(TransportType::Tcp, TransportMode::Connect, addr);
+            let req = Request::post("/node/tcp/connection").body(body);
+            self.apply(format!("tcp-connection {addr}"), req).await?;
+        }
+        Ok(())
+    }
+
+    async fn secure_channel_listeners(&mut self, spec: &NodeSpec) -> anyhow::Result<()> {
+        let current: Vec<Address> = match self.rpc.clone() {
+            Some(mut rpc) => {
+                rpc.request(api::list_secure_channel_listener()).await?;
+                rpc.parse_response::<Vec<String>>()?
+                    .iter()
+                    .map(Address::from_string)
+                    .collect()
+            }
+            None => vec![],
+        };
+        let from_services = spec
+            .services
+            .as_ref()
+            .and_then(|s| s.secure_channel_listener.as_ref());
+        for l in spec.secure_channel_listeners.iter().chain(from_services) {
+            let addr = Address::from_string(&l.address);
+            if l.disabled || current.contains(&addr) {
+                continue;
+            }
+            let body =
+                CreateSecureChannelListenerRequest::new(&addr, l.authorized_identifiers.clone());
+            let req = Request::post("/node/secure_channel_listener").body(body);
+            self.apply(format!("secure-channel-listener {}", l.address), req)
+                .await?;
+        }
+        Ok(())
+    }
+
+    async fn outlets(&mut self, spec: &NodeSpec) -> anyhow::Result<()> {
+        let current: Vec<(Address, String)> = match self.rpc.clone() {
+            Some(mut rpc) => {
+                rpc.request(api::list_outlets()).await?;
+                rpc.parse_response::<OutletList>()?
+                    .list
+                    .iter()
+                    .map(|o| {
+                        (
+                            Address::from_string(&*o.worker_addr),
+                            o.tcp_addr.to_string(),
+                        )
+                    })
+                    .collect()
+            }
+            None => vec![],
+        };
+        for o in &spec.outlets {
+            let what = format!("tcp-outlet {}", o.from);
+            let addr = Address::from_string(&o.from);
+            match current.iter().find(|(a, _)| a == &addr) {
+                Some((_, to)) if !same_socket_addr(to, &o.to.to_string()) => {
+                    self.conflict(what, &format!("outlet forwards to {to}"))
+                }
+                Some(_) => {}
+                None => {
+                    let body = CreateOutlet::new(
+                        o.to.to_string(),
+                        o.from.clone(),
+                        o.alias.clone().map(|a| a.into()),
+                        o.check_credential,
+                    );
+                    let req = Request::post("/node/outlet").body(body);
+                    self.apply(what, req).await?
+                }
+            }
+        }
+        Ok(())
+    }
+
+    async fn inlets(&mut self, spec: &NodeSpec) -> anyhow::Result<()> {
+        let current: Vec<String> = match self.rpc.clone() {
+            Some(mut rpc) => {
+                rpc.request(api::list_inlets()).await?;
+                rpc.parse_response::<InletList>()?
+                    .list
+                    .iter()
+                    .map(|i| i.bind_addr.to_string())
+                    .collect()
+            }
+            None => vec![],
+        };
+        let lookup = self.opts.config.lookup();
+        for i in spec.inlets.iter().filter(|i| {
+            !current
+                .iter()
+                .any(|c| same_socket_addr(c, &i.from.to_string()))
+        }) {
+            let to = match resolve_names(&i.to, &lookup) {
+                Ok(to) => to,
+                // The nodes of the route may only be created by this run
+                Err(_) if self.dry_run => {
+                    self.opts.progress(format_args!("+ tcp-inlet {}", i.from));
+                    self.report.changes.push(format!("tcp-inlet {}", i.from));
+                    continue;
+                }
+                Err(e) => return Err(e),
+            };
+            let mut body = if to.matches(0, &[Project::CODE.into()]) {
+                CreateInlet::via_project(i.from, to, i.check_credential)
+            } else {
+                CreateInlet::to_node(i.from, to, i.check_credential, i.authorized.clone())
+            };
+            if let Some(a) = &i.alias {
+                body.set_alias(a.clone())
+            }
+            let req = Request::post("/node/inlet").body(body);
+            self.apply(format!("tcp-inlet {}", i.from), req).await?;
+        }
+        Ok(())
+    }
+
+    async fn forwarders(&mut self, spec: &NodeSpec) -> anyhow::Result<()> {
+        let current: Vec<String> = match self.rpc.clone() {
+            Some(mut rpc) => {
+                rpc.request(api::list_forwarders()).await?;
+                rpc.parse_response::<ForwarderList>()?
+                    .list
+                    .iter()
+                    .filter_map(|f| f.alias().map(String::from))
+                    .collect()
+            }
+            None => vec![],
+        };
+        for f in &spec.forwarders {
+            let body = forwarder::make_api_body(self.opts, &f.name, &f.at, f.authorized.clone())
+                .map_err(|e| anyhow!("invalid forwarder {}: {e}", f.name))?;
+            if body
+                .alias()
+                .map_or(false, |a| current.iter().any(|c| c == a))
+            {
+                continue;
+            }
+            let req = Request::post("/node/forwarder").body(body);
+            self.apply(format!("forwarder {} at {}", f.name, f.at), req)
+                .await?;
+        }
+        Ok(())
+    }
+
+    async fn policies(&mut self, spec: &NodeSpec) -> anyhow::Result<()> {
+        for p in &spec.policies {
+            let (resource, action) = (Resource::new(&p.resource), Action::new(&p.action));
+            let expression = Expr::try_from(p.expression.as_str())
+                .map_err(|e| anyhow!("invalid policy expression `{}`: {e}", p.expression))?;
+            let path = format!("/policy/{resource}/{action}");
+            if let Some(mut rpc) = self.rpc.clone() {
+                rpc.request(Request::get(&path)).await?;
+                let (res, mut dec) = rpc.check_response()?;
+                if res.status() == Some(Status::Ok)
+                    && dec.decode::<Policy>()?.expression() == &expression
+                {
+                    continue;
+                }
+            }
+            let req = Request::post(&path).body(Policy::new(expression));
+            self.apply(format!("policy {resource}/{action}"), req)
+                .await?;
+        }
+        Ok(())
+    }
+
+    async fn services(&mut self, spec: &NodeSpec) -> anyhow::Result<()> {
+        let services = match &spec.services {
+            Some(s) => s,
+            None => return Ok(()),
+        };
+        let current: Vec<(String, String)> = match self.rpc.clone() {
+            Some(mut rpc) => {
+                rpc.request(api::list_services()).await?;
+                rpc.parse_response::<ServiceList>()?
+                    .list
+                    .iter()
+                    .map(|s| (s.addr.to_string(), s.service_type.to_string()))
+                    .collect()
+            }
+            None => vec![],
+        };
+        let missing = |addr: &str, ty: &str| !current.iter().any(|(a, t)| a == addr && t == ty);
+
+        if let Some(c) = services.vault.as_ref().filter(|c| !c.disabled) {
+            if missing(&c.address, "vault") {
+                let req = api::start_vault_service(&c.address);
+                self.apply(format!("vault service {}", c.address), req)
+                    .await?;
+            }
+        }
+        if let Some(c) = services.identity.as_ref().filter(|c| !c.disabled) {
+            if missing(&c.address, "identity") {
+                let req = api::start_identity_service(&c.address);
+                self.apply(format!("identity service {}", c.address), req)
+                    .await?;
+            }
+        }
+        if let Some(c) = services.verifier.as_ref().filter(|c| !c.disabled) {
+            if missing(&c.address, "verifier") {
+                let req = api::start_verifier_service(&c.address);
+                self.apply(format!("verifier service {}", c.address), req)
+                    .await?;
+            }
+        }
+        if let Some(c) = services.authenticator.as_ref().filter(|c| !c.disabled) {
+            if missing(&c.address, "authenticator") {
+                let req = api::start_authenticator_service(&c.address, &c.enrollers, &c.project);
+                self.apply(format!("authenticator service {}", c.address), req)
+                    .await?;
+            }
+        }
+        if let Some(c) = services
+            .okta_identity_provider
+            .as_ref()
+            .filter(|c| !c.disabled)
+        {
+            if missing(&c.address, "okta_identity_provider") {
+                let req = api::start_okta_identity_provider(c);
+                self.apply(format!("okta identity provider service {}", c.address), req)
+                    .await?;
+            }
+        }
+        if let Some(c) = services.oidc_authenticator.as_ref().filter(|c| !c.disabled) {
+            if missing(&c.address, "oidc_authenticator") {
+                let req = api::start_oidc_authenticator(c);
+                self.apply(format!("oidc authenticator service {}", c.address), req)
+                    .await?;
+            }
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn yaml_and_toml_specs_are_equivalent() {
+        let yaml = r#"
+name: n1
+tcp_listeners:
+  - 127.0.0.1:6001
+outlets:
+  - from: db
+    to: 127.0.0.1:5432
+inlets:
+  - from: 127.0.0.1:7000
+    to: /node/n2/service/db
+    alias: db
+forwarders:
+  - name: n1
+    at: /node/relay
+policies:
+  - resource: tcp-outlet
+    action: handle_message
+    expression: (= subject.component "db")
+services:
+  verifier: {}
+"#;
+        let toml = r#"
+name = "n1"
+tcp_listeners = ["127.0.0.1:6001"]
+
+[[outlets]]
+from = "db"
+to = "127.0.0.1:5432"
+
+[[inlets]]
+from = "127.0.0.1:7000"
+to = "/node/n2/service/db"
+alias = "db"
+
+[[forwarders]]
+name = "n1"
+at = "/node/relay"
+
+[[policies]]
+resource = "tcp-outlet"
+action = "handle_message"
+expression = '(= subject.component "db")'
+
+[services.verifier]
+"#;
+        let a = NodeSpec::parse(yaml, false).unwrap();
+        let b = NodeSpec::parse(toml, true).unwrap();
+        assert_eq!(
+            serde_json::to_value(&a).unwrap(),
+            serde_json::to_value(&b).unwrap()
+        );
+        assert_eq!(a.inlets[0].to.to_string(), "/node/n2/service/db");
+        assert_eq!(a.services.unwrap().verifier.unwrap().address, "verifier");
+    }
+
+    #[test]
+    fn socket_addrs_are_compared_after_resolution() {
+        assert!(same_socket_addr("localhost:6001", "127.0.0.1:6001"));
+        assert!(same_socket_addr("127.0.0.1:6001", "127.0.0.1:6001"));
+        assert!(!same_socket_addr("localhost:6001", "127.0.0.1:6002"));
+        assert!(!same_socket_addr("not an address", "127.0.0.1:6001"));
+    }
+
+    #[test]
+    fn unknown_fields_are_rejected() {
+        assert!(NodeSpec::parse("name: n1\ntcp_listener: 127.0.0.1:6001\n", false).is_err());
+    }
+}
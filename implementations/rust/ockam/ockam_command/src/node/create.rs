@@ -13,7 +13,10 @@ use crate::project::ProjectInfo;
 use crate::secure_channel::listener::create as secure_channel_listener;
 use crate::service::config::Config;
 use crate::service::start;
-use crate::util::{bind_to_port_check, embedded_node_that_is_not_stopped, exitcode};
+use crate::util::{
+    bind_to_port_check, embedded_node_that_is_not_stopped, exitcode, logging, tcp_transport,
+};
+use crate::vault::util::{read_storage_key, set_storage_key, storage_key};
 use crate::{
     help,
     node::show::print_query_status,
@@ -62,6 +65,13 @@ pub struct CreateCommand {
     )]
     pub tcp_listener_address: String,
 
+    /// Serve the health of the node over plain HTTP at this address, for the
+    /// liveness and readiness probes of orchestrators: `GET /health` and
+    /// `GET /ready` answer 200 when the node is alive or ready, and 503 when
+    /// it is not ready.
+    #[arg(display_order = 900, long, value_name = "SOCKET_ADDRESS")]
+    pub health_address: Option<SocketAddr>,
+
     /// Skip creation of default Vault and Identity
     #[arg(long, short, hide = true)]
     pub skip_defaults: bool,
@@ -94,6 +104,10 @@ pub struct CreateCommand {
 
     #[arg(long, hide = true)]
     pub config: Option<PathBuf>,
+
+    /// Read the key of encrypted vaults from the standard input, see `spawn_node`
+    #[arg(long, hide = true)]
+    pub vault_key_stdin: bool,
 }
 
 impl Default for CreateCommand {
@@ -102,6 +116,7 @@ impl Default for CreateCommand {
             node_name: hex::encode(&random::<[u8; 4]>()),
             foreground: false,
             tcp_listener_address: "127.0.0.1:0".to_string(),
+            health_address: None,
             skip_defaults: false,
             enable_credential_checks: false,
             no_shared_identity: false,
@@ -110,18 +125,27 @@ impl Default for CreateCommand {
             no_watchdog: false,
             project: None,
             config: None,
+            vault_key_stdin: false,
         }
     }
 }
 
 impl CreateCommand {
+    /// Whether the command runs a node started by `spawn_node`
+    ///
+    /// The logging of such a node is set up once its log file is known,
+    /// instead of when the command starts.
+    pub(crate) fn is_child_process(&self) -> bool {
+        self.foreground && self.child_process
+    }
+
     pub fn run(self, options: CommandGlobalOpts) {
         if self.foreground {
             // Create a new node in the foreground (i.e. in this OS process)
             if let Err(e) = create_foreground_node(&options, &self) {
                 error!(%e);
                 eprintln!("{e:?}");
-                std::process::exit(e.code());
+                crate::util::exit(e.code());
             }
         } else {
             // Create a new node running in the background (i.e. another, new OS process)
@@ -129,7 +153,7 @@ impl CreateCommand {
         }
     }
 
-    fn overwrite_addr(&self) -> Result<Self> {
+    pub(crate) fn overwrite_addr(&self) -> Result<Self> {
         let cmd = self.clone();
         let addr: SocketAddr = if &cmd.tcp_listener_address == "127.0.0.1:0" {
             let port = find_available_port().context("failed to acquire available port")?;
@@ -163,7 +187,7 @@ async fn run_impl(
     spawn_background_node(&ctx, &opts, &cmd, addr).await?;
 
     // Print node status
-    let tcp = TcpTransport::create(&ctx).await?;
+    let tcp = tcp_transport(&ctx).await?;
     let mut rpc = RpcBuilder::new(&ctx, &opts, node_name).tcp(&tcp)?.build();
     let port = cfg.get_node_port(node_name)?;
     print_query_status(&mut rpc, port, node_name, true).await?;
@@ -187,12 +211,22 @@ fn create_foreground_node(opts: &CommandGlobalOpts, cmd: &CreateCommand) -> crat
     let cmd = cmd.overwrite_addr()?;
     let addr = SocketAddr::from_str(&cmd.tcp_listener_address)?;
 
+    // A background node logs to its own file, see `CreateCommand::is_child_process`
+    if cmd.child_process {
+        if let Some((log, _)) = cfg.node_log_paths(node_name) {
+            logging::setup_node_logging(verbose, node_name, &log)
+        }
+    }
+    if cmd.vault_key_stdin {
+        set_storage_key(read_storage_key(std::io::stdin().lock())?);
+    }
+
     // HACK: try to get the current node dir.  If it doesn't
     // exist the user PROBABLY started a non-detached node.
     // Thus we need to create the node dir so that subsequent
     // calls to it don't fail
     if cfg.get_node_dir(node_name).is_err() {
-        println!("Creating node directory...");
+        opts.progress("Creating node directory...");
         cfg.create_node(node_name, addr, verbose)?;
         cfg.persist_config_updates()?;
     }
@@ -230,7 +264,7 @@ async fn run_foreground_node(
         None => None,
     };
 
-    let tcp = TcpTransport::create(&ctx).await?;
+    let tcp = tcp_transport(&ctx).await?;
     let bind = cmd.tcp_listener_address;
     tcp.listen(&bind).await?;
 
@@ -244,7 +278,10 @@ async fn run_foreground_node(
             cmd.skip_defaults || cmd.launch_config.is_some(),
             cmd.enable_credential_checks,
             identity_override,
-        ),
+            logging::log_filter(),
+            cmd.health_address,
+        )
+        .with_vault_key(storage_key()),
         NodeManagerProjectsOptions::new(
             Some(&cfg.authorities(&cmd.node_name)?.snapshot()),
             project_id,
@@ -293,16 +330,25 @@ async fn start_services(
 
     if let Some(cfg) = config.vault {
         if !cfg.disabled {
-            println!("starting vault service ...");
-            start::start_vault_service(ctx, opts, &node_opts.api_node, &cfg.address, Some(tcp))
-                .await?
+            opts.progress("starting vault service ...");
+            let started =
+                start::start_vault_service(ctx, opts, &node_opts.api_node, &cfg.address, Some(tcp))
+                    .await?;
+            opts.progress(started);
         }
     }
     if let Some(cfg) = config.identity {
         if !cfg.disabled {
-            println!("starting identity service ...");
-            start::start_identity_service(ctx, opts, &node_opts.api_node, &cfg.address, Some(tcp))
-                .await?
+            opts.progress("starting identity service ...");
+            let started = start::start_identity_service(
+                ctx,
+                opts,
+                &node_opts.api_node,
+                &cfg.address,
+                Some(tcp),
+            )
+            .await?;
+            opts.progress(started);
         }
     }
     if let Some(cfg) = config.secure_channel_listener {
@@ -310,21 +356,29 @@ async fn start_services(
             let adr = Address::from((LOCAL, cfg.address));
             let ids = cfg.authorized_identifiers;
             let rte = addr.clone().into();
-            println!("starting secure-channel listener ...");
-            secure_channel_listener::create_listener(ctx, adr, ids, rte).await?;
+            opts.progress("starting secure-channel listener ...");
+            let address = secure_channel_listener::create_listener(ctx, adr, ids, rte).await?;
+            opts.progress(address);
         }
     }
     if let Some(cfg) = config.verifier {
         if !cfg.disabled {
-            println!("starting verifier service ...");
-            start::start_verifier_service(ctx, opts, &node_opts.api_node, &cfg.address, Some(tcp))
-                .await?
+            opts.progress("starting verifier service ...");
+            let started = start::start_verifier_service(
+                ctx,
+                opts,
+                &node_opts.api_node,
+                &cfg.address,
+                Some(tcp),
+            )
+            .await?;
+            opts.progress(started);
         }
     }
     if let Some(cfg) = config.authenticator {
         if !cfg.disabled {
-            println!("starting authenticator service ...");
-            start::start_authenticator_service(
+            opts.progress("starting authenticator service ...");
+            let started = start::start_authenticator_service(
                 ctx,
                 opts,
                 &node_opts.api_node,
@@ -333,21 +387,38 @@ async fn start_services(
                 &cfg.project,
                 Some(tcp),
             )
-            .await?
+            .await?;
+            opts.progress(started);
         }
     }
     if let Some(cfg) = config.okta_identity_provider {
         if !cfg.disabled {
-            println!("starting okta identity provider service ...");
-            start::start_okta_identity_provider(ctx, opts, &node_opts.api_node, &cfg, Some(tcp))
-                .await?
+            opts.progress("starting okta identity provider service ...");
+            let started = start::start_okta_identity_provider(
+                ctx,
+                opts,
+                &node_opts.api_node,
+                &cfg,
+                Some(tcp),
+            )
+            .await?;
+            opts.progress(started);
+        }
+    }
+    if let Some(cfg) = config.oidc_authenticator {
+        if !cfg.disabled {
+            opts.progress("starting oidc authenticator service ...");
+            let started =
+                start::start_oidc_authenticator(ctx, opts, &node_opts.api_node, &cfg, Some(tcp))
+                    .await?;
+            opts.progress(started);
         }
     }
 
     Ok(())
 }
 
-async fn spawn_background_node(
+pub(crate) async fn spawn_background_node(
     ctx: &Context,
     opts: &CommandGlobalOpts,
     cmd: &CreateCommand,
@@ -382,6 +453,7 @@ async fn spawn_background_node(
         cmd.enable_credential_checks,
         &cmd.node_name,
         &cmd.tcp_listener_address,
+        cmd.health_address,
         cmd.project.as_deref(),
     )?;
 
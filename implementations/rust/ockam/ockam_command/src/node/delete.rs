@@ -1,6 +1,8 @@
 use crate::node::util::{delete_all_nodes, delete_node};
+use crate::util::output::Output;
 use crate::{help, node::HELP_DETAIL, CommandGlobalOpts};
 use clap::Args;
+use serde::Serialize;
 
 /// Delete Nodes
 #[derive(Clone, Debug, Args)]
@@ -23,18 +25,39 @@ impl DeleteCommand {
     pub fn run(self, options: CommandGlobalOpts) {
         if let Err(e) = run_impl(options, self) {
             eprintln!("{}", e);
-            std::process::exit(e.code());
+            crate::util::exit(e.code());
         }
     }
 }
 
 fn run_impl(opts: CommandGlobalOpts, cmd: DeleteCommand) -> crate::Result<()> {
-    if cmd.all {
-        delete_all_nodes(opts, cmd.force)?;
+    let nodes = if cmd.all {
+        delete_all_nodes(opts.clone(), cmd.force)?
     } else {
         delete_node(&opts, &cmd.node_name, cmd.force);
         opts.config.persist_config_updates()?;
-        println!("Deleted node '{}'", &cmd.node_name);
-    }
+        vec![cmd.node_name]
+    };
+    opts.print(&DeletedNodes { nodes })?;
     Ok(())
 }
+
+/// Names of the nodes deleted by a command
+#[derive(Debug, Serialize)]
+pub(crate) struct DeletedNodes {
+    pub(crate) nodes: Vec<String>,
+}
+
+impl Output for DeletedNodes {
+    fn output(&self) -> anyhow::Result<String> {
+        if self.nodes.is_empty() {
+            return Ok("No nodes to delete".to_string());
+        }
+        let lines: Vec<_> = self
+            .nodes
+            .iter()
+            .map(|n| format!("Deleted node '{n}'"))
+            .collect();
+        Ok(lines.join("\n"))
+    }
+}
@@ -0,0 +1,32 @@
+use crate::util::{api, node_rpc, tcp_transport, RpcBuilder};
+use crate::{help, node::HELP_DETAIL, CommandGlobalOpts};
+use clap::Args;
+use ockam_api::nodes::models::workers::WorkerList;
+
+/// List the workers and processors registered on a node
+#[derive(Clone, Debug, Args)]
+#[command(after_long_help = help::template(HELP_DETAIL))]
+pub struct InspectCommand {
+    /// Name of the node.
+    #[arg(default_value = "default")]
+    node_name: String,
+}
+
+impl InspectCommand {
+    pub fn run(self, options: CommandGlobalOpts) {
+        node_rpc(run_impl, (options, self))
+    }
+}
+
+async fn run_impl(
+    ctx: ockam::Context,
+    (opts, cmd): (CommandGlobalOpts, InspectCommand),
+) -> crate::Result<()> {
+    let tcp = tcp_transport(&ctx).await?;
+    let mut rpc = RpcBuilder::new(&ctx, &opts, &cmd.node_name)
+        .tcp(&tcp)?
+        .build();
+    rpc.request(api::list_workers()).await?;
+    rpc.parse_and_print_response::<WorkerList>()?;
+    Ok(())
+}
@@ -1,8 +1,7 @@
-use crate::util::{exitcode, node_rpc, verify_pids, RpcBuilder};
-use crate::{help, node::show::print_query_status, node::HELP_DETAIL, CommandGlobalOpts};
+use crate::util::{exitcode, node_rpc, tcp_transport, verify_pids, RpcBuilder};
+use crate::{help, node::show::query_status, node::HELP_DETAIL, CommandGlobalOpts};
 use anyhow::anyhow;
 use clap::Args;
-use ockam::TcpTransport;
 
 /// List Nodes
 #[derive(Clone, Debug, Args)]
@@ -37,15 +36,17 @@ async fn run_impl(
         }
         inner.nodes.iter().map(|(name, _)| name.clone()).collect()
     };
-    let tcp = TcpTransport::create(&ctx).await?;
+    let tcp = tcp_transport(&ctx).await?;
     verify_pids(&ctx, &opts, &tcp, cfg, &node_names).await?;
 
     // Print node states
+    let mut nodes = vec![];
     for node_name in &node_names {
         let mut rpc = RpcBuilder::new(&ctx, &opts, node_name).tcp(&tcp)?.build();
         let port = cfg.get_node_port(node_name)?;
-        print_query_status(&mut rpc, port, node_name, false).await?;
+        nodes.push(query_status(&mut rpc, port, node_name, false).await?);
     }
+    opts.print(&nodes)?;
 
     Ok(())
 }
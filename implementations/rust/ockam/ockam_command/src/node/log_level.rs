@@ -0,0 +1,40 @@
+use clap::Args;
+use ockam_api::nodes::models::base::LogLevel;
+
+use crate::util::{api, node_rpc, tcp_transport, RpcBuilder};
+use crate::{help, node::HELP_DETAIL, CommandGlobalOpts};
+
+/// Show or change the log filter of a running node
+#[derive(Clone, Debug, Args)]
+#[command(after_long_help = help::template(HELP_DETAIL))]
+pub struct LogLevelCommand {
+    /// Name of the node.
+    #[arg(default_value = "default")]
+    node_name: String,
+
+    /// New filter, as a level such as `debug` or with the syntax of the
+    /// `OCKAM_LOG` environment variable, e.g. `info,ockam_api=trace`
+    filter: Option<String>,
+}
+
+impl LogLevelCommand {
+    pub fn run(self, options: CommandGlobalOpts) {
+        node_rpc(run_impl, (options, self))
+    }
+}
+
+async fn run_impl(
+    ctx: ockam::Context,
+    (opts, cmd): (CommandGlobalOpts, LogLevelCommand),
+) -> crate::Result<()> {
+    let tcp = tcp_transport(&ctx).await?;
+    let mut rpc = RpcBuilder::new(&ctx, &opts, &cmd.node_name)
+        .tcp(&tcp)?
+        .build();
+    match &cmd.filter {
+        Some(filter) => rpc.request(api::set_log_level(filter)).await?,
+        None => rpc.request(api::get_log_level()).await?,
+    }
+    rpc.parse_and_print_response::<LogLevel>()?;
+    Ok(())
+}
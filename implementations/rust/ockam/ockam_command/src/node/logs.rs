@@ -0,0 +1,337 @@
+use std::fs::{self, File};
+use std::io::{self, Read, Seek, SeekFrom, Write};
+use std::path::Path;
+use std::thread::sleep;
+use std::time::Duration;
+
+use anyhow::{anyhow, Context};
+use clap::Args;
+use serde_json::Value;
+use time::format_description::well_known::Rfc3339;
+use time::OffsetDateTime;
+use tracing::Level;
+
+use crate::node::HELP_DETAIL;
+use crate::util::{exitcode, extract_address_value, logging};
+use crate::{help, CommandGlobalOpts, OutputFormat};
+
+/// How often the log is checked for new lines when following it
+const FOLLOW_INTERVAL: Duration = Duration::from_millis(250);
+
+/// Show the logs of a node
+#[derive(Clone, Debug, Args)]
+#[command(after_long_help = help::template(HELP_DETAIL))]
+pub struct LogsCommand {
+    /// Name of the node.
+    #[arg(default_value = "default")]
+    node_name: String,
+
+    /// Keep printing the lines logged by the node
+    #[arg(long, short)]
+    follow: bool,
+
+    /// Only show the events at this level or a more severe one
+    #[arg(long, value_name = "LEVEL")]
+    level: Option<Level>,
+
+    /// Only show the events logged since this time, given as an RFC 3339
+    /// timestamp or as a duration such as `30s`, `10m`, `2h` or `1d`
+    #[arg(long, value_name = "TIME", value_parser = parse_since)]
+    since: Option<OffsetDateTime>,
+
+    /// Number of lines to show, from the end of the log
+    #[arg(long, short = 'n')]
+    lines: Option<usize>,
+
+    /// Show the standard output and error of the node instead of its log
+    #[arg(long)]
+    stderr: bool,
+}
+
+impl LogsCommand {
+    pub fn run(self, options: CommandGlobalOpts) {
+        if let Err(e) = run_impl(options, self) {
+            eprintln!("{}", e);
+            crate::util::exit(e.code());
+        }
+    }
+}
+
+fn run_impl(opts: CommandGlobalOpts, cmd: LogsCommand) -> crate::Result<()> {
+    let node_name = extract_address_value(&cmd.node_name)?;
+    let (log, stderr) = opts.config.node_log_paths(&node_name).ok_or_else(|| {
+        crate::Error::new(
+            exitcode::IOERR,
+            anyhow!("Node {node_name} does not exist or has no logs"),
+        )
+    })?;
+    let path = if cmd.stderr { stderr } else { log };
+
+    let lines: Vec<_> = read_log(&path)?
+        .into_iter()
+        .filter(|l| cmd.keep(l))
+        .collect();
+    let skip = cmd.lines.map_or(0, |n| lines.len().saturating_sub(n));
+    for l in &lines[skip..] {
+        println!("{}", l.format(&opts.global_args.output_format));
+    }
+
+    if cmd.follow {
+        let mut stdout = io::stdout();
+        follow(&path, lines.last(), |l| {
+            if !cmd.keep(l) {
+                return Ok(());
+            }
+            writeln!(stdout, "{}", l.format(&opts.global_args.output_format))
+        })?;
+    }
+    Ok(())
+}
+
+impl LogsCommand {
+    fn keep(&self, line: &LogLine) -> bool {
+        let level = match (self.level, line.level) {
+            (Some(max), Some(l)) => l <= max,
+            _ => true,
+        };
+        let since = match (self.since, line.timestamp) {
+            (Some(since), Some(t)) => t >= since,
+            (Some(_), None) => false,
+            (None, _) => true,
+        };
+        level && since
+    }
+}
+
+/// Print the lines appended to a log until interrupted, or until they can't be printed
+///
+/// When the log is rotated, the lines of the new file are read from its start.
+fn follow(
+    path: &Path,
+    last: Option<&LogLine>,
+    mut f: impl FnMut(&LogLine) -> io::Result<()>,
+) -> anyhow::Result<()> {
+    let mut last = last.cloned();
+    let (mut id, mut pos) = match fs::metadata(path) {
+        Ok(m) => (file_id(&m), m.len()),
+        Err(_) => (0, 0),
+    };
+    loop {
+        sleep(FOLLOW_INTERVAL);
+        let meta = match fs::metadata(path) {
+            Ok(m) => m,
+            Err(_) => continue,
+        };
+        if file_id(&meta) != id || meta.len() < pos {
+            id = file_id(&meta);
+            pos = 0;
+        }
+        if meta.len() == pos {
+            continue;
+        }
+        let mut file = File::open(path).with_context(|| anyhow!("failed to read {path:?}"))?;
+        file.seek(SeekFrom::Start(pos))?;
+        let mut buf = String::new();
+        file.read_to_string(&mut buf)?;
+        // A line is only read once it is complete
+        let end = match buf.rfind('\n') {
+            Some(i) => i + 1,
+            None => continue,
+        };
+        pos += end as u64;
+        for raw in buf[..end].lines() {
+            let l = LogLine::parse(raw, last.as_ref());
+            if f(&l).is_err() {
+                return Ok(());
+            }
+            last = Some(l);
+        }
+    }
+}
+
+/// Identifies a file, so that a log replaced by rotation is noticed
+#[cfg(unix)]
+fn file_id(meta: &fs::Metadata) -> u64 {
+    use std::os::unix::fs::MetadataExt;
+    meta.ino()
+}
+
+/// Identifies a file, so that a log replaced by rotation is noticed
+#[cfg(not(unix))]
+fn file_id(meta: &fs::Metadata) -> u64 {
+    meta.created()
+        .ok()
+        .and_then(|t| t.duration_since(std::time::UNIX_EPOCH).ok())
+        .map_or(0, |d| d.as_nanos() as u64)
+}
+
+/// Read all the lines of a log, including its rotated files
+pub(crate) fn read_log(path: &Path) -> anyhow::Result<Vec<LogLine>> {
+    let mut lines: Vec<LogLine> = vec![];
+    for p in logging::log_files(path) {
+        let content = fs::read_to_string(&p).with_context(|| anyhow!("failed to read {p:?}"))?;
+        for raw in content.lines() {
+            let l = LogLine::parse(raw, lines.last());
+            lines.push(l);
+        }
+    }
+    Ok(lines)
+}
+
+/// A line of a node log
+///
+/// Background nodes log an event per line, as a JSON object. Nodes started by
+/// former versions, and the stderr log, contain plain lines, where the lines of
+/// an event that follow the first one have no timestamp nor level.
+#[derive(Debug, Clone)]
+pub(crate) struct LogLine {
+    /// Time of the event, taken from the previous line if the line has none
+    pub(crate) timestamp: Option<OffsetDateTime>,
+    /// Level of the event, taken from the previous line if the line has none
+    pub(crate) level: Option<Level>,
+    event: Option<Value>,
+    raw: String,
+}
+
+impl LogLine {
+    pub(crate) fn parse(raw: &str, prev: Option<&LogLine>) -> Self {
+        let (timestamp, level, event) = match serde_json::from_str::<Value>(raw) {
+            Ok(event @ Value::Object(_)) => {
+                let timestamp = event["timestamp"]
+                    .as_str()
+                    .and_then(|t| OffsetDateTime::parse(t, &Rfc3339).ok());
+                let level = event["level"].as_str().and_then(|l| l.parse().ok());
+                (timestamp, level, Some(event))
+            }
+            _ => {
+                let mut words = raw.split_whitespace();
+                match words
+                    .next()
+                    .and_then(|t| OffsetDateTime::parse(t, &Rfc3339).ok())
+                {
+                    Some(t) => (Some(t), words.next().and_then(|l| l.parse().ok()), None),
+                    None => (None, None, None),
+                }
+            }
+        };
+        let inherit = timestamp.is_none() && event.is_none();
+        Self {
+            timestamp: if inherit {
+                prev.and_then(|p| p.timestamp)
+            } else {
+                timestamp
+            },
+            level: if inherit {
+                prev.and_then(|p| p.level)
+            } else {
+                level
+            },
+            event,
+            raw: raw.to_string(),
+        }
+    }
+
+    /// Format the line for the given output format
+    ///
+    /// JSON events are shown like the log lines of commands in plain format,
+    /// and as they were logged in JSON format.
+    pub(crate) fn format(&self, format: &OutputFormat) -> String {
+        let event = match (&self.event, format) {
+            (Some(e), OutputFormat::Plain) => e,
+            _ => return self.raw.clone(),
+        };
+        let str = |k: &str| event[k].as_str().unwrap_or_default();
+        let mut line = format!(
+            "{} {:>5} {}: {}",
+            str("timestamp"),
+            str("level"),
+            str("target"),
+            str("message")
+        );
+        if let Some(fields) = event["fields"].as_object() {
+            for (k, v) in fields {
+                match v {
+                    Value::String(s) => line.push_str(&format!(" {k}={s}")),
+                    v => line.push_str(&format!(" {k}={v}")),
+                }
+            }
+        }
+        line
+    }
+}
+
+fn parse_since(s: &str) -> Result<OffsetDateTime, String> {
+    if let Ok(t) = OffsetDateTime::parse(s, &Rfc3339) {
+        return Ok(t);
+    }
+    let err = || format!("invalid time {s:?}, expected a timestamp or a duration such as `10m`");
+    let (n, unit) = s.split_at(s.find(|c: char| !c.is_ascii_digit()).ok_or_else(err)?);
+    let n: u64 = n.parse().map_err(|_| err())?;
+    let secs = match unit {
+        "s" => n,
+        "m" => n * 60,
+        "h" => n * 60 * 60,
+        "d" => n * 24 * 60 * 60,
+        _ => return Err(err()),
+    };
+    Ok(OffsetDateTime::now_utc() - Duration::from_secs(secs))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parse_log_lines() {
+        let json = r#"{"timestamp":"2022-11-01T10:00:00.5Z","level":"WARN","target":"ockam_api::nodes","message":"slow","fields":{"ms":12,"to":"n1"}}"#;
+        let l = LogLine::parse(json, None);
+        assert_eq!(l.level, Some(Level::WARN));
+        assert_eq!(
+            l.format(&OutputFormat::Plain),
+            "2022-11-01T10:00:00.5Z  WARN ockam_api::nodes: slow ms=12 to=n1"
+        );
+        assert_eq!(l.format(&OutputFormat::Json), json);
+
+        let plain = LogLine::parse("2022-11-01T10:00:01.000001Z DEBUG a: one", Some(&l));
+        assert_eq!(plain.level, Some(Level::DEBUG));
+        let next = LogLine::parse("  continued", Some(&plain));
+        assert_eq!(next.level, Some(Level::DEBUG));
+        assert_eq!(next.timestamp, plain.timestamp);
+        assert!(l.timestamp < plain.timestamp);
+    }
+
+    #[test]
+    fn continuation_lines_keep_the_event_timestamp() {
+        let log = "\
+2022-11-01T10:00:00.000001Z  INFO a: one
+  continued
+2022-11-01T10:00:01.000001Z  INFO a: two
+";
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("n1.log.stderr");
+        std::fs::write(&path, log).unwrap();
+        let lines: Vec<_> = read_log(&path)
+            .unwrap()
+            .into_iter()
+            .map(|l| l.timestamp)
+            .collect();
+        let t = |s| Some(OffsetDateTime::parse(s, &Rfc3339).unwrap());
+        assert_eq!(
+            lines,
+            [
+                t("2022-11-01T10:00:00.000001Z"),
+                t("2022-11-01T10:00:00.000001Z"),
+                t("2022-11-01T10:00:01.000001Z")
+            ]
+        );
+    }
+
+    #[test]
+    fn since_durations() {
+        assert!(parse_since("10m").unwrap() < OffsetDateTime::now_utc());
+        assert!(parse_since("2022-11-01T10:00:00Z").is_ok());
+        assert!(parse_since("10").is_err());
+        assert!(parse_since("m").is_err());
+        assert!(parse_since("10w").is_err());
+    }
+}
@@ -1,18 +1,33 @@
 use clap::{Args, Subcommand};
 
-pub(crate) use create::CreateCommand;
+pub(crate) use apply::{
+    apply_spec, create_node, plan_new_node, start_node, summary, ApplyCommand, ApplyReport,
+    InletSpec, NodeSpec,
+};
+pub(crate) use create::{spawn_background_node, CreateCommand};
 use delete::DeleteCommand;
+pub(crate) use delete::DeletedNodes;
+use inspect::InspectCommand;
 use list::ListCommand;
+use log_level::LogLevelCommand;
+pub(crate) use logs::read_log;
+use logs::LogsCommand;
 use run::RunCommand;
 use show::ShowCommand;
+pub(crate) use show::{is_node_up, print_query_status};
+pub(crate) use start::restart_background_node;
 use start::StartCommand;
 use stop::StopCommand;
 
 use crate::{help, CommandGlobalOpts};
 
+mod apply;
 mod create;
 mod delete;
+mod inspect;
 mod list;
+mod log_level;
+mod logs;
 mod run;
 mod show;
 mod start;
@@ -96,15 +111,34 @@ Examples:
     # Create a node, with a specified tcp listener address
     $ ockam node create n1 --tcp-listener-address 127.0.0.1:6001
 
+    # Create a node which answers the health checks of orchestrators over HTTP
+    $ ockam node create n1 --health-address 0.0.0.0:8080
+    $ curl http://localhost:8080/ready
+
     # Create a node, and run it in the foreground with verbose traces
     $ ockam node create n1 --foreground -vvv
 
     # Show information about a specific node
     $ ockam node show n1
 
+    # List the workers running on a node, with their mailbox stats
+    $ ockam node inspect n1
+
     # List all created nodes
     $ ockam node list
 
+    # Show the last lines logged by a node, and keep printing new ones
+    $ ockam node logs n1 -n 20 --follow
+
+    # Show the warnings and errors logged by a node in the last hour
+    $ ockam node logs n1 --level warn --since 1h
+
+    # Log the debug events of a running node
+    $ ockam node log-level n1 debug
+
+    # Create or update a node from a configuration file
+    $ ockam node apply -f n1.yaml
+
     # Delete the node
     $ ockam node delete n1
 
@@ -139,14 +173,27 @@ pub enum NodeSubcommand {
     #[command(display_order = 800)]
     Show(ShowCommand),
     #[command(display_order = 800)]
+    Inspect(InspectCommand),
+    #[command(display_order = 800)]
     Run(RunCommand),
     #[command(display_order = 800)]
     Start(StartCommand),
     #[command(display_order = 800)]
     Stop(StopCommand),
+    #[command(display_order = 800)]
+    Apply(ApplyCommand),
+    #[command(display_order = 800)]
+    Logs(LogsCommand),
+    #[command(display_order = 800)]
+    LogLevel(LogLevelCommand),
 }
 
 impl NodeCommand {
+    /// Whether the command runs a background node, which sets up its own logging
+    pub(crate) fn is_child_process(&self) -> bool {
+        matches!(&self.subcommand, NodeSubcommand::Create(c) if c.is_child_process())
+    }
+
     pub fn run(self, options: CommandGlobalOpts) {
         match self.subcommand {
             NodeSubcommand::Create(c) => c.run(options),
@@ -154,8 +201,12 @@ impl NodeCommand {
             NodeSubcommand::List(c) => c.run(options),
             NodeSubcommand::Run(c) => c.run(options),
             NodeSubcommand::Show(c) => c.run(options),
+            NodeSubcommand::Inspect(c) => c.run(options),
             NodeSubcommand::Start(c) => c.run(options),
             NodeSubcommand::Stop(c) => c.run(options),
+            NodeSubcommand::Apply(c) => c.run(options),
+            NodeSubcommand::Logs(c) => c.run(options),
+            NodeSubcommand::LogLevel(c) => c.run(options),
         }
     }
 }
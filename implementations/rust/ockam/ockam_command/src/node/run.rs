@@ -19,7 +19,7 @@ impl RunCommand {
         if let Err(e) = self.run_impl(options) {
             error!(%e);
             eprintln!("{e:?}");
-            std::process::exit(e.code());
+            crate::util::exit(e.code());
         }
     }
 
@@ -1,17 +1,20 @@
-use crate::util::{api, node_rpc, Rpc, RpcBuilder};
+use crate::util::output::Output;
+use crate::util::{api, node_rpc, tcp_transport, Rpc, RpcBuilder};
 use crate::{help, node::HELP_DETAIL, CommandGlobalOpts};
 use clap::Args;
 use colorful::Colorful;
+use core::fmt::Write;
 use core::time::Duration;
-use ockam::TcpTransport;
+use ockam_api::nodes::models::base::{CredentialState, NodeHealth};
 use ockam_api::nodes::models::identity::ShortIdentityResponse;
 use ockam_api::nodes::models::portal::{InletList, OutletList};
 use ockam_api::nodes::models::services::ServiceList;
-use ockam_api::nodes::models::transport::TransportList;
+use ockam_api::nodes::models::transport::TransportMode;
 use ockam_api::{addr_to_multiaddr, route_to_multiaddr};
 use ockam_core::Route;
 use ockam_multiaddr::proto::{DnsAddr, Node, Tcp};
 use ockam_multiaddr::MultiAddr;
+use serde::Serialize;
 use tokio_retry::strategy::FibonacciBackoff;
 use tracing::debug;
 
@@ -40,160 +43,311 @@ async fn run_impl(
     let node_name = cmd.node_name;
     let cfg = &opts.config;
     let node_cfg = cfg.get_node(&node_name)?;
-    let tcp = TcpTransport::create(&ctx).await?;
+    let tcp = tcp_transport(&ctx).await?;
     let mut rpc = RpcBuilder::new(&ctx, &opts, &node_name).tcp(&tcp)?.build();
     print_query_status(&mut rpc, node_cfg.port(), &node_name, false).await?;
     Ok(())
 }
 
-// TODO: This function should be replaced with a better system of
-// printing the node state in the future but for now we can just tell
-// clippy to stop complainaing about it.
-#[allow(clippy::too_many_arguments)]
-fn print_node_info(
-    node_port: u16,
-    node_name: &str,
-    status_is_up: bool,
-    default_id: Option<&str>,
-    services: Option<&ServiceList>,
-    tcp_listeners: Option<&TransportList>,
-    secure_channel_listeners: Option<&Vec<String>>,
-    inlets_outlets: Option<(&InletList, &OutletList)>,
-) {
-    println!();
-    println!("Node:");
-    println!("  Name: {}", node_name);
-    println!(
-        "  Status: {}",
-        match status_is_up {
-            true => "UP".light_green(),
-            false => "DOWN".light_red(),
-        }
-    );
+/// State of a node, as shown by `node show`, `node list`, `node create` and `node start`
+#[derive(Debug, Serialize)]
+pub(crate) struct NodeInfo {
+    name: String,
+    status: NodeState,
+    short_route: Option<String>,
+    verbose_route: Option<String>,
+    identity: Option<String>,
+    ready: Option<bool>,
+    workers: Option<u32>,
+    transports: Vec<TransportInfo>,
+    secure_channel_listeners: Vec<String>,
+    credential: Option<CredentialState>,
+    sessions: Vec<SessionInfo>,
+    inlets: Vec<InletInfo>,
+    outlets: Vec<OutletInfo>,
+    services: Vec<ServiceInfo>,
+}
 
-    println!("  Route To Node:");
-    let mut m = MultiAddr::default();
-    if m.push_back(Node::new(node_name)).is_ok() {
-        println!("    Short: {}", m);
-    }
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "snake_case")]
+enum NodeState {
+    Up,
+    Down,
+}
 
-    let mut m = MultiAddr::default();
-    if m.push_back(DnsAddr::new("localhost")).is_ok() && m.push_back(Tcp::new(node_port)).is_ok() {
-        println!("    Verbose: {}", m);
-    }
+#[derive(Debug, Serialize)]
+struct TransportInfo {
+    #[serde(rename = "type")]
+    tt: String,
+    mode: String,
+    address: String,
+}
 
-    if let Some(id) = default_id {
-        println!("  Identity: {}", id);
-    }
+#[derive(Debug, Serialize)]
+struct SessionInfo {
+    address: String,
+    status: String,
+}
+
+#[derive(Debug, Serialize)]
+struct InletInfo {
+    listen_address: String,
+    route_to_outlet: Option<String>,
+}
+
+#[derive(Debug, Serialize)]
+struct OutletInfo {
+    forward_address: String,
+    address: Option<String>,
+}
 
-    if let Some(list) = tcp_listeners {
-        println!("  Transports:");
-        for e in &list.list {
-            println!("    Transport:");
-            println!("      Type: {}", e.tt);
-            println!("      Mode: {}", e.tm);
-            println!("      Address: {}", e.payload);
+#[derive(Debug, Serialize)]
+struct ServiceInfo {
+    #[serde(rename = "type")]
+    service_type: String,
+    address: Option<String>,
+}
+
+impl NodeInfo {
+    fn new(node_port: u16, node_name: &str, status: NodeState) -> Self {
+        let mut short = MultiAddr::default();
+        let short = short
+            .push_back(Node::new(node_name))
+            .ok()
+            .map(|_| short.to_string());
+        let mut verbose = MultiAddr::default();
+        let verbose = (verbose.push_back(DnsAddr::new("localhost")).is_ok()
+            && verbose.push_back(Tcp::new(node_port)).is_ok())
+        .then(|| verbose.to_string());
+        Self {
+            name: node_name.to_string(),
+            status,
+            short_route: short,
+            verbose_route: verbose,
+            identity: None,
+            ready: None,
+            workers: None,
+            transports: vec![],
+            secure_channel_listeners: vec![],
+            credential: None,
+            sessions: vec![],
+            inlets: vec![],
+            outlets: vec![],
+            services: vec![],
         }
     }
 
-    if let Some(list) = secure_channel_listeners {
-        println!("  Secure Channel Listeners:");
-        for e in list {
-            println!("    Listener:");
-            if let Some(ma) = addr_to_multiaddr(e) {
-                println!("      Address: {}", ma);
-            }
+    fn set_health(&mut self, health: &NodeHealth) {
+        self.ready = Some(health.ready);
+        self.workers = Some(health.workers);
+        self.transports = health
+            .transports
+            .iter()
+            .filter(|t| t.tm == TransportMode::Listen)
+            .map(|t| TransportInfo {
+                tt: t.tt.to_string(),
+                mode: t.tm.to_string(),
+                address: t.payload.to_string(),
+            })
+            .collect();
+        self.secure_channel_listeners = health
+            .secure_channel_listeners
+            .iter()
+            .filter_map(|l| addr_to_multiaddr(l.as_ref()).map(|ma| ma.to_string()))
+            .collect();
+        if health.credential != CredentialState::NotRequired {
+            self.credential = Some(health.credential);
         }
+        self.sessions = health
+            .sessions
+            .iter()
+            .map(|s| SessionInfo {
+                address: s.addr.to_string(),
+                status: s.status.to_string(),
+            })
+            .collect();
     }
+}
+
+impl Output for NodeInfo {
+    fn output(&self) -> anyhow::Result<String> {
+        let mut w = String::new();
+        writeln!(w)?;
+        writeln!(w, "Node:")?;
+        writeln!(w, "  Name: {}", self.name)?;
+        let status = match self.status {
+            NodeState::Up => "UP".light_green(),
+            NodeState::Down => "DOWN".light_red(),
+        };
+        write!(w, "  Status: {}", status)?;
+
+        write!(w, "\n  Route To Node:")?;
+        if let Some(m) = &self.short_route {
+            write!(w, "\n    Short: {}", m)?;
+        }
+        if let Some(m) = &self.verbose_route {
+            write!(w, "\n    Verbose: {}", m)?;
+        }
+
+        if let Some(id) = &self.identity {
+            write!(w, "\n  Identity: {}", id)?;
+        }
+
+        if let (Some(ready), Some(workers)) = (self.ready, self.workers) {
+            let ready = match ready {
+                true => "YES".light_green(),
+                false => "NO".light_red(),
+            };
+            write!(w, "\n  Ready: {}", ready)?;
+            write!(w, "\n  Workers: {}", workers)?;
+
+            write!(w, "\n  Transports:")?;
+            for e in &self.transports {
+                write!(w, "\n    Transport:")?;
+                write!(w, "\n      Type: {}", e.tt)?;
+                write!(w, "\n      Mode: {}", e.mode)?;
+                write!(w, "\n      Address: {}", e.address)?;
+            }
 
-    if let Some((inlets, outlets)) = inlets_outlets {
-        println!("  Inlets:");
-        for e in &inlets.list {
-            println!("    Inlet:");
-            println!("      Listen Address: {}", e.bind_addr);
-            if let Some(r) = Route::parse(e.outlet_route.as_ref()) {
-                if let Some(ma) = route_to_multiaddr(&r) {
-                    println!("      Route To Outlet: {}", ma);
+            write!(w, "\n  Secure Channel Listeners:")?;
+            for e in &self.secure_channel_listeners {
+                write!(w, "\n    Listener:")?;
+                write!(w, "\n      Address: {}", e)?;
+            }
+
+            if let Some(credential) = self.credential {
+                write!(w, "\n  Credential: {}", credential)?;
+            }
+
+            if !self.sessions.is_empty() {
+                write!(w, "\n  Sessions:")?;
+                for s in &self.sessions {
+                    write!(w, "\n    Session:")?;
+                    write!(w, "\n      Address: {}", s.address)?;
+                    write!(w, "\n      Status: {}", s.status)?;
                 }
             }
         }
-        println!("  Outlets:");
-        for e in &outlets.list {
-            println!("    Outlet:");
-            println!("      Forward Address: {}", e.tcp_addr);
 
-            if let Some(ma) = addr_to_multiaddr(e.worker_addr.as_ref()) {
-                println!("      Address: {}", ma);
+        if self.status == NodeState::Up {
+            write!(w, "\n  Inlets:")?;
+            for e in &self.inlets {
+                write!(w, "\n    Inlet:")?;
+                write!(w, "\n      Listen Address: {}", e.listen_address)?;
+                if let Some(ma) = &e.route_to_outlet {
+                    write!(w, "\n      Route To Outlet: {}", ma)?;
+                }
+            }
+            write!(w, "\n  Outlets:")?;
+            for e in &self.outlets {
+                write!(w, "\n    Outlet:")?;
+                write!(w, "\n      Forward Address: {}", e.forward_address)?;
+                if let Some(ma) = &e.address {
+                    write!(w, "\n      Address: {}", ma)?;
+                }
             }
-        }
-    }
 
-    if let Some(list) = services {
-        println!("  Services:");
-        for e in &list.list {
-            println!("    Service:");
-            println!("      Type: {}", e.service_type);
-            if let Some(ma) = addr_to_multiaddr(e.addr.as_ref()) {
-                println!("      Address: {}", ma);
+            write!(w, "\n  Services:")?;
+            for e in &self.services {
+                write!(w, "\n    Service:")?;
+                write!(w, "\n      Type: {}", e.service_type)?;
+                if let Some(ma) = &e.address {
+                    write!(w, "\n      Address: {}", ma)?;
+                }
             }
         }
+        Ok(w)
     }
 }
 
+impl Output for Vec<NodeInfo> {
+    fn output(&self) -> anyhow::Result<String> {
+        let nodes = self
+            .iter()
+            .map(|n| n.output())
+            .collect::<anyhow::Result<Vec<_>>>()?;
+        Ok(nodes.join("\n"))
+    }
+}
+
+/// Print the state of a node, see [`query_status`]
 pub async fn print_query_status(
     rpc: &mut Rpc<'_>,
     node_port: u16,
     node_name: &str,
     wait_until_ready: bool,
 ) -> anyhow::Result<()> {
-    if !is_node_up(rpc, wait_until_ready).await? {
-        print_node_info(node_port, node_name, false, None, None, None, None, None);
-    } else {
-        // Get short id for the node
-        rpc.request(api::short_identity()).await?;
-        let default_id = match rpc.parse_response::<ShortIdentityResponse>() {
-            Ok(resp) => String::from(resp.identity_id),
-            Err(_) => String::from("None"),
-        };
+    let info = query_status(rpc, node_port, node_name, wait_until_ready).await?;
+    rpc.print_response(info)?;
+    Ok(())
+}
 
-        // Get list of services for the node
-        let mut rpc = rpc.clone();
-        rpc.request(api::list_services()).await?;
-        let services = rpc.parse_response::<ServiceList>()?;
-
-        // Get list of TCP listeners for node
-        let mut rpc = rpc.clone();
-        rpc.request(api::list_tcp_listeners()).await?;
-        let tcp_listeners = rpc.parse_response::<TransportList>()?;
-
-        // Get list of Secure Channel Listeners
-        let mut rpc = rpc.clone();
-        rpc.request(api::list_secure_channel_listener()).await?;
-        let secure_channel_listeners = rpc.parse_response::<Vec<String>>()?;
-
-        // Get list of inlets
-        let mut rpc = rpc.clone();
-        rpc.request(api::list_inlets()).await?;
-        let inlets = rpc.parse_response::<InletList>()?;
-
-        // Get list of outlets
-        let mut rpc = rpc.clone();
-        rpc.request(api::list_outlets()).await?;
-        let outlets = rpc.parse_response::<OutletList>()?;
-
-        print_node_info(
-            node_port,
-            node_name,
-            true,
-            Some(&default_id),
-            Some(&services),
-            Some(&tcp_listeners),
-            Some(&secure_channel_listeners),
-            Some((&inlets, &outlets)),
-        );
+/// Query the state of a node, which is only `Down` when it doesn't answer
+pub(crate) async fn query_status(
+    rpc: &mut Rpc<'_>,
+    node_port: u16,
+    node_name: &str,
+    wait_until_ready: bool,
+) -> anyhow::Result<NodeInfo> {
+    if !is_node_up(rpc, wait_until_ready).await? {
+        return Ok(NodeInfo::new(node_port, node_name, NodeState::Down));
     }
+    let mut info = NodeInfo::new(node_port, node_name, NodeState::Up);
 
-    Ok(())
+    // Get short id for the node
+    rpc.request(api::short_identity()).await?;
+    info.identity = Some(match rpc.parse_response::<ShortIdentityResponse>() {
+        Ok(resp) => String::from(resp.identity_id),
+        Err(_) => String::from("None"),
+    });
+
+    // Get list of services for the node
+    let mut rpc = rpc.clone();
+    rpc.request(api::list_services()).await?;
+    info.services = rpc
+        .parse_response::<ServiceList>()?
+        .list
+        .iter()
+        .map(|e| ServiceInfo {
+            service_type: e.service_type.to_string(),
+            address: addr_to_multiaddr(e.addr.as_ref()).map(|ma| ma.to_string()),
+        })
+        .collect();
+
+    // Get the transports, listeners, credential and sessions of the node
+    let mut rpc = rpc.clone();
+    rpc.request(api::node_health()).await?;
+    info.set_health(&rpc.parse_response::<NodeHealth>()?);
+
+    // Get list of inlets
+    let mut rpc = rpc.clone();
+    rpc.request(api::list_inlets()).await?;
+    info.inlets = rpc
+        .parse_response::<InletList>()?
+        .list
+        .iter()
+        .map(|e| InletInfo {
+            listen_address: e.bind_addr.to_string(),
+            route_to_outlet: Route::parse(e.outlet_route.as_ref())
+                .and_then(|r| route_to_multiaddr(&r))
+                .map(|ma| ma.to_string()),
+        })
+        .collect();
+
+    // Get list of outlets
+    let mut rpc = rpc.clone();
+    rpc.request(api::list_outlets()).await?;
+    info.outlets = rpc
+        .parse_response::<OutletList>()?
+        .list
+        .iter()
+        .map(|e| OutletInfo {
+            forward_address: e.tcp_addr.to_string(),
+            address: addr_to_multiaddr(e.worker_addr.as_ref()).map(|ma| ma.to_string()),
+        })
+        .collect();
+
+    Ok(info)
 }
 
 /// Send message(s) to a node to determine if it is 'up' and
@@ -203,7 +357,7 @@ pub async fn print_query_status(
 /// appear to be 'up', retry the test at time intervals up to
 /// a maximum number of retries. A use case for this is to
 /// allow a node time to start up and become ready.
-async fn is_node_up(rpc: &mut Rpc<'_>, wait_until_ready: bool) -> anyhow::Result<bool> {
+pub(crate) async fn is_node_up(rpc: &mut Rpc<'_>, wait_until_ready: bool) -> anyhow::Result<bool> {
     let attempts = match wait_until_ready {
         true => IS_NODE_UP_MAX_ATTEMPTS,
         false => 1,
@@ -3,11 +3,9 @@ use clap::Args;
 use nix::unistd::Pid;
 use rand::prelude::random;
 
-use ockam::TcpTransport;
-
 use crate::node::show::print_query_status;
 use crate::node::util::run::CommandsRunner;
-use crate::util::{node_rpc, RpcBuilder};
+use crate::util::{node_rpc, tcp_transport, RpcBuilder};
 use crate::{
     help,
     node::HELP_DETAIL,
@@ -56,10 +54,10 @@ async fn run_impl(
     }
 
     // Restart node
-    restart_background_node(&opts, &cmd).await?;
+    restart_background_node(&opts, node_name)?;
 
     // Print node status
-    let tcp = TcpTransport::create(&ctx).await?;
+    let tcp = tcp_transport(&ctx).await?;
     let mut rpc = RpcBuilder::new(&ctx, &opts, node_name).tcp(&tcp)?.build();
     print_query_status(&mut rpc, cfg_node.port(), node_name, true).await?;
 
@@ -72,12 +70,13 @@ async fn run_impl(
     Ok(())
 }
 
-async fn restart_background_node(
+/// Start an existing node again, with the settings it was created with
+pub(crate) fn restart_background_node(
     opts: &CommandGlobalOpts,
-    cmd: &StartCommand,
+    node_name: &str,
 ) -> crate::Result<()> {
     let cfg = &opts.config;
-    let cfg_node = cfg.get_node(&cmd.node_name)?;
+    let cfg_node = cfg.get_node(node_name)?;
 
     // Construct the arguments list and re-execute the ockam
     // CLI in foreground mode to start the newly created node
@@ -89,6 +88,7 @@ async fn restart_background_node(
         false,                        // Default value. TODO: implement persistence of this option
         cfg_node.name(),              // The selected node name
         &cfg_node.addr().to_string(), // The selected node api address
+        None,                         // The node keeps its health address in its state
         None,                         // No project information available
     )?;
 
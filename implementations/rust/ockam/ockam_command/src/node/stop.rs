@@ -26,29 +26,29 @@ impl StopCommand {
             Ok(Some(pid)) => {
                 if let Err(e) = startup::stop(pid, self.force) {
                     eprintln!("{e:?}");
-                    std::process::exit(exitcode::OSERR);
+                    crate::util::exit(exitcode::OSERR);
                 } else {
                     // Clear pid in config, so StartCommand does not have to rely on
                     // `kill 0 pid` to detect if a node is running.
                     if let Err(e) = cfg.set_node_pid(&self.node_name, None) {
                         eprintln!("Failed to update pid for node {}: {}", &self.node_name, e);
-                        std::process::exit(exitcode::IOERR);
+                        crate::util::exit(exitcode::IOERR);
                     }
 
                     // Save the config update
                     if let Err(e) = cfg.persist_config_updates() {
                         eprintln!("Failed to update configuration: {}", e);
-                        std::process::exit(exitcode::IOERR);
+                        crate::util::exit(exitcode::IOERR);
                     }
                 }
             }
             Ok(_) => {
                 eprintln!("Node {} is not running!", &self.node_name);
-                std::process::exit(exitcode::IOERR);
+                crate::util::exit(exitcode::IOERR);
             }
             Err(_) => {
                 eprintln!("Node {} does not exist!", &self.node_name);
-                std::process::exit(exitcode::IOERR);
+                crate::util::exit(exitcode::IOERR);
             }
         };
     }
@@ -1,11 +1,9 @@
-use std::sync::Arc;
-
 use anyhow::{anyhow, Context as _, Result};
 use sysinfo::{get_current_pid, ProcessExt, System, SystemExt};
 use tracing::trace;
 
 use ockam::identity::{Identity, PublicIdentity};
-use ockam::{Context, TcpTransport};
+use ockam::Context;
 use ockam_api::config::cli;
 use ockam_api::config::cli::OckamConfig as OckamConfigApi;
 use ockam_api::nodes::models::transport::{TransportMode, TransportType};
@@ -14,15 +12,22 @@ use ockam_api::nodes::service::{
 };
 use ockam_api::nodes::{IdentityOverride, NodeManager, NodeManagerWorker, NODEMANAGER_ADDR};
 use ockam_multiaddr::MultiAddr;
-use ockam_vault::storage::FileStorage;
 use ockam_vault::Vault;
 
 use crate::node::CreateCommand;
 use crate::project::ProjectInfo;
+use crate::shell::ShellNode;
+use crate::util::tcp_transport;
+use crate::vault::util::{open_vault, storage_key};
 use crate::{project, OckamConfig};
 use crate::{util::startup, CommandGlobalOpts};
 
 pub async fn start_embedded_node(ctx: &Context, cfg: &OckamConfig) -> Result<String> {
+    // The commands of a shell share the embedded node manager of the first one
+    let shell = ShellNode::current();
+    if let Some(name) = shell.as_ref().and_then(ShellNode::embedded_node) {
+        return Ok(name);
+    }
     let cmd = CreateCommand::default();
 
     // Create node directory if it doesn't exist
@@ -51,7 +56,7 @@ pub async fn start_embedded_node(ctx: &Context, cfg: &OckamConfig) -> Result<Str
         None => None,
     };
 
-    let tcp = TcpTransport::create(ctx).await?;
+    let tcp = tcp_transport(ctx).await?;
     let bind = cmd.tcp_listener_address;
     tcp.listen(&bind).await?;
     let node_dir = cfg.get_node_dir_raw(&cmd.node_name)?;
@@ -64,7 +69,10 @@ pub async fn start_embedded_node(ctx: &Context, cfg: &OckamConfig) -> Result<Str
             cmd.skip_defaults || cmd.launch_config.is_some(),
             cmd.enable_credential_checks,
             identity_override,
-        ),
+            None,
+            None,
+        )
+        .with_vault_key(storage_key()),
         NodeManagerProjectsOptions::new(
             Some(&cfg.authorities(&cmd.node_name)?.snapshot()),
             project_id,
@@ -79,6 +87,9 @@ pub async fn start_embedded_node(ctx: &Context, cfg: &OckamConfig) -> Result<Str
     ctx.start_worker(NODEMANAGER_ADDR, node_manager_worker)
         .await?;
 
+    if let Some(shell) = shell {
+        shell.set_embedded_node(&cmd.node_name);
+    }
     Ok(cmd.node_name.clone())
 }
 
@@ -97,8 +108,7 @@ pub(super) async fn create_default_identity_if_needed(
         default_vault_path
     });
 
-    let storage = FileStorage::create(default_vault_path.clone()).await?;
-    let vault = Vault::new(Some(Arc::new(storage)));
+    let vault = open_vault(default_vault_path.clone()).await?;
 
     // Get default root identity (create if needed)
     if cfg.get_default_identity().is_none() {
@@ -121,8 +131,7 @@ pub(super) async fn get_identity_override(
         .get_default_vault_path()
         .context("Default vault was not found")?;
 
-    let storage = FileStorage::create(default_vault_path.clone()).await?;
-    let vault = Vault::new(Some(Arc::new(storage)));
+    let vault = open_vault(default_vault_path.clone()).await?;
 
     // Get default root identity
     let default_identity = cfg
@@ -163,13 +172,18 @@ pub(super) async fn add_project_authority(
 }
 
 pub async fn delete_embedded_node(cfg: &OckamConfig, name: &str) {
+    // The shell deletes its embedded node when it exits
+    if ShellNode::current().is_some() {
+        return;
+    }
     // Try removing the node's directory
     if let Ok(dir) = cfg.get_node_dir_raw(name) {
         let _ = tokio::fs::remove_dir_all(dir).await;
     }
 }
 
-pub fn delete_all_nodes(opts: CommandGlobalOpts, force: bool) -> anyhow::Result<()> {
+/// Delete all the nodes, and return their names
+pub fn delete_all_nodes(opts: CommandGlobalOpts, force: bool) -> anyhow::Result<Vec<String>> {
     // Try to delete all nodes found in the config file + their associated processes
     let nn: Vec<String> = {
         let inner = &opts.config.inner();
@@ -213,7 +227,7 @@ pub fn delete_all_nodes(opts: CommandGlobalOpts, force: bool) -> anyhow::Result<
         eprintln!("Failed to update config file. You might need to run the command with --force to delete all config directories");
         return Err(e);
     }
-    Ok(())
+    Ok(nn)
 }
 
 pub fn delete_node(opts: &CommandGlobalOpts, node_name: &str, sigkill: bool) {
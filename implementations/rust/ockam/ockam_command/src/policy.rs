@@ -1,3 +1,4 @@
+use crate::util::output::Output;
 use crate::util::{extract_address_value, node_rpc, Rpc};
 use crate::{help, CommandGlobalOpts, Result};
 use clap::{Args, Subcommand};
@@ -5,6 +6,7 @@ use ockam::Context;
 use ockam_abac::{Action, Expr, Resource};
 use ockam_api::nodes::models::policy::{Policy, PolicyList};
 use ockam_core::api::Request;
+use serde::Serialize;
 
 const HELP_DETAIL: &str = "";
 
@@ -86,7 +88,8 @@ async fn rpc(ctx: Context, (opts, cmd): (CommandGlobalOpts, PolicyCommand)) -> R
             let mut rpc = Rpc::background(&ctx, &opts, &node)?;
             rpc.request(req).await?;
             let pol: Policy = rpc.parse_response()?;
-            println!("{}", pol.expression())
+            opts.print(&PolicyInfo::new(&resource, &action, pol.expression()))?
+
         }
         PolicySubcommand::Delete { at, resource, action } => {
             let node = extract_address_value(&at)?;
@@ -101,14 +104,51 @@ async fn rpc(ctx: Context, (opts, cmd): (CommandGlobalOpts, PolicyCommand)) -> R
             let mut rpc = Rpc::background(&ctx, &opts, &node)?;
             rpc.request(req).await?;
             let pol: PolicyList = rpc.parse_response()?;
-            for (a, e) in pol.expressions() {
-                println!("{resource}/{a}: {e}")
-            }
+            let list: Vec<_> = pol
+                .expressions()
+                .iter()
+                .map(|(a, e)| PolicyInfo::new(&resource, a, e))
+                .collect();
+            opts.print(&list)?
         }
     }
     Ok(())
 }
 
+/// The policy of an action on a resource
+#[derive(Debug, Serialize)]
+struct PolicyInfo {
+    resource: String,
+    action: String,
+    expression: String,
+}
+
+impl PolicyInfo {
+    fn new(r: &Resource, a: &Action, e: &Expr) -> Self {
+        Self {
+            resource: r.to_string(),
+            action: a.to_string(),
+            expression: e.to_string(),
+        }
+    }
+}
+
+impl Output for PolicyInfo {
+    fn output(&self) -> anyhow::Result<String> {
+        Ok(self.expression.clone())
+    }
+}
+
+impl Output for Vec<PolicyInfo> {
+    fn output(&self) -> anyhow::Result<String> {
+        let lines: Vec<_> = self
+            .iter()
+            .map(|p| format!("{}/{}: {}", p.resource, p.action, p.expression))
+            .collect();
+        Ok(lines.join("\n"))
+    }
+}
+
 fn policy_path(r: &Resource, a: &Action) -> String {
     format!("/policy/{r}/{a}")
 }
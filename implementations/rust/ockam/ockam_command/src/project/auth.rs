@@ -97,7 +97,7 @@ async fn run_impl(
         info!("Enrolled successfully");
         let mut client = Client::new(authenticator_route, &ctx).await?;
         let credential = client.credential().await?;
-        println!("{}", credential);
+        opts.print(&credential)?;
         Ok(())
     } else {
         eprintln!("{}", rpc.parse_err_msg(res, dec));
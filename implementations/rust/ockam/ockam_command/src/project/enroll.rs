@@ -14,7 +14,7 @@ use crate::node::util::{delete_embedded_node, start_embedded_node};
 use crate::node::NodeOpts;
 use crate::project::util::create_secure_channel_to_authority;
 use crate::util::api::CloudOpts;
-use crate::util::{node_rpc, RpcBuilder};
+use crate::util::{node_rpc, resolve_aliases, RpcBuilder};
 use crate::{help, CommandGlobalOpts, Result};
 
 /// An authorised enroller can add members to a project.
@@ -74,21 +74,22 @@ impl Runner {
         let node_name = start_embedded_node(&self.ctx, &self.opts.config).await?;
 
         let map = self.opts.config.lookup();
-        let to = if let Some(a) = project_authority(&self.cmd.to, &map)? {
+        let to = resolve_aliases(&self.cmd.to, &map)?;
+        let to = if let Some(a) = project_authority(&to, &map)? {
             let mut addr = create_secure_channel_to_authority(
                 &self.ctx,
                 &self.opts,
                 &node_name,
                 a,
-                &replace_project(&self.cmd.to, a.address())?,
+                &replace_project(&to, a.address())?,
             )
             .await?;
-            for proto in self.cmd.to.iter().skip(1) {
+            for proto in to.iter().skip(1) {
                 addr.push_back_value(&proto).map_err(anyhow::Error::from)?
             }
             addr
         } else {
-            self.cmd.to.clone()
+            to
         };
         debug!(addr = %to, member = %self.cmd.member, attrs = ?self.cmd.attributes, "requesting to add member");
         let req = Request::post("/members")
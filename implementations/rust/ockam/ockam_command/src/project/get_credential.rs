@@ -8,7 +8,7 @@ use ockam_multiaddr::MultiAddr;
 
 use crate::node::NodeOpts;
 use crate::util::api::CloudOpts;
-use crate::util::{node_rpc, RpcBuilder};
+use crate::util::{tcp_transport, node_rpc, RpcBuilder};
 use crate::{stop_node, CommandGlobalOpts, Result};
 
 #[derive(Clone, Debug, Args)]
@@ -39,7 +39,7 @@ async fn rpc(
         opts: &CommandGlobalOpts,
         cmd: &GetCredentialCommand,
     ) -> Result<()> {
-        let tcp = TcpTransport::create(ctx).await?;
+        let tcp = tcp_transport(ctx).await?;
         let (to, meta) = clean_multiaddr(&cmd.to, &opts.config.get_lookup()).unwrap();
         let projects_sc = crate::project::util::lookup_projects(
             ctx,
@@ -20,7 +20,7 @@ fn run_impl(opts: CommandGlobalOpts, cmd: ResetCommand) -> crate::Result<()> {
     if cmd.yes || get_user_confirmation() {
         if let Err(e) = delete_all_nodes(opts, true) {
             eprintln!("{}", e);
-            std::process::exit(crate::util::exitcode::IOERR);
+            crate::util::exit(crate::util::exitcode::IOERR);
         }
     }
     Ok(())
@@ -1,18 +1,16 @@
 use crate::{
     help,
     util::{api, exitcode, extract_address_value, node_rpc},
-    CommandGlobalOpts, OutputFormat, Result,
+    CommandGlobalOpts, Result,
 };
 
-use anyhow::Context as _;
-use atty::Stream;
+use anyhow::{anyhow, Context as _};
 use clap::Args;
-use colorful::Colorful;
-use serde_json::json;
 
+use crate::secure_channel::ChannelAddress;
 use crate::secure_channel::HELP_DETAIL;
 use crate::util::api::CloudOpts;
-use crate::util::RpcBuilder;
+use crate::util::{tcp_transport, RpcBuilder};
 use ockam::{identity::IdentityIdentifier, route, Context, TcpTransport};
 use ockam_api::config::lookup::ConfigLookup;
 use ockam_api::nodes::models::secure_channel::CredentialExchangeMode;
@@ -78,84 +76,10 @@ impl CreateCommand {
     fn parse_from_node(&self, _config: &ConfigLookup) -> String {
         extract_address_value(&self.from).unwrap_or_else(|_| "".to_string())
     }
-
-    fn print_output(
-        &self,
-        parsed_from: &String,
-        parsed_to: &MultiAddr,
-        options: &CommandGlobalOpts,
-        response: CreateSecureChannelResponse,
-    ) {
-        let route = &route![response.addr.to_string()];
-        match route_to_multiaddr(route) {
-            Some(multiaddr) => {
-                // if stdout is not interactive/tty write the secure channel address to it
-                // in case some other program is trying to read it as piped input
-                if !atty::is(Stream::Stdout)
-                // or if the `--pipe` flag is set
-                || options.global_args.export.pipe()
-                {
-                    println!("{}", multiaddr)
-                }
-
-                // if output format is json, write json to stdout.
-                if options.global_args.output_format == OutputFormat::Json {
-                    let json = json!([{ "address": multiaddr.to_string() }]);
-                    println!("{}", json);
-                }
-
-                // if stderr is interactive/tty and we haven't been asked to be quiet
-                // and output format is plain then write a plain info to stderr.
-                if atty::is(Stream::Stderr)
-                    && !options.global_args.quiet
-                    && options.global_args.output_format == OutputFormat::Plain
-                {
-                    if options.global_args.no_color {
-                        eprintln!("\n  Created Secure Channel:");
-                        eprintln!("  • From: /node/{}", parsed_from);
-                        eprintln!("  •   To: {} ({})", &self.to, &parsed_to);
-                        eprintln!("  •   At: {}", multiaddr);
-                    } else {
-                        eprintln!("\n  Created Secure Channel:");
-
-                        // From:
-                        eprint!("{}", "  • From: ".light_magenta());
-                        eprintln!("{}", format!("/node/{}", parsed_from).light_yellow());
-
-                        // To:
-                        eprint!("{}", "  •   To: ".light_magenta());
-                        let t = format!("{} ({})", &self.to, &parsed_to);
-                        eprintln!("{}", t.light_yellow());
-
-                        // At:
-                        eprint!("{}", "  •   At: ".light_magenta());
-                        eprintln!("{}", multiaddr.to_string().light_yellow());
-                    }
-                }
-            }
-            None => {
-                // if stderr is interactive/tty and we haven't been asked to be quiet
-                // and output format is plain then write a plain info to stderr.
-                if atty::is(Stream::Stderr)
-                    && !options.global_args.quiet
-                    && options.global_args.output_format == OutputFormat::Plain
-                {
-                    eprintln!(
-                        "Could not convert returned secure channel address {} into a multiaddr",
-                        route
-                    );
-                }
-
-                // return the exitcode::PROTOCOL since if things are going as expected
-                // a route in the response should be convertible to multiaddr.
-                std::process::exit(exitcode::PROTOCOL);
-            }
-        };
-    }
 }
 
 async fn rpc(ctx: Context, (opts, cmd): (CommandGlobalOpts, CreateCommand)) -> Result<()> {
-    let tcp = TcpTransport::create(&ctx).await?;
+    let tcp = tcp_transport(&ctx).await?;
 
     let config = &opts.config.lookup();
     let from = &cmd.parse_from_node(config);
@@ -173,7 +97,15 @@ async fn rpc(ctx: Context, (opts, cmd): (CommandGlobalOpts, CreateCommand)) -> R
     rpc.request(request).await?;
     let response = rpc.parse_response::<CreateSecureChannelResponse>()?;
 
-    cmd.print_output(from, to, &opts, response);
+    // A route in the response converts to a multiaddr, unless the node misbehaves
+    let route = route![response.addr.to_string()];
+    let multiaddr = route_to_multiaddr(&route).ok_or_else(|| {
+        crate::Error::new(
+            exitcode::PROTOCOL,
+            anyhow!("Could not convert returned secure channel address {route} into a multiaddr"),
+        )
+    })?;
+    opts.print(&ChannelAddress::list(multiaddr.to_string()))?;
 
     Ok(())
 }
@@ -1,14 +1,12 @@
-use crate::secure_channel::HELP_DETAIL;
+use crate::secure_channel::{ChannelAddress, HELP_DETAIL};
 use crate::{
     help,
     util::{api, exitcode, extract_address_value, node_rpc, Rpc},
-    CommandGlobalOpts, OutputFormat, Result,
+    CommandGlobalOpts, Result,
 };
 use std::str::FromStr;
 
-use atty::Stream;
-use colorful::Colorful;
-use serde_json::json;
+use anyhow::anyhow;
 
 use clap::Parser;
 use ockam::{route, Context};
@@ -37,90 +35,6 @@ impl DeleteCommand {
     fn parse_at_node(&self) -> String {
         extract_address_value(&self.at).unwrap_or_else(|_| "".to_string())
     }
-
-    fn print_output(
-        &self,
-        parsed_at: &String,
-        address: &Address,
-        options: &CommandGlobalOpts,
-        response: DeleteSecureChannelResponse,
-    ) {
-        match response.channel {
-            Some(address) => {
-                let route = &route![address.to_string()];
-                match route_to_multiaddr(route) {
-                    Some(multiaddr) => {
-                        // if stdout is not interactive/tty write the secure channel address to it
-                        // in case some other program is trying to read it as piped input
-                        if !atty::is(Stream::Stdout) {
-                            println!("{}", multiaddr)
-                        }
-
-                        // if output format is json, write json to stdout.
-                        if options.global_args.output_format == OutputFormat::Json {
-                            let json = json!([{ "address": multiaddr.to_string() }]);
-                            println!("{}", json);
-                        }
-
-                        // if stderr is interactive/tty and we haven't been asked to be quiet
-                        // and output format is plain then write a plain info to stderr.
-                        if atty::is(Stream::Stderr)
-                            && !options.global_args.quiet
-                            && options.global_args.output_format == OutputFormat::Plain
-                        {
-                            if options.global_args.no_color {
-                                eprintln!("\n  Deleted Secure Channel:");
-                                eprintln!("  •        At: /node/{}", &self.at);
-                                eprintln!("  •   Address: {}", &self.address);
-                            } else {
-                                eprintln!("\n  Deleted Secure Channel:");
-
-                                // At:
-                                eprintln!("{}", "  •        At: ".light_magenta());
-                                eprintln!("{}", format!("/node/{}", parsed_at).light_yellow());
-
-                                // Address:
-                                eprintln!("{}", "  •   Address: ".light_magenta());
-                                eprintln!("{}", &self.address.to_string().light_yellow());
-                            }
-                        }
-                    }
-                    None => {
-                        // if stderr is interactive/tty and we haven't been asked to be quiet
-                        // and output format is plain then write a plain info to stderr.
-                        if atty::is(Stream::Stderr)
-                            && !options.global_args.quiet
-                            && options.global_args.output_format == OutputFormat::Plain
-                        {
-                            eprintln!(
-                                "Could not convert returned secure channel route {} into a multiaddr",
-                                route
-                            );
-                        }
-
-                        // return the exitcode::PROTOCOL since if things are going as expected
-                        // a route in the response should be convertible to multiaddr.
-                        std::process::exit(exitcode::PROTOCOL);
-                    }
-                }
-            }
-            None => {
-                // if stderr is interactive/tty and we haven't been asked to be quiet
-                // and output format is plain then write a plain info to stderr.
-                if atty::is(Stream::Stderr)
-                    && !options.global_args.quiet
-                    && options.global_args.output_format == OutputFormat::Plain
-                {
-                    eprintln!(
-                        "Could not find secure channel with address {} at node {}",
-                        address, &self.at
-                    );
-                }
-
-                println!("channel with address {} not found", address)
-            }
-        }
-    }
 }
 
 fn parse_address(input: &str) -> core::result::Result<Address, AddressParseError> {
@@ -149,7 +63,22 @@ async fn rpc(ctx: Context, (options, command): (CommandGlobalOpts, DeleteCommand
     rpc.request(request).await?;
     let response = rpc.parse_response::<DeleteSecureChannelResponse>()?;
 
-    command.print_output(at, address, &options, response);
+    // A channel that doesn't exist is an error whatever the output format,
+    // so that scripts can tell it apart from a deleted channel.
+    let deleted = response.channel.ok_or_else(|| {
+        crate::Error::new(
+            exitcode::DATAERR,
+            anyhow!("Could not find secure channel with address {address} at node {at}"),
+        )
+    })?;
+    let route = route![deleted.to_string()];
+    let multiaddr = route_to_multiaddr(&route).ok_or_else(|| {
+        crate::Error::new(
+            exitcode::PROTOCOL,
+            anyhow!("Could not convert returned secure channel route {route} into a multiaddr"),
+        )
+    })?;
+    options.print(&ChannelAddress::list(multiaddr.to_string()))?;
 
     Ok(())
 }
@@ -1,20 +1,21 @@
-use atty::Stream;
+use anyhow::anyhow;
 use clap::Args;
-use colorful::Colorful;
+use cli_table::{Cell, Style, Table};
 
-use ockam::{Context, TcpTransport};
+use ockam::Context;
 use ockam_api::nodes::models::secure_channel::ShowSecureChannelResponse;
 use ockam_api::route_to_multiaddr;
 use ockam_core::{route, Address};
 
-use serde_json::json;
+use serde::Serialize;
 
 use crate::secure_channel::HELP_DETAIL;
-use crate::util::RpcBuilder;
+use crate::util::output::Output;
+use crate::util::{tcp_transport, RpcBuilder};
 use crate::{
     exitcode, help,
     util::{api, node_rpc},
-    CommandGlobalOpts, OutputFormat,
+    CommandGlobalOpts,
 };
 
 /// List Secure Channels
@@ -31,18 +32,14 @@ impl ListCommand {
         node_rpc(rpc, (opts, self));
     }
 
-    fn print_output(
+    fn channels(
         &self,
-        options: &CommandGlobalOpts,
         channel_identifiers: Vec<String>,
         show_responses: Vec<ShowSecureChannelResponse>,
-    ) -> Result<(), String> {
+    ) -> Result<Vec<ChannelInfo>, String> {
         let zipped = channel_identifiers.iter().zip(show_responses);
 
-        if zipped.len() > 0 && has_plain_stderr(options) {
-            println!("\nSecure Channels")
-        }
-
+        let mut channels = vec![];
         for (channel_address, show_response) in zipped {
             let from = &self.at;
 
@@ -55,6 +52,8 @@ impl ListCommand {
                 channel_multiaddr.to_string()
             };
 
+            let status = show_response.status.as_deref().unwrap_or("unmonitored");
+
             let to = {
                 let show_route = show_response
                     .route
@@ -76,50 +75,53 @@ impl ListCommand {
                 format!("{}{}", ma1, ma2)
             };
 
-            // if stdout is not interactive/tty write the secure channel address to it
-            // in case some other program is trying to read it as piped input
-            if !atty::is(Stream::Stdout) {
-                println!("{}", at)
-            }
-
-            // if output format is json, write json to stdout.
-            if options.global_args.output_format == OutputFormat::Json {
-                let json = json!([{ "address": at }]);
-                println!("{}", json);
-            }
-
-            // if stderr is interactive/tty and we haven't been asked to be quiet
-            // and output format is plain then write a plain info to stderr.
-            if has_plain_stderr(options) {
-                println!("\n    Secure Channel:");
-                if options.global_args.no_color {
-                    eprintln!("      • From: /node/{}", from);
-                    eprintln!("      •   To: {}", to);
-                    eprintln!("      •   At: {}", at);
-                } else {
-                    // From:
-                    eprint!("{}", "      • From: ".light_magenta());
-                    eprintln!("{}", format!("/node/{}", from).light_yellow());
-
-                    // To:
-                    eprint!("{}", "      •   To: ".light_magenta());
-                    eprintln!("{}", to.light_yellow());
-
-                    // At:
-                    eprint!("{}", "      •   At: ".light_magenta());
-                    eprintln!("{}", at.light_yellow());
-                }
-            }
+            channels.push(ChannelInfo {
+                address: at,
+                status: status.to_string(),
+                from: format!("/node/{}", from),
+                to,
+            });
         }
-        Ok(())
+        Ok(channels)
     }
 }
 
-#[inline]
-fn has_plain_stderr(options: &CommandGlobalOpts) -> bool {
-    atty::is(Stream::Stderr)
-        && !options.global_args.quiet
-        && options.global_args.output_format == OutputFormat::Plain
+/// A secure channel, as listed
+#[derive(Debug, Serialize)]
+struct ChannelInfo {
+    address: String,
+    status: String,
+    from: String,
+    to: String,
+}
+
+impl Output for Vec<ChannelInfo> {
+    fn output(&self) -> anyhow::Result<String> {
+        if self.is_empty() {
+            return Ok("No secure channels found".to_string());
+        }
+        let mut rows = vec![];
+        for ChannelInfo {
+            address,
+            status,
+            from,
+            to,
+        } in self
+        {
+            rows.push([address.cell(), from.cell(), to.cell(), status.cell()]);
+        }
+        let table = rows
+            .table()
+            .title([
+                "Address".cell().bold(true),
+                "From".cell().bold(true),
+                "To".cell().bold(true),
+                "Status".cell().bold(true),
+            ])
+            .display()?
+            .to_string();
+        Ok(table)
+    }
 }
 
 async fn rpc(
@@ -129,7 +131,7 @@ async fn rpc(
     // We need this TCPTransport handle to ensure that we are using the same transport across
     // multiple RPC calls. Creating a RPC instance without explicit transport results in a router
     // instance being registered for the same transport type multiple times which is not allowed
-    let tcp = TcpTransport::create(&ctx).await?;
+    let tcp = tcp_transport(&ctx).await?;
     let mut rpc = RpcBuilder::new(&ctx, &options, &command.at)
         .tcp(&tcp)?
         .build();
@@ -152,15 +154,10 @@ async fn rpc(
         .collect();
     let responses = results?;
 
-    if let Err(e) = command.print_output(&options, channel_identifiers, responses) {
-        if atty::is(Stream::Stderr)
-            && !options.global_args.quiet
-            && options.global_args.output_format == OutputFormat::Plain
-        {
-            eprintln!("{}", e);
-        }
-        std::process::exit(exitcode::PROTOCOL)
-    }
+    let channels = command
+        .channels(channel_identifiers, responses)
+        .map_err(|e| crate::Error::new(exitcode::PROTOCOL, anyhow!(e)))?;
+    options.print(&channels)?;
 
     Ok(())
 }
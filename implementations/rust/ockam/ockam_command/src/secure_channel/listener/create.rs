@@ -7,8 +7,10 @@ use ockam_api::nodes::models::secure_channel::CreateSecureChannelListenerRequest
 use ockam_api::nodes::NODEMANAGER_ADDR;
 use ockam_core::api::{Request, Status};
 use ockam_core::{Address, Route};
+use serde::Serialize;
 
 use crate::secure_channel::HELP_DETAIL;
+use crate::util::output::Output;
 use crate::util::{api, exitcode, extract_address_value, node_rpc, Rpc};
 use crate::{help, CommandGlobalOpts};
 
@@ -56,7 +58,9 @@ async fn run_impl(
     rpc.request(req).await?;
     match rpc.is_ok() {
         Ok(_) => {
-            println!("/service/{}", cmd.address.address());
+            opts.print(&CreatedListener {
+                address: format!("/service/{}", cmd.address.address()),
+            })?;
             Ok(())
         }
         Err(e) => Err(crate::error::Error::new(
@@ -66,12 +70,24 @@ async fn run_impl(
     }
 }
 
+/// Result of creating a secure channel listener
+#[derive(Debug, Serialize)]
+struct CreatedListener {
+    address: String,
+}
+
+impl Output for CreatedListener {
+    fn output(&self) -> anyhow::Result<String> {
+        Ok(self.address.clone())
+    }
+}
+
 pub async fn create_listener(
     ctx: &Context,
     addr: Address,
     authorized_identifiers: Option<Vec<IdentityIdentifier>>,
     mut base_route: Route,
-) -> anyhow::Result<()> {
+) -> anyhow::Result<String> {
     let resp: Vec<u8> = ctx
         .send_and_receive(
             base_route.modify().append(NODEMANAGER_ADDR),
@@ -82,13 +98,10 @@ pub async fn create_listener(
     let response = api::parse_create_secure_channel_listener_response(&resp)?;
 
     match response.status() {
-        Some(Status::Ok) => {
-            println!("/service/{}", addr.address());
-            Ok(())
-        }
+        Some(Status::Ok) => Ok(format!("/service/{}", addr.address())),
         _ => {
             eprintln!("An error occurred while creating secure channel listener",);
-            std::process::exit(exitcode::CANTCREAT)
+            crate::util::exit(exitcode::CANTCREAT)
         }
     }
 }
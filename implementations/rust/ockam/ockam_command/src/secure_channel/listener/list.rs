@@ -1,10 +1,13 @@
 use clap::Args;
+use core::fmt::Write;
+use serde::Serialize;
 
 use ockam::Context;
 
 use crate::node::NodeOpts;
 use crate::secure_channel::HELP_DETAIL;
 use crate::util::api;
+use crate::util::output::Output;
 use crate::util::{node_rpc, Rpc};
 use crate::{help, CommandGlobalOpts};
 
@@ -35,14 +38,27 @@ async fn run_impl(
     let mut rpc = Rpc::background(ctx, &opts, &cmd.node_opts.api_node)?;
     rpc.request(api::list_secure_channel_listener()).await?;
     let res = rpc.parse_response::<Vec<String>>()?;
-
-    println!(
-        "Secure channel listeners for node `{}`:",
-        &cmd.node_opts.api_node
-    );
-    for addr in res {
-        println!("  {}", addr);
-    }
+    opts.print(&ListenerList {
+        node: cmd.node_opts.api_node,
+        listeners: res,
+    })?;
 
     Ok(())
 }
+
+/// The secure channel listeners of a node
+#[derive(Debug, Serialize)]
+struct ListenerList {
+    node: String,
+    listeners: Vec<String>,
+}
+
+impl Output for ListenerList {
+    fn output(&self) -> anyhow::Result<String> {
+        let mut w = format!("Secure channel listeners for node `{}`:", self.node);
+        for addr in &self.listeners {
+            write!(w, "\n  {}", addr)?;
+        }
+        Ok(w)
+    }
+}
@@ -10,8 +10,10 @@ pub use delete::DeleteCommand;
 pub use list::ListCommand;
 pub use show::ShowCommand;
 
+use crate::util::output::Output;
 use crate::{help, CommandGlobalOpts};
 use clap::{Args, Subcommand};
+use serde::Serialize;
 
 const HELP_DETAIL: &str = "\
 About:
@@ -110,6 +112,11 @@ About:
     $ ockam secure-channel list --node n1
 ```
 
+    Channels created with `ockam secure-channel create` are checked periodically and
+    are recreated when they stop responding, followed by the inlets, forwarders and
+    other channels that were created through them. `ockam secure-channel list` shows
+    whether each channel is currently `up` or `down`.
+
 
     Delete Secure Channels initiated from a node
     ------
@@ -170,3 +177,27 @@ impl SecureChannelCommand {
         }
     }
 }
+
+/// Address of a created or deleted secure channel
+///
+/// It is printed in a list, which is what scripts reading the JSON output expect.
+#[derive(Debug, Serialize)]
+struct ChannelAddress {
+    address: String,
+}
+
+impl ChannelAddress {
+    fn list(address: String) -> Vec<Self> {
+        vec![ChannelAddress { address }]
+    }
+}
+
+impl Output for Vec<ChannelAddress> {
+    fn output(&self) -> anyhow::Result<String> {
+        Ok(self
+            .iter()
+            .map(|c| c.address.as_str())
+            .collect::<Vec<_>>()
+            .join("\n"))
+    }
+}
@@ -1,5 +1,6 @@
 use anyhow::{anyhow, Context, Result};
 use ockam::identity::IdentityIdentifier;
+use ockam_api::oidc::ClaimMapping;
 use ockam_api::DefaultAddress;
 use serde::{Deserialize, Serialize};
 use std::path::{Path, PathBuf};
@@ -73,6 +74,27 @@ pub struct OktaIdentityProviderConfig {
     pub(crate) disabled: bool,
 }
 
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct OidcAuthenticatorConfig {
+    #[serde(default = "oidc_authenticator_default_addr")]
+    pub(crate) address: String,
+
+    pub(crate) issuer: String,
+
+    pub(crate) audience: String,
+
+    /// Path to the identity provider's JSON Web Key Set.
+    pub(crate) jwks: PathBuf,
+
+    pub(crate) project: String,
+
+    #[serde(default)]
+    pub(crate) mappings: Vec<ClaimMapping>,
+
+    #[serde(default)]
+    pub(crate) disabled: bool,
+}
+
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct ServiceConfigs {
     pub(crate) vault: Option<VaultConfig>,
@@ -81,6 +103,7 @@ pub struct ServiceConfigs {
     pub(crate) verifier: Option<VerifierConfig>,
     pub(crate) authenticator: Option<AuthenticatorConfig>,
     pub(crate) okta_identity_provider: Option<OktaIdentityProviderConfig>,
+    pub(crate) oidc_authenticator: Option<OidcAuthenticatorConfig>,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -119,3 +142,7 @@ fn authenticator_default_addr() -> String {
 fn okta_identity_provider_default_addr() -> String {
     DefaultAddress::OKTA_IDENTITY_PROVIDER.to_string()
 }
+
+fn oidc_authenticator_default_addr() -> String {
+    DefaultAddress::OIDC_AUTHENTICATOR.to_string()
+}
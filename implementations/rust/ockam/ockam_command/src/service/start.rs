@@ -1,14 +1,17 @@
 use crate::node::NodeOpts;
-use crate::service::config::OktaIdentityProviderConfig;
-use crate::util::{api, node_rpc, RpcBuilder};
+use crate::service::config::{OidcAuthenticatorConfig, OktaIdentityProviderConfig};
+use crate::util::output::Output;
+use crate::util::{api, node_rpc, tcp_transport, RpcBuilder};
 use crate::CommandGlobalOpts;
 use anyhow::{anyhow, Result};
 use clap::{Args, Subcommand};
 use minicbor::Encode;
 use ockam::{Context, TcpTransport};
-use ockam_api::nodes::models::services::StartOktaIdentityProviderRequest;
+use ockam_api::oidc::ClaimMapping;
 use ockam_api::DefaultAddress;
-use ockam_core::api::{Request, RequestBuilder, Status};
+use ockam_core::api::{RequestBuilder, Status};
+use serde::Serialize;
+use std::fmt;
 use std::path::{Path, PathBuf};
 
 #[derive(Clone, Debug, Args)]
@@ -55,6 +58,33 @@ pub enum StartSubCommand {
         #[arg(long)]
         project: String,
     },
+    OidcAuthenticator {
+        #[arg(long, default_value_t = oidc_authenticator_default_addr())]
+        addr: String,
+
+        /// Expected `iss` claim of tokens
+        #[arg(long)]
+        issuer: String,
+
+        /// Expected `aud` claim of tokens
+        #[arg(long)]
+        audience: String,
+
+        /// Path to the identity provider's JSON Web Key Set
+        #[arg(long)]
+        jwks: PathBuf,
+
+        #[arg(long)]
+        project: String,
+
+        /// Map a token claim to a credential attribute
+        #[arg(long = "claim", value_name = "CLAIM=ATTRIBUTE")]
+        claims: Vec<String>,
+
+        /// Map a token claim to a credential attribute and reject tokens without it
+        #[arg(long = "required-claim", value_name = "CLAIM=ATTRIBUTE")]
+        required_claims: Vec<String>,
+    },
 }
 
 fn vault_default_addr() -> String {
@@ -81,6 +111,23 @@ fn authenticator_default_addr() -> String {
     DefaultAddress::AUTHENTICATOR.to_string()
 }
 
+fn oidc_authenticator_default_addr() -> String {
+    DefaultAddress::OIDC_AUTHENTICATOR.to_string()
+}
+
+fn claim_mappings(claims: &[String], required: &[String]) -> Result<Vec<ClaimMapping>> {
+    let parse = |s: &String| {
+        s.split_once('=')
+            .map(|(c, a)| ClaimMapping::new(c, a))
+            .ok_or_else(|| anyhow!("claim mapping `{s}` is not in `claim=attribute` format"))
+    };
+    let mut mappings = claims.iter().map(parse).collect::<Result<Vec<_>>>()?;
+    for r in required {
+        mappings.push(parse(r)?.required())
+    }
+    Ok(mappings)
+}
+
 impl StartCommand {
     pub fn run(self, options: CommandGlobalOpts) {
         node_rpc(rpc, (options, self));
@@ -100,8 +147,8 @@ async fn run_impl(
     cmd: StartCommand,
 ) -> crate::Result<()> {
     let node_name = &cmd.node_opts.api_node;
-    let tcp = TcpTransport::create(ctx).await?;
-    match cmd.create_subcommand {
+    let tcp = tcp_transport(ctx).await?;
+    let started = match cmd.create_subcommand {
         StartSubCommand::Vault { addr, .. } => {
             start_vault_service(ctx, &opts, node_name, &addr, Some(&tcp)).await?
         }
@@ -145,11 +192,55 @@ async fn run_impl(
             )
             .await?
         }
-    }
+        StartSubCommand::OidcAuthenticator {
+            addr,
+            issuer,
+            audience,
+            jwks,
+            project,
+            claims,
+            required_claims,
+        } => {
+            let cfg = OidcAuthenticatorConfig {
+                address: addr,
+                issuer,
+                audience,
+                jwks,
+                project,
+                mappings: claim_mappings(&claims, &required_claims)?,
+                disabled: false,
+            };
+            start_oidc_authenticator(ctx, &opts, node_name, &cfg, Some(&tcp)).await?
+        }
+    };
+    opts.print(&started)?;
 
     Ok(())
 }
 
+/// Result of starting a service on a node
+#[derive(Debug, Serialize)]
+pub struct StartedService {
+    service: String,
+    address: String,
+}
+
+impl fmt::Display for StartedService {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "{} service started at address: {}",
+            self.service, self.address
+        )
+    }
+}
+
+impl Output for StartedService {
+    fn output(&self) -> Result<String> {
+        Ok(self.to_string())
+    }
+}
+
 /// Helper function.
 async fn start_service_impl<T>(
     ctx: &Context,
@@ -159,7 +250,7 @@ async fn start_service_impl<T>(
     serv_name: &str,
     req: RequestBuilder<'_, T>,
     tcp: Option<&'_ TcpTransport>,
-) -> Result<()>
+) -> Result<StartedService>
 where
     T: Encode<()>,
 {
@@ -168,10 +259,10 @@ where
 
     let (res, dec) = rpc.check_response()?;
     match res.status() {
-        Some(Status::Ok) => {
-            println!("{serv_name} service started at address: {serv_addr}");
-            Ok(())
-        }
+        Some(Status::Ok) => Ok(StartedService {
+            service: serv_name.to_string(),
+            address: serv_addr.to_string(),
+        }),
         _ => {
             eprintln!("{}", rpc.parse_err_msg(res, dec));
             Err(anyhow!("Failed to start {serv_name} service"))
@@ -186,7 +277,7 @@ pub async fn start_vault_service(
     node_name: &str,
     serv_addr: &str,
     tcp: Option<&'_ TcpTransport>,
-) -> Result<()> {
+) -> Result<StartedService> {
     let req = api::start_vault_service(serv_addr);
     start_service_impl(ctx, opts, node_name, serv_addr, "Vault", req, tcp).await
 }
@@ -198,7 +289,7 @@ pub async fn start_identity_service(
     node_name: &str,
     serv_addr: &str,
     tcp: Option<&'_ TcpTransport>,
-) -> Result<()> {
+) -> Result<StartedService> {
     let req = api::start_identity_service(serv_addr);
     start_service_impl(ctx, opts, node_name, serv_addr, "Identity", req, tcp).await
 }
@@ -210,7 +301,7 @@ pub async fn start_verifier_service(
     node_name: &str,
     serv_addr: &str,
     tcp: Option<&'_ TcpTransport>,
-) -> Result<()> {
+) -> Result<StartedService> {
     let req = api::start_verifier_service(serv_addr);
     start_service_impl(ctx, opts, node_name, serv_addr, "Verifier", req, tcp).await
 }
@@ -224,11 +315,32 @@ pub async fn start_authenticator_service(
     enrollers: &Path,
     project: &str,
     tcp: Option<&'_ TcpTransport>,
-) -> Result<()> {
+) -> Result<StartedService> {
     let req = api::start_authenticator_service(serv_addr, enrollers, project);
     start_service_impl(ctx, opts, node_name, serv_addr, "Authenticator", req, tcp).await
 }
 
+/// Public so `ockam_command::node::create` can use it.
+pub async fn start_oidc_authenticator(
+    ctx: &Context,
+    opts: &CommandGlobalOpts,
+    node_name: &str,
+    cfg: &OidcAuthenticatorConfig,
+    tcp: Option<&'_ TcpTransport>,
+) -> Result<StartedService> {
+    let req = api::start_oidc_authenticator(cfg);
+    start_service_impl(
+        ctx,
+        opts,
+        node_name,
+        &cfg.address,
+        "OIDC Authenticator",
+        req,
+        tcp,
+    )
+    .await
+}
+
 /// Public so `ockam_command::node::create` can use it.
 pub async fn start_okta_identity_provider(
     ctx: &Context,
@@ -236,15 +348,8 @@ pub async fn start_okta_identity_provider(
     node_name: &str,
     cfg: &OktaIdentityProviderConfig,
     tcp: Option<&'_ TcpTransport>,
-) -> Result<()> {
-    let payload = StartOktaIdentityProviderRequest::new(
-        &cfg.address,
-        &cfg.tenant_base_url,
-        &cfg.certificate,
-        cfg.attributes.iter().map(|s| s as &str).collect(),
-        cfg.project.as_bytes(),
-    );
-    let req = Request::post("/node/services/okta_identity_provider").body(payload);
+) -> Result<StartedService> {
+    let req = api::start_okta_identity_provider(cfg);
     start_service_impl(
         ctx,
         opts,
@@ -0,0 +1,95 @@
+use std::collections::VecDeque;
+
+use dialoguer::{Completion, History};
+
+/// Maximum number of lines kept in the history of a shell session
+const MAX_HISTORY: usize = 500;
+
+/// Completion of the last word of a line
+#[derive(Debug, Default)]
+pub(super) struct Completer {
+    /// Completion of the first word: builtins and `ockam` subcommands
+    pub(super) commands: Vec<String>,
+    /// Completion of the other words: node names and addresses
+    pub(super) words: Vec<String>,
+}
+
+impl Completion for Completer {
+    fn get(&self, input: &str) -> Option<String> {
+        let start = input.rfind(char::is_whitespace).map_or(0, |i| i + 1);
+        let (head, word) = input.split_at(start);
+        let completed = if head.trim().is_empty() {
+            complete(word, &self.commands)?
+        } else {
+            // An address is completed from any of its protocols, so that
+            // e.g. `/node/n1/service/ec` is completed to the `echo` service.
+            word.match_indices('/')
+                .find_map(|(i, _)| {
+                    complete(&word[i..], &self.words).map(|c| format!("{}{c}", &word[..i]))
+                })
+                .or_else(|| complete(word, &self.words))?
+        };
+        Some(format!("{head}{completed}"))
+    }
+}
+
+/// Complete a word to the longest prefix shared by all candidates starting with it
+fn complete(word: &str, candidates: &[String]) -> Option<String> {
+    let mut matches = candidates.iter().filter(|c| c.starts_with(word));
+    let first = matches.next()?;
+    let len = matches.fold(first.len(), |len, c| {
+        first
+            .char_indices()
+            .zip(c.chars())
+            .take_while(|((_, a), b)| a == b)
+            .last()
+            .map_or(0, |((i, a), _)| i + a.len_utf8())
+            .min(len)
+    });
+    (len > word.len()).then(|| first[..len].to_string())
+}
+
+/// The lines entered during a shell session, most recent first
+#[derive(Debug, Default)]
+pub(super) struct LineHistory {
+    lines: VecDeque<String>,
+}
+
+impl History<String> for LineHistory {
+    fn read(&self, pos: usize) -> Option<String> {
+        self.lines.get(pos).cloned()
+    }
+
+    fn write(&mut self, line: &String) {
+        if line.trim().is_empty() || self.lines.front() == Some(line) {
+            return;
+        }
+        if self.lines.len() == MAX_HISTORY {
+            self.lines.pop_back();
+        }
+        self.lines.push_front(line.clone())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn complete_last_word() {
+        let c = Completer {
+            commands: vec!["services".into(), "send".into(), "use".into()],
+            words: vec!["n1".into(), "/node/n1".into(), "/service/echo".into()],
+        };
+        assert_eq!(c.get("u").as_deref(), Some("use"));
+        assert_eq!(c.get("se").as_deref(), None);
+        assert_eq!(c.get("ser").as_deref(), Some("services"));
+        assert_eq!(c.get("use n").as_deref(), Some("use n1"));
+        assert_eq!(c.get("send /no").as_deref(), Some("send /node/n1"));
+        assert_eq!(
+            c.get("send /node/n1/service/e").as_deref(),
+            Some("send /node/n1/service/echo")
+        );
+        assert_eq!(c.get("send /node/n2").as_deref(), None);
+    }
+}
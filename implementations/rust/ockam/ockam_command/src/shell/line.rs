@@ -0,0 +1,89 @@
+use anyhow::anyhow;
+
+/// Split a line into the commands of a pipeline, and each command into its arguments
+///
+/// Arguments are separated by whitespace and commands by `|`. Single quotes
+/// keep their content as is, while double quotes and backslashes allow
+/// whitespace, quotes and `|` to be part of an argument.
+pub(super) fn parse(line: &str) -> anyhow::Result<Vec<Vec<String>>> {
+    let mut pipeline = vec![];
+    let mut args = vec![];
+    let mut arg: Option<String> = None;
+    let mut chars = line.chars();
+    while let Some(c) = chars.next() {
+        match c {
+            '\'' => {
+                let a = arg.get_or_insert_with(String::new);
+                loop {
+                    match chars.next() {
+                        Some('\'') => break,
+                        Some(c) => a.push(c),
+                        None => return Err(anyhow!("unterminated single quote")),
+                    }
+                }
+            }
+            '"' => {
+                let a = arg.get_or_insert_with(String::new);
+                loop {
+                    match chars.next() {
+                        Some('"') => break,
+                        Some('\\') => match chars.next() {
+                            Some(c @ ('"' | '\\')) => a.push(c),
+                            Some(c) => {
+                                a.push('\\');
+                                a.push(c)
+                            }
+                            None => return Err(anyhow!("unterminated double quote")),
+                        },
+                        Some(c) => a.push(c),
+                        None => return Err(anyhow!("unterminated double quote")),
+                    }
+                }
+            }
+            '\\' => match chars.next() {
+                Some(c) => arg.get_or_insert_with(String::new).push(c),
+                None => return Err(anyhow!("nothing to escape at the end of the line")),
+            },
+            '|' => {
+                args.extend(arg.take());
+                if args.is_empty() {
+                    return Err(anyhow!("missing command before `|`"));
+                }
+                pipeline.push(std::mem::take(&mut args))
+            }
+            c if c.is_whitespace() => args.extend(arg.take()),
+            c => arg.get_or_insert_with(String::new).push(c),
+        }
+    }
+    args.extend(arg);
+    if args.is_empty() {
+        if !pipeline.is_empty() {
+            return Err(anyhow!("missing command after `|`"));
+        }
+    } else {
+        pipeline.push(args)
+    }
+    Ok(pipeline)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn split_pipeline() {
+        assert!(parse("  ").unwrap().is_empty());
+        assert_eq!(
+            parse(r#"forwarder create --at /node/n1|message send --to /-/x "a | b" 'c\d' e\ f """#)
+                .unwrap(),
+            [
+                vec!["forwarder", "create", "--at", "/node/n1"],
+                vec!["message", "send", "--to", "/-/x", "a | b", r"c\d", "e f", ""]
+            ]
+        );
+        assert!(parse("a 'b").is_err());
+        assert!(parse("a \"b").is_err());
+        assert!(parse("a |").is_err());
+        assert!(parse("| a").is_err());
+    }
+}
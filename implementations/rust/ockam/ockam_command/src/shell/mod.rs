@@ -0,0 +1,534 @@
+mod complete;
+mod line;
+mod node;
+
+use std::io::{Read, Seek, Write};
+use std::os::unix::io::AsRawFd;
+use std::str::FromStr;
+use std::sync::{Arc, Mutex};
+
+use anyhow::{anyhow, Context as _};
+use clap::{Args, CommandFactory, Parser};
+use itertools::Itertools;
+use tokio::io::{AsyncBufReadExt, BufReader, Lines, Stdin};
+
+use ockam::{Context, TcpTransport};
+use ockam_api::clean_multiaddr;
+use ockam_api::nodes::models::base::NodeStatus;
+use ockam_api::nodes::models::forwarder::ForwarderList;
+use ockam_api::nodes::models::portal::{InletList, OutletList};
+use ockam_api::nodes::models::services::ServiceList;
+use ockam_multiaddr::MultiAddr;
+
+use crate::node::util::delete_embedded_node;
+use crate::node::{is_node_up, NodeOpts};
+use crate::util::{api, exitcode, extract_address_value, node_rpc, OckamConfig, Rpc, RpcBuilder};
+use crate::{
+    help, message, replace_hyphen_with, CommandGlobalOpts, OckamCommand, OckamSubcommand, Result,
+};
+use complete::{Completer, LineHistory};
+pub(crate) use node::{exit, ShellNode};
+
+const HELP_DETAIL: &str = "\
+About:
+    Run commands one after the other against a node, without starting a new `ockam`
+    process and connecting to the node again for every request.
+
+    The shell reads lines from the terminal, with a history and the completion of
+    commands, node names, aliases and services (with the Tab or Right arrow key), or
+    from its standard input when it is not a terminal. Besides its own commands, which
+    are listed by `help`, the shell runs any `ockam` subcommand, such as `tcp-inlet create`,
+    on its own node. A subcommand which takes a node is sent to the current node, unless
+    it is given another one.
+
+    Commands can be chained with `|`. An argument containing `/-` or `-/` is completed
+    with the first word printed by the previous command, the same way `ockam` replaces
+    it with its standard input.
+
+Examples:
+```sh
+    # Start a shell connected to the node n1
+    $ ockam shell --node n1
+
+    # Send a message to the echo service of the node
+    /node/n1: send /service/echo hello
+
+    # Create a forwarder on n2 and send a message through it
+    /node/n1: forwarder create n1 --at /node/n2 --to /node/n1 | send /node/n2/-/service/echo hi
+
+    # Run the commands of a file
+    $ ockam shell --node n1 < commands.txt
+```
+";
+
+/// The commands run by the shell itself, with their usage and description
+const BUILTINS: &[(&str, &str)] = &[
+    ("use NODE", "Send the next requests to another node"),
+    ("nodes", "List the nodes and whether they are running"),
+    ("status", "Show the status of the current node"),
+    ("services", "List the services of the current node"),
+    ("inlets", "List the TCP inlets of the current node"),
+    ("outlets", "List the TCP outlets of the current node"),
+    ("forwarders", "List the forwarders of the current node"),
+    ("channels", "List the secure channels of the current node"),
+    (
+        "send ADDRESS MESSAGE",
+        "Send a message from the current node",
+    ),
+    ("help", "Show this help"),
+    ("exit", "Leave the shell"),
+];
+
+/// Run commands interactively
+#[derive(Clone, Debug, Args)]
+#[command(after_long_help = help::template(HELP_DETAIL))]
+pub struct ShellCommand {
+    #[command(flatten)]
+    node_opts: NodeOpts,
+}
+
+impl ShellCommand {
+    pub fn run(self, options: CommandGlobalOpts) {
+        node_rpc(run_impl, (options, self))
+    }
+}
+
+async fn run_impl(ctx: Context, (opts, cmd): (CommandGlobalOpts, ShellCommand)) -> Result<()> {
+    let node = extract_address_value(&cmd.node_opts.api_node)?;
+    opts.config.get_node(&node)?;
+    let ctx = Arc::new(ctx);
+    let tcp = Arc::new(TcpTransport::create(&ctx).await?);
+    let mut input = Input::new();
+    let mut shell = Shell {
+        ctx: &ctx,
+        tcp: &tcp,
+        runner: ShellNode::new(ctx.clone(), tcp.clone()),
+        opts,
+        node,
+        services: vec![],
+    };
+    shell.refresh().await;
+    while let Some(line) = input.read(shell.prompt(), shell.completer()).await? {
+        match shell.eval(&line).await {
+            Ok(Flow::Continue) => {}
+            Ok(Flow::Exit) => break,
+            Err(e) => eprintln!("{e:?}"),
+        }
+    }
+    if let Some(name) = shell.runner.embedded_node() {
+        delete_embedded_node(&shell.opts.config, &name).await;
+    }
+    Ok(())
+}
+
+/// Source of the lines run by the shell
+enum Input {
+    /// A terminal, with a history and completion
+    Terminal(Arc<Mutex<LineHistory>>),
+    /// Any other standard input, such as a file of commands
+    Lines(Lines<BufReader<Stdin>>),
+}
+
+impl Input {
+    fn new() -> Self {
+        if atty::is(atty::Stream::Stdin) {
+            Input::Terminal(Arc::default())
+        } else {
+            Input::Lines(BufReader::new(tokio::io::stdin()).lines())
+        }
+    }
+
+    /// Read the next line, if any
+    async fn read(&mut self, prompt: String, completer: Completer) -> Result<Option<String>> {
+        match self {
+            Input::Terminal(history) => {
+                let history = history.clone();
+                let line = tokio::task::spawn_blocking(move || {
+                    let mut history = history.lock().unwrap();
+                    let line = dialoguer::Input::<String>::new()
+                        .with_prompt(prompt)
+                        .allow_empty(true)
+                        .history_with(&mut *history)
+                        .completion_with(&completer)
+                        .interact_text();
+                    line
+                })
+                .await
+                .context("Failed to read from the terminal")?;
+                match line {
+                    Ok(line) => Ok(Some(line)),
+                    // Ctrl-C leaves the shell
+                    Err(e) if e.kind() == std::io::ErrorKind::Interrupted => Ok(None),
+                    Err(e) => Err(anyhow!(e)
+                        .context("Failed to read from the terminal")
+                        .into()),
+                }
+            }
+            Input::Lines(lines) => Ok(lines
+                .next_line()
+                .await
+                .context("Failed to read from standard input")?),
+        }
+    }
+}
+
+/// What to do once a line has been run
+enum Flow {
+    Continue,
+    Exit,
+}
+
+struct Shell<'a> {
+    ctx: &'a Context,
+    tcp: &'a TcpTransport,
+    /// Runs the `ockam` subcommands
+    runner: ShellNode,
+    opts: CommandGlobalOpts,
+    /// The node requests are sent to
+    node: String,
+    /// Addresses of the services of the node, for completion
+    services: Vec<String>,
+}
+
+impl<'a> Shell<'a> {
+    fn prompt(&self) -> String {
+        format!("/node/{}", self.node)
+    }
+
+    fn rpc(&self) -> Result<Rpc<'_>> {
+        Ok(RpcBuilder::new(self.ctx, &self.opts, &self.node)
+            .tcp(self.tcp)?
+            .build())
+    }
+
+    /// Update the services of the node, which are unknown while it is down
+    async fn refresh(&mut self) {
+        self.services = match self.list_services().await {
+            Ok(list) => list.into_iter().map(|(addr, _)| addr).collect(),
+            Err(_) => vec![],
+        }
+    }
+
+    fn completer(&self) -> Completer {
+        let commands = BUILTINS
+            .iter()
+            .filter_map(|(usage, _)| usage.split_whitespace().next())
+            .map(String::from)
+            .chain(
+                OckamCommand::command()
+                    .get_subcommands()
+                    .map(|c| c.get_name().to_string()),
+            )
+            .collect();
+        let lookup = self.opts.config.lookup();
+        let nodes = self
+            .opts
+            .config
+            .inner()
+            .nodes
+            .keys()
+            .cloned()
+            .collect::<Vec<_>>();
+        let words = nodes
+            .iter()
+            .map(|n| format!("/node/{n}"))
+            .chain(nodes.iter().cloned())
+            .chain(lookup.aliases().map(|(a, _)| format!("/alias/{a}")))
+            .chain(self.services.iter().cloned())
+            .collect();
+        Completer { commands, words }
+    }
+
+    /// Run a line, printing the output of its last command
+    async fn eval(&mut self, line: &str) -> Result<Flow> {
+        let pipeline = line::parse(line)?;
+        let mut piped: Option<String> = None;
+        let mut stages = pipeline.into_iter().peekable();
+        while let Some(mut args) = stages.next() {
+            if let Some(out) = piped.take() {
+                let word = out
+                    .split_whitespace()
+                    .next()
+                    .ok_or_else(|| anyhow!("`{}` printed nothing to pass on", args[0]))?;
+                args = args
+                    .into_iter()
+                    .map(|a| replace_hyphen_with(a, word))
+                    .collect();
+            }
+            if matches!(args[0].as_str(), "exit" | "quit") {
+                return Ok(Flow::Exit);
+            }
+            let last = stages.peek().is_none();
+            let out = match self.builtin(&args).await? {
+                Some(out) => out,
+                None => self.external(&args, !last).await?,
+            };
+            if !last {
+                piped = Some(out)
+            } else if !out.is_empty() {
+                println!("{out}")
+            }
+        }
+        Ok(Flow::Continue)
+    }
+
+    /// Run a command of the shell, if `args` is one
+    async fn builtin(&mut self, args: &[String]) -> Result<Option<String>> {
+        let args: Vec<&str> = args.iter().map(String::as_str).collect();
+        let out = match args[..] {
+            ["help"] => help_text(),
+            ["use", node] => {
+                let node = extract_address_value(node)?;
+                self.opts.config.get_node(&node)?;
+                self.node = node;
+                self.refresh().await;
+                String::new()
+            }
+            ["nodes"] => self.nodes().await?,
+            ["status"] => {
+                let mut rpc = self.rpc()?;
+                rpc.request(api::query_status()).await?;
+                let s = rpc.parse_response::<NodeStatus>()?;
+                columns([vec![
+                    format!("/node/{}", s.node_name),
+                    s.status.to_string(),
+                    format!("pid {}", s.pid),
+                    format!("{} workers", s.workers),
+                    format!("{} transports", s.transports),
+                ]])
+            }
+            ["services"] => columns(
+                self.list_services()
+                    .await?
+                    .into_iter()
+                    .map(|(addr, kind)| vec![addr, kind]),
+            ),
+            ["inlets"] => {
+                let mut rpc = self.rpc()?;
+                rpc.request(api::list_inlets()).await?;
+                let list = rpc.parse_response::<InletList>()?;
+                columns(list.list.iter().map(|i| {
+                    vec![
+                        i.bind_addr.to_string(),
+                        i.outlet_route.to_string(),
+                        i.alias.to_string(),
+                    ]
+                }))
+            }
+            ["outlets"] => {
+                let mut rpc = self.rpc()?;
+                rpc.request(api::list_outlets()).await?;
+                let list = rpc.parse_response::<OutletList>()?;
+                columns(list.list.iter().map(|o| {
+                    vec![
+                        format!("/service/{}", o.worker_addr),
+                        o.tcp_addr.to_string(),
+                        o.alias.to_string(),
+                    ]
+                }))
+            }
+            ["forwarders"] => {
+                let mut rpc = self.rpc()?;
+                rpc.request(api::list_forwarders()).await?;
+                let list = rpc.parse_response::<ForwarderList>()?;
+                columns(list.list.iter().map(|f| {
+                    vec![
+                        format!("/service/{}", f.remote_address()),
+                        f.forwarding_route().to_string(),
+                        f.alias().unwrap_or("-").to_string(),
+                    ]
+                }))
+            }
+            ["channels"] => {
+                let mut rpc = self.rpc()?;
+                rpc.request(api::list_secure_channels()).await?;
+                let list = rpc.parse_response::<Vec<String>>()?;
+                list.iter().map(|c| format!("/service/{c}")).join("\n")
+            }
+            ["send", to, ref message @ ..] if !message.is_empty() => {
+                self.send(to, &message.join(" ")).await?
+            }
+            [name, ..] => match BUILTINS
+                .iter()
+                .find(|(usage, _)| usage.split_whitespace().next() == Some(name))
+            {
+                Some((usage, _)) => return Err(anyhow!("usage: {usage}").into()),
+                None => return Ok(None),
+            },
+            [] => String::new(),
+        };
+        Ok(Some(out))
+    }
+
+    async fn nodes(&self) -> Result<String> {
+        let names = self
+            .opts
+            .config
+            .inner()
+            .nodes
+            .keys()
+            .cloned()
+            .collect::<Vec<_>>();
+        let mut rows = vec![];
+        for name in names {
+            let mut rpc = RpcBuilder::new(self.ctx, &self.opts, &name)
+                .tcp(self.tcp)?
+                .build();
+            let status = if is_node_up(&mut rpc, false).await? {
+                "UP"
+            } else {
+                "DOWN"
+            };
+            let current = if name == self.node { "*" } else { "" };
+            rows.push(vec![
+                format!("/node/{name}"),
+                status.to_string(),
+                current.into(),
+            ]);
+        }
+        Ok(columns(rows))
+    }
+
+    /// The address and type of the services of the node
+    async fn list_services(&self) -> Result<Vec<(String, String)>> {
+        let mut rpc = self.rpc()?;
+        rpc.request(api::list_services()).await?;
+        let list = rpc.parse_response::<ServiceList>()?;
+        Ok(list
+            .list
+            .iter()
+            .map(|s| (format!("/service/{}", s.addr), s.service_type.to_string()))
+            .collect())
+    }
+
+    async fn send(&self, to: &str, message: &str) -> Result<String> {
+        let to = MultiAddr::from_str(to).context("Invalid address")?;
+        let (to, meta) = clean_multiaddr(&to, &self.opts.config.lookup())
+            .ok_or_else(|| anyhow!("Invalid address {to}"))?;
+        if !meta.project.is_empty() {
+            return Err(anyhow!("Use `message send` to send messages to a project").into());
+        }
+        let mut rpc = self.rpc()?;
+        rpc.request(message::send::req(&to, message)).await?;
+        let res = rpc.parse_response::<Vec<u8>>()?;
+        Ok(String::from_utf8(res).context("Received content is not a valid utf8 string")?)
+    }
+
+    /// Run an `ockam` subcommand on the node of the shell, returning its output if captured
+    async fn external(&mut self, args: &[String], capture: bool) -> Result<String> {
+        let args = self.with_node(args);
+        let argv = std::iter::once("ockam").chain(args.iter().map(String::as_str));
+        let cmd = match OckamCommand::try_parse_from(argv) {
+            Ok(cmd) => cmd,
+            // Help and version requests are not errors
+            Err(e) if !e.use_stderr() => return Ok(e.to_string().trim_end().to_string()),
+            Err(e) => return Err(anyhow!("{}", e.to_string().trim_end()).into()),
+        };
+        if matches!(cmd.subcommand, OckamSubcommand::Shell(_)) {
+            return Err(anyhow!("Already in a shell").into());
+        }
+        let runner = self.runner.clone();
+        let (code, out) = tokio::task::spawn_blocking(move || {
+            if capture {
+                capture_stdout(|| runner.run(cmd))
+            } else {
+                Ok((runner.run(cmd), String::new()))
+            }
+        })
+        .await
+        .context("Failed to run the command")??;
+        // The command may have changed the configuration, e.g. created a node
+        self.opts.config = OckamConfig::load()?;
+        if code != exitcode::OK {
+            return Err(anyhow!("`{}` failed (exit code {code})", args.join(" ")).into());
+        }
+        Ok(out)
+    }
+
+    /// Send a subcommand to the current node if it takes a node but isn't given one
+    fn with_node(&self, args: &[String]) -> Vec<String> {
+        let mut args = args.to_vec();
+        let mut root = OckamCommand::command();
+        root.build();
+        let mut cmd = &root;
+        for a in args.iter().take_while(|a| !a.starts_with('-')) {
+            match cmd.find_subcommand(a) {
+                Some(c) => cmd = c,
+                None => break,
+            }
+        }
+        let node_arg = cmd.get_arguments().find(|a| {
+            a.get_long().is_some()
+                && (a.get_id() == "node"
+                    || a.get_id() == "NODE"
+                    || a.get_value_names() == Some(&["NODE".into()]))
+        });
+        if let Some(arg) = node_arg {
+            let long = format!("--{}", arg.get_long().unwrap_or_default());
+            let short = arg.get_short().map(|c| format!("-{c}"));
+            let given = args.iter().any(|a| {
+                a == &long || a.starts_with(&format!("{long}=")) || Some(a) == short.as_ref()
+            });
+            if !given {
+                args.push(long);
+                args.push(self.node.clone());
+            }
+        }
+        args
+    }
+}
+
+/// Run `f`, returning what it printed on the standard output
+fn capture_stdout<T>(f: impl FnOnce() -> T) -> Result<(T, String)> {
+    use nix::unistd::{close, dup, dup2};
+    let mut file = tempfile::tempfile().context("Failed to capture the output")?;
+    std::io::stdout().flush()?;
+    let stdout = dup(1).context("Failed to capture the output")?;
+    dup2(file.as_raw_fd(), 1).context("Failed to capture the output")?;
+    let res = f();
+    let _ = std::io::stdout().flush();
+    dup2(stdout, 1).context("Failed to restore the output")?;
+    let _ = close(stdout);
+    let mut out = String::new();
+    file.rewind()?;
+    file.read_to_string(&mut out)?;
+    Ok((res, out))
+}
+
+fn help_text() -> String {
+    let width = BUILTINS
+        .iter()
+        .map(|(u, _)| u.len())
+        .max()
+        .unwrap_or_default();
+    let mut s = BUILTINS
+        .iter()
+        .map(|(usage, about)| format!("    {usage:width$}  {about}"))
+        .join("\n");
+    s.push_str("\n\nAny other command is run as an `ockam` subcommand.");
+    format!("Commands:\n{s}")
+}
+
+/// Align rows of words in columns
+fn columns<I: IntoIterator<Item = Vec<String>>>(rows: I) -> String {
+    let rows: Vec<_> = rows.into_iter().collect();
+    let mut widths: Vec<usize> = vec![];
+    for row in &rows {
+        for (i, w) in row.iter().enumerate() {
+            match widths.get_mut(i) {
+                Some(n) => *n = (*n).max(w.chars().count()),
+                None => widths.push(w.chars().count()),
+            }
+        }
+    }
+    rows.iter()
+        .map(|row| {
+            row.iter()
+                .zip(&widths)
+                .map(|(w, &width)| format!("{w:width$}"))
+                .join("  ")
+                .trim_end()
+                .to_string()
+        })
+        .join("\n")
+}
@@ -0,0 +1,101 @@
+//! The node of `ockam shell`, on which the `ockam` subcommands typed in the
+//! shell are run, instead of each of them starting a process and a node.
+
+use std::cell::RefCell;
+use std::panic::{self, AssertUnwindSafe};
+use std::sync::{Arc, Mutex};
+
+use tokio::runtime::Handle;
+
+use ockam::{Address, Context, TcpTransport};
+use ockam_core::AsyncTryClone;
+
+use crate::util::exitcode;
+use crate::OckamCommand;
+
+thread_local! {
+    /// The shell node of the command running on this thread, if any
+    static CURRENT: RefCell<Option<ShellNode>> = RefCell::new(None);
+}
+
+/// Unwinds a command run by the shell, in place of exiting the process
+struct Exit(i32);
+
+#[derive(Clone)]
+pub(crate) struct ShellNode {
+    runtime: Handle,
+    ctx: Arc<Context>,
+    tcp: Arc<TcpTransport>,
+    /// The embedded node manager started by a command, kept for the next ones
+    embedded: Arc<Mutex<Option<String>>>,
+}
+
+impl ShellNode {
+    /// Must be called from the runtime of the shell's node
+    pub(crate) fn new(ctx: Arc<Context>, tcp: Arc<TcpTransport>) -> Self {
+        ShellNode {
+            runtime: Handle::current(),
+            ctx,
+            tcp,
+            embedded: Arc::default(),
+        }
+    }
+
+    /// The shell node of the command running on this thread, if any
+    pub(crate) fn current() -> Option<ShellNode> {
+        CURRENT.with(|c| c.borrow().clone())
+    }
+
+    /// Run a command on this node, returning its exit code
+    ///
+    /// This blocks until the command is done, so it must not be called from
+    /// an asynchronous context.
+    pub(crate) fn run(&self, cmd: OckamCommand) -> i32 {
+        CURRENT.with(|c| *c.borrow_mut() = Some(self.clone()));
+        let res = panic::catch_unwind(AssertUnwindSafe(|| cmd.run()));
+        CURRENT.with(|c| *c.borrow_mut() = None);
+        match res {
+            Ok(()) => exitcode::OK,
+            Err(e) => match e.downcast::<Exit>() {
+                Ok(exit) => exit.0,
+                Err(e) => panic::resume_unwind(e),
+            },
+        }
+    }
+
+    /// Run `f` with a new context of this node
+    pub(crate) fn block_on<A, F, Fut, T>(&self, f: F, a: A) -> crate::Result<T>
+    where
+        F: FnOnce(Context, A) -> Fut,
+        Fut: core::future::Future<Output = crate::Result<T>>,
+    {
+        self.runtime.block_on(async {
+            let ctx = self.ctx.new_detached(Address::random_local()).await?;
+            f(ctx, a).await
+        })
+    }
+
+    /// The name of the embedded node manager running on this node, if any
+    pub(crate) fn embedded_node(&self) -> Option<String> {
+        self.embedded.lock().unwrap().clone()
+    }
+
+    pub(crate) fn set_embedded_node(&self, name: &str) {
+        *self.embedded.lock().unwrap() = Some(name.to_string())
+    }
+
+    /// A handle to the TCP transport of this node
+    pub(crate) async fn tcp(&self) -> ockam::Result<TcpTransport> {
+        self.tcp.as_ref().async_try_clone().await
+    }
+}
+
+/// Exit the process with the given code or, for a command run by the shell,
+/// only end the command
+pub(crate) fn exit(code: i32) -> ! {
+    if ShellNode::current().is_some() {
+        // Unlike `panic!`, this does not run the panic hook
+        panic::resume_unwind(Box::new(Exit(code)))
+    }
+    std::process::exit(code)
+}
@@ -1,14 +1,12 @@
 use crate::{
-    util::{api, extract_address_value, node_rpc, Rpc},
-    CommandGlobalOpts, OutputFormat,
+    util::{api, extract_address_value, node_rpc, output::Output, Rpc},
+    CommandGlobalOpts,
 };
 use anyhow::Context;
 use clap::Args;
-use colorful::Colorful;
 use ockam::{route, Route, TCP};
 use ockam_api::{nodes::models, route_to_multiaddr};
-use serde_json::json;
-use std::net::SocketAddrV4;
+use serde::Serialize;
 
 #[derive(Clone, Debug, Args)]
 pub struct TcpConnectionNodeOpts {
@@ -38,46 +36,6 @@ impl CreateCommand {
     pub fn run(self, options: CommandGlobalOpts) {
         node_rpc(run_impl, (options, self))
     }
-
-    fn print_output(
-        &self,
-        node_name: &str,
-        options: &CommandGlobalOpts,
-        response: &models::transport::TransportStatus,
-    ) -> crate::Result<()> {
-        // if output format is json, write json to stdout.
-        match options.global_args.output_format {
-            OutputFormat::Plain => {
-                let from = &self.node_opts.from;
-                let to = response.payload.parse::<SocketAddrV4>()?;
-                if options.global_args.no_color {
-                    println!("\n  Created TCP Connection:");
-                    println!("  • From: /node/{}", from);
-                    println!("  •   To: {} (/ip4/{}/tcp/{})", to, to.ip(), to.port());
-                } else {
-                    println!("\n  Created TCP Connection:");
-                    println!("{}", format!("  • From: /node/{}", from).light_magenta());
-                    println!(
-                        "{}",
-                        format!("  •   To: {} (/ip4/{}/tcp/{})", to, to.ip(), to.port())
-                            .light_magenta()
-                    );
-                }
-            }
-            OutputFormat::Json => {
-                let port = options.config.get_node_port(node_name)?;
-                let route: Route = route![(TCP, format!("localhost:{}", port))]
-                    .modify()
-                    .append_t(TCP, response.payload.to_string())
-                    .into();
-                let multiaddr = route_to_multiaddr(&route)
-                    .context("Couldn't convert given address into `MultiAddr`")?;
-                let json = json!([{"route": multiaddr.to_string() }]);
-                println!("{}", json);
-            }
-        }
-        Ok(())
-    }
 }
 
 async fn run_impl(
@@ -90,5 +48,39 @@ async fn run_impl(
     rpc.request(request).await?;
     let response = rpc.parse_response::<models::transport::TransportStatus>()?;
 
-    command.print_output(&node_name, &options, &response)
+    let port = options.config.get_node_port(&node_name)?;
+    let to = route![(TCP, response.payload.to_string())];
+    let route: Route = route![(TCP, format!("localhost:{}", port))]
+        .modify()
+        .append_t(TCP, response.payload.to_string())
+        .into();
+    let address =
+        route_to_multiaddr(&to).context("Couldn't convert given address into `MultiAddr`")?;
+    let multiaddr =
+        route_to_multiaddr(&route).context("Couldn't convert given address into `MultiAddr`")?;
+    options.print(&CreatedConnection {
+        from: format!("/node/{}", node_name),
+        to: response.payload.to_string(),
+        address: address.to_string(),
+        route: multiaddr.to_string(),
+    })?;
+    Ok(())
+}
+
+/// Result of creating a TCP connection
+#[derive(Debug, Serialize)]
+struct CreatedConnection {
+    from: String,
+    to: String,
+    address: String,
+    route: String,
+}
+
+impl Output for CreatedConnection {
+    fn output(&self) -> anyhow::Result<String> {
+        Ok(format!(
+            "\n  Created TCP Connection:\n  • From: {}\n  •   To: {} ({})",
+            self.from, self.to, self.address
+        ))
+    }
 }
@@ -1,16 +1,23 @@
-use crate::util::{bind_to_port_check, exitcode, extract_address_value, node_rpc, RpcBuilder};
+use crate::util::output::Output;
+use crate::util::{
+    bind_to_port_check, exitcode, extract_address_value, node_rpc, resolve_names, tcp_transport,
+    RpcBuilder,
+};
 use crate::Result;
 use crate::{help, CommandGlobalOpts};
 use anyhow::anyhow;
 use anyhow::ensure;
 use clap::Args;
 use ockam::identity::IdentityIdentifier;
-use ockam::{Context, TcpTransport};
+use ockam::Context;
 use ockam_api::nodes::models::portal::CreateInlet;
 use ockam_api::nodes::models::portal::InletStatus;
+use ockam_api::route_to_multiaddr;
 use ockam_core::api::Request;
-use ockam_multiaddr::proto::{Node, Project};
+use ockam_core::Route;
+use ockam_multiaddr::proto::Project;
 use ockam_multiaddr::{MultiAddr, Protocol as _};
+use serde::Serialize;
 use std::net::SocketAddr;
 
 const HELP_DETAIL: &str = "\
@@ -87,25 +94,7 @@ impl CreateCommand {
 }
 
 async fn rpc(ctx: Context, (opts, mut cmd): (CommandGlobalOpts, CreateCommand)) -> Result<()> {
-    let lookup = opts.config.lookup();
-    cmd.to = {
-        let mut to = MultiAddr::default();
-        for proto in cmd.to.iter() {
-            match proto.code() {
-                Node::CODE => {
-                    let alias = proto
-                        .cast::<Node>()
-                        .ok_or_else(|| anyhow!("invalid node address protocol"))?;
-                    let addr = lookup
-                        .node_address(&alias)
-                        .ok_or_else(|| anyhow!("no address for node {}", &*alias))?;
-                    to.try_extend(&addr)?
-                }
-                _ => to.push_back_value(&proto)?,
-            }
-        }
-        to
-    };
+    cmd.to = resolve_names(&cmd.to, &opts.config.lookup())?;
 
     // Check if the port is used by some other services or process
     if !bind_to_port_check(&cmd.from) {
@@ -115,7 +104,7 @@ async fn rpc(ctx: Context, (opts, mut cmd): (CommandGlobalOpts, CreateCommand))
         ));
     }
 
-    let tcp = TcpTransport::create(&ctx).await?;
+    let tcp = tcp_transport(&ctx).await?;
     let node = extract_address_value(&cmd.at)?;
 
     let req = {
@@ -136,11 +125,32 @@ async fn rpc(ctx: Context, (opts, mut cmd): (CommandGlobalOpts, CreateCommand))
 
     let mut rpc = RpcBuilder::new(&ctx, &opts, &node).tcp(&tcp)?.build();
     rpc.request(req).await?;
-    rpc.parse_response::<InletStatus>()?;
+    let inlet = rpc.parse_response::<InletStatus>()?;
+    opts.print(&CreatedInlet {
+        from: inlet.bind_addr.to_string(),
+        to: Route::parse(inlet.outlet_route.as_ref())
+            .and_then(|r| route_to_multiaddr(&r))
+            .map_or_else(|| inlet.outlet_route.to_string(), |ma| ma.to_string()),
+        alias: inlet.alias.to_string(),
+    })?;
 
     Ok(())
 }
 
+/// Result of creating a TCP inlet
+#[derive(Debug, Serialize)]
+struct CreatedInlet {
+    from: String,
+    to: String,
+    alias: String,
+}
+
+impl Output for CreatedInlet {
+    fn output(&self) -> anyhow::Result<String> {
+        Ok(self.from.clone())
+    }
+}
+
 fn alias_parser(arg: &str) -> anyhow::Result<String> {
     ensure! {
         !arg.contains(':'),
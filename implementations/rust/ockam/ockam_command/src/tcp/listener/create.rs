@@ -1,5 +1,6 @@
 use crate::util::extract_address_value;
 use crate::util::node_rpc;
+use crate::util::output::Output;
 use crate::util::Rpc;
 use crate::CommandGlobalOpts;
 use anyhow::Context;
@@ -7,6 +8,8 @@ use clap::Args;
 use ockam::{route, Route, TCP};
 use ockam_api::{nodes::models, route_to_multiaddr};
 use ockam_core::api::Request;
+use serde::Serialize;
+
 #[derive(Args, Clone, Debug)]
 pub struct CreateCommand {
     #[command(flatten)]
@@ -47,7 +50,28 @@ async fn run_impl(
         .into();
     let multiaddr =
         route_to_multiaddr(&r).context("Couldn't convert given address into `MultiAddr`")?;
-    println!("Tcp listener created! You can send messages to it via this route:\n`{multiaddr}`",);
+    opts.print(&CreatedListener {
+        id: response.tid.to_string(),
+        address: response.payload.to_string(),
+        route: multiaddr.to_string(),
+    })?;
 
     Ok(())
 }
+
+/// Result of creating a TCP listener
+#[derive(Debug, Serialize)]
+struct CreatedListener {
+    id: String,
+    address: String,
+    route: String,
+}
+
+impl Output for CreatedListener {
+    fn output(&self) -> anyhow::Result<String> {
+        Ok(format!(
+            "Tcp listener created! You can send messages to it via this route:\n`{}`",
+            self.route
+        ))
+    }
+}
@@ -6,6 +6,7 @@ use ockam::Context;
 use ockam_api::nodes::models;
 use ockam_core::api::Request;
 
+use crate::tcp::DeletedTransport;
 use crate::util::{node_rpc, Rpc};
 use crate::{exitcode, node::NodeOpts, CommandGlobalOpts};
 
@@ -38,7 +39,10 @@ async fn run_impl(
         .body(models::transport::DeleteTransport::new(&cmd.id, cmd.force));
     rpc.request(req).await?;
     if rpc.parse_response::<Vec<u8>>().is_ok() {
-        println!("Tcp listener `{}` successfully deleted", cmd.id);
+        opts.print(&DeletedTransport {
+            kind: "listener",
+            id: cmd.id,
+        })?;
         Ok(())
     } else {
         let mut msg = "Failed to delete tcp listener".to_string();
@@ -1,7 +1,6 @@
 use clap::Args;
-use cli_table::{print_stdout, Cell, Style, Table};
 use ockam::Context;
-use ockam_api::nodes::models::transport::{TransportList, TransportStatus};
+use ockam_api::nodes::models::transport::TransportList;
 
 use crate::node::NodeOpts;
 use crate::util::{api, node_rpc, Rpc};
@@ -30,40 +29,6 @@ async fn run_impl(
 ) -> crate::Result<()> {
     let mut rpc = Rpc::background(ctx, &opts, &cmd.node_opts.api_node)?;
     rpc.request(api::list_tcp_listeners()).await?;
-    let res = rpc.parse_response::<TransportList>()?;
-
-    list_listeners(&res.list).await?;
-
-    Ok(())
-}
-
-pub async fn list_listeners<'a>(list: &[TransportStatus<'a>]) -> crate::Result<()> {
-    let table = list
-        .iter()
-        .fold(
-            vec![],
-            |mut acc,
-             TransportStatus {
-                 tt,
-                 tm,
-                 payload,
-                 tid,
-                 ..
-             }| {
-                let row = vec![tid.cell(), tt.cell(), tm.cell(), payload.cell()];
-                acc.push(row);
-                acc
-            },
-        )
-        .table()
-        .title(vec![
-            "Transport ID".cell().bold(true),
-            "Transport Type".cell().bold(true),
-            "Mode".cell().bold(true),
-            "Address bind".cell().bold(true),
-        ]);
-
-    print_stdout(table)?;
-
+    rpc.parse_and_print_response::<TransportList>()?;
     Ok(())
 }
@@ -1,4 +1,25 @@
+use serde::Serialize;
+
+use crate::util::output::Output;
+
 pub(crate) mod connection;
 pub(crate) mod inlet;
 pub(crate) mod listener;
 pub(crate) mod outlet;
+
+/// Result of deleting a TCP listener or connection
+#[derive(Debug, Serialize)]
+pub(crate) struct DeletedTransport {
+    #[serde(skip)]
+    kind: &'static str,
+    id: String,
+}
+
+impl Output for DeletedTransport {
+    fn output(&self) -> anyhow::Result<String> {
+        Ok(format!(
+            "Tcp {} `{}` successfully deleted",
+            self.kind, self.id
+        ))
+    }
+}
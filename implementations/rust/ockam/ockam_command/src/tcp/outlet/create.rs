@@ -1,3 +1,4 @@
+use crate::util::output::Output;
 use crate::util::{extract_address_value, node_rpc, Rpc};
 use crate::{help, CommandGlobalOpts};
 use anyhow::ensure;
@@ -10,6 +11,7 @@ use ockam_api::{
 };
 use ockam_core::api::{Request, RequestBuilder};
 use ockam_core::route;
+use serde::Serialize;
 use std::net::SocketAddr;
 
 const HELP_DETAIL: &str = "\
@@ -94,15 +96,38 @@ pub async fn run_impl(
     };
 
     rpc.request(make_api_request(cmd)?).await?;
-    let OutletStatus { worker_addr, .. } = rpc.parse_response()?;
+    let OutletStatus {
+        tcp_addr,
+        worker_addr,
+        alias,
+        ..
+    } = rpc.parse_response()?;
 
     let addr = route_to_multiaddr(&route![worker_addr.to_string()])
         .ok_or_else(|| ApiError::generic("Invalid Outlet Address"))?;
-    println!("{}", addr);
+    options.print(&CreatedOutlet {
+        address: addr.to_string(),
+        to: tcp_addr.to_string(),
+        alias: alias.to_string(),
+    })?;
 
     Ok(())
 }
 
+/// Result of creating a TCP outlet
+#[derive(Debug, Serialize)]
+struct CreatedOutlet {
+    address: String,
+    to: String,
+    alias: String,
+}
+
+impl Output for CreatedOutlet {
+    fn output(&self) -> anyhow::Result<String> {
+        Ok(self.address.clone())
+    }
+}
+
 /// Construct a request to create a tcp outlet
 fn make_api_request<'a>(cmd: CreateCommand) -> crate::Result<RequestBuilder<'a, CreateOutlet<'a>>> {
     let tcp_addr = cmd.to.to_string();
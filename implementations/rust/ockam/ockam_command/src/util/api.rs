@@ -10,7 +10,8 @@ use clap::Args;
 use minicbor::Decoder;
 use ockam_api::nodes::models::services::{
     StartAuthenticatedServiceRequest, StartAuthenticatorRequest, StartCredentialsService,
-    StartIdentityServiceRequest, StartVaultServiceRequest, StartVerifierService,
+    StartIdentityServiceRequest, StartOidcAuthenticatorRequest, StartOktaIdentityProviderRequest,
+    StartVaultServiceRequest, StartVerifierService,
 };
 use tracing::trace;
 
@@ -24,6 +25,7 @@ use ockam_core::api::{Request, Response};
 use ockam_core::Address;
 use ockam_multiaddr::MultiAddr;
 
+use crate::service::config::{OidcAuthenticatorConfig, OktaIdentityProviderConfig};
 use crate::util::DEFAULT_CONTROLLER_ADDRESS;
 
 ////////////// !== generators
@@ -33,6 +35,11 @@ pub(crate) fn query_status() -> RequestBuilder<'static, ()> {
     Request::get("/node")
 }
 
+/// Construct a request to query the health of a node
+pub(crate) fn node_health() -> RequestBuilder<'static, ()> {
+    Request::get("/node/health")
+}
+
 /// Construct a request to query node tcp listeners
 pub(crate) fn list_tcp_listeners() -> RequestBuilder<'static, ()> {
     Request::get("/node/tcp/listener")
@@ -52,18 +59,6 @@ pub(crate) fn create_tcp_connection(
     Request::post("/node/tcp/connection").body(payload)
 }
 
-/// Construct a request to delete node tcp connection
-pub(crate) fn delete_tcp_connection(
-    cmd: &crate::tcp::connection::DeleteCommand,
-) -> Result<Vec<u8>> {
-    let mut buf = vec![];
-    Request::delete("/node/tcp/connection")
-        .body(models::transport::DeleteTransport::new(&cmd.id, cmd.force))
-        .encode(&mut buf)?;
-
-    Ok(buf)
-}
-
 /// Construct a request to print Identity Id
 pub(crate) fn short_identity() -> RequestBuilder<'static, ()> {
     Request::post("/node/identity/actions/show/short")
@@ -74,6 +69,21 @@ pub(crate) fn list_services() -> RequestBuilder<'static, ()> {
     Request::get("/node/services")
 }
 
+/// Construct a request to list the workers registered on the given node
+pub(crate) fn list_workers() -> RequestBuilder<'static, ()> {
+    Request::get("/node/workers")
+}
+
+/// Construct a request to show the log filter of the given node
+pub(crate) fn get_log_level() -> RequestBuilder<'static, ()> {
+    Request::get("/node/log_level")
+}
+
+/// Construct a request to change the log filter of the given node
+pub(crate) fn set_log_level(filter: &str) -> RequestBuilder<'_, models::base::LogLevel<'_>> {
+    Request::put("/node/log_level").body(models::base::LogLevel::new(filter))
+}
+
 /// Construct a request to print a list of inlets for the given node
 pub(crate) fn list_inlets() -> RequestBuilder<'static, ()> {
     Request::get("/node/inlet")
@@ -84,6 +94,11 @@ pub(crate) fn list_outlets() -> RequestBuilder<'static, ()> {
     Request::get("/node/outlet")
 }
 
+/// Construct a request to print a list of forwarders for the given node
+pub(crate) fn list_forwarders() -> RequestBuilder<'static, ()> {
+    Request::get("/node/forwarder")
+}
+
 /// Construct a request builder to list all secure channels on the given node
 pub(crate) fn list_secure_channels() -> RequestBuilder<'static, ()> {
     Request::get("/node/secure_channel")
@@ -186,16 +201,49 @@ pub(crate) fn start_authenticator_service<'a>(
     Request::post("/node/services/authenticator").body(payload)
 }
 
+/// Construct a request to start an OIDC Authenticator Service
+pub(crate) fn start_oidc_authenticator(
+    cfg: &OidcAuthenticatorConfig,
+) -> RequestBuilder<'static, StartOidcAuthenticatorRequest<'_>> {
+    let payload = StartOidcAuthenticatorRequest::new(
+        &cfg.address,
+        &cfg.issuer,
+        &cfg.audience,
+        cfg.jwks.to_string_lossy().to_string(),
+        cfg.mappings.clone(),
+        cfg.project.as_bytes(),
+    );
+    Request::post("/node/services/oidc_authenticator").body(payload)
+}
+
+/// Construct a request to start an Okta Identity Provider Service
+pub(crate) fn start_okta_identity_provider(
+    cfg: &OktaIdentityProviderConfig,
+) -> RequestBuilder<'static, StartOktaIdentityProviderRequest<'_>> {
+    let payload = StartOktaIdentityProviderRequest::new(
+        &cfg.address,
+        &cfg.tenant_base_url,
+        &cfg.certificate,
+        cfg.attributes.iter().map(|s| s as &str).collect(),
+        cfg.project.as_bytes(),
+    );
+    Request::post("/node/services/okta_identity_provider").body(payload)
+}
+
 pub(crate) mod credentials {
     use ockam_api::nodes::models::credentials::{GetCredentialRequest, PresentCredentialRequest};
 
     use super::*;
 
-    pub(crate) fn present_credential(
+    pub(crate) fn present_credential<'a>(
         to: &MultiAddr,
         oneway: bool,
-    ) -> RequestBuilder<PresentCredentialRequest> {
-        let b = PresentCredentialRequest::new(to, oneway);
+        attributes: &'a [String],
+    ) -> RequestBuilder<'a, PresentCredentialRequest<'a>> {
+        let mut b = PresentCredentialRequest::new(to, oneway);
+        if !attributes.is_empty() {
+            b = b.with_attributes(attributes.iter().map(String::as_str));
+        }
         Request::post("/node/credentials/actions/present").body(b)
     }
 
@@ -205,6 +253,55 @@ pub(crate) mod credentials {
     }
 }
 
+/// Helpers to create self-hosted authority API requests
+pub(crate) mod authority {
+    use ockam_api::authenticator::direct::types::{AddMember, CreateToken, MemberToken};
+    use std::collections::HashMap;
+    use std::time::Duration;
+
+    use super::*;
+
+    pub(crate) fn list_members() -> RequestBuilder<'static, ()> {
+        Request::get("/members")
+    }
+
+    pub(crate) fn update_member(
+        member: IdentityIdentifier,
+        attributes: &HashMap<String, String>,
+    ) -> RequestBuilder<'_, AddMember<'_>> {
+        Request::put("/members").body(
+            AddMember::new(member).with_attributes(
+                attributes
+                    .iter()
+                    .map(|(k, v)| (k.as_str(), v.as_str()))
+                    .collect(),
+            ),
+        )
+    }
+
+    pub(crate) fn remove_member(member: &IdentityIdentifier) -> RequestBuilder<'static, ()> {
+        Request::delete(format!("/members/{member}"))
+    }
+
+    pub(crate) fn create_token(
+        attributes: &HashMap<String, String>,
+        valid_for: Duration,
+    ) -> RequestBuilder<'_, CreateToken<'_>> {
+        Request::post("/tokens").body(
+            CreateToken::new(valid_for).with_attributes(
+                attributes
+                    .iter()
+                    .map(|(k, v)| (k.as_str(), v.as_str()))
+                    .collect(),
+            ),
+        )
+    }
+
+    pub(crate) fn enroll(token: &str) -> RequestBuilder<'_, MemberToken<'_>> {
+        Request::post("/enroll").body(MemberToken::new(token))
+    }
+}
+
 /// Helpers to create enroll API requests
 pub(crate) mod enroll {
     use ockam_api::cloud::enroll::auth0::{Auth0Token, AuthenticateAuth0Token};
@@ -322,12 +419,6 @@ pub(crate) mod project {
 
 ////////////// !== parsers
 
-/// Parse the base response without the inner payload
-pub(crate) fn parse_response(resp: &[u8]) -> Result<Response> {
-    let mut dec = Decoder::new(resp);
-    Ok(dec.decode::<Response>()?)
-}
-
 pub(crate) fn parse_create_secure_channel_listener_response(resp: &[u8]) -> Result<Response> {
     let mut dec = Decoder::new(resp);
     let response = dec.decode::<Response>()?;
@@ -11,6 +11,7 @@ use ockam_api::config::cli::NodeConfigOld;
 use ockam_api::config::lookup::ProjectLookup;
 use ockam_api::config::{cli, lookup::ConfigLookup, lookup::InternetAddress, Config};
 use ockam_api::nodes::config::NodeConfig;
+use ockam_multiaddr::MultiAddr;
 
 /// A simple wrapper around the main configuration structure to add
 /// local config utility/ query functions
@@ -172,7 +173,9 @@ impl OckamConfig {
     /// Get the log path for a specific node
     ///
     /// The convention is to name the main log `node-name.log` and the
-    /// supplementary log `node-name.log.stderr`
+    /// supplementary log `node-name.log.stderr`. Background nodes write
+    /// their main log as JSON, rotated to `node-name.log.1` and so on, and
+    /// their standard output and error to the supplementary log.
     pub fn node_log_paths(&self, node_name: &str) -> Option<(PathBuf, PathBuf)> {
         let inner = self.inner.read();
         let base = inner.nodes.get(node_name)?.state_dir()?;
@@ -283,6 +286,17 @@ impl OckamConfig {
         inner.lookup.set_node(&alias, addr);
     }
 
+    pub fn set_alias(&self, name: &str, addr: MultiAddr) {
+        let mut inner = self.inner.write();
+        inner.lookup.set_alias(name, addr);
+    }
+
+    /// Remove an address alias, returning whether it existed
+    pub fn remove_alias(&self, name: &str) -> bool {
+        let mut inner = self.inner.write();
+        inner.lookup.remove_alias(name).is_some()
+    }
+
     pub fn set_space_alias(&self, id: &str, name: &str) {
         let mut inner = self.inner.write();
         inner.lookup.set_space(id, name);
@@ -0,0 +1,440 @@
+//! Logging of the command and of the nodes it runs
+//!
+//! Commands log to the standard output, in the format of `tracing_subscriber`.
+//! Background nodes log to a file in their state directory instead, as one JSON
+//! object per event, and the file is rotated when it gets too big or too old.
+//!
+//! When `OTEL_EXPORTER_OTLP_ENDPOINT` is set, spans are also exported to that
+//! OpenTelemetry collector, so that traced messages can be followed across nodes.
+
+use std::collections::BTreeMap;
+use std::env;
+use std::fmt;
+use std::fs::{self, File, OpenOptions};
+use std::io::{self, Write};
+use std::path::{Path, PathBuf};
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, SystemTime};
+
+use ockam_api::nodes::service::LogFilter;
+use once_cell::sync::OnceCell;
+use opentelemetry::sdk::{trace, Resource};
+use opentelemetry::KeyValue;
+use opentelemetry_otlp::{WithExportConfig, OTEL_EXPORTER_OTLP_ENDPOINT};
+use serde_json::{json, Map, Value};
+use time::format_description::well_known::Rfc3339;
+use time::OffsetDateTime;
+use tracing::field::{Field, Visit};
+use tracing::{Event, Level, Subscriber};
+use tracing_opentelemetry::OpenTelemetryLayer;
+use tracing_subscriber::filter::{LevelFilter, Targets};
+use tracing_subscriber::fmt::format::Writer;
+use tracing_subscriber::fmt::{FmtContext, FormatEvent, FormatFields};
+use tracing_subscriber::prelude::*;
+use tracing_subscriber::registry::LookupSpan;
+use tracing_subscriber::util::TryInitError;
+use tracing_subscriber::{fmt as tracing_fmt, reload, EnvFilter, Registry};
+
+/// Size above which a node log is rotated
+const MAX_LOG_SIZE: u64 = 10 * 1024 * 1024;
+
+/// Age above which a node log is rotated
+const MAX_LOG_AGE: Duration = Duration::from_secs(24 * 60 * 60);
+
+/// Number of rotated logs kept for each node, next to the current one
+const MAX_LOG_FILES: usize = 5;
+
+/// Crates whose events are logged when the log level is given with `-v`
+const OCKAM_CRATES: [&str; 9] = [
+    "ockam",
+    "ockam_node",
+    "ockam_core",
+    "ockam_command",
+    "ockam_identity",
+    "ockam_channel",
+    "ockam_transport_tcp",
+    "ockam_vault",
+    "ockam_vault_sync_core",
+];
+
+/// Handle on the filter of the process, once logging is set up
+static FILTER: OnceCell<reload::Handle<EnvFilter, Registry>> = OnceCell::new();
+
+/// Runtime of the OpenTelemetry exporter, once it is started
+static OTLP_RUNTIME: OnceCell<tokio::runtime::Runtime> = OnceCell::new();
+
+pub fn setup_logging(verbose: u8, no_color: bool) {
+    let filter = match env_filter(verbose) {
+        Some(f) => f,
+        None => return setup_span_export("ockam"),
+    };
+    let (filter, handle) = reload::Layer::new(filter);
+    let fmt = tracing_fmt::Layer::default().with_ansi(!no_color);
+    let result = tracing_subscriber::registry()
+        .with(filter)
+        .with(tracing_error::ErrorLayer::default())
+        .with(fmt)
+        .with(otlp_layer("ockam"))
+        .try_init();
+    register(result, handle)
+}
+
+/// Set up the logging of a background node, to the given file
+pub fn setup_node_logging(verbose: u8, node_name: &str, path: &Path) {
+    let service = format!("ockam node {node_name}");
+    let filter = match env_filter(verbose) {
+        Some(f) => f,
+        None => return setup_span_export(&service),
+    };
+    let file = match RotatingFile::open(path, MAX_LOG_SIZE, MAX_LOG_AGE) {
+        Ok(f) => f,
+        Err(e) => {
+            eprintln!("Failed to open the log file {path:?}: {e}");
+            return;
+        }
+    };
+    let (filter, handle) = reload::Layer::new(filter);
+    let fmt = tracing_fmt::Layer::default()
+        .with_ansi(false)
+        .event_format(JsonFormat)
+        .with_writer(Mutex::new(file));
+    let result = tracing_subscriber::registry()
+        .with(filter)
+        .with(tracing_error::ErrorLayer::default())
+        .with(fmt)
+        .with(otlp_layer(&service))
+        .try_init();
+    register(result, handle)
+}
+
+/// Export the spans of the Ockam crates, when logging is disabled
+fn setup_span_export(service: &str) {
+    if let Some(otlp) = otlp_layer(service) {
+        let targets = Targets::new().with_targets(OCKAM_CRATES.map(|c| (c, Level::INFO)));
+        let _ = tracing_subscriber::registry()
+            .with(otlp.with_filter(targets))
+            .try_init();
+    }
+}
+
+/// A layer exporting spans to the OpenTelemetry collector at
+/// `OTEL_EXPORTER_OTLP_ENDPOINT`, if this variable is set
+fn otlp_layer<S>(service: &str) -> Option<OpenTelemetryLayer<S, trace::Tracer>>
+where
+    S: Subscriber + for<'a> LookupSpan<'a>,
+{
+    match env::var(OTEL_EXPORTER_OTLP_ENDPOINT) {
+        Ok(s) if !s.is_empty() => {}
+        _ => return None,
+    }
+    // Logging is set up before the node runtime exists, so the
+    // exporter runs on its own
+    let runtime = OTLP_RUNTIME.get_or_try_init(|| {
+        tokio::runtime::Builder::new_multi_thread()
+            .worker_threads(1)
+            .thread_name("otlp-exporter")
+            .enable_all()
+            .build()
+    });
+    let runtime = match runtime {
+        Ok(rt) => rt,
+        Err(e) => {
+            eprintln!("Failed to start the OpenTelemetry exporter: {e}");
+            return None;
+        }
+    };
+    let _guard = runtime.enter();
+    let resource = Resource::new([KeyValue::new("service.name", service.to_string())]);
+    let tracer = opentelemetry_otlp::new_pipeline()
+        .tracing()
+        .with_exporter(opentelemetry_otlp::new_exporter().tonic().with_env())
+        .with_trace_config(trace::config().with_resource(resource))
+        .install_batch(opentelemetry::runtime::Tokio);
+    match tracer {
+        Ok(tracer) => Some(tracing_opentelemetry::layer().with_tracer(tracer)),
+        Err(e) => {
+            eprintln!("Failed to start the OpenTelemetry exporter: {e}");
+            None
+        }
+    }
+}
+
+/// Export the spans which are not exported yet, before the process exits
+pub fn flush_spans() {
+    if OTLP_RUNTIME.get().is_some() {
+        opentelemetry::global::shutdown_tracer_provider()
+    }
+}
+
+fn register(result: Result<(), TryInitError>, handle: reload::Handle<EnvFilter, Registry>) {
+    if result.is_err() {
+        eprintln!("Failed to initialise tracing logging.");
+        return;
+    }
+    let _ = FILTER.set(handle);
+}
+
+fn env_filter(verbose: u8) -> Option<EnvFilter> {
+    let builder = EnvFilter::builder();
+    // If `verbose` is not set, try to read the log level from the OCKAM_LOG env variable.
+    // If both `verbose` and OCKAM_LOG are not set, logging will not be enabled.
+    // Otherwise, use `verbose` to define the log level.
+    let filter = match verbose {
+        0 => match env::var("OCKAM_LOG") {
+            Ok(s) if !s.is_empty() => builder.with_env_var("OCKAM_LOG").from_env_lossy(),
+            _ => return None,
+        },
+        1 => builder
+            .with_default_directive(LevelFilter::INFO.into())
+            .parse_lossy(OCKAM_CRATES.map(|c| format!("{c}=info")).join(",")),
+        2 => builder
+            .with_default_directive(LevelFilter::DEBUG.into())
+            .parse_lossy(OCKAM_CRATES.map(|c| format!("{c}=debug")).join(",")),
+        _ => builder
+            .with_default_directive(LevelFilter::TRACE.into())
+            .parse_lossy(OCKAM_CRATES.map(|c| format!("{c}=trace")).join(",")),
+    };
+    Some(filter)
+}
+
+/// The filter of the process, to be changed by the node manager
+///
+/// This is `None` when logging is disabled.
+pub fn log_filter() -> Option<Arc<dyn LogFilter>> {
+    let handle = FILTER.get()?.clone();
+    Some(Arc::new(ReloadableFilter(handle)))
+}
+
+struct ReloadableFilter(reload::Handle<EnvFilter, Registry>);
+
+impl LogFilter for ReloadableFilter {
+    fn get(&self) -> String {
+        self.0.with_current(|f| f.to_string()).unwrap_or_default()
+    }
+
+    fn set(&self, filter: &str) -> Result<(), String> {
+        let f = EnvFilter::try_new(filter).map_err(|e| e.to_string())?;
+        self.0.reload(f).map_err(|e| e.to_string())
+    }
+}
+
+/// Writes every event as a JSON object on its own line
+struct JsonFormat;
+
+impl<S, N> FormatEvent<S, N> for JsonFormat
+where
+    S: Subscriber + for<'a> LookupSpan<'a>,
+    N: for<'a> FormatFields<'a> + 'static,
+{
+    fn format_event(
+        &self,
+        ctx: &FmtContext<'_, S, N>,
+        mut writer: Writer<'_>,
+        event: &Event<'_>,
+    ) -> fmt::Result {
+        let meta = event.metadata();
+        let mut fields = JsonFields::default();
+        event.record(&mut fields);
+        let timestamp = OffsetDateTime::now_utc()
+            .format(&Rfc3339)
+            .map_err(|_| fmt::Error)?;
+        let mut entry = Map::new();
+        entry.insert("timestamp".into(), timestamp.into());
+        entry.insert("level".into(), meta.level().as_str().into());
+        entry.insert("target".into(), meta.target().into());
+        entry.insert("message".into(), fields.message.unwrap_or_default().into());
+        if !fields.fields.is_empty() {
+            entry.insert("fields".into(), json!(fields.fields));
+        }
+        if let Some(scope) = ctx.event_scope() {
+            let spans: Vec<_> = scope.from_root().map(|s| s.name()).collect();
+            entry.insert("spans".into(), json!(spans));
+        }
+        writeln!(writer, "{}", Value::Object(entry))
+    }
+}
+
+#[derive(Default)]
+struct JsonFields {
+    message: Option<String>,
+    fields: BTreeMap<&'static str, Value>,
+}
+
+impl JsonFields {
+    fn insert(&mut self, field: &Field, value: Value) {
+        if field.name() == "message" {
+            self.message = Some(match value {
+                Value::String(s) => s,
+                v => v.to_string(),
+            })
+        } else {
+            self.fields.insert(field.name(), value);
+        }
+    }
+}
+
+impl Visit for JsonFields {
+    fn record_f64(&mut self, field: &Field, value: f64) {
+        self.insert(field, value.into())
+    }
+
+    fn record_i64(&mut self, field: &Field, value: i64) {
+        self.insert(field, value.into())
+    }
+
+    fn record_u64(&mut self, field: &Field, value: u64) {
+        self.insert(field, value.into())
+    }
+
+    fn record_bool(&mut self, field: &Field, value: bool) {
+        self.insert(field, value.into())
+    }
+
+    fn record_str(&mut self, field: &Field, value: &str) {
+        self.insert(field, value.into())
+    }
+
+    fn record_debug(&mut self, field: &Field, value: &dyn fmt::Debug) {
+        self.insert(field, format!("{value:?}").into())
+    }
+}
+
+/// A log file that is rotated when it gets too big or too old
+///
+/// The current file keeps its path, and rotated files are suffixed with
+/// `.1` for the most recent one up to `.5` for the oldest one.
+pub(crate) struct RotatingFile {
+    path: PathBuf,
+    file: File,
+    size: u64,
+    created: SystemTime,
+    max_size: u64,
+    max_age: Duration,
+}
+
+impl RotatingFile {
+    pub(crate) fn open(path: &Path, max_size: u64, max_age: Duration) -> io::Result<Self> {
+        let file = OpenOptions::new().create(true).append(true).open(path)?;
+        let meta = file.metadata()?;
+        Ok(Self {
+            path: path.to_path_buf(),
+            file,
+            size: meta.len(),
+            created: meta.created().unwrap_or_else(|_| SystemTime::now()),
+            max_size,
+            max_age,
+        })
+    }
+
+    fn rotate(&mut self) -> io::Result<()> {
+        for i in (1..MAX_LOG_FILES).rev() {
+            rename(
+                &rotated_path(&self.path, i),
+                &rotated_path(&self.path, i + 1),
+            )?
+        }
+        rename(&self.path, &rotated_path(&self.path, 1))?;
+        self.file = OpenOptions::new()
+            .create(true)
+            .append(true)
+            .open(&self.path)?;
+        self.size = 0;
+        self.created = SystemTime::now();
+        Ok(())
+    }
+
+    fn is_full(&self, len: usize) -> bool {
+        let too_old = self
+            .created
+            .elapsed()
+            .map_or(false, |age| age > self.max_age);
+        self.size > 0 && (self.size + len as u64 > self.max_size || too_old)
+    }
+}
+
+impl Write for RotatingFile {
+    fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+        if self.is_full(buf.len()) {
+            self.rotate()?
+        }
+        let n = self.file.write(buf)?;
+        self.size += n as u64;
+        Ok(n)
+    }
+
+    fn flush(&mut self) -> io::Result<()> {
+        self.file.flush()
+    }
+}
+
+fn rotated_path(path: &Path, n: usize) -> PathBuf {
+    let mut p = path.as_os_str().to_owned();
+    p.push(format!(".{n}"));
+    PathBuf::from(p)
+}
+
+fn rename(from: &Path, to: &Path) -> io::Result<()> {
+    match fs::rename(from, to) {
+        Err(e) if e.kind() == io::ErrorKind::NotFound => Ok(()),
+        r => r,
+    }
+}
+
+/// Rotate a log that is written by someone else, if it is too big or too old
+///
+/// This is used for the stderr log of a background node: the node can't
+/// reopen its own standard streams, so the log is rotated when the node starts.
+pub(crate) fn rotate_if_full(path: &Path) -> io::Result<()> {
+    if !path.exists() {
+        return Ok(());
+    }
+    let mut f = RotatingFile::open(path, MAX_LOG_SIZE, MAX_LOG_AGE)?;
+    if f.is_full(0) {
+        f.rotate()?
+    }
+    Ok(())
+}
+
+/// The files of a log that exist, from the oldest rotated one to the current one
+pub(crate) fn log_files(path: &Path) -> Vec<PathBuf> {
+    (1..=MAX_LOG_FILES)
+        .rev()
+        .map(|i| rotated_path(path, i))
+        .chain([path.to_path_buf()])
+        .filter(|p| p.exists())
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn rotate_by_size() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("n1.log");
+        let mut f = RotatingFile::open(&path, 10, MAX_LOG_AGE).unwrap();
+        for i in 0..(MAX_LOG_FILES + 3) {
+            writeln!(f, "line {i:03}").unwrap();
+        }
+        let files = log_files(&path);
+        assert_eq!(files.len(), MAX_LOG_FILES + 1);
+        let content: Vec<_> = files
+            .iter()
+            .map(|p| fs::read_to_string(p).unwrap())
+            .collect();
+        assert_eq!(content.first().unwrap(), "line 002\n");
+        assert_eq!(content.last().unwrap(), "line 007\n");
+    }
+
+    #[test]
+    fn rotate_if_full_keeps_small_logs() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("n1.log.stderr");
+        rotate_if_full(&path).unwrap();
+        assert!(!path.exists());
+        fs::write(&path, "panicked\n").unwrap();
+        rotate_if_full(&path).unwrap();
+        assert_eq!(log_files(&path), vec![path]);
+    }
+}
@@ -1,6 +1,5 @@
 use core::time::Duration;
 use std::{
-    env,
     net::{SocketAddr, TcpListener},
     path::Path,
     str::FromStr,
@@ -9,23 +8,27 @@ use std::{
 use anyhow::{anyhow, Context as _, Result};
 use minicbor::{data::Type, Decode, Decoder, Encode};
 use tracing::{debug, error, trace};
-use tracing_subscriber::prelude::*;
-use tracing_subscriber::{filter::LevelFilter, fmt, EnvFilter};
 
 pub use addon::AddonCommand;
 pub use config::*;
-use ockam::{route, Address, Context, NodeBuilder, Route, TcpTransport, TCP};
+pub use logging::setup_logging;
+use ockam::{Address, Context, NodeBuilder, Route, TcpTransport, TCP};
+use ockam_api::config::lookup::ConfigLookup;
 use ockam_api::nodes::NODEMANAGER_ADDR;
 use ockam_api::{config::cli::NodeConfigOld, nodes::models::base::NodeStatus};
 use ockam_core::api::{RequestBuilder, Response, Status};
+use ockam_multiaddr::pattern::Rewriter;
 use ockam_multiaddr::{proto, MultiAddr, Protocol};
 
 use crate::node::util::start_embedded_node;
+pub(crate) use crate::shell::exit;
+use crate::shell::ShellNode;
 use crate::util::output::Output;
-use crate::{CommandGlobalOpts, OutputFormat};
+use crate::CommandGlobalOpts;
 
 pub mod api;
 pub mod exitcode;
+pub mod logging;
 pub mod startup;
 
 mod addon;
@@ -143,12 +146,13 @@ impl<'a> Rpc<'a> {
     where
         T: Encode<()>,
     {
-        let route = self.route_impl(self.ctx).await?;
+        let route = self.route_impl(self.ctx).await.map_err(unavailable)?;
         self.buf = self
             .ctx
             .send_and_receive(route.clone(), req.to_vec()?)
             .await
-            .context("Failed to receive response from node")?;
+            .context("Failed to receive response from node")
+            .map_err(unavailable)?;
         Ok(())
     }
 
@@ -162,12 +166,13 @@ impl<'a> Rpc<'a> {
         T: Encode<()>,
     {
         let mut ctx = self.ctx.new_detached(Address::random_local()).await?;
-        let route = self.route_impl(&ctx).await?;
+        let route = self.route_impl(&ctx).await.map_err(unavailable)?;
         ctx.send(route.clone(), req.to_vec()?).await?;
         self.buf = ctx
             .receive_duration_timeout::<Vec<u8>>(timeout)
             .await
-            .context("Failed to receive response from node")?
+            .context("Failed to receive response from node")
+            .map_err(unavailable)?
             .take()
             .body();
         Ok(())
@@ -180,10 +185,16 @@ impl<'a> Rpc<'a> {
                 let addr = Address::from((TCP, format!("localhost:{}", cfg.port())));
                 let addr_str = addr.address();
                 match tcp {
-                    None => {
-                        let tcp = TcpTransport::create(ctx).await?;
-                        tcp.connect(addr_str).await?;
-                    }
+                    None => match ShellNode::current() {
+                        // Ignore "already connected" error.
+                        Some(shell) => {
+                            let _ = shell.tcp().await?.connect(addr_str).await;
+                        }
+                        None => {
+                            let tcp = TcpTransport::create(ctx).await?;
+                            tcp.connect(addr_str).await?;
+                        }
+                    },
                     Some(tcp) => {
                         // Ignore "already connected" error.
                         let _ = tcp.connect(addr_str).await;
@@ -234,8 +245,15 @@ impl<'a> Rpc<'a> {
         if hdr.status() == Some(Status::Ok) {
             Ok(dec)
         } else {
+            let code = match hdr.status() {
+                Some(Status::BadRequest | Status::NotFound | Status::Conflict) => exitcode::DATAERR,
+                Some(Status::Unauthorized | Status::Forbidden) => exitcode::NOPERM,
+                Some(Status::MethodNotAllowed | Status::NotImplemented) => exitcode::UNAVAILABLE,
+                None => exitcode::PROTOCOL,
+                Some(_) => exitcode::SOFTWARE,
+            };
             let msg = self.parse_err_msg(hdr, dec);
-            Err(anyhow!(msg))
+            Err(crate::Error::new(code, anyhow!(msg)).into())
         }
     }
 
@@ -284,72 +302,32 @@ impl<'a> Rpc<'a> {
     where
         T: Output + serde::Serialize,
     {
-        let o = match self.opts.global_args.output_format {
-            OutputFormat::Plain => b.output().context("Failed to serialize response body")?,
-            OutputFormat::Json => {
-                serde_json::to_string_pretty(&b).context("Failed to serialize response body")?
-            }
-        };
-        println!("{}", o);
+        self.opts.print(&b)?;
         Ok(b)
     }
 }
 
+/// Exit with `UNAVAILABLE` when a node can't be reached
+fn unavailable(e: anyhow::Error) -> anyhow::Error {
+    crate::Error::new(exitcode::UNAVAILABLE, e).into()
+}
+
 /// A simple wrapper for shutting down the local embedded node (for
 /// the client side of the CLI).  Swallows errors and turns them into
 /// eprintln logs.
 ///
 /// TODO: We may want to change this behaviour in the future.
 pub async fn stop_node(mut ctx: Context) -> Result<()> {
+    // The node of the shell outlives its commands
+    if ShellNode::current().is_some() {
+        return Ok(());
+    }
     if let Err(e) = ctx.stop().await {
         eprintln!("an error occurred while shutting down local node: {}", e);
     }
     Ok(())
 }
 
-/// Connect to a remote node (on localhost for now)
-///
-/// This function requires the "remote" port, some command payload,
-/// and a user function to run.  It uses `embedded_node` internally,
-/// while also configuring a TcpTransport and connecting to another
-/// node.
-///
-pub fn connect_to<A, F, Fut>(port: u16, a: A, lambda: F)
-where
-    A: Send + Sync + 'static,
-    F: FnOnce(Context, A, Route) -> Fut + Send + Sync + 'static,
-    Fut: core::future::Future<Output = Result<()>> + Send + 'static,
-{
-    let res = embedded_node(
-        move |ctx, a| async move {
-            let tcp = match TcpTransport::create(&ctx).await {
-                Ok(tcp) => tcp,
-                Err(e) => {
-                    eprintln!("Failed to create TcpTransport. {e}");
-                    error!(%e);
-                    std::process::exit(exitcode::CANTCREAT);
-                }
-            };
-            if let Err(e) = tcp.connect(format!("localhost:{}", port)).await {
-                eprintln!("Failed to connect to node. {e}");
-                error!(%e);
-                std::process::exit(exitcode::IOERR);
-            }
-            let route = route![(TCP, format!("localhost:{}", port))];
-            if let Err(e) = lambda(ctx, a, route).await {
-                eprintln!("Encountered an error in command handler code. {e}");
-                error!(%e);
-                std::process::exit(exitcode::IOERR);
-            }
-            Ok(())
-        },
-        a,
-    );
-    if let Err(e) = res {
-        eprintln!("Ockam node failed: {:?}", e,);
-    }
-}
-
 pub fn node_rpc<A, F, Fut>(f: F, a: A)
 where
     A: Send + Sync + 'static,
@@ -362,15 +340,17 @@ where
             if let Err(e) = res {
                 error!(%e);
                 eprintln!("{e:?}");
-                std::process::exit(e.code());
+                logging::flush_spans();
+                exit(e.code());
             }
             Ok(())
         },
         a,
     );
+    logging::flush_spans();
     if let Err(e) = res {
         eprintln!("Ockam node failed: {e}");
-        std::process::exit(exitcode::SOFTWARE);
+        exit(exitcode::SOFTWARE);
     }
 }
 
@@ -381,6 +361,9 @@ where
     Fut: core::future::Future<Output = crate::Result<T>> + Send + 'static,
     T: Send + 'static,
 {
+    if let Some(shell) = ShellNode::current() {
+        return on_shell_node(&shell, f, a);
+    }
     let (ctx, mut executor) = NodeBuilder::without_access_control().no_logging().build();
     let r = executor.execute(async move {
         let child_ctx = ctx
@@ -393,7 +376,7 @@ where
             Err(e) => {
                 error!(%e);
                 eprintln!("{e:?}");
-                std::process::exit(e.code());
+                exit(e.code());
             }
             Ok(v) => v,
         }
@@ -408,6 +391,9 @@ where
     Fut: core::future::Future<Output = crate::Result<T>> + Send + 'static,
     T: Send + 'static,
 {
+    if let Some(shell) = ShellNode::current() {
+        return on_shell_node(&shell, f, a);
+    }
     let (ctx, mut executor) = NodeBuilder::without_access_control().no_logging().build();
     let r = executor.execute(async move {
         let child_ctx = ctx
@@ -418,7 +404,7 @@ where
             Err(e) => {
                 error!(%e);
                 eprintln!("{e:?}");
-                std::process::exit(e.code());
+                exit(e.code());
             }
             Ok(v) => v,
         }
@@ -426,6 +412,32 @@ where
     Ok(r)
 }
 
+/// Run `f` on the node of the shell running the command, exiting like an
+/// embedded node on errors
+fn on_shell_node<A, F, Fut, T>(shell: &ShellNode, f: F, a: A) -> crate::Result<T>
+where
+    F: FnOnce(Context, A) -> Fut,
+    Fut: core::future::Future<Output = crate::Result<T>>,
+{
+    match shell.block_on(f, a) {
+        Err(e) => {
+            error!(%e);
+            eprintln!("{e:?}");
+            exit(e.code());
+        }
+        Ok(v) => Ok(v),
+    }
+}
+
+/// Create the TCP transport of a command's node, or use the one of the shell
+/// running the command
+pub async fn tcp_transport(ctx: &Context) -> ockam::Result<TcpTransport> {
+    match ShellNode::current() {
+        Some(shell) => shell.tcp().await,
+        None => TcpTransport::create(ctx).await,
+    }
+}
+
 pub fn find_available_port() -> Result<u16> {
     let listener = TcpListener::bind("127.0.0.1:0").context("Unable to bind to an open port")?;
     let address = listener
@@ -434,48 +446,6 @@ pub fn find_available_port() -> Result<u16> {
     Ok(address.port())
 }
 
-pub fn setup_logging(verbose: u8, no_color: bool) {
-    let ockam_crates = [
-        "ockam",
-        "ockam_node",
-        "ockam_core",
-        "ockam_command",
-        "ockam_identity",
-        "ockam_channel",
-        "ockam_transport_tcp",
-        "ockam_vault",
-        "ockam_vault_sync_core",
-    ];
-    let builder = EnvFilter::builder();
-    // If `verbose` is not set, try to read the log level from the OCKAM_LOG env variable.
-    // If both `verbose` and OCKAM_LOG are not set, logging will not be enabled.
-    // Otherwise, use `verbose` to define the log level.
-    let filter = match verbose {
-        0 => match env::var("OCKAM_LOG") {
-            Ok(s) if !s.is_empty() => builder.with_env_var("OCKAM_LOG").from_env_lossy(),
-            _ => return,
-        },
-        1 => builder
-            .with_default_directive(LevelFilter::INFO.into())
-            .parse_lossy(ockam_crates.map(|c| format!("{c}=info")).join(",")),
-        2 => builder
-            .with_default_directive(LevelFilter::DEBUG.into())
-            .parse_lossy(ockam_crates.map(|c| format!("{c}=debug")).join(",")),
-        _ => builder
-            .with_default_directive(LevelFilter::TRACE.into())
-            .parse_lossy(ockam_crates.map(|c| format!("{c}=trace")).join(",")),
-    };
-    let fmt = fmt::Layer::default().with_ansi(!no_color);
-    let result = tracing_subscriber::registry()
-        .with(filter)
-        .with(tracing_error::ErrorLayer::default())
-        .with(fmt)
-        .try_init();
-    if result.is_err() {
-        eprintln!("Failed to initialise tracing logging.");
-    }
-}
-
 #[allow(unused)]
 pub fn print_path(p: &Path) -> String {
     p.to_str().unwrap_or("<unprintable>").to_string()
@@ -524,6 +494,36 @@ pub fn extract_address_value(input: &str) -> anyhow::Result<String> {
     Ok(addr)
 }
 
+/// Replace the `/alias/<name>` and `/node/<name>` protocols of a
+/// multi-address with the addresses they stand for.
+pub fn resolve_names(input: &MultiAddr, lookup: &ConfigLookup) -> Result<MultiAddr> {
+    let ma = lookup.rewriter().rewrite(input)?;
+    if let Some(p) = ma.iter().find(|p| p.code() == proto::Node::CODE) {
+        let node = p
+            .cast::<proto::Node>()
+            .ok_or_else(|| anyhow!("invalid node address protocol"))?;
+        return Err(anyhow!("no address for node {}", &*node));
+    }
+    check_aliases(ma)
+}
+
+/// Replace the `/alias/<name>` protocols of a multi-address with the
+/// addresses they stand for.
+pub fn resolve_aliases(input: &MultiAddr, lookup: &ConfigLookup) -> Result<MultiAddr> {
+    let rules: Rewriter = lookup.alias_rules().collect();
+    check_aliases(rules.rewrite(input)?)
+}
+
+fn check_aliases(ma: MultiAddr) -> Result<MultiAddr> {
+    if let Some(p) = ma.iter().find(|p| p.code() == proto::Alias::CODE) {
+        let alias = p
+            .cast::<proto::Alias>()
+            .ok_or_else(|| anyhow!("invalid alias protocol"))?;
+        return Err(anyhow!("unknown alias {}", &*alias));
+    }
+    Ok(ma)
+}
+
 pub fn comma_separated<T: AsRef<str>>(data: &[T]) -> String {
     use itertools::Itertools;
 
@@ -2,15 +2,21 @@ use anyhow::Context;
 use cli_table::{Cell, Style, Table};
 use core::fmt::Write;
 use ockam::identity::credential::Credential;
+use ockam_api::authenticator::direct::types::{MemberInfo, MemberToken};
 use ockam_api::cloud::project::{Enroller, Project};
 
 use crate::project::ProjectInfo;
 use crate::util::comma_separated;
 use colorful::Colorful;
 use ockam_api::cloud::space::Space;
+use ockam_api::nodes::models::base::LogLevel;
+use ockam_api::nodes::models::forwarder::ForwarderList;
+use ockam_api::nodes::models::identity::CreateIdentityResponse;
 use ockam_api::nodes::models::secure_channel::{
     CreateSecureChannelResponse, ShowSecureChannelResponse,
 };
+use ockam_api::nodes::models::transport::{TransportList, TransportStatus};
+use ockam_api::nodes::models::workers::WorkerList;
 use ockam_api::route_to_multiaddr;
 use ockam_core::route;
 
@@ -231,6 +237,145 @@ impl Output for Vec<Enroller<'_>> {
     }
 }
 
+impl Output for LogLevel<'_> {
+    fn output(&self) -> anyhow::Result<String> {
+        Ok(self.filter.to_string())
+    }
+}
+
+impl Output for CreateIdentityResponse<'_> {
+    fn output(&self) -> anyhow::Result<String> {
+        Ok(format!("Identity {} created!", self.identity_id))
+    }
+}
+
+impl Output for TransportList<'_> {
+    fn output(&self) -> anyhow::Result<String> {
+        let mut rows = vec![];
+        for TransportStatus {
+            tt,
+            tm,
+            payload,
+            tid,
+            ..
+        } in &self.list
+        {
+            rows.push([tid.cell(), tt.cell(), tm.cell(), payload.cell()]);
+        }
+        let table = rows
+            .table()
+            .title([
+                "Transport ID".cell().bold(true),
+                "Transport Type".cell().bold(true),
+                "Mode".cell().bold(true),
+                "Address bind".cell().bold(true),
+            ])
+            .display()?
+            .to_string();
+        Ok(table)
+    }
+}
+
+impl Output for WorkerList<'_> {
+    fn output(&self) -> anyhow::Result<String> {
+        if self.list.is_empty() {
+            return Ok("No workers found".to_string());
+        }
+        let mut rows = vec![];
+        for w in &self.list {
+            rows.push([
+                (&w.addr).cell(),
+                comma_separated(&w.aliases).cell(),
+                w.cluster.as_deref().unwrap_or("-").cell(),
+                (&w.worker_type).cell(),
+                (&w.access_control).cell(),
+                w.queued.cell(),
+                w.handled.cell(),
+                w.started_at
+                    .map(|t| t.to_string())
+                    .unwrap_or_else(|| "-".to_string())
+                    .cell(),
+            ]);
+        }
+        let table = rows
+            .table()
+            .title([
+                "Address".cell().bold(true),
+                "Aliases".cell().bold(true),
+                "Cluster".cell().bold(true),
+                "Type".cell().bold(true),
+                "Access Control".cell().bold(true),
+                "Queued".cell().bold(true),
+                "Handled".cell().bold(true),
+                "Started At".cell().bold(true),
+            ])
+            .display()?
+            .to_string();
+        Ok(table)
+    }
+}
+
+impl Output for ForwarderList<'_> {
+    fn output(&self) -> anyhow::Result<String> {
+        if self.list.is_empty() {
+            return Ok("No forwarders found".to_string());
+        }
+        let mut rows = vec![];
+        for f in &self.list {
+            rows.push([
+                f.alias().unwrap_or("-").cell(),
+                format!("/service/{}", f.remote_address()).cell(),
+                f.forwarding_route().cell(),
+                f.worker_address().cell(),
+            ]);
+        }
+        let table = rows
+            .table()
+            .title([
+                "Alias".cell().bold(true),
+                "Remote Address".cell().bold(true),
+                "Forwarding Route".cell().bold(true),
+                "Worker".cell().bold(true),
+            ])
+            .display()?
+            .to_string();
+        Ok(table)
+    }
+}
+
+impl Output for MemberToken<'_> {
+    fn output(&self) -> anyhow::Result<String> {
+        Ok(self.token().to_string())
+    }
+}
+
+impl Output for Vec<MemberInfo<'_>> {
+    fn output(&self) -> anyhow::Result<String> {
+        if self.is_empty() {
+            return Ok("No members found".to_string());
+        }
+        let mut rows = vec![];
+        for m in self {
+            let mut attrs: Vec<String> = m
+                .attributes()
+                .iter()
+                .map(|(k, v)| format!("{k}={v}"))
+                .collect();
+            attrs.sort();
+            rows.push([m.member().to_string().cell(), attrs.join(", ").cell()]);
+        }
+        let table = rows
+            .table()
+            .title([
+                "Identity ID".cell().bold(true),
+                "Attributes".cell().bold(true),
+            ])
+            .display()?
+            .to_string();
+        Ok(table)
+    }
+}
+
 impl Output for Credential<'_> {
     fn output(&self) -> anyhow::Result<String> {
         Ok(self.to_string())
@@ -3,12 +3,14 @@
 #![allow(unused)]
 
 use crate::exitcode;
-use crate::util::OckamConfig;
+use crate::util::{logging, OckamConfig};
+use crate::vault::util::{storage_key, write_storage_key};
 use anyhow::Context;
 use nix::sys::signal::{self, Signal};
 use nix::unistd::Pid;
 use std::collections::VecDeque;
 use std::io::Stdout;
+use std::net::SocketAddr;
 use std::process::Stdio;
 use std::{
     env::current_exe,
@@ -44,6 +46,7 @@ pub fn spawn_node(
     enable_credential_checks: bool,
     name: &str,
     address: &str,
+    health_address: Option<SocketAddr>,
     project: Option<&Path>,
 ) -> crate::Result<()> {
     // On systems with non-obvious path setups (or during
@@ -51,19 +54,20 @@ pub fn spawn_node(
     // deterministic way of starting a node.
     let ockam_exe = current_exe().unwrap_or_else(|_| "ockam".into());
 
-    let (mlog, elog) = cfg.node_log_paths(name).unwrap();
-
-    let main_log_file = OpenOptions::new()
-        .create(true)
-        .append(true)
-        .open(mlog)
-        .context("failed to open log path")?;
+    // The node writes its main log itself, so that it can rotate it. Anything
+    // else it prints, such as the message of a panic, goes to the stderr log.
+    // That log is rotated here, before the node holds it open.
+    let (_, elog) = cfg.node_log_paths(name).unwrap();
+    logging::rotate_if_full(&elog).context("failed to rotate stderr log")?;
 
     let stderr_log_file = OpenOptions::new()
         .create(true)
         .append(true)
         .open(elog)
         .context("failed to open stderr log path")?;
+    let stdout_log_file = stderr_log_file
+        .try_clone()
+        .context("failed to open stderr log path")?;
 
     let mut args = vec![
         match verbose {
@@ -79,6 +83,11 @@ pub fn spawn_node(
         "--child-process".to_string(),
     ];
 
+    if let Some(addr) = health_address {
+        args.push("--health-address".to_string());
+        args.push(addr.to_string());
+    }
+
     if let Some(path) = project {
         args.push("--project".to_string());
         let p = path
@@ -99,14 +108,30 @@ pub fn spawn_node(
         args.push("--enable-credential-checks".to_string());
     }
 
+    // The key of encrypted vaults is sent through a pipe, rather than exported
+    // to the environment of the node
+    let vault_key = storage_key();
+    if vault_key.is_some() {
+        args.push("--vault-key-stdin".to_string());
+    }
+
     args.push(name.to_owned());
 
-    let child = Command::new(ockam_exe)
+    let mut child = Command::new(ockam_exe)
         .args(args)
-        .stdout(main_log_file)
+        .stdin(if vault_key.is_some() {
+            Stdio::piped()
+        } else {
+            Stdio::null()
+        })
+        .stdout(stdout_log_file)
         .stderr(stderr_log_file)
         .spawn()?;
 
+    if let (Some(key), Some(stdin)) = (vault_key, child.stdin.take()) {
+        write_storage_key(stdin, &key)?;
+    }
+
     // Update the pid in the config (should we remove this?)
     cfg.set_node_pid(name, child.id() as i32)?;
     cfg.persist_config_updates()?;
@@ -1,16 +1,20 @@
 use crate::node::NodeOpts;
 use crate::util::exitcode::CANTCREAT;
+use crate::util::output::Output;
 use crate::util::{node_rpc, Rpc};
 use crate::CommandGlobalOpts;
 use crate::Result;
 use anyhow::anyhow;
 use clap::Args;
 use ockam::Context;
-use ockam_api::config::cli;
 use ockam_api::nodes::models::vault::CreateVaultRequest;
+use ockam_api::nodes::vault::HsmConfig;
 use ockam_core::api::Request;
-use ockam_vault::storage::FileStorage;
-use slug::slugify;
+use ockam_vault::storage::{FileStorage, StorageKey};
+use serde::Serialize;
+use std::path::PathBuf;
+
+use super::util::{prompt_passphrase, vault_dir, vault_path};
 
 /// Create vaults
 #[derive(Clone, Debug, Args)]
@@ -25,6 +29,28 @@ pub struct CreateCommand {
 
     #[arg(long = "name", conflicts_with = "node")]
     vault_name: Option<String>,
+
+    /// Encrypt the vault with a passphrase, read from OCKAM_VAULT_PASSPHRASE or the terminal
+    #[arg(long, requires = "vault_name")]
+    encrypt: bool,
+
+    /// Encrypt the vault with a key derived from the contents of this file
+    #[arg(long, requires = "vault_name", conflicts_with = "encrypt")]
+    key_file: Option<PathBuf>,
+
+    /// Keep the secrets of the vault on a PKCS#11 token, such as an HSM, using this module.
+    /// The user PIN of the token is read from OCKAM_PKCS11_PIN
+    #[arg(
+        long,
+        value_name = "MODULE",
+        requires_all = ["vault_name", "hsm_token"],
+        conflicts_with_all = ["encrypt", "key_file"]
+    )]
+    hsm_module: Option<PathBuf>,
+
+    /// Label of the PKCS#11 token holding the secrets of the vault
+    #[arg(long, value_name = "LABEL", requires = "hsm_module")]
+    hsm_token: Option<String>,
 }
 
 impl CreateCommand {
@@ -41,27 +67,70 @@ async fn run_impl(ctx: Context, (options, cmd): (CommandGlobalOpts, CreateComman
             let request = Request::post("/node/vault").body(CreateVaultRequest::new(cmd.path));
             rpc.request(request).await?;
             rpc.is_ok()?;
-            println!("Vault created for the Node {}!", node_name);
+            options.print(&CreatedVault {
+                node: Some(node_name),
+                name: None,
+                path: None,
+            })?;
         }
         (None, Some(vault_name)) => {
-            let dirs = cli::OckamConfig::directories();
-            let dir = dirs
-                .config_dir()
-                .to_path_buf()
-                .join("vaults")
-                .join(slugify(&format!("vault-{}", vault_name)));
+            let dir = vault_dir(&vault_name);
             if dir.as_path().exists() {
                 return Err(crate::error::Error::new(
                     CANTCREAT,
                     anyhow!("Vault with name {} already exists!", vault_name),
                 ));
             }
-            tokio::fs::create_dir_all(dir.as_path()).await?;
-            let file = dir.join("vault.json");
-            let _ = FileStorage::create(file.clone()).await?;
-            println!("Vault created with name: {}!", vault_name);
+            let file = vault_path(&vault_name);
+            match cmd.hsm_module.zip(cmd.hsm_token) {
+                Some((module, token)) => {
+                    // Check that the token can be used before recording it
+                    let hsm = HsmConfig::new(module, token);
+                    hsm.open()
+                        .map_err(|e| anyhow!("Failed to open the PKCS#11 token: {e}"))?;
+                    tokio::fs::create_dir_all(dir.as_path()).await?;
+                    hsm.write(&file)?;
+                }
+                None => {
+                    let key = match (cmd.encrypt, cmd.key_file) {
+                        (_, Some(path)) => Some(StorageKey::KeyFile(path)),
+                        (true, None) => match StorageKey::from_env() {
+                            Some(key @ StorageKey::Passphrase(_)) => Some(key),
+                            _ => Some(prompt_passphrase(true)?),
+                        },
+                        (false, None) => None,
+                    };
+                    tokio::fs::create_dir_all(dir.as_path()).await?;
+                    let _ = FileStorage::create_with_key(file.clone(), key).await?;
+                }
+            }
+            options.print(&CreatedVault {
+                node: None,
+                name: Some(vault_name),
+                path: Some(file),
+            })?;
         }
         _ => unreachable!(),
     }
     Ok(())
 }
+
+/// Result of creating a vault, either for a node or with a name
+#[derive(Debug, Serialize)]
+struct CreatedVault {
+    node: Option<String>,
+    name: Option<String>,
+    path: Option<PathBuf>,
+}
+
+impl Output for CreatedVault {
+    fn output(&self) -> anyhow::Result<String> {
+        match (&self.node, &self.name) {
+            (Some(node), _) => Ok(format!("Vault created for the Node {}!", node)),
+            (_, name) => Ok(format!(
+                "Vault created with name: {}!",
+                name.as_deref().unwrap_or_default()
+            )),
+        }
+    }
+}
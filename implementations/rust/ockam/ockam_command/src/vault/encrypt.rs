@@ -0,0 +1,83 @@
+use crate::util::output::Output;
+use crate::util::{exitcode, node_rpc};
+use crate::CommandGlobalOpts;
+use crate::Result;
+use anyhow::anyhow;
+use clap::Args;
+use ockam::Context;
+use ockam_vault::storage::{FileStorage, StorageKey};
+use serde::Serialize;
+use std::path::PathBuf;
+
+use super::util::{prompt_passphrase, vault_path};
+
+/// Encrypt an existing plaintext vault
+///
+/// Nodes keep their own copy of the default vault, which stays in plaintext.
+#[derive(Clone, Debug, Args)]
+pub struct EncryptCommand {
+    /// Name of the vault to encrypt, instead of the default vault
+    #[arg(long = "name")]
+    vault_name: Option<String>,
+
+    /// Encrypt the vault with a key derived from the contents of this file,
+    /// instead of a passphrase read from OCKAM_VAULT_PASSPHRASE or the terminal
+    #[arg(long)]
+    key_file: Option<PathBuf>,
+}
+
+impl EncryptCommand {
+    pub fn run(self, options: CommandGlobalOpts) {
+        node_rpc(run_impl, (options, self))
+    }
+}
+
+async fn run_impl(
+    _ctx: Context,
+    (options, cmd): (CommandGlobalOpts, EncryptCommand),
+) -> Result<()> {
+    let path = match &cmd.vault_name {
+        Some(name) => vault_path(name),
+        None => options
+            .config
+            .get_default_vault_path()
+            .ok_or_else(|| anyhow!("The default vault was not found"))?,
+    };
+    if !path.exists() {
+        return Err(crate::Error::new(
+            exitcode::CONFIG,
+            anyhow!("Vault at {} was not found", path.display()),
+        ));
+    }
+    if FileStorage::is_encrypted(&path) {
+        return Err(crate::Error::new(
+            exitcode::CANTCREAT,
+            anyhow!("Vault at {} is already encrypted", path.display()),
+        ));
+    }
+    let key = match cmd.key_file {
+        Some(path) => StorageKey::KeyFile(path),
+        None => match StorageKey::from_env() {
+            Some(key @ StorageKey::Passphrase(_)) => key,
+            _ => prompt_passphrase(true)?,
+        },
+    };
+    FileStorage::encrypt(path.clone(), key).await?;
+    options.print(&EncryptedVault { path })?;
+    Ok(())
+}
+
+/// Result of encrypting a vault
+#[derive(Debug, Serialize)]
+struct EncryptedVault {
+    path: PathBuf,
+}
+
+impl Output for EncryptedVault {
+    fn output(&self) -> anyhow::Result<String> {
+        Ok(format!(
+            "Vault at {} is now encrypted!",
+            self.path.display()
+        ))
+    }
+}
@@ -1,6 +1,9 @@
 mod create;
+mod encrypt;
+pub(crate) mod util;
 
 pub(crate) use create::CreateCommand;
+pub(crate) use encrypt::EncryptCommand;
 
 use crate::help;
 use crate::CommandGlobalOpts;
@@ -16,12 +19,14 @@ pub struct VaultCommand {
 #[derive(Clone, Debug, Subcommand)]
 pub enum VaultSubcommand {
     Create(CreateCommand),
+    Encrypt(EncryptCommand),
 }
 
 impl VaultCommand {
     pub fn run(self, options: CommandGlobalOpts) {
         match self.subcommand {
             VaultSubcommand::Create(c) => c.run(options),
+            VaultSubcommand::Encrypt(c) => c.run(options),
         }
     }
 }
@@ -0,0 +1,145 @@
+use anyhow::{anyhow, Context as _, Result};
+use atty::Stream;
+use dialoguer::Password;
+use ockam_api::config::cli;
+use ockam_api::nodes::vault::{HsmConfig, NodeVault};
+use ockam_vault::storage::{FileStorage, StorageKey, VAULT_KEY_FILE_ENV, VAULT_PASSPHRASE_ENV};
+use ockam_vault::Vault;
+use once_cell::sync::Lazy;
+use slug::slugify;
+use std::io::{BufRead, Write};
+use std::path::PathBuf;
+use std::sync::{Arc, Mutex};
+
+/// The key unlocking the encrypted vaults of this process, once read
+static STORAGE_KEY: Lazy<Mutex<Option<StorageKey>>> = Lazy::new(Mutex::default);
+
+/// The directory of the vault with the given name.
+pub(crate) fn vault_dir(name: &str) -> PathBuf {
+    cli::OckamConfig::directories()
+        .config_dir()
+        .join("vaults")
+        .join(slugify(format!("vault-{}", name)))
+}
+
+/// The storage file of the vault with the given name.
+pub(crate) fn vault_path(name: &str) -> PathBuf {
+    vault_dir(name).join("vault.json")
+}
+
+/// Open the vault storage file at the given path.
+///
+/// Encrypted vaults are unlocked with the key of this process, found in the
+/// environment, given by the parent process or, failing that, read from the
+/// terminal. Plaintext vaults stay in plaintext, see `ockam vault encrypt`.
+pub(crate) async fn open_storage(path: PathBuf) -> Result<FileStorage> {
+    let key = match storage_key() {
+        Some(key) => Some(key),
+        None if FileStorage::is_encrypted(&path) => {
+            let key = prompt_passphrase(false)?;
+            set_storage_key(key.clone());
+            Some(key)
+        }
+        None => None,
+    };
+    Ok(FileStorage::create_with_key(path, key).await?)
+}
+
+/// Open the vault at the given path, on a PKCS#11 token or in its storage file.
+pub(crate) async fn open_vault(path: PathBuf) -> Result<NodeVault> {
+    match HsmConfig::read(&path) {
+        Some(hsm) => Ok(NodeVault::Pkcs11(hsm.open()?)),
+        None => {
+            let storage = open_storage(path).await?;
+            Ok(NodeVault::Software(Vault::new(Some(Arc::new(storage)))))
+        }
+    }
+}
+
+/// The key unlocking the encrypted vaults of this process, if known.
+pub(crate) fn storage_key() -> Option<StorageKey> {
+    let key = STORAGE_KEY.lock().unwrap().clone();
+    key.or_else(StorageKey::from_env)
+}
+
+pub(crate) fn set_storage_key(key: StorageKey) {
+    *STORAGE_KEY.lock().unwrap() = Some(key)
+}
+
+/// Send a storage key to a child process, through its standard input.
+pub(crate) fn write_storage_key(mut w: impl Write, key: &StorageKey) -> Result<()> {
+    let line = match key {
+        StorageKey::Passphrase(p) => format!("passphrase:{p}"),
+        StorageKey::KeyFile(path) => format!(
+            "key-file:{}",
+            path.to_str()
+                .ok_or_else(|| anyhow!("unsupported path {path:?}"))?
+        ),
+    };
+    writeln!(w, "{line}").context("Failed to send the vault key")
+}
+
+/// Read the storage key sent by the parent process with [`write_storage_key`].
+pub(crate) fn read_storage_key(r: impl BufRead) -> Result<StorageKey> {
+    let line = r
+        .lines()
+        .next()
+        .transpose()
+        .context("Failed to read the vault key")?
+        .unwrap_or_default();
+    match line.split_once(':') {
+        Some(("passphrase", p)) => Ok(StorageKey::Passphrase(p.to_string())),
+        Some(("key-file", path)) => Ok(StorageKey::KeyFile(path.into())),
+        _ => Err(anyhow!("Invalid vault key")),
+    }
+}
+
+/// Read a vault passphrase from the terminal.
+pub(crate) fn prompt_passphrase(confirm: bool) -> Result<StorageKey> {
+    if !atty::is(Stream::Stdin) {
+        return Err(anyhow!(
+            "The vault is encrypted. Set {} or {} to unlock it",
+            VAULT_PASSPHRASE_ENV,
+            VAULT_KEY_FILE_ENV
+        ));
+    }
+    read_passphrase("Vault passphrase", confirm)
+}
+
+/// Read a passphrase from the terminal, using the given prompt.
+pub(crate) fn read_passphrase(prompt: &str, confirm: bool) -> Result<StorageKey> {
+    if !atty::is(Stream::Stdin) {
+        return Err(anyhow!(
+            "A terminal is required to read the {}",
+            prompt.to_lowercase()
+        ));
+    }
+    let mut input = Password::new();
+    input.with_prompt(prompt);
+    if confirm {
+        input.with_confirmation("Confirm passphrase", "Passphrases don't match");
+    }
+    Ok(StorageKey::Passphrase(input.interact()?))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn storage_key_roundtrip() {
+        for key in [
+            StorageKey::Passphrase("correct: horse".into()),
+            StorageKey::KeyFile("/tmp/vault.key".into()),
+        ] {
+            let mut buf = vec![];
+            write_storage_key(&mut buf, &key).unwrap();
+            let read = read_storage_key(buf.as_slice()).unwrap();
+            assert_eq!(format!("{read:?}"), format!("{key:?}"));
+            if let (StorageKey::Passphrase(a), StorageKey::Passphrase(b)) = (&read, &key) {
+                assert_eq!(a, b)
+            }
+        }
+        assert!(read_storage_key("".as_bytes()).is_err());
+    }
+}
@@ -0,0 +1,84 @@
+use assert_cmd::prelude::*;
+use serde_json::Value;
+use std::net::TcpListener;
+use std::path::Path;
+use std::process::Command;
+
+/// An `ockam` command using its own configuration directory
+fn ockam(dir: &Path) -> Command {
+    let mut cmd = Command::cargo_bin("ockam").unwrap();
+    cmd.env("OCKAM_PROJECT_PATH", dir);
+    cmd
+}
+
+fn free_port() -> u16 {
+    TcpListener::bind("127.0.0.1:0")
+        .unwrap()
+        .local_addr()
+        .unwrap()
+        .port()
+}
+
+/// Run `ockam node apply` and return its report
+fn apply(dir: &Path, spec: &Path, dry_run: bool) -> Value {
+    let mut cmd = ockam(dir);
+    cmd.args(["node", "apply", "--output", "json", "-f"])
+        .arg(spec);
+    if dry_run {
+        cmd.arg("--dry-run");
+    }
+    let out = cmd.output().unwrap();
+    out.clone().assert().success();
+    serde_json::from_slice(&out.stdout).unwrap()
+}
+
+/// Applying a spec a second time changes nothing, and a stopped node is started
+#[test]
+fn node_apply_converges() {
+    let dir = tempfile::tempdir().unwrap();
+    let spec = dir.path().join("node.yaml");
+    let (listener, outlet, inlet) = (free_port(), free_port(), free_port());
+    std::fs::write(
+        &spec,
+        format!(
+            r#"
+name: n1
+tcp_listeners:
+  - localhost:{listener}
+outlets:
+  - from: db
+    to: 127.0.0.1:{outlet}
+inlets:
+  - from: 127.0.0.1:{inlet}
+    to: /node/n1/service/db
+policies:
+  - resource: tcp-outlet
+    action: handle_message
+    expression: (= subject.component "db")
+"#
+        ),
+    )
+    .unwrap();
+
+    let planned = apply(dir.path(), &spec, true);
+    let first = apply(dir.path(), &spec, false);
+    let second = apply(dir.path(), &spec, false);
+    ockam(dir.path())
+        .args(["node", "stop", "n1"])
+        .assert()
+        .success();
+    let restarted = apply(dir.path(), &spec, false);
+    let _ = ockam(dir.path())
+        .args(["node", "delete", "--all", "--force"])
+        .output();
+
+    assert_eq!(planned["changes"].as_array().unwrap().len(), 4);
+    assert_eq!(planned["changes"], first["changes"]);
+    assert_eq!(first["created"], true);
+    assert_eq!(second["created"], false);
+    assert_eq!(second["changes"], Value::Array(vec![]));
+    assert_eq!(second["conflicts"], Value::Array(vec![]));
+    assert_eq!(second["started"], false);
+    assert_eq!(restarted["started"], true);
+    assert_eq!(restarted["conflicts"], Value::Array(vec![]));
+}
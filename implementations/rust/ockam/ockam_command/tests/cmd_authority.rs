@@ -0,0 +1,51 @@
+use assert_cmd::prelude::*;
+use std::process::Command;
+
+#[test]
+fn valid_arguments() -> Result<(), Box<dyn std::error::Error>> {
+    let prefix_args = ["--test-argument-parser", "authority"];
+
+    let mut cmd = Command::cargo_bin("ockam")?;
+    cmd.args(&prefix_args)
+        .arg("create")
+        .arg("--project")
+        .arg("p1");
+    cmd.assert().success();
+
+    let mut cmd = Command::cargo_bin("ockam")?;
+    cmd.args(&prefix_args)
+        .arg("create-token")
+        .arg("--attribute")
+        .arg("role=member")
+        .arg("--expires-in")
+        .arg("60");
+    cmd.assert().success();
+
+    let mut cmd = Command::cargo_bin("ockam")?;
+    cmd.args(&prefix_args)
+        .arg("enroll")
+        .arg("--at")
+        .arg("/dnsaddr/localhost/tcp/4000/service/api")
+        .arg("--token")
+        .arg("abcd");
+    cmd.assert().success();
+
+    let mut cmd = Command::cargo_bin("ockam")?;
+    cmd.args(&prefix_args).arg("list-members");
+    cmd.assert().success();
+
+    let id = "P6c20e814b56579306f55c64e8747e6c1b4a53d9a3f4ca83c252cc2fbfc72fa94";
+    let mut cmd = Command::cargo_bin("ockam")?;
+    cmd.args(&prefix_args)
+        .arg("update-member")
+        .arg(id)
+        .arg("--attribute")
+        .arg("role=admin");
+    cmd.assert().success();
+
+    let mut cmd = Command::cargo_bin("ockam")?;
+    cmd.args(&prefix_args).arg("remove-member").arg(id);
+    cmd.assert().success();
+
+    Ok(())
+}
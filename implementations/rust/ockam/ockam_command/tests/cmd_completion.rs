@@ -0,0 +1,63 @@
+use assert_cmd::prelude::*;
+use std::path::Path;
+use std::process::Command;
+
+/// An `ockam` command using its own configuration directory
+fn ockam(dir: &Path) -> Command {
+    let mut cmd = Command::cargo_bin("ockam").unwrap();
+    cmd.env("OCKAM_PROJECT_PATH", dir);
+    cmd
+}
+
+/// The scripts ask for the aliases that exist when completing
+#[test]
+fn completed_aliases_follow_the_configuration() {
+    let dir = tempfile::tempdir().unwrap();
+    let names = |kind: &str| {
+        let out = ockam(dir.path())
+            .args(["completion", "--names", kind])
+            .output()
+            .unwrap();
+        out.clone().assert().success();
+        String::from_utf8(out.stdout).unwrap()
+    };
+
+    ockam(dir.path())
+        .args(["alias", "add", "db", "/service/db"])
+        .assert()
+        .success();
+    assert_eq!(names("aliases"), "db\n");
+    assert_eq!(names("addresses"), "/alias/db\n");
+
+    // Names which would be pattern placeholders in the alias rules are refused
+    ockam(dir.path())
+        .args(["alias", "add", "*", "/service/db"])
+        .assert()
+        .failure();
+
+    ockam(dir.path())
+        .args(["alias", "remove", "db"])
+        .assert()
+        .success();
+    assert_eq!(names("aliases"), "");
+
+    for shell in ["bash", "zsh", "fish"] {
+        let out = ockam(dir.path())
+            .args(["completion", "--shell", shell])
+            .output()
+            .unwrap();
+        let script = String::from_utf8(out.stdout).unwrap();
+        assert!(script.contains("completion --names"), "{shell}");
+        if shell == "bash" {
+            let path = dir.path().join("ockam.bash");
+            std::fs::write(&path, script).unwrap();
+            // A plain bash, without extglob, installs the completion
+            Command::new("bash")
+                .arg("--norc")
+                .arg("-c")
+                .arg(format!("source {} && complete -p ockam", path.display()))
+                .assert()
+                .success();
+        }
+    }
+}
@@ -0,0 +1,109 @@
+use assert_cmd::prelude::*;
+use serde_json::Value;
+use std::io::{Read, Write};
+use std::net::{TcpListener, TcpStream};
+use std::path::Path;
+use std::process::Command;
+
+/// An `ockam` command using its own configuration directory
+fn ockam(dir: &Path) -> Command {
+    let mut cmd = Command::cargo_bin("ockam").unwrap();
+    cmd.env("OCKAM_PROJECT_PATH", dir);
+    cmd
+}
+
+fn free_port() -> u16 {
+    TcpListener::bind("127.0.0.1:0")
+        .unwrap()
+        .local_addr()
+        .unwrap()
+        .port()
+}
+
+/// Run `ockam compose up` and return the changes made to each node
+fn up(dir: &Path) -> Vec<Value> {
+    let out = ockam(dir)
+        .current_dir(dir)
+        .args(["compose", "up", "--output", "json"])
+        .output()
+        .unwrap();
+    out.clone().assert().success();
+    let topology: Value = serde_json::from_slice(&out.stdout).unwrap();
+    topology["nodes"]
+        .as_array()
+        .unwrap()
+        .iter()
+        .map(|n| n["changes"].clone())
+        .collect()
+}
+
+/// An inlet goes through an explicit channel, `up` converges and `down` deletes the nodes
+#[test]
+fn compose_up_and_down() {
+    let dir = tempfile::tempdir().unwrap();
+
+    // The service behind the outlet echoes one message per connection
+    let service = TcpListener::bind("127.0.0.1:0").unwrap();
+    let outlet = service.local_addr().unwrap().port();
+    std::thread::spawn(move || {
+        for mut stream in service.incoming().flatten() {
+            let mut buf = [0; 4];
+            if stream.read_exact(&mut buf).is_ok() {
+                let _ = stream.write_all(&buf);
+            }
+        }
+    });
+
+    let inlet = free_port();
+    std::fs::write(
+        dir.path().join("ockam-compose.yaml"),
+        format!(
+            r#"
+nodes:
+  - name: server
+    outlets:
+      - from: db
+        to: 127.0.0.1:{outlet}
+  - name: client
+channels:
+  - from: client
+    to: /node/server/service/api
+    inlets:
+      - from: 127.0.0.1:{inlet}
+        to: /service/db
+"#
+        ),
+    )
+    .unwrap();
+
+    let first = up(dir.path());
+    let echoed = TcpStream::connect(("127.0.0.1", inlet)).and_then(|mut s| {
+        s.write_all(b"ping")?;
+        let mut buf = [0; 4];
+        s.read_exact(&mut buf).map(|_| buf)
+    });
+    let second = up(dir.path());
+    let down = ockam(dir.path())
+        .current_dir(dir.path())
+        .args(["compose", "down"])
+        .output()
+        .unwrap();
+
+    assert_eq!(first[0], serde_json::json!(["tcp-outlet db"]));
+    assert_eq!(
+        first[1],
+        serde_json::json!([
+            "secure-channel /node/server/service/api",
+            format!("tcp-inlet 127.0.0.1:{inlet}")
+        ])
+    );
+    assert_eq!(&echoed.unwrap(), b"ping");
+    assert_eq!(second, vec![serde_json::json!([]), serde_json::json!([])]);
+    down.assert().success();
+    for node in ["server", "client"] {
+        ockam(dir.path())
+            .args(["node", "show", node])
+            .assert()
+            .failure();
+    }
+}
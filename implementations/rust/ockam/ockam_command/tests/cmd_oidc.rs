@@ -0,0 +1,29 @@
+use assert_cmd::prelude::*;
+use std::process::Command;
+
+#[test]
+fn oidc_authenticator_arguments() -> Result<(), Box<dyn std::error::Error>> {
+    let prefix_args = ["--test-argument-parser", "service", "start"];
+
+    let mut cmd = Command::cargo_bin("ockam")?;
+    cmd.args(prefix_args)
+        .arg("oidc-authenticator")
+        .args(["--issuer", "https://idp.example.com"])
+        .args(["--audience", "ockam"])
+        .args(["--jwks", "jwks.json"])
+        .args(["--project", "p1"])
+        .args(["--claim", "email=email"])
+        .args(["--required-claim", "groups=role"]);
+    cmd.assert().success();
+
+    // The JSON Web Key Set is required to verify tokens offline
+    let mut cmd = Command::cargo_bin("ockam")?;
+    cmd.args(prefix_args)
+        .arg("oidc-authenticator")
+        .args(["--issuer", "https://idp.example.com"])
+        .args(["--audience", "ockam"])
+        .args(["--project", "p1"]);
+    cmd.assert().failure();
+
+    Ok(())
+}
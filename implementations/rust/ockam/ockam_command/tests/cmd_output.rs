@@ -0,0 +1,82 @@
+use assert_cmd::prelude::*;
+use serde_json::Value;
+use std::path::Path;
+use std::process::Command;
+
+/// An `ockam` command using its own configuration directory
+fn ockam(dir: &Path) -> Result<Command, Box<dyn std::error::Error>> {
+    let mut cmd = Command::cargo_bin("ockam")?;
+    cmd.env("OCKAM_PROJECT_PATH", dir);
+    Ok(cmd)
+}
+
+/// Run a command with `--output json` and parse what it printed
+fn json(cmd: &mut Command) -> Result<Value, Box<dyn std::error::Error>> {
+    let out = cmd.arg("--output").arg("json").assert().success();
+    Ok(serde_json::from_slice(&out.get_output().stdout)?)
+}
+
+#[test]
+fn bad_arguments() -> Result<(), Box<dyn std::error::Error>> {
+    let dir = tempfile::tempdir()?;
+
+    // unknown argument
+    let mut cmd = ockam(dir.path())?;
+    cmd.arg("node").arg("show").arg("--bogus");
+    cmd.assert().code(64);
+
+    // invalid value
+    let mut cmd = ockam(dir.path())?;
+    cmd.arg("node").arg("list").arg("--output").arg("yaml");
+    cmd.assert().code(64);
+
+    Ok(())
+}
+
+#[test]
+fn missing_node() -> Result<(), Box<dyn std::error::Error>> {
+    let dir = tempfile::tempdir()?;
+    let mut cmd = ockam(dir.path())?;
+    cmd.arg("node").arg("show").arg("missing");
+    cmd.assert().code(78);
+    Ok(())
+}
+
+#[test]
+fn node_output_and_stopped_node() -> Result<(), Box<dyn std::error::Error>> {
+    let dir = tempfile::tempdir()?;
+    let res = node_output_and_stopped_node_impl(dir.path());
+    let _ = ockam(dir.path())?
+        .args(["node", "delete", "--all", "--force"])
+        .output();
+    res
+}
+
+fn node_output_and_stopped_node_impl(dir: &Path) -> Result<(), Box<dyn std::error::Error>> {
+    let node = json(ockam(dir)?.args(["node", "create", "n1"]))?;
+    assert_eq!(node["name"], "n1");
+    assert_eq!(node["status"], "up");
+
+    let nodes = json(ockam(dir)?.args(["node", "list"]))?;
+    assert_eq!(nodes.as_array().map(Vec::len), Some(1));
+    assert_eq!(nodes[0]["name"], "n1");
+
+    let channels = json(ockam(dir)?.args(["secure-channel", "list", "--at", "n1"]))?;
+    assert_eq!(channels, Value::Array(vec![]));
+
+    ockam(dir)?.args(["node", "stop", "n1"]).assert().success();
+
+    // a stopped node is still shown, as being down
+    let node = json(ockam(dir)?.args(["node", "show", "n1"]))?;
+    assert_eq!(node["status"], "down");
+
+    // but it can't answer requests
+    ockam(dir)?
+        .args(["tcp-listener", "list", "--node", "n1"])
+        .arg("--output")
+        .arg("json")
+        .assert()
+        .code(69);
+
+    Ok(())
+}
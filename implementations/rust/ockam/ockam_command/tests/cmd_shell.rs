@@ -0,0 +1,66 @@
+use assert_cmd::prelude::*;
+use serde_json::Value;
+use std::path::Path;
+use std::process::Command;
+
+/// An `ockam` command using its own configuration directory
+fn ockam(dir: &Path) -> Result<Command, Box<dyn std::error::Error>> {
+    let mut cmd = Command::cargo_bin("ockam")?;
+    cmd.env("OCKAM_PROJECT_PATH", dir);
+    Ok(cmd)
+}
+
+/// The addresses of the TCP listeners of a node, as listed by `tcp-listener list`
+fn listeners(list: &Value) -> Vec<String> {
+    list["list"]
+        .as_array()
+        .into_iter()
+        .flatten()
+        .map(|l| l["payload"].as_str().unwrap_or_default().to_string())
+        .collect()
+}
+
+#[test]
+fn use_changes_the_node_of_subcommands() -> Result<(), Box<dyn std::error::Error>> {
+    let dir = tempfile::tempdir()?;
+    let res = use_changes_the_node_of_subcommands_impl(dir.path());
+    let _ = ockam(dir.path())?
+        .args(["node", "delete", "--all", "--force"])
+        .output();
+    res
+}
+
+fn use_changes_the_node_of_subcommands_impl(dir: &Path) -> Result<(), Box<dyn std::error::Error>> {
+    ockam(dir)?
+        .args(["node", "create", "n1"])
+        .assert()
+        .success();
+    ockam(dir)?
+        .args(["node", "create", "n2"])
+        .assert()
+        .success();
+
+    let out = assert_cmd::Command::from_std(ockam(dir)?)
+        .args(["shell", "--node", "n1"])
+        .write_stdin("tcp-listener list --output json\nuse n2\ntcp-listener list --output json\n")
+        .assert()
+        .success();
+    let lists = serde_json::Deserializer::from_slice(&out.get_output().stdout)
+        .into_iter::<Value>()
+        .collect::<Result<Vec<_>, _>>()?;
+    assert_eq!(lists.len(), 2);
+
+    let expected = |node: &str| -> Result<Vec<String>, Box<dyn std::error::Error>> {
+        let out = ockam(dir)?
+            .args(["tcp-listener", "list", "--node", node, "--output", "json"])
+            .assert()
+            .success();
+        Ok(listeners(&serde_json::from_slice(
+            &out.get_output().stdout,
+        )?))
+    };
+    assert_eq!(listeners(&lists[0]), expected("n1")?);
+    assert_eq!(listeners(&lists[1]), expected("n2")?);
+    assert_ne!(listeners(&lists[0]), listeners(&lists[1]));
+    Ok(())
+}
@@ -0,0 +1,72 @@
+use assert_cmd::prelude::*;
+use ockam::compat::tokio::task::spawn_blocking;
+use ockam::{Context, NodeBuilder};
+use ockam_api::echoer::Echoer;
+use ockam_transport_udp::UdpTransport;
+use ockam_transport_websocket::WebSocketTransport;
+use std::net::UdpSocket;
+use std::path::{Path, PathBuf};
+use std::process::{Command, Output};
+
+/// An `ockam` command using its own configuration directory
+fn ockam(dir: &Path) -> Command {
+    let mut cmd = Command::cargo_bin("ockam").unwrap();
+    cmd.env("OCKAM_PROJECT_PATH", dir);
+    cmd
+}
+
+/// Send `message` to `to` with `ockam message send`
+async fn send(dir: PathBuf, to: String, message: &'static str) -> Output {
+    spawn_blocking(move || {
+        ockam(&dir)
+            .args(["message", "send", message, "--to", &to, "--timeout", "10"])
+            .output()
+            .unwrap()
+    })
+    .await
+    .unwrap()
+}
+
+/// The CLI routes `/udp` and `/ws` addresses through the matching transports
+#[test]
+fn message_send_over_udp_and_websocket() {
+    let dir = tempfile::tempdir().unwrap();
+    let path = dir.path().to_path_buf();
+    let (mut ctx, mut executor) = NodeBuilder::without_access_control().build();
+    let outputs = executor
+        .execute(async move {
+            let res = message_send_impl(&ctx, path).await;
+            let _ = ctx.stop().await;
+            res
+        })
+        .unwrap()
+        .unwrap();
+    let _ = ockam(dir.path())
+        .args(["node", "delete", "--all", "--force"])
+        .output();
+
+    for (out, message) in outputs.into_iter().zip(["over udp", "over ws"]) {
+        out.assert().success().stdout(format!("{message}\n"));
+    }
+}
+
+async fn message_send_impl(ctx: &Context, dir: PathBuf) -> ockam::Result<Vec<Output>> {
+    ctx.start_worker("echoer", Echoer).await?;
+
+    let port = UdpSocket::bind("127.0.0.1:0")
+        .unwrap()
+        .local_addr()
+        .unwrap()
+        .port();
+    let udp = UdpTransport::create(ctx).await?;
+    udp.listen(format!("127.0.0.1:{port}")).await?;
+    let to = format!("/ip4/127.0.0.1/udp/{port}/service/echoer");
+    let over_udp = send(dir.clone(), to, "over udp").await;
+
+    let ws = WebSocketTransport::create(ctx).await?;
+    let addr = ws.listen("127.0.0.1:0").await?;
+    let to = format!("/ip4/127.0.0.1/tcp/{}/ws/%2F/service/echoer", addr.port());
+    let over_ws = send(dir, to, "over ws").await;
+
+    Ok(vec![over_udp, over_ws])
+}
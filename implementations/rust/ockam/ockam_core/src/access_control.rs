@@ -30,6 +30,13 @@ use core::fmt::Debug;
 pub trait AccessControl: Debug + Send + Sync + 'static {
     /// Return true if the message is allowed to pass, and false if not.
     async fn is_authorized(&self, local_msg: &LocalMessage) -> Result<bool>;
+
+    /// Return the name of this access control, shown when inspecting workers.
+    ///
+    /// Defaults to the path of the implementing type.
+    fn name(&self) -> &'static str {
+        core::any::type_name::<Self>()
+    }
 }
 
 mod all;
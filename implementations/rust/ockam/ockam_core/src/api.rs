@@ -121,7 +121,7 @@ pub fn internal_error<'a>(r: &'a Request, msg: &'a str) -> ResponseBuilder<Error
 pub struct Id(#[n(0)] u32);
 
 /// Request methods.
-#[derive(Debug, Copy, Clone, Encode, Decode)]
+#[derive(Debug, Copy, Clone, Encode, Decode, PartialEq, Eq)]
 #[rustfmt::skip]
 #[cbor(index_only)]
 pub enum Method {
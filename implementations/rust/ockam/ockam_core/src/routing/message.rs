@@ -5,3 +5,6 @@ pub use transport_message::*;
 
 mod local_message;
 pub use local_message::*;
+
+mod trace_context;
+pub use trace_context::*;
@@ -0,0 +1,150 @@
+use crate::compat::{
+    rand::random,
+    string::{String, ToString},
+    vec::Vec,
+};
+use core::fmt::{self, Display, Formatter};
+use serde::{Deserialize, Serialize};
+
+/// Trace context propagated with a [`TransportMessage`] across nodes.
+///
+/// The fields match the [W3C Trace Context] `traceparent` header, so a
+/// message path can be correlated with spans of other systems and
+/// exported to any OpenTelemetry compatible collector.
+///
+/// [`TransportMessage`]: crate::TransportMessage
+/// [W3C Trace Context]: https://www.w3.org/TR/trace-context/
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, Hash, Ord, PartialOrd, Eq, PartialEq)]
+pub struct TraceContext {
+    trace_id: [u8; 16],
+    span_id: [u8; 8],
+    flags: u8,
+}
+
+impl TraceContext {
+    /// The `sampled` trace flag
+    pub const SAMPLED: u8 = 0x01;
+
+    /// Start a new sampled trace
+    pub fn new_root() -> Self {
+        Self {
+            trace_id: random_non_zero(),
+            span_id: random_non_zero(),
+            flags: Self::SAMPLED,
+        }
+    }
+
+    /// Create a context from its identifiers, which must not be all zeros
+    pub fn new(trace_id: [u8; 16], span_id: [u8; 8], flags: u8) -> Option<Self> {
+        if trace_id == [0; 16] || span_id == [0; 8] {
+            return None;
+        }
+        Some(Self {
+            trace_id,
+            span_id,
+            flags,
+        })
+    }
+
+    /// Create the context of a span which is a child of this one
+    pub fn child(&self) -> Self {
+        Self {
+            trace_id: self.trace_id,
+            span_id: random_non_zero(),
+            flags: self.flags,
+        }
+    }
+
+    /// Identifier of the whole trace
+    pub fn trace_id(&self) -> [u8; 16] {
+        self.trace_id
+    }
+
+    /// Identifier of the span which sent the message
+    pub fn span_id(&self) -> [u8; 8] {
+        self.span_id
+    }
+
+    /// Trace flags, see [`TraceContext::SAMPLED`]
+    pub fn flags(&self) -> u8 {
+        self.flags
+    }
+
+    /// Hex encoded trace identifier
+    pub fn trace_id_hex(&self) -> String {
+        hex::encode(self.trace_id)
+    }
+
+    /// Hex encoded span identifier
+    pub fn span_id_hex(&self) -> String {
+        hex::encode(self.span_id)
+    }
+
+    /// Parse a version `00` `traceparent` header value
+    pub fn from_traceparent(value: &str) -> Option<Self> {
+        let parts: Vec<&str> = value.trim().split('-').collect();
+        match parts.as_slice() {
+            ["00", trace_id, span_id, flags] => {
+                let mut trace_id_bytes = [0; 16];
+                let mut span_id_bytes = [0; 8];
+                let mut flags_byte = [0u8; 1];
+                hex::decode_to_slice(trace_id, &mut trace_id_bytes).ok()?;
+                hex::decode_to_slice(span_id, &mut span_id_bytes).ok()?;
+                hex::decode_to_slice(flags, &mut flags_byte).ok()?;
+                Self::new(trace_id_bytes, span_id_bytes, flags_byte[0])
+            }
+            _ => None,
+        }
+    }
+
+    /// Format this context as a `traceparent` header value
+    pub fn to_traceparent(&self) -> String {
+        self.to_string()
+    }
+}
+
+impl Display for TraceContext {
+    fn fmt(&self, f: &mut Formatter) -> fmt::Result {
+        write!(
+            f,
+            "00-{}-{}-{:02x}",
+            self.trace_id_hex(),
+            self.span_id_hex(),
+            self.flags
+        )
+    }
+}
+
+fn random_non_zero<const N: usize>() -> [u8; N] {
+    loop {
+        let mut bytes = [0u8; N];
+        bytes.iter_mut().for_each(|b| *b = random());
+        if bytes != [0u8; N] {
+            return bytes;
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn traceparent_roundtrip() {
+        let header = "00-4bf92f3577b34da6a3ce929d0e0e4736-00f067aa0ba902b7-01";
+        let ctx = TraceContext::from_traceparent(header).unwrap();
+        assert_eq!(ctx.trace_id_hex(), "4bf92f3577b34da6a3ce929d0e0e4736");
+        assert_eq!(ctx.flags(), TraceContext::SAMPLED);
+        assert_eq!(ctx.to_traceparent(), header);
+
+        let child = ctx.child();
+        assert_eq!(child.trace_id(), ctx.trace_id());
+        assert_ne!(child.span_id(), ctx.span_id());
+
+        assert!(TraceContext::from_traceparent("01-00-00-00").is_none());
+        assert!(TraceContext::from_traceparent(
+            "00-00000000000000000000000000000000-00f067aa0ba902b7-01"
+        )
+        .is_none());
+    }
+}
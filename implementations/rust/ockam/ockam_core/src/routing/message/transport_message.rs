@@ -1,6 +1,10 @@
-use crate::{compat::vec::Vec, Message, Route};
+use crate::{compat::vec::Vec, Message, Route, TraceContext};
 use core::fmt::{self, Display, Formatter};
-use serde::{Deserialize, Serialize};
+use serde::{
+    de::{self, SeqAccess, Visitor},
+    ser::SerializeStruct,
+    Deserialize, Deserializer, Serialize, Serializer,
+};
 
 /// A generic transport message type.
 ///
@@ -15,7 +19,10 @@ use serde::{Deserialize, Serialize};
 ///
 /// See `ockam_transport_tcp::workers::sender::TcpSendWorker` for a usage example.
 ///
-#[derive(Serialize, Deserialize, Debug, Clone, Hash, Ord, PartialOrd, Eq, PartialEq, Message)]
+/// A message carrying a [`TraceContext`] is encoded with protocol
+/// version 2, which appends the context after the payload.  Nodes
+/// which only know version 1 ignore it.
+#[derive(Debug, Clone, Hash, Ord, PartialOrd, Eq, PartialEq, Message)]
 pub struct TransportMessage {
     /// The transport protocol version.
     pub version: u8,
@@ -28,8 +35,13 @@ pub struct TransportMessage {
     pub return_route: Route,
     /// The message payload.
     pub payload: Vec<u8>,
+    /// Optional trace context of the span which sent this message.
+    pub tracing_context: Option<TraceContext>,
 }
 
+/// First protocol version able to carry a [`TraceContext`]
+const TRACING_VERSION: u8 = 2;
+
 impl TransportMessage {
     /// Create a new v1 transport message with empty return route.
     pub fn v1(
@@ -42,7 +54,89 @@ impl TransportMessage {
             onward_route: onward_route.into(),
             return_route: return_route.into(),
             payload,
+            tracing_context: None,
+        }
+    }
+
+    /// Attach a trace context to this message
+    pub fn with_tracing_context(mut self, tracing_context: Option<TraceContext>) -> Self {
+        self.tracing_context = tracing_context;
+        self
+    }
+}
+
+impl Serialize for TransportMessage {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        match &self.tracing_context {
+            None => {
+                let mut s = serializer.serialize_struct("TransportMessage", 4)?;
+                s.serialize_field("version", &self.version)?;
+                s.serialize_field("onward_route", &self.onward_route)?;
+                s.serialize_field("return_route", &self.return_route)?;
+                s.serialize_field("payload", &self.payload)?;
+                s.end()
+            }
+            Some(tracing_context) => {
+                let mut s = serializer.serialize_struct("TransportMessage", 5)?;
+                s.serialize_field("version", &self.version.max(TRACING_VERSION))?;
+                s.serialize_field("onward_route", &self.onward_route)?;
+                s.serialize_field("return_route", &self.return_route)?;
+                s.serialize_field("payload", &self.payload)?;
+                s.serialize_field("tracing_context", tracing_context)?;
+                s.end()
+            }
+        }
+    }
+}
+
+impl<'de> Deserialize<'de> for TransportMessage {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        struct TransportMessageVisitor;
+
+        impl<'de> Visitor<'de> for TransportMessageVisitor {
+            type Value = TransportMessage;
+
+            fn expecting(&self, f: &mut Formatter) -> fmt::Result {
+                f.write_str("struct TransportMessage")
+            }
+
+            fn visit_seq<A: SeqAccess<'de>>(self, mut seq: A) -> Result<Self::Value, A::Error> {
+                let version: u8 = seq
+                    .next_element()?
+                    .ok_or_else(|| de::Error::invalid_length(0, &self))?;
+                let onward_route = seq
+                    .next_element()?
+                    .ok_or_else(|| de::Error::invalid_length(1, &self))?;
+                let return_route = seq
+                    .next_element()?
+                    .ok_or_else(|| de::Error::invalid_length(2, &self))?;
+                let payload = seq
+                    .next_element()?
+                    .ok_or_else(|| de::Error::invalid_length(3, &self))?;
+                // Version 1 messages end after the payload
+                let tracing_context = if version >= TRACING_VERSION {
+                    seq.next_element()?
+                } else {
+                    None
+                };
+                Ok(TransportMessage {
+                    version,
+                    onward_route,
+                    return_route,
+                    payload,
+                    tracing_context,
+                })
+            }
         }
+
+        const FIELDS: &[&str] = &[
+            "version",
+            "onward_route",
+            "return_route",
+            "payload",
+            "tracing_context",
+        ];
+        deserializer.deserialize_struct("TransportMessage", FIELDS, TransportMessageVisitor)
     }
 }
 
@@ -55,3 +149,23 @@ impl Display for TransportMessage {
         )
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{route, Decodable, Encodable};
+
+    #[test]
+    fn encode_with_and_without_tracing_context() {
+        let msg = TransportMessage::v1(route!["a"], route!["b"], vec![1, 2, 3]);
+        let decoded = TransportMessage::decode(&msg.clone().encode().unwrap()).unwrap();
+        assert_eq!(decoded, msg);
+
+        let ctx = TraceContext::new_root();
+        let traced = msg.with_tracing_context(Some(ctx));
+        let decoded = TransportMessage::decode(&traced.encode().unwrap()).unwrap();
+        assert_eq!(decoded.version, TRACING_VERSION);
+        assert_eq!(decoded.tracing_context, Some(ctx));
+        assert_eq!(decoded.payload, vec![1, 2, 3]);
+    }
+}
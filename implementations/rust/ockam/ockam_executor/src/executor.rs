@@ -10,6 +10,10 @@ use ockam_core::compat::boxed::Box;
 use ockam_core::compat::collections::BTreeMap;
 use ockam_core::compat::sync::Arc;
 use ockam_core::compat::task::Wake;
+use ockam_core::compat::vec::Vec;
+
+use crate::time::Instant;
+use crate::timer::TimerSource;
 
 /// Returns current executor.
 /// WARNING: TODO this is not thread-safe
@@ -37,6 +41,13 @@ pub struct Executor<'a> {
     // TODO tasks: Arc<Mutex<BTreeMap<TaskId, Box<Task>>>>,
     // TODO waker_cache: Arc<Mutex<BTreeMap<TaskId, Waker>>>,
     task_queue: Arc<SegQueue<TaskId>>,
+    // tasks currently being polled, and wake-ups received for them
+    // while they were, when a task blocks on a nested future
+    running: UnsafeCell<Vec<TaskId>>,
+    deferred: UnsafeCell<Vec<TaskId>>,
+    main_woken: Arc<AtomicBool>,
+    timers: UnsafeCell<BTreeMap<(Instant, TimerId), Waker>>,
+    timer_source: UnsafeCell<Option<&'static dyn TimerSource>>,
     marker: core::marker::PhantomData<&'a ()>,
 }
 
@@ -48,20 +59,77 @@ impl<'a> Executor<'a> {
             // TODO tasks: Arc::new(Mutex::new(BTreeMap::new())),
             // TODO waker_cache: Arc::new(Mutex::new(BTreeMap::new())),
             task_queue: Arc::new(SegQueue::new()),
+            running: UnsafeCell::new(Vec::new()),
+            deferred: UnsafeCell::new(Vec::new()),
+            main_woken: Arc::new(AtomicBool::new(false)),
+            timers: UnsafeCell::new(BTreeMap::new()),
+            timer_source: UnsafeCell::new(None),
             marker: core::marker::PhantomData,
         }
     }
 
+    /// Install the source of time used by timers
+    ///
+    /// With the `std` feature the system clock is used by default.
+    pub fn set_timer_source(&self, source: &'static dyn TimerSource) {
+        unsafe { *self.timer_source.get() = Some(source) }
+    }
+
+    fn get_timer_source(&self) -> Option<&'static dyn TimerSource> {
+        let source = unsafe { &mut *self.timer_source.get() };
+        #[cfg(feature = "std")]
+        if source.is_none() {
+            *source = Some(Box::leak(Box::new(crate::timer::StdTimerSource::new())));
+        }
+        *source
+    }
+
+    /// Current time of the timer source
+    pub fn now(&self) -> Instant {
+        self.get_timer_source()
+            .expect("no timer source installed, see `ockam_executor::timer::set_timer_source`")
+            .now()
+    }
+
+    pub(crate) fn register_timer(&self, deadline: Instant, id: TimerId, waker: Waker) {
+        let timers = unsafe { &mut *self.timers.get() };
+        timers.insert((deadline, id), waker);
+    }
+
+    pub(crate) fn cancel_timer(&self, deadline: Instant, id: TimerId) {
+        let timers = unsafe { &mut *self.timers.get() };
+        timers.remove(&(deadline, id));
+    }
+
+    /// Wake the tasks of expired timers and return the next deadline
+    fn fire_timers(&self) -> Option<Instant> {
+        let timers = unsafe { &mut *self.timers.get() };
+        if timers.is_empty() {
+            return None;
+        }
+        let now = self.now();
+        while let Some(&(deadline, id)) = timers.keys().next() {
+            if deadline > now {
+                return Some(deadline);
+            }
+            if let Some(waker) = timers.remove(&(deadline, id)) {
+                waker.wake();
+            }
+        }
+        None
+    }
+
     pub fn block_on<T>(&self, future: impl Future<Output = T>) -> T {
         let mut node = Node {
             id: TaskId::new(),
             _name: "Node",
             future: UnsafeCell::new(future),
         };
-        let node_waker = NodeWaker::new(node.id);
+        let node_waker = NodeWaker::new(node.id, self.main_woken.clone());
 
         let result = loop {
             // progress on main task
+            self.main_woken.store(false, Ordering::Relaxed);
             let mut context = Context::from_waker(&node_waker);
             if let Poll::Ready(result) = node.poll(&mut context) {
                 // exit main task
@@ -99,12 +167,20 @@ impl<'a> Executor<'a> {
             let tasksp = self.tasks.get();
             &mut (*tasksp)
         };
-        let task = match tasks.get_mut(&task_id) {
+        let running = unsafe { &mut *self.running.get() };
+        let deferred = unsafe { &mut *self.deferred.get() };
+        // The task is taken out while it is polled, so that a nested
+        // `block_on` from within the task can't poll it again
+        let mut task = match tasks.remove(&task_id) {
             Some(task) => {
                 //let task_count = NEXT_ID.load(Ordering::Relaxed);
                 //trace!("poll task: {}@{} / {}", task.name, task.id.0, task_count);
                 task
             }
+            None if running.contains(&task_id) => {
+                deferred.push(task_id);
+                return;
+            }
             None => {
                 warn!("No task for id: {:?}", task_id);
                 return;
@@ -120,16 +196,30 @@ impl<'a> Executor<'a> {
             .or_insert_with(|| TaskWaker::new(task_id, self.task_queue.clone()));
 
         let mut context = Context::from_waker(waker);
-        match task.poll(&mut context) {
+        running.push(task_id);
+        let poll = task.poll(&mut context);
+        running.pop();
+        match poll {
             Poll::Ready(()) => {
-                // task completed, remove it and its cached waker
-                if let Some(task) = tasks.remove(&task_id) {
-                    drop(task);
-                }
+                // task completed, drop it and its cached waker
+                drop(task);
                 waker_cache.remove(&task_id);
             }
-            Poll::Pending => (),
+            Poll::Pending => {
+                tasks.insert(task_id, task);
+            }
         }
+
+        // reschedule the wake-ups received while tasks were running
+        let task_queue = &self.task_queue;
+        deferred.retain(|id| {
+            if running.contains(id) {
+                true
+            } else {
+                task_queue.push(*id);
+                false
+            }
+        });
     }
 
     /// spawn
@@ -160,9 +250,13 @@ impl<'a> Executor<'a> {
     }
 
     fn sleep_if_idle(&self) {
+        let next_deadline = self.fire_timers();
         // TODO disable interrupts
-        if self.task_queue.is_empty() {
-            // TODO sleep
+        if self.task_queue.is_empty() && !self.main_woken.load(Ordering::Relaxed) {
+            if let Some(source) = self.get_timer_source() {
+                source.wait(next_deadline);
+                self.fire_timers();
+            }
         }
     }
 }
@@ -248,19 +342,36 @@ impl TaskId {
     }
 }
 
+// - TimerId ------------------------------------------------------------------
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+pub(crate) struct TimerId(usize);
+
+impl TimerId {
+    pub(crate) fn new() -> Self {
+        static NEXT_ID: AtomicUsize = AtomicUsize::new(0);
+        TimerId(NEXT_ID.fetch_add(1, Ordering::Relaxed))
+    }
+}
+
 // - Waker --------------------------------------------------------------------
 
-struct NodeWaker;
+// The main task is polled on every iteration, its waker only records
+// that it should not wait for timers
+struct NodeWaker {
+    woken: Arc<AtomicBool>,
+}
+
 impl NodeWaker {
     #[allow(clippy::new_ret_no_self)]
-    fn new(_task_id: TaskId) -> Waker {
-        Waker::from(Arc::new(NodeWaker {}))
+    fn new(_task_id: TaskId, woken: Arc<AtomicBool>) -> Waker {
+        Waker::from(Arc::new(NodeWaker { woken }))
     }
 }
 
 impl Wake for NodeWaker {
     fn wake(self: Arc<Self>) {
-        // no-op
+        self.woken.store(true, Ordering::Relaxed);
     }
 }
 
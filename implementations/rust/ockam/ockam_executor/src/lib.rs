@@ -30,6 +30,7 @@ pub mod channel;
 pub mod executor;
 pub mod runtime;
 pub mod time;
+pub mod timer;
 
 pub mod tokio {
     pub use crate::runtime;
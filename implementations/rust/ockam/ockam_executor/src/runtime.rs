@@ -19,26 +19,24 @@ where
 }
 
 /// block_future
-pub fn block_future<'r, F>(_runtime: &'r Runtime, _future: F) -> <F as Future>::Output
+///
+/// Other tasks keep making progress while the future is blocked on,
+/// except the task calling this function.
+pub fn block_future<'r, F>(_runtime: &'r Runtime, future: F) -> <F as Future>::Output
 where
     F: Future + Send,
     F::Output: Send,
 {
-    // task::block_in_place(move || {
-    //     let local = task::LocalSet::new();
-    //     local.block_on(rt, f)
-    // })
-    unimplemented!();
+    executor::current().block_on(future)
 }
 
 /// spawn
-pub fn spawn<F: 'static>(_future: F)
+pub fn spawn<F>(future: F)
 where
-    F: Future + Send,
+    F: Future + Send + 'static,
     F::Output: Send,
 {
-    // task::spawn(f)
-    unimplemented!();
+    executor::current().spawn(future);
 }
 
 /// Runtime
@@ -1,22 +1,168 @@
+use crate::executor;
 use core::future::Future;
+use core::ops::{Add, AddAssign, Sub};
+use core::pin::Pin;
+use core::task::{Context, Poll};
 pub use core::time::Duration;
 use pin_project_lite::pin_project;
 
+#[cfg(feature = "std")]
+pub use crate::timer::StdTimerSource;
+pub use crate::timer::{set_timer_source, MockTimerSource, TimerSource};
+
+/// A measurement of the executor's monotonic clock
+///
+/// Instants are counted in microseconds from the epoch of the
+/// installed [`TimerSource`].
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct Instant(u64);
+
+impl Instant {
+    /// Current time of the executor's timer source
+    pub fn now() -> Instant {
+        executor::current().now()
+    }
+
+    /// Create an instant from a number of microseconds since the epoch
+    pub const fn from_micros(micros: u64) -> Self {
+        Self(micros)
+    }
+
+    /// Number of microseconds since the epoch
+    pub const fn as_micros(&self) -> u64 {
+        self.0
+    }
+
+    /// Time elapsed from `earlier` to this instant, or zero
+    pub fn saturating_duration_since(&self, earlier: Instant) -> Duration {
+        Duration::from_micros(self.0.saturating_sub(earlier.0))
+    }
+
+    /// Time elapsed from `earlier` to this instant, or zero
+    pub fn duration_since(&self, earlier: Instant) -> Duration {
+        self.saturating_duration_since(earlier)
+    }
+
+    /// Time elapsed since this instant
+    pub fn elapsed(&self) -> Duration {
+        Instant::now().saturating_duration_since(*self)
+    }
+
+    /// Add a duration, returning `None` on overflow
+    pub fn checked_add(&self, duration: Duration) -> Option<Instant> {
+        u64::try_from(duration.as_micros())
+            .ok()
+            .and_then(|micros| self.0.checked_add(micros))
+            .map(Instant)
+    }
+}
+
+impl Add<Duration> for Instant {
+    type Output = Instant;
+
+    fn add(self, duration: Duration) -> Instant {
+        self.checked_add(duration)
+            .expect("overflow when adding duration to instant")
+    }
+}
+
+impl AddAssign<Duration> for Instant {
+    fn add_assign(&mut self, duration: Duration) {
+        *self = *self + duration;
+    }
+}
+
+impl Sub<Instant> for Instant {
+    type Output = Duration;
+
+    fn sub(self, earlier: Instant) -> Duration {
+        self.saturating_duration_since(earlier)
+    }
+}
+
+/// Future returned by [`sleep`] and [`sleep_until`]
+#[derive(Debug)]
+pub struct Sleep {
+    deadline: Instant,
+    id: executor::TimerId,
+    registered: bool,
+}
+
+impl Sleep {
+    fn new(deadline: Instant) -> Self {
+        Self {
+            deadline,
+            id: executor::TimerId::new(),
+            registered: false,
+        }
+    }
+
+    /// Instant at which this future completes
+    pub fn deadline(&self) -> Instant {
+        self.deadline
+    }
+
+    /// Whether the deadline has been reached
+    pub fn is_elapsed(&self) -> bool {
+        Instant::now() >= self.deadline
+    }
+}
+
+impl Future for Sleep {
+    type Output = ();
+
+    fn poll(mut self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<()> {
+        let executor = executor::current();
+        if executor.now() >= self.deadline {
+            if self.registered {
+                executor.cancel_timer(self.deadline, self.id);
+                self.registered = false;
+            }
+            return Poll::Ready(());
+        }
+        executor.register_timer(self.deadline, self.id, cx.waker().clone());
+        self.registered = true;
+        Poll::Pending
+    }
+}
+
+impl Drop for Sleep {
+    fn drop(&mut self) {
+        if self.registered {
+            executor::current().cancel_timer(self.deadline, self.id);
+        }
+    }
+}
+
+/// Wait until `duration` has elapsed
+pub fn sleep(duration: Duration) -> Sleep {
+    Sleep::new(Instant::now() + duration)
+}
+
+/// Wait until `deadline` is reached
+pub fn sleep_until(deadline: Instant) -> Sleep {
+    Sleep::new(deadline)
+}
+
 pin_project! {
+    /// Future returned by [`timeout`]
     #[derive(Debug)]
     pub struct Timeout<F> {
-        #[pin]
-        duration: Duration,
         #[pin]
         future: F,
+        delay: Sleep,
     }
 }
 
+/// Require a future to complete before `duration` has elapsed
 pub fn timeout<F>(duration: Duration, future: F) -> Timeout<F>
 where
     F: Future,
 {
-    Timeout { duration, future }
+    Timeout {
+        future,
+        delay: sleep(duration),
+    }
 }
 
 impl<F> Future for Timeout<F>
@@ -25,30 +171,58 @@ where
 {
     type Output = Result<F::Output, error::Elapsed>;
 
-    fn poll(
-        self: core::pin::Pin<&mut Self>,
-        cx: &mut core::task::Context<'_>,
-    ) -> core::task::Poll<Self::Output> {
+    fn poll(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Self::Output> {
         let timeout = self.project();
 
         // try polling the future
-        if let core::task::Poll::Ready(v) = timeout.future.poll(cx) {
-            return core::task::Poll::Ready(Ok(v));
+        if let Poll::Ready(v) = timeout.future.poll(cx) {
+            return Poll::Ready(Ok(v));
         }
 
-        // TODO check the timer
-        /*match timeout.delay.poll(cx) {
-            Poll::Ready(()) => Poll::Ready(Err(Elapsed::new())),
+        match Pin::new(timeout.delay).poll(cx) {
+            Poll::Ready(()) => Poll::Ready(Err(error::Elapsed::new())),
             Poll::Pending => Poll::Pending,
-        }*/
-        core::task::Poll::Pending
+        }
     }
 }
 
-pub struct Sleep;
+/// Ticks at a fixed period, see [`interval`]
+#[derive(Debug)]
+pub struct Interval {
+    next: Instant,
+    period: Duration,
+}
+
+/// Create an [`Interval`] whose first tick completes immediately
+///
+/// Missed ticks are caught up with as fast as possible, so that the
+/// average tick rate stays at one per `period`.
+pub fn interval(period: Duration) -> Interval {
+    interval_at(Instant::now(), period)
+}
 
-pub async fn sleep(_duration: Duration) -> Sleep {
-    unimplemented!();
+/// Create an [`Interval`] whose first tick completes at `start`
+pub fn interval_at(start: Instant, period: Duration) -> Interval {
+    assert!(period > Duration::ZERO, "`period` must be non-zero.");
+    Interval {
+        next: start,
+        period,
+    }
+}
+
+impl Interval {
+    /// Wait for the next tick and return its scheduled instant
+    pub async fn tick(&mut self) -> Instant {
+        let tick = self.next;
+        sleep_until(tick).await;
+        self.next = tick + self.period;
+        tick
+    }
+
+    /// The period of this interval
+    pub fn period(&self) -> Duration {
+        self.period
+    }
 }
 
 pub mod error {
@@ -59,7 +233,6 @@ pub mod error {
     pub struct Elapsed(());
 
     impl Elapsed {
-        #![allow(dead_code)]
         pub(crate) fn new() -> Self {
             Elapsed(())
         }
@@ -79,3 +252,47 @@ pub mod error {
         }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use futures::channel::oneshot;
+    use ockam_core::compat::boxed::Box;
+
+    #[test]
+    fn sleep_timeout_and_interval() {
+        let source: &'static MockTimerSource = Box::leak(Box::new(MockTimerSource::new()));
+        set_timer_source(source);
+        let executor = executor::current();
+
+        executor.block_on(async {
+            let start = Instant::now();
+            sleep(Duration::from_secs(10)).await;
+            assert_eq!(start.elapsed(), Duration::from_secs(10));
+
+            let res = timeout(Duration::from_secs(1), sleep(Duration::from_secs(5))).await;
+            assert_eq!(res, Err(error::Elapsed::new()));
+            assert_eq!(start.elapsed(), Duration::from_secs(11));
+            let res = timeout(Duration::from_secs(5), sleep(Duration::from_secs(1))).await;
+            assert_eq!(res, Ok(()));
+            assert_eq!(start.elapsed(), Duration::from_secs(12));
+
+            let mut interval = interval(Duration::from_secs(2));
+            for i in 0..3 {
+                let tick = interval.tick().await;
+                assert_eq!(tick - start, Duration::from_secs(12 + 2 * i));
+            }
+
+            // Spawned tasks are woken by their timers
+            let (tx, rx) = oneshot::channel();
+            executor.spawn(async move {
+                sleep(Duration::from_secs(3)).await;
+                tx.send(Instant::now()).unwrap();
+            });
+            source.advance(Duration::from_secs(1));
+            let woken_at = rx.await.unwrap();
+            // The task started sleeping after the clock was advanced
+            assert_eq!(woken_at - start, Duration::from_secs(20));
+        });
+    }
+}
@@ -0,0 +1,127 @@
+//! Pluggable timer sources for the executor
+//!
+//! The executor has no notion of time on its own: it asks a
+//! [`TimerSource`] for the current time and lets it wait while no task
+//! is ready.  On embedded targets this is typically backed by a
+//! SysTick or RTC peripheral; on a host, [`StdTimerSource`] uses the
+//! system clock and [`MockTimerSource`] provides a manually driven
+//! clock for tests.
+
+use crate::executor;
+use crate::time::Instant;
+use core::time::Duration;
+use ockam_core::compat::sync::Mutex;
+
+/// Source of monotonic time for the executor timers
+///
+/// Implementations for hardware timers are expected to arm an alarm
+/// for the deadline passed to [`TimerSource::wait`] and put the core to
+/// sleep (e.g. with `wfi`) until an interrupt fires.
+pub trait TimerSource: Send + Sync {
+    /// Current value of the monotonic clock
+    fn now(&self) -> Instant;
+
+    /// Wait while the executor is idle
+    ///
+    /// `deadline` is the earliest pending timer, if any.  Returning
+    /// before the deadline is always allowed: the executor polls its
+    /// tasks again and calls `wait` once more if nothing happened.
+    fn wait(&self, deadline: Option<Instant>) {
+        let _ = deadline;
+    }
+}
+
+/// Install the timer source of the current executor
+pub fn set_timer_source(source: &'static dyn TimerSource) {
+    executor::current().set_timer_source(source)
+}
+
+/// A manually driven clock for host-side tests
+///
+/// Time only moves with [`advance`](Self::advance), or when the
+/// executor is idle: it then jumps straight to the next pending timer,
+/// so that sleeping tests complete instantly.
+pub struct MockTimerSource {
+    now: Mutex<Instant>,
+}
+
+impl MockTimerSource {
+    /// Create a clock starting at zero
+    pub fn new() -> Self {
+        Self {
+            now: Mutex::new(Instant::from_micros(0)),
+        }
+    }
+
+    /// Move the clock forward
+    pub fn advance(&self, duration: Duration) {
+        let mut now = self.now.lock().unwrap();
+        *now += duration;
+    }
+}
+
+impl Default for MockTimerSource {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl TimerSource for MockTimerSource {
+    fn now(&self) -> Instant {
+        *self.now.lock().unwrap()
+    }
+
+    fn wait(&self, deadline: Option<Instant>) {
+        if let Some(deadline) = deadline {
+            let mut now = self.now.lock().unwrap();
+            if deadline > *now {
+                *now = deadline;
+            }
+        }
+    }
+}
+
+/// Timer source backed by the system clock
+#[cfg(feature = "std")]
+#[derive(Debug)]
+pub struct StdTimerSource {
+    start: std::time::Instant,
+}
+
+#[cfg(feature = "std")]
+impl StdTimerSource {
+    /// Longest time spent in a single `wait`, so that wake-ups from
+    /// other threads are not delayed for too long
+    const MAX_WAIT: Duration = Duration::from_millis(1);
+
+    /// Create a clock starting now
+    pub fn new() -> Self {
+        Self {
+            start: std::time::Instant::now(),
+        }
+    }
+}
+
+#[cfg(feature = "std")]
+impl Default for StdTimerSource {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+#[cfg(feature = "std")]
+impl TimerSource for StdTimerSource {
+    fn now(&self) -> Instant {
+        Instant::from_micros(self.start.elapsed().as_micros() as u64)
+    }
+
+    fn wait(&self, deadline: Option<Instant>) {
+        match deadline {
+            Some(deadline) => {
+                let remaining = deadline.saturating_duration_since(self.now());
+                std::thread::sleep(remaining.min(Self::MAX_WAIT));
+            }
+            None => std::thread::yield_now(),
+        }
+    }
+}
@@ -0,0 +1,174 @@
+//! Identity backup and restore
+use crate::change_history::IdentityChangeHistory;
+use crate::{Identity, IdentityError, IdentityVault};
+use ockam_core::compat::{string::String, vec::Vec};
+use ockam_core::vault::SecretKey;
+use ockam_core::Result;
+use ockam_node::Context;
+use ockam_vault::{KeyId, SecretAttributes};
+use serde::{Deserialize, Serialize};
+
+/// Identity change history together with the vault secrets of its current keys.
+///
+/// A bundle holds private keys in plain text and should only be persisted
+/// after being encrypted.
+#[derive(Serialize, Deserialize)]
+pub struct IdentityBundle {
+    version: u8,
+    identity: Vec<u8>,
+    secrets: Vec<BundleSecret>,
+}
+
+/// A single vault entry referenced by an [`IdentityBundle`].
+#[derive(Serialize, Deserialize)]
+struct BundleSecret {
+    key_id: KeyId,
+    attributes: SecretAttributes,
+    secret: SecretKey,
+}
+
+impl IdentityBundle {
+    /// Current version of the bundle structure
+    pub const CURRENT_VERSION: u8 = 1;
+
+    /// Exported change history of the bundled identity.
+    pub fn identity(&self) -> &[u8] {
+        &self.identity
+    }
+
+    pub fn encode(&self) -> Result<Vec<u8>> {
+        serde_bare::to_vec(self).map_err(|_| IdentityError::BareError.into())
+    }
+
+    pub fn decode(data: &[u8]) -> Result<Self> {
+        let bundle: Self =
+            serde_bare::from_slice(data).map_err(|_| IdentityError::InvalidBundle)?;
+        if bundle.version != Self::CURRENT_VERSION {
+            return Err(IdentityError::InvalidBundle.into());
+        }
+        Ok(bundle)
+    }
+}
+
+impl<V: IdentityVault> Identity<V> {
+    /// Export the change history along with the secrets of the current keys.
+    pub async fn export_with_secrets(&self) -> Result<IdentityBundle> {
+        let history = self.change_history.read().await;
+        let mut secrets = Vec::new();
+        for key_id in Self::current_key_ids(&history, &self.vault).await? {
+            let attributes = self.vault.secret_attributes_get(&key_id).await?;
+            let secret = self.vault.secret_export(&key_id).await?;
+            secrets.push(BundleSecret {
+                key_id,
+                attributes,
+                secret,
+            });
+        }
+
+        Ok(IdentityBundle {
+            version: IdentityBundle::CURRENT_VERSION,
+            identity: history.export()?,
+            secrets,
+        })
+    }
+
+    /// Restore an identity and its secrets into `vault`.
+    ///
+    /// The change history is verified, and every imported secret must match
+    /// the key id it was exported under. If the import fails, the secrets
+    /// already imported are removed from `vault`.
+    pub async fn import_with_secrets(
+        ctx: &Context,
+        bundle: &IdentityBundle,
+        vault: &V,
+    ) -> Result<Self> {
+        let identity = Self::import(ctx, &bundle.identity, vault).await?;
+
+        let expected = {
+            let history = identity.change_history.read().await;
+            Self::current_key_ids(&history, vault).await?
+        };
+        if !expected
+            .iter()
+            .all(|k| bundle.secrets.iter().any(|s| &s.key_id == k))
+        {
+            return Err(IdentityError::InvalidBundle.into());
+        }
+
+        let mut imported = Vec::new();
+        if let Err(e) = Self::import_secrets(bundle, vault, &mut imported).await {
+            for key_id in imported {
+                let _ = vault.secret_destroy(key_id).await;
+            }
+            return Err(e);
+        }
+
+        Ok(identity)
+    }
+
+    /// Import the secrets of `bundle` which are not in `vault` yet,
+    /// recording the key ids of those imported in `imported`.
+    async fn import_secrets(
+        bundle: &IdentityBundle,
+        vault: &V,
+        imported: &mut Vec<KeyId>,
+    ) -> Result<()> {
+        for s in &bundle.secrets {
+            if vault.secret_attributes_get(&s.key_id).await.is_ok() {
+                continue;
+            }
+            let key_id = vault.secret_import(s.secret.as_ref(), s.attributes).await?;
+            imported.push(key_id.clone());
+            if key_id != s.key_id {
+                return Err(IdentityError::InvalidBundle.into());
+            }
+        }
+        Ok(())
+    }
+
+    /// Key ids of the latest key for every label in the history.
+    async fn current_key_ids(history: &IdentityChangeHistory, vault: &V) -> Result<Vec<KeyId>> {
+        let mut labels: Vec<String> = Vec::new();
+        for change in history.as_ref() {
+            let label = change.change().label();
+            if !labels.iter().any(|l| l == label) {
+                labels.push(label.into());
+            }
+        }
+
+        let mut key_ids = Vec::with_capacity(labels.len());
+        for label in labels {
+            let change = IdentityChangeHistory::find_last_key_change(history.as_ref(), &label)?;
+            key_ids.push(Self::get_secret_key_from_change(change, vault).await?);
+        }
+        Ok(key_ids)
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use ockam_core::vault::SecretVault;
+    use ockam_vault::Vault;
+
+    #[ockam_macros::test]
+    async fn failed_import_removes_imported_secrets(ctx: &mut Context) -> Result<()> {
+        let alice = Identity::create(ctx, &Vault::create()).await?;
+        alice.create_key("Second".into()).await?;
+        let mut bundle = alice.export_with_secrets().await?;
+        assert_eq!(bundle.secrets.len(), 2);
+
+        // The last secret doesn't match its key id
+        bundle.secrets[1].secret = SecretKey::new(vec![7; 32]);
+
+        let vault = Vault::create();
+        assert!(Identity::import_with_secrets(ctx, &bundle, &vault)
+            .await
+            .is_err());
+        for s in &bundle.secrets {
+            assert!(vault.secret_attributes_get(&s.key_id).await.is_err());
+        }
+
+        ctx.stop().await
+    }
+}
@@ -1,12 +1,16 @@
 #![allow(missing_docs)]
 
+mod disclosure;
 mod identity;
 mod public_identity;
+mod schema;
 mod storage_utils;
 mod worker;
 
 pub mod access_control;
 
+pub use disclosure::*;
+pub use schema::*;
 pub use storage_utils::*;
 
 use crate::IdentityIdentifier;
@@ -49,6 +53,8 @@ pub struct Credential<'a> {
     #[b(1)] data: CowBytes<'a>,
     /// Cryptographic signature of attributes data.
     #[b(2)] signature: CowBytes<'a>,
+    /// Revealed values of selectively disclosable attributes.
+    #[b(3)] disclosures: Option<Vec<Disclosure<'a>>>,
 }
 
 impl fmt::Display for Credential<'_> {
@@ -71,9 +77,17 @@ impl fmt::Display for Credential<'_> {
                 credential_data
                     .attributes
                     .iter()
+                    .chain(self.disclosures().iter().map(|d| (d.name(), d.value())))
                     .map(|(k, v)| (k, std::str::from_utf8(v).unwrap_or("**binary**"))),
             )
             .finish()?;
+        if let Some(digests) = &credential_data.digests {
+            let hidden = digests
+                .names()
+                .filter(|n| !self.disclosures().iter().any(|d| d.name() == *n));
+            write!(f, "\n Undisclosed: ")?;
+            f.debug_list().entries(hidden).finish()?;
+        }
         writeln!(f, "\n")?;
         writeln!(f, " Signature: {}", hex::encode(self.signature.deref()))?;
         writeln!(f, "---")
@@ -104,7 +118,9 @@ pub struct CredentialData<'a, T> {
     /// The time this credential expires.
     #[n(7)] expires: Timestamp,
     /// Term to represent the verification status type.
-    #[n(8)] status: Option<PhantomData<T>>
+    #[n(8)] status: Option<PhantomData<T>>,
+    /// Digests of attributes which are disclosed separately from the signed data.
+    #[b(9)] digests: Option<Digests<'a>>
 }
 
 impl<'a> CredentialData<'a, Unverified> {
@@ -118,6 +134,7 @@ impl<'a> CredentialData<'a, Unverified> {
             created: self.created,
             expires: self.expires,
             status: None::<PhantomData<Verified>>,
+            digests: self.digests,
         }
     }
 }
@@ -129,6 +146,7 @@ impl<'a> Credential<'a> {
             subject,
             attrs: Attributes::new(),
             validity: MAX_CREDENTIAL_VALIDITY,
+            selective: false,
         }
     }
 
@@ -140,7 +158,7 @@ impl<'a> Credential<'a> {
         &self.data
     }
 
-    fn new<A, S>(data: A, signature: S) -> Self
+    fn new<A, S>(data: A, signature: S, disclosures: Option<Vec<Disclosure<'a>>>) -> Self
     where
         A: Into<Cow<'a, [u8]>>,
         S: Into<Cow<'a, [u8]>>,
@@ -150,6 +168,7 @@ impl<'a> Credential<'a> {
             tag: TypeTag,
             data: CowBytes(data.into()),
             signature: CowBytes(signature.into()),
+            disclosures,
         }
     }
 
@@ -159,6 +178,10 @@ impl<'a> Credential<'a> {
             tag: TypeTag,
             data: self.data.to_owned(),
             signature: self.signature.to_owned(),
+            disclosures: self
+                .disclosures
+                .as_ref()
+                .map(|ds| ds.iter().map(|d| d.to_owned()).collect()),
         }
     }
 }
@@ -195,6 +218,11 @@ impl<'a> CredentialData<'a, Verified> {
     pub fn into_attributes(self) -> Attributes<'a> {
         self.attributes
     }
+
+    /// Digests of the selectively disclosable attributes, if any.
+    pub fn digests(&self) -> Option<&Digests<'a>> {
+        self.digests.as_ref()
+    }
 }
 
 impl<'a> CredentialData<'a, Unverified> {
@@ -204,6 +232,12 @@ impl<'a> CredentialData<'a, Unverified> {
     pub fn unverfied_key_label(&self) -> &str {
         &self.issuer_key_label
     }
+    pub fn unverified_created_at(&self) -> Timestamp {
+        self.created
+    }
+    pub fn unverified_expires_at(&self) -> Timestamp {
+        self.expires
+    }
 }
 
 impl<'a, 'b: 'a> TryFrom<&'b Credential<'a>> for CredentialData<'a, Unverified> {
@@ -315,6 +349,7 @@ pub struct CredentialBuilder<'a> {
     attrs: Attributes<'a>,
     subject: IdentityIdentifier,
     validity: Duration,
+    selective: bool,
 }
 
 impl<'a> CredentialBuilder<'a> {
@@ -330,6 +365,15 @@ impl<'a> CredentialBuilder<'a> {
         self
     }
 
+    /// Commit to every attribute individually instead of signing their values.
+    ///
+    /// The holder of such a credential can use [`Credential::disclose`] to
+    /// reveal only some of the attributes to a verifier.
+    pub fn with_selective_disclosure(mut self) -> Self {
+        self.selective = true;
+        self
+    }
+
     /// Set validity duration of the credential.
     ///
     /// # Panics
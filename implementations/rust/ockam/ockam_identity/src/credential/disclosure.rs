@@ -0,0 +1,141 @@
+use crate::credential::{Attributes, Credential};
+use crate::IdentityError;
+use minicbor::{Decode, Encode};
+use ockam_core::compat::{collections::BTreeMap, rand::random, string::ToString, vec::Vec};
+use ockam_core::vault::Hasher;
+use ockam_core::{CowBytes, CowStr, Result};
+
+#[cfg(feature = "tag")]
+use crate::TypeTag;
+
+/// Length of the random salt mixed into every attribute digest.
+const SALT_LEN: usize = 16;
+
+/// A salted attribute value which has been committed to in a credential.
+///
+/// Only the SHA-256 digest of the encoded disclosure is signed by the issuer,
+/// so holders may reveal any subset of their attributes to a verifier.
+#[derive(Clone, Debug, Decode, Encode)]
+#[rustfmt::skip]
+#[cbor(map)]
+pub struct Disclosure<'a> {
+    #[cfg(feature = "tag")]
+    #[n(0)] tag: TypeTag<5930418>,
+    #[b(1)] salt: CowBytes<'a>,
+    #[b(2)] name: CowStr<'a>,
+    #[b(3)] value: CowBytes<'a>,
+}
+
+/// Digests of selectively disclosable attributes, keyed by attribute name.
+#[derive(Debug, Clone, Default, Encode, Decode, PartialEq, Eq)]
+#[rustfmt::skip]
+#[cbor(map)]
+pub struct Digests<'a> {
+    #[cfg(feature = "tag")]
+    #[n(0)] tag: TypeTag<2249375>,
+    #[b(1)] digests: BTreeMap<&'a str, CowBytes<'a>>,
+}
+
+impl<'a> Disclosure<'a> {
+    /// Create a disclosure for the given attribute with a fresh random salt.
+    pub(crate) fn new(name: &str, value: &[u8]) -> Disclosure<'static> {
+        Disclosure {
+            #[cfg(feature = "tag")]
+            tag: TypeTag,
+            salt: CowBytes(random::<[u8; SALT_LEN]>().to_vec().into()),
+            name: CowStr(name.to_string().into()),
+            value: CowBytes(value.to_vec().into()),
+        }
+    }
+
+    pub fn name(&self) -> &str {
+        &self.name
+    }
+
+    pub fn value(&self) -> &[u8] {
+        &self.value
+    }
+
+    /// Compute the digest the issuer signs for this disclosure.
+    pub async fn digest(&self, hasher: &impl Hasher) -> Result<[u8; 32]> {
+        hasher.sha256(&minicbor::to_vec(self)?).await
+    }
+
+    pub fn to_owned<'r>(&self) -> Disclosure<'r> {
+        Disclosure {
+            #[cfg(feature = "tag")]
+            tag: TypeTag,
+            salt: self.salt.to_owned(),
+            name: self.name.to_owned(),
+            value: self.value.to_owned(),
+        }
+    }
+}
+
+impl<'a> Digests<'a> {
+    pub(crate) fn new() -> Self {
+        Digests {
+            #[cfg(feature = "tag")]
+            tag: TypeTag,
+            digests: BTreeMap::new(),
+        }
+    }
+
+    pub(crate) fn put(&mut self, name: &'a str, digest: [u8; 32]) {
+        self.digests.insert(name, CowBytes(digest.to_vec().into()));
+    }
+
+    pub fn get(&self, name: &str) -> Option<&[u8]> {
+        self.digests.get(name).map(|d| &**d)
+    }
+
+    /// Names of all attributes committed to in the credential.
+    pub fn names(&self) -> impl Iterator<Item = &str> {
+        self.digests.keys().copied()
+    }
+
+    /// Check the given disclosures against the signed digests.
+    ///
+    /// On success the disclosed attributes are added to `attrs`.
+    pub(crate) async fn verify<'b: 'a>(
+        &self,
+        disclosures: &'b [Disclosure<'b>],
+        attrs: &mut Attributes<'a>,
+        hasher: &impl Hasher,
+    ) -> Result<()> {
+        for d in disclosures {
+            match self.get(d.name()) {
+                Some(expected) if expected == d.digest(hasher).await? => {
+                    attrs.put(d.name(), d.value());
+                }
+                _ => return Err(IdentityError::InvalidDisclosure.into()),
+            }
+        }
+        Ok(())
+    }
+}
+
+impl<'a> Credential<'a> {
+    /// Create a copy of this credential which only discloses the given attributes.
+    ///
+    /// Attributes which are not selectively disclosable are always revealed.
+    pub fn disclose<'r>(&self, names: &[&str]) -> Credential<'r> {
+        Credential {
+            #[cfg(feature = "tag")]
+            tag: TypeTag,
+            data: self.data.to_owned(),
+            signature: self.signature.to_owned(),
+            disclosures: self.disclosures.as_ref().map(|ds| {
+                ds.iter()
+                    .filter(|d| names.contains(&d.name()))
+                    .map(|d| d.to_owned())
+                    .collect::<Vec<_>>()
+            }),
+        }
+    }
+
+    /// The attribute values revealed by this credential alongside its signed data.
+    pub fn disclosures(&self) -> &[Disclosure<'a>] {
+        self.disclosures.as_deref().unwrap_or_default()
+    }
+}
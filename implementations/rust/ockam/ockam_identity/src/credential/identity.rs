@@ -1,8 +1,8 @@
 use crate::authenticated_storage::AuthenticatedStorage;
 use crate::credential::worker::CredentialExchangeWorker;
 use crate::credential::{
-    AttributesEntry, AttributesStorageUtils, Credential, CredentialBuilder, CredentialData,
-    Timestamp, Unverified, Verified,
+    Attributes, AttributesEntry, AttributesStorageUtils, Credential, CredentialBuilder,
+    CredentialData, Digests, Disclosure, Schema, SchemaRegistry, Timestamp, Unverified, Verified,
 };
 use crate::{
     Identity, IdentityError, IdentityIdentifier, IdentitySecureChannelLocalInfo,
@@ -27,29 +27,63 @@ impl<V: IdentityVault> Identity<V> {
         self.credential.read().await.clone()
     }
 
+    /// Register a credential schema.
+    ///
+    /// Credentials of this schema are checked against it when issued or received.
+    pub async fn register_schema(&self, schema: Schema) {
+        self.schemas.write().await.register(schema);
+    }
+
+    pub async fn schemas(&self) -> SchemaRegistry {
+        self.schemas.read().await.clone()
+    }
+
+    /// Check a credential against the registered schemas without issuing it.
+    pub async fn validate_credential(&self, builder: &CredentialBuilder<'_>) -> Result<()> {
+        self.schemas.read().await.validate_builder(builder)
+    }
+
     /// Create a signed credential based on the given values.
     pub async fn issue_credential<'a>(
         &self,
         builder: CredentialBuilder<'a>,
     ) -> Result<Credential<'a>> {
+        self.schemas.read().await.validate_builder(&builder)?;
         let key_label = IdentityStateConst::ROOT_LABEL;
         let now = Timestamp::now()
             .ok_or_else(|| Error::new(Origin::Core, Kind::Internal, "invalid system time"))?;
         let exp = Timestamp(u64::from(now).saturating_add(builder.validity.as_secs()));
+
+        // With selective disclosure only the salted attribute digests are signed,
+        // while the values travel next to the signed data.
+        let (attributes, digests, disclosures) = if builder.selective {
+            let mut digests = Digests::new();
+            let mut disclosures = Vec::new();
+            for (k, v) in builder.attrs.iter() {
+                let d = Disclosure::new(k, v);
+                digests.put(k, d.digest(&self.vault).await?);
+                disclosures.push(d);
+            }
+            (Attributes::new(), Some(digests), Some(disclosures))
+        } else {
+            (builder.attrs, None, None)
+        };
+
         let dat = CredentialData {
             schema: builder.schema,
-            attributes: builder.attrs,
+            attributes,
             subject: builder.subject,
             issuer: self.identifier().clone(),
             issuer_key_label: CowStr(key_label.into()),
             created: now,
             expires: exp,
             status: None::<PhantomData<Verified>>,
+            digests,
         };
         let bytes = minicbor::to_vec(&dat)?;
 
         let sig = self.create_signature(&bytes, None).await?;
-        Ok(Credential::new(bytes, SignatureVec::from(sig)))
+        Ok(Credential::new(bytes, SignatureVec::from(sig), disclosures))
     }
 
     /// Start worker that will be available to receive others attributes and put them into storage,
@@ -79,12 +113,37 @@ impl<V: IdentityVault> Identity<V> {
             )
         })?;
 
+        self.send_credential(route.into(), credential).await
+    }
+
+    /// Present credential to other party, only disclosing the given attributes.
+    ///
+    /// Attributes which were not issued as selectively disclosable are always revealed.
+    pub async fn present_credential_disclosing(
+        &self,
+        route: impl Into<Route>,
+        attributes: &[&str],
+    ) -> Result<()> {
+        let credentials = self.credential.read().await;
+        let credential = credentials.as_ref().ok_or_else(|| {
+            Error::new(
+                Origin::Application,
+                Kind::Invalid,
+                "no credential to present",
+            )
+        })?;
+
+        self.send_credential(route.into(), &credential.disclose(attributes))
+            .await
+    }
+
+    async fn send_credential(&self, route: Route, credential: &Credential<'_>) -> Result<()> {
         let mut child_ctx = self.ctx.new_detached(Address::random_local()).await?;
         let buf = request(
             &mut child_ctx,
             "credential",
             None,
-            route.into(),
+            route,
             Request::post("actions/present").body(credential),
         )
         .await?;
@@ -107,6 +166,38 @@ impl<V: IdentityVault> Identity<V> {
         route: impl Into<Route>,
         authorities: impl IntoIterator<Item = &PublicIdentity>,
         authenticated_storage: &impl AuthenticatedStorage,
+    ) -> Result<()> {
+        self.present_mutual(route.into(), authorities, authenticated_storage, None)
+            .await
+    }
+
+    /// Present credential to other party, only disclosing the given attributes, and
+    /// receive its credential in response.
+    ///
+    /// Attributes which were not issued as selectively disclosable are always revealed.
+    /// What the other party presents back is up to it.
+    pub async fn present_credential_mutual_disclosing(
+        &self,
+        route: impl Into<Route>,
+        authorities: impl IntoIterator<Item = &PublicIdentity>,
+        authenticated_storage: &impl AuthenticatedStorage,
+        attributes: &[&str],
+    ) -> Result<()> {
+        self.present_mutual(
+            route.into(),
+            authorities,
+            authenticated_storage,
+            Some(attributes),
+        )
+        .await
+    }
+
+    async fn present_mutual(
+        &self,
+        route: Route,
+        authorities: impl IntoIterator<Item = &PublicIdentity>,
+        authenticated_storage: &impl AuthenticatedStorage,
+        attributes: Option<&[&str]>,
     ) -> Result<()> {
         let credentials = self.credential.read().await;
         let credential = credentials.as_ref().ok_or_else(|| {
@@ -116,6 +207,14 @@ impl<V: IdentityVault> Identity<V> {
                 "no credential to present",
             )
         })?;
+        let disclosed;
+        let credential = match attributes {
+            Some(attributes) => {
+                disclosed = credential.disclose(attributes);
+                &disclosed
+            }
+            None => credential,
+        };
 
         let mut child_ctx = self.ctx.new_detached(Address::random_local()).await?;
         let path = "actions/present_mutual";
@@ -123,7 +222,7 @@ impl<V: IdentityVault> Identity<V> {
             &mut child_ctx,
             "credential",
             None,
-            route.into(),
+            route,
             Request::post(path).body(credential),
         )
         .await?;
@@ -201,6 +300,7 @@ impl<V: IdentityVault> Identity<V> {
     ) -> Result<()> {
         let credential_data =
             Self::verify_credential(&sender, &credential, authorities, &self.vault).await?;
+        self.schemas.read().await.validate_data(&credential_data)?;
 
         AttributesStorageUtils::put_attributes(
             &sender,
@@ -70,7 +70,24 @@ impl PublicIdentity {
                 "invalid signature",
             ));
         }
-        Ok(dat.into_verified())
+
+        let mut dat = dat.into_verified();
+        match &dat.digests {
+            Some(digests) => {
+                digests
+                    .verify(credential.disclosures(), &mut dat.attributes, vault)
+                    .await?
+            }
+            None if !credential.disclosures().is_empty() => {
+                return Err(Error::new(
+                    Origin::Application,
+                    Kind::Invalid,
+                    "unexpected attribute disclosures",
+                ))
+            }
+            None => {}
+        }
+        Ok(dat)
     }
 
     /// Return authenticated non-expired attributes attached to that Identity
@@ -0,0 +1,250 @@
+use crate::credential::{
+    Attributes, CredentialBuilder, CredentialData, SchemaId, Verified, MAX_CREDENTIAL_VALIDITY,
+};
+use crate::IdentityError;
+use core::time::Duration;
+use minicbor::{Decode, Encode};
+use ockam_core::compat::{collections::BTreeMap, string::String, vec::Vec};
+use ockam_core::errcode::{Kind, Origin};
+use ockam_core::{Error, Result};
+
+#[cfg(feature = "tag")]
+use crate::TypeTag;
+
+/// The type of values an attribute may have.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Encode, Decode)]
+#[rustfmt::skip]
+#[cbor(index_only)]
+pub enum AttributeType {
+    /// Arbitrary bytes.
+    #[n(0)] Bytes,
+    /// UTF-8 encoded text.
+    #[n(1)] Utf8,
+    /// Either `true` or `false`.
+    #[n(2)] Bool,
+    /// A decimal integer.
+    #[n(3)] Integer,
+}
+
+impl AttributeType {
+    /// Check if the given value is of this type.
+    pub fn accepts(&self, value: &[u8]) -> bool {
+        match self {
+            AttributeType::Bytes => true,
+            AttributeType::Utf8 => core::str::from_utf8(value).is_ok(),
+            AttributeType::Bool => value == b"true" || value == b"false",
+            AttributeType::Integer => core::str::from_utf8(value)
+                .map(|s| s.parse::<i64>().is_ok())
+                .unwrap_or(false),
+        }
+    }
+}
+
+/// Definition of a single credential attribute.
+#[derive(Debug, Clone, PartialEq, Eq, Encode, Decode)]
+#[rustfmt::skip]
+#[cbor(map)]
+pub struct AttributeSchema {
+    #[cfg(feature = "tag")]
+    #[n(0)] tag: TypeTag<1862345>,
+    #[n(1)] name: String,
+    #[n(2)] typ: AttributeType,
+    #[n(3)] required: bool,
+}
+
+impl AttributeSchema {
+    pub fn required(name: impl Into<String>, typ: AttributeType) -> Self {
+        Self::new(name.into(), typ, true)
+    }
+
+    pub fn optional(name: impl Into<String>, typ: AttributeType) -> Self {
+        Self::new(name.into(), typ, false)
+    }
+
+    fn new(name: String, typ: AttributeType, required: bool) -> Self {
+        AttributeSchema {
+            #[cfg(feature = "tag")]
+            tag: TypeTag,
+            name,
+            typ,
+            required,
+        }
+    }
+
+    pub fn name(&self) -> &str {
+        &self.name
+    }
+
+    pub fn attribute_type(&self) -> AttributeType {
+        self.typ
+    }
+
+    pub fn is_required(&self) -> bool {
+        self.required
+    }
+}
+
+/// Describes the attributes a credential of some [`SchemaId`] contains.
+#[derive(Debug, Clone, PartialEq, Eq, Encode, Decode)]
+#[rustfmt::skip]
+#[cbor(map)]
+pub struct Schema {
+    #[cfg(feature = "tag")]
+    #[n(0)] tag: TypeTag<7701452>,
+    #[n(1)] id: SchemaId,
+    #[n(2)] name: String,
+    #[n(3)] attributes: Vec<AttributeSchema>,
+    /// Type of attributes not listed in the schema, if they are allowed at all.
+    #[n(4)] additional: Option<AttributeType>,
+    /// Maximum validity in seconds.
+    #[n(5)] max_validity: u64,
+}
+
+impl Schema {
+    pub fn new(id: SchemaId, name: impl Into<String>) -> Self {
+        Schema {
+            #[cfg(feature = "tag")]
+            tag: TypeTag,
+            id,
+            name: name.into(),
+            attributes: Vec::new(),
+            additional: None,
+            max_validity: MAX_CREDENTIAL_VALIDITY.as_secs(),
+        }
+    }
+
+    /// Add an attribute definition.
+    pub fn with_attribute(mut self, a: AttributeSchema) -> Self {
+        self.attributes.push(a);
+        self
+    }
+
+    /// Allow attributes not listed in this schema, if their values are of the given type.
+    pub fn with_additional_attributes(mut self, typ: AttributeType) -> Self {
+        self.additional = Some(typ);
+        self
+    }
+
+    /// Set the maximum validity of credentials of this schema.
+    pub fn with_max_validity(mut self, val: Duration) -> Self {
+        self.max_validity = val.as_secs();
+        self
+    }
+
+    pub fn id(&self) -> SchemaId {
+        self.id
+    }
+
+    pub fn name(&self) -> &str {
+        &self.name
+    }
+
+    pub fn attributes(&self) -> &[AttributeSchema] {
+        &self.attributes
+    }
+
+    pub fn max_validity(&self) -> Duration {
+        Duration::from_secs(self.max_validity)
+    }
+
+    /// Check that the given attributes and validity conform to this schema.
+    pub fn validate(&self, attrs: &Attributes<'_>, validity: Duration) -> Result<()> {
+        if validity > self.max_validity() {
+            return Err(self.error("validity exceeds schema maximum"));
+        }
+        for a in &self.attributes {
+            if a.required && attrs.get(&a.name).is_none() {
+                return Err(self.error(&format!("missing attribute `{}`", a.name)));
+            }
+        }
+        for (k, v) in attrs.iter() {
+            let typ = match self.attributes.iter().find(|a| a.name == k) {
+                Some(a) => a.typ,
+                None => match self.additional {
+                    Some(t) => t,
+                    None => return Err(self.error(&format!("unknown attribute `{}`", k))),
+                },
+            };
+            if !typ.accepts(v) {
+                return Err(self.error(&format!("attribute `{}` is not of type {:?}", k, typ)));
+            }
+        }
+        Ok(())
+    }
+
+    fn error(&self, msg: &str) -> Error {
+        let msg = format!("schema {} ({}): {}", u64::from(self.id), self.name, msg);
+        Error::new(Origin::Identity, Kind::Invalid, msg)
+    }
+}
+
+/// A set of known [`Schema`]s.
+///
+/// Credentials referring to a registered [`SchemaId`] must conform to its
+/// schema. Credentials without a schema or with an unknown one are not checked.
+#[derive(Debug, Clone, Default)]
+pub struct SchemaRegistry {
+    schemas: BTreeMap<SchemaId, Schema>,
+}
+
+impl SchemaRegistry {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Register a schema, replacing any previous one with the same identifier.
+    pub fn register(&mut self, schema: Schema) -> &mut Self {
+        self.schemas.insert(schema.id, schema);
+        self
+    }
+
+    pub fn get(&self, id: SchemaId) -> Option<&Schema> {
+        self.schemas.get(&id)
+    }
+
+    pub fn iter(&self) -> impl Iterator<Item = &Schema> {
+        self.schemas.values()
+    }
+
+    /// Check a credential before it gets issued.
+    pub fn validate_builder(&self, builder: &CredentialBuilder<'_>) -> Result<()> {
+        match builder.schema.and_then(|id| self.get(id)) {
+            Some(s) => s.validate(&builder.attrs, builder.validity),
+            None => Ok(()),
+        }
+    }
+
+    /// Check the data of a verified credential.
+    pub fn validate_data(&self, data: &CredentialData<'_, Verified>) -> Result<()> {
+        let schema = match data.schema.and_then(|id| self.get(id)) {
+            Some(s) => s,
+            None => return Ok(()),
+        };
+        let validity = data
+            .expires
+            .elapsed(data.created)
+            .ok_or(IdentityError::InvalidCredentialFormat)?;
+        // Selectively disclosable attributes may legitimately be withheld,
+        // so only the disclosed ones are checked for presence.
+        match &data.digests {
+            Some(digests) => {
+                let committed: Vec<&str> = digests.names().collect();
+                for a in schema.attributes().iter().filter(|a| a.required) {
+                    if !committed.contains(&a.name()) && data.attributes.get(a.name()).is_none() {
+                        return Err(schema.error(&format!("missing attribute `{}`", a.name)));
+                    }
+                }
+                let relaxed = Schema {
+                    attributes: schema
+                        .attributes
+                        .iter()
+                        .map(|a| AttributeSchema::optional(a.name.clone(), a.typ))
+                        .collect(),
+                    ..schema.clone()
+                };
+                relaxed.validate(&data.attributes, validity)
+            }
+            None => schema.validate(&data.attributes, validity),
+        }
+    }
+}
@@ -20,6 +20,8 @@ pub enum IdentityError {
     InvalidCredentialFormat,
     UnknownAuthority,
     CredentialVerificationFailed,
+    InvalidDisclosure,
+    InvalidBundle,
 }
 
 impl ockam_core::compat::error::Error for IdentityError {}
@@ -1,7 +1,7 @@
 use crate::authenticated_storage::AuthenticatedStorage;
 use crate::change::IdentitySignedChange;
 use crate::change_history::{IdentityChangeHistory, IdentityHistoryComparison};
-use crate::credential::Credential;
+use crate::credential::{Credential, SchemaRegistry};
 use crate::{
     ChangeIdentifier, IdentityError, IdentityIdentifier, IdentityVault, KeyAttributes,
     PublicIdentity,
@@ -25,6 +25,7 @@ use ockam_vault::{KeyId, SecretAttributes};
 pub struct Identity<V: IdentityVault> {
     id: IdentityIdentifier,
     pub(crate) credential: Arc<RwLock<Option<Credential<'static>>>>,
+    pub(crate) schemas: Arc<RwLock<SchemaRegistry>>,
     pub(crate) change_history: Arc<RwLock<IdentityChangeHistory>>,
     pub(crate) ctx: Context,
     pub(crate) vault: V,
@@ -57,6 +58,7 @@ impl<V: IdentityVault> Identity<V> {
         Self {
             id,
             credential: Arc::new(RwLock::new(None)),
+            schemas: Arc::new(RwLock::new(SchemaRegistry::new())),
             change_history: Arc::new(RwLock::new(change_history)),
             ctx,
             vault,
@@ -33,6 +33,7 @@ pub mod error;
 
 pub use error::*;
 
+mod bundle;
 mod channel;
 mod identifiers;
 mod identity;
@@ -40,6 +41,7 @@ mod identity_builder;
 mod key_attributes;
 mod public_identity;
 
+pub use bundle::*;
 pub use channel::*;
 pub use identifiers::*;
 pub use identity::*;
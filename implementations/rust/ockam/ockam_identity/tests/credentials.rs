@@ -3,7 +3,9 @@ use ockam_core::{async_trait, Any};
 use ockam_core::{route, Result, Routed, Worker};
 use ockam_identity::authenticated_storage::mem::InMemoryStorage;
 use ockam_identity::credential::access_control::CredentialAccessControl;
-use ockam_identity::credential::{AttributesStorageUtils, Credential};
+use ockam_identity::credential::{
+    AttributeSchema, AttributeType, AttributesStorageUtils, Credential, Schema, SchemaId,
+};
 use ockam_identity::{Identity, TrustEveryonePolicy, TrustIdentifierPolicy};
 use ockam_node::{Context, WorkerBuilder};
 use ockam_vault::Vault;
@@ -131,6 +133,229 @@ async fn full_flow_twoway(ctx: &mut Context) -> Result<()> {
     ctx.stop().await
 }
 
+#[ockam_macros::test]
+async fn selective_disclosure(ctx: &mut Context) -> Result<()> {
+    let vault = Vault::create();
+
+    let authority = Identity::create(ctx, &vault).await?;
+
+    let server = Identity::create(ctx, &vault).await?;
+    let server_storage = InMemoryStorage::new();
+
+    server
+        .create_secure_channel_listener("listener", TrustEveryonePolicy, &server_storage)
+        .await?;
+
+    let authorities = vec![authority.to_public().await?];
+
+    server
+        .start_credentials_exchange_worker(
+            authorities,
+            "credential_exchange",
+            false,
+            server_storage.clone(),
+        )
+        .await?;
+
+    let client = Identity::create(ctx, &vault).await?;
+    let client_storage = InMemoryStorage::new();
+    let channel = client
+        .create_secure_channel(
+            route!["listener"],
+            TrustIdentifierPolicy::new(server.identifier().clone()),
+            &client_storage,
+        )
+        .await?;
+
+    let credential = Credential::builder(client.identifier().clone())
+        .with_attribute("role", b"member")
+        .with_attribute("email", b"alice@example.com")
+        .with_selective_disclosure();
+
+    let credential = authority.issue_credential(credential).await?;
+
+    client.set_credential(Some(credential)).await;
+
+    client
+        .present_credential_disclosing(route![channel, "credential_exchange"], &["role"])
+        .await?;
+
+    let attrs = AttributesStorageUtils::get_attributes(client.identifier(), &server_storage)
+        .await?
+        .unwrap();
+
+    assert_eq!(attrs.get("role").unwrap().as_slice(), b"member");
+    assert!(!attrs.contains_key("email"));
+
+    ctx.stop().await
+}
+
+#[ockam_macros::test]
+async fn mutual_selective_disclosure(ctx: &mut Context) -> Result<()> {
+    let vault = Vault::create();
+
+    let authority = Identity::create(ctx, &vault).await?;
+    let authorities = vec![authority.to_public().await?];
+
+    let server = Identity::create(ctx, &vault).await?;
+    let server_storage = InMemoryStorage::new();
+    let server_credential =
+        Credential::builder(server.identifier().clone()).with_attribute("is_admin", b"true");
+    let server_credential = authority.issue_credential(server_credential).await?;
+    server.set_credential(Some(server_credential)).await;
+
+    server
+        .create_secure_channel_listener("listener", TrustEveryonePolicy, &server_storage)
+        .await?;
+    server
+        .start_credentials_exchange_worker(
+            authorities.clone(),
+            "credential_exchange",
+            true,
+            server_storage.clone(),
+        )
+        .await?;
+
+    let client = Identity::create(ctx, &vault).await?;
+    let client_storage = InMemoryStorage::new();
+    let credential = Credential::builder(client.identifier().clone())
+        .with_attribute("role", b"member")
+        .with_attribute("email", b"alice@example.com")
+        .with_selective_disclosure();
+    let credential = authority.issue_credential(credential).await?;
+    client.set_credential(Some(credential)).await;
+
+    let channel = client
+        .create_secure_channel(route!["listener"], TrustEveryonePolicy, &client_storage)
+        .await?;
+
+    client
+        .present_credential_mutual_disclosing(
+            route![channel, "credential_exchange"],
+            &authorities,
+            &client_storage,
+            &["role"],
+        )
+        .await?;
+
+    let attrs = AttributesStorageUtils::get_attributes(client.identifier(), &server_storage)
+        .await?
+        .unwrap();
+    assert_eq!(attrs.get("role").unwrap().as_slice(), b"member");
+    assert!(!attrs.contains_key("email"));
+
+    let server_attrs = AttributesStorageUtils::get_attributes(server.identifier(), &client_storage)
+        .await?
+        .unwrap();
+    assert_eq!(server_attrs.get("is_admin").unwrap().as_slice(), b"true");
+
+    ctx.stop().await
+}
+
+#[ockam_macros::test]
+async fn tampered_disclosure(ctx: &mut Context) -> Result<()> {
+    let vault = Vault::create();
+
+    let authority = Identity::create(ctx, &vault).await?;
+    let client = Identity::create(ctx, &vault).await?;
+    let authorities = [authority.to_public().await?];
+
+    let role_credential = Credential::builder(client.identifier().clone())
+        .with_attribute("role", b"member")
+        .with_selective_disclosure();
+    let role_credential = authority.issue_credential(role_credential).await?;
+    client
+        .verify_self_credential(&role_credential, authorities.iter())
+        .await?;
+
+    // Only digests are signed, so altering a disclosed value must be detected.
+    let mut tampered = minicbor::to_vec(&role_credential)?;
+    let pos = tampered.windows(6).position(|w| w == b"member").unwrap();
+    tampered[pos..pos + 6].copy_from_slice(b"admin!");
+    let tampered: Credential = minicbor::decode(&tampered)?;
+    assert!(client
+        .verify_self_credential(&tampered, authorities.iter())
+        .await
+        .is_err());
+
+    ctx.stop().await
+}
+
+#[ockam_macros::test]
+async fn schema_validation(ctx: &mut Context) -> Result<()> {
+    let vault = Vault::create();
+
+    let schema = Schema::new(SchemaId(42), "test")
+        .with_attribute(AttributeSchema::required("level", AttributeType::Integer))
+        .with_max_validity(Duration::from_secs(3600));
+
+    let authority = Identity::create(ctx, &vault).await?;
+    authority.register_schema(schema.clone()).await;
+
+    let client = Identity::create(ctx, &vault).await?;
+
+    // Missing required attribute.
+    let missing = Credential::builder(client.identifier().clone()).with_schema(SchemaId(42));
+    assert!(authority.issue_credential(missing).await.is_err());
+
+    // Validity exceeds schema maximum.
+    let too_long = Credential::builder(client.identifier().clone())
+        .with_schema(SchemaId(42))
+        .with_attribute("level", b"3");
+    assert!(authority.issue_credential(too_long).await.is_err());
+
+    let valid = Credential::builder(client.identifier().clone())
+        .with_schema(SchemaId(42))
+        .with_attribute("level", b"3")
+        .valid_for(Duration::from_secs(60));
+    assert!(authority.issue_credential(valid).await.is_ok());
+
+    // An issuer unaware of the schema can still issue non-conforming credentials,
+    // which verifiers knowing the schema must reject.
+    let rogue = Identity::create(ctx, &vault).await?;
+    let invalid = Credential::builder(client.identifier().clone())
+        .with_schema(SchemaId(42))
+        .with_attribute("level", b"high")
+        .valid_for(Duration::from_secs(60));
+    let invalid = rogue.issue_credential(invalid).await?;
+
+    let server = Identity::create(ctx, &vault).await?;
+    let server_storage = InMemoryStorage::new();
+    server.register_schema(schema).await;
+    server
+        .create_secure_channel_listener("listener", TrustEveryonePolicy, &server_storage)
+        .await?;
+    server
+        .start_credentials_exchange_worker(
+            vec![rogue.to_public().await?],
+            "credential_exchange",
+            false,
+            server_storage.clone(),
+        )
+        .await?;
+
+    let channel = client
+        .create_secure_channel(
+            route!["listener"],
+            TrustIdentifierPolicy::new(server.identifier().clone()),
+            &InMemoryStorage::new(),
+        )
+        .await?;
+
+    client.set_credential(Some(invalid)).await;
+    assert!(client
+        .present_credential(route![channel, "credential_exchange"])
+        .await
+        .is_err());
+    assert!(
+        AttributesStorageUtils::get_attributes(client.identifier(), &server_storage)
+            .await?
+            .is_none()
+    );
+
+    ctx.stop().await
+}
+
 struct CountingWorker {
     msgs_count: Arc<AtomicI8>,
 }
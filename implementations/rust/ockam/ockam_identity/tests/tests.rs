@@ -2,7 +2,7 @@ use ockam_core::errcode::{Kind, Origin};
 use ockam_core::vault::{SecretAttributes, SecretPersistence, SecretType, SecretVault};
 use ockam_core::{Error, Result};
 use ockam_identity::authenticated_storage::mem::InMemoryStorage;
-use ockam_identity::Identity;
+use ockam_identity::{Identity, IdentityBundle};
 use ockam_node::Context;
 use ockam_vault::Vault;
 use rand::{thread_rng, RngCore};
@@ -180,3 +180,31 @@ async fn add_key(ctx: &mut Context) -> Result<()> {
 
     ctx.stop().await
 }
+
+#[ockam_macros::test]
+async fn test_export_import_with_secrets(ctx: &mut Context) -> Result<()> {
+    let alice_vault = Vault::create();
+    let alice = Identity::create(ctx, &alice_vault).await?;
+    alice.create_key("Second".into()).await?;
+    alice.rotate_root_key().await?;
+
+    let bundle = alice.export_with_secrets().await?.encode()?;
+    let bundle = IdentityBundle::decode(&bundle)?;
+
+    let restored_vault = Vault::create();
+    let restored = Identity::import_with_secrets(ctx, &bundle, &restored_vault).await?;
+    assert_eq!(alice.identifier(), restored.identifier());
+
+    let state = [1u8; 32];
+    let proof = restored.create_signature(&state, None).await?;
+    if !alice
+        .to_public()
+        .await?
+        .verify_signature(&proof, &state, None, &alice_vault)
+        .await?
+    {
+        return test_error("restored identity produced an invalid signature");
+    }
+
+    ctx.stop().await
+}
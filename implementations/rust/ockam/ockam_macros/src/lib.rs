@@ -116,6 +116,12 @@ pub fn node(attrs: TokenStream, item: TokenStream) -> TokenStream {
 ///   indefinitely. If the test times out it will panic. Defaults to 30000 (30
 ///   seconds).
 ///
+/// - `#[ockam::test(seed = 42)]`: the macro runs the test on a deterministic
+///   simulated node with a virtual clock, and seeds its `SimNetwork` with
+///   the given value. This
+///   requires the `simulation` feature of `ockam_node`. The timeout is still
+///   measured in wall-clock time.
+///
 /// Example of use:
 ///
 /// ```ignore
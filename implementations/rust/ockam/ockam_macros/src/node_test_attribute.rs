@@ -92,6 +92,30 @@ fn output(mut cont: Container) -> TokenStream {
     let test_fn_ident = &cont.test_fn.sig.ident;
     let ockam_crate = cont.data.attrs.ockam_crate;
     let timeout_ms = cont.data.attrs.timeout_ms;
+    let node_builder = match cont.data.attrs.seed {
+        None => quote! { NodeBuilder::without_access_control() },
+        Some(seed) => quote! { NodeBuilder::without_access_control().simulated(#seed) },
+    };
+    // The virtual clock of a simulated node jumps to the next timer while
+    // the node waits for real sockets, so its timeout runs on a thread.
+    let test_with_timeout = match cont.data.attrs.seed {
+        None => quote! {
+            timeout(Duration::from_millis(#timeout_ms), #test_fn_ident(&mut #ctx_ident)).await
+        },
+        Some(_) => quote! {
+            {
+                let (expired, deadline) = #ockam_crate::compat::tokio::sync::oneshot::channel::<()>();
+                std::thread::spawn(move || {
+                    std::thread::sleep(Duration::from_millis(#timeout_ms));
+                    let _ = expired.send(());
+                });
+                #ockam_crate::compat::tokio::select! {
+                    r = #test_fn_ident(&mut #ctx_ident) => Ok(r),
+                    _ = deadline => Err(()),
+                }
+            }
+        },
+    };
     cont.original_fn.block = parse2(quote! {
         {
             use core::panic::AssertUnwindSafe;
@@ -99,12 +123,12 @@ fn output(mut cont: Container) -> TokenStream {
             use ockam_core::{Error, errcode::{Origin, Kind}};
             use #ockam_crate::{NodeBuilder, compat::{tokio::time::timeout, futures::FutureExt}};
 
-            let (mut #ctx_ident, mut executor) = NodeBuilder::without_access_control().build();
+            let (mut #ctx_ident, mut executor) = #node_builder.build();
             executor
                 .execute(async move {
                     // Wraps the test function call in a `catch_unwind` to catch possible panics.
                     match AssertUnwindSafe(async {
-                        match timeout(Duration::from_millis(#timeout_ms), #test_fn_ident(&mut #ctx_ident)).await {
+                        match #test_with_timeout {
                             // Test went well. Return result as is.
                             Ok(r) => r,
                             // Test timed out. Return a custom error that we can handle.
@@ -133,7 +157,8 @@ fn output(mut cont: Container) -> TokenStream {
                 .expect("Test panicked")
                 .expect("Test function returned error");
         }
-    }).expect("Parsing failure");
+    })
+    .expect("Parsing failure");
     let input_fn = &cont.original_fn;
     quote! {
         #test_fn
@@ -214,12 +239,14 @@ impl<'a> Data<'a> {
 struct Attributes {
     ockam_crate: TokenStream,
     timeout_ms: u64,
+    seed: Option<u64>,
 }
 
 impl Attributes {
     fn from_ast(ctx: &Context, attrs: &AttributeArgs) -> Self {
         let mut ockam_crate = Attr::none(ctx, OCKAM_CRATE);
         let mut timeout_ms = Attr::none(ctx, TIMEOUT_MS);
+        let mut seed = Attr::none(ctx, SEED);
         for attr in attrs {
             match attr {
                 // Parse `#[ockam::test(crate = "ockam")]`
@@ -234,6 +261,12 @@ impl Attributes {
                         timeout_ms.set(&nv.path, timeout);
                     }
                 }
+                // Parse `#[ockam::test(seed = 42)]`
+                NestedMeta::Meta(NameValue(nv)) if nv.path == SEED => {
+                    if let Ok(value) = parse_lit_into_int::<u64>(ctx, SEED, &nv.lit) {
+                        seed.set(&nv.path, value);
+                    }
+                }
                 NestedMeta::Meta(m) => {
                     let path = m.path().into_token_stream().to_string().replace(' ', "");
                     ctx.error_spanned_by(m.path(), format!("unknown attribute `{}`", path));
@@ -246,6 +279,7 @@ impl Attributes {
         Self {
             ockam_crate: ockam_crate.get().unwrap_or(quote! { ockam_node }),
             timeout_ms: timeout_ms.get().unwrap_or(30_000),
+            seed: seed.get(),
         }
     }
 }
@@ -1,5 +1,7 @@
 use super::{Buffer, Checked, Code, Codec, Protocol};
-use crate::proto::{DnsAddr, Node, Project, Secure, Service, Space, Tcp};
+use crate::proto::{
+    Alias, Ble, DnsAddr, Node, Project, Secure, Service, Space, Tcp, Udp, Unix, Ws, Wss,
+};
 use crate::{Error, ProtoValue};
 use core::fmt;
 use unsigned_varint::decode;
@@ -42,9 +44,9 @@ impl Codec for StdCodec {
                 let (x, y) = input.split_at(16);
                 Ok((Checked(x), y))
             }
-            Tcp::CODE => {
+            c @ Tcp::CODE | c @ Udp::CODE => {
                 if input.len() < 2 {
-                    return Err(Error::required_bytes(Tcp::CODE, 2));
+                    return Err(Error::required_bytes(c, 2));
                 }
                 let (x, y) = input.split_at(2);
                 Ok((Checked(x), y))
@@ -54,7 +56,12 @@ impl Codec for StdCodec {
             | c @ Node::CODE
             | c @ Project::CODE
             | c @ Space::CODE
-            | c @ Secure::CODE => {
+            | c @ Secure::CODE
+            | c @ Ble::CODE
+            | c @ Alias::CODE
+            | c @ Ws::CODE
+            | c @ Wss::CODE
+            | c @ Unix::CODE => {
                 let (len, input) = decode::usize(input)?;
                 if input.len() < len {
                     return Err(Error::required_bytes(c, len));
@@ -79,6 +86,12 @@ impl Codec for StdCodec {
             Project::CODE => Project::read_bytes(input).is_ok(),
             Space::CODE => Space::read_bytes(input).is_ok(),
             Secure::CODE => Secure::read_bytes(input).is_ok(),
+            Udp::CODE => Udp::read_bytes(input).is_ok(),
+            Ws::CODE => Ws::read_bytes(input).is_ok(),
+            Wss::CODE => Wss::read_bytes(input).is_ok(),
+            Unix::CODE => Unix::read_bytes(input).is_ok(),
+            Ble::CODE => Ble::read_bytes(input).is_ok(),
+            Alias::CODE => Alias::read_bytes(input).is_ok(),
             _ => false,
         }
     }
@@ -96,6 +109,12 @@ impl Codec for StdCodec {
             Project::CODE => Project::read_bytes(val.data())?.write_bytes(buf),
             Space::CODE => Space::read_bytes(val.data())?.write_bytes(buf),
             Secure::CODE => Secure::read_bytes(val.data())?.write_bytes(buf),
+            Udp::CODE => Udp::read_bytes(val.data())?.write_bytes(buf),
+            Ws::CODE => Ws::read_bytes(val.data())?.write_bytes(buf),
+            Wss::CODE => Wss::read_bytes(val.data())?.write_bytes(buf),
+            Unix::CODE => Unix::read_bytes(val.data())?.write_bytes(buf),
+            Ble::CODE => Ble::read_bytes(val.data())?.write_bytes(buf),
+            Alias::CODE => Alias::read_bytes(val.data())?.write_bytes(buf),
             code => return Err(Error::unregistered(code)),
         }
         Ok(())
@@ -146,6 +165,30 @@ impl Codec for StdCodec {
                 Secure::read_str(value)?.write_bytes(buf);
                 Ok(())
             }
+            Udp::PREFIX => {
+                Udp::read_str(value)?.write_bytes(buf);
+                Ok(())
+            }
+            Ws::PREFIX => {
+                Ws::read_str(value)?.write_bytes(buf);
+                Ok(())
+            }
+            Wss::PREFIX => {
+                Wss::read_str(value)?.write_bytes(buf);
+                Ok(())
+            }
+            Unix::PREFIX => {
+                Unix::read_str(value)?.write_bytes(buf);
+                Ok(())
+            }
+            Ble::PREFIX => {
+                Ble::read_str(value)?.write_bytes(buf);
+                Ok(())
+            }
+            Alias::PREFIX => {
+                Alias::read_str(value)?.write_bytes(buf);
+                Ok(())
+            }
             _ => Err(Error::unregistered_prefix(prefix)),
         }
     }
@@ -195,6 +238,30 @@ impl Codec for StdCodec {
                 Secure::read_bytes(value)?.write_str(f)?;
                 Ok(())
             }
+            Udp::CODE => {
+                Udp::read_bytes(value)?.write_str(f)?;
+                Ok(())
+            }
+            Ws::CODE => {
+                Ws::read_bytes(value)?.write_str(f)?;
+                Ok(())
+            }
+            Wss::CODE => {
+                Wss::read_bytes(value)?.write_str(f)?;
+                Ok(())
+            }
+            Unix::CODE => {
+                Unix::read_bytes(value)?.write_str(f)?;
+                Ok(())
+            }
+            Ble::CODE => {
+                Ble::read_bytes(value)?.write_str(f)?;
+                Ok(())
+            }
+            Alias::CODE => {
+                Alias::read_bytes(value)?.write_str(f)?;
+                Ok(())
+            }
             _ => Err(Error::unregistered(code)),
         }
     }
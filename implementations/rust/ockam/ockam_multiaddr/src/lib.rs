@@ -6,6 +6,7 @@
 //! - [`Protocol`]: A type that can be read from and written to strings and bytes.
 //! - [`Codec`]: A type that understands protocols.
 //! - [`ProtoValue`]: A section of a MultiAddr.
+//! - [`pattern::Rule`]: A rule to rewrite MultiAddrs that match a pattern.
 
 #![cfg_attr(not(feature = "std"), no_std)]
 
@@ -16,6 +17,7 @@ mod registry;
 
 pub mod codec;
 pub mod iter;
+pub mod pattern;
 pub mod proto;
 
 use alloc::vec::Vec;
@@ -649,8 +651,9 @@ impl serde::Serialize for MultiAddr {
 impl<'de> serde::Deserialize<'de> for MultiAddr {
     fn deserialize<D: serde::Deserializer<'de>>(d: D) -> Result<Self, D::Error> {
         if d.is_human_readable() {
-            let s = <&'de str>::deserialize(d)?;
-            MultiAddr::try_from(s).map_err(serde::de::Error::custom)
+            // Not borrowed, so that formats unescaping strings are supported too.
+            let s = alloc::string::String::deserialize(d)?;
+            MultiAddr::try_from(s.as_str()).map_err(serde::de::Error::custom)
         } else {
             let b = <&'de [u8]>::deserialize(d)?;
             MultiAddr::try_from(b).map_err(serde::de::Error::custom)
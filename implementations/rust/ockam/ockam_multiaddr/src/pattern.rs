@@ -0,0 +1,461 @@
+//! Patterns over multi-addresses and rules to rewrite them.
+//!
+//! A [`Pattern`] is written like a multi-address, with some protocols
+//! or values replaced by placeholders:
+//!
+//! ```text
+//! /node/n1            the protocol /node with value n1
+//! /node/*             a /node protocol with any value
+//! /node/$name         a /node protocol, whose value is captured as `name`
+//! /*/*                any single protocol
+//! /{ip4,dnsaddr}/$h   an /ip4 or a /dnsaddr protocol, captured as `h`
+//! /..                 any number of protocols, including none
+//! /$rest..            any number of protocols, captured as `rest`
+//! ```
+//!
+//! A pattern has to match the whole multi-address, e.g.
+//! `/$a../service/$s` matches every address that ends with a service.
+//!
+//! A [`Template`] builds a new multi-address from the captures of a
+//! pattern. `/$x` inserts the protocols captured as `x`, `/service/$x`
+//! puts the value of the single protocol captured as `x` into a new
+//! `/service` protocol, and all other components are taken literally.
+//!
+//! A [`Rule`] pairs a pattern with a template, written `pattern => template`,
+//! and a [`Rewriter`] applies a list of rules to an address until none of
+//! them matches any more.
+
+use crate::{default_registry, Code, Error, MultiAddr, ProtoValue};
+use alloc::collections::btree_map::BTreeMap;
+use alloc::format;
+use alloc::string::{String, ToString};
+use alloc::vec::Vec;
+use core::fmt;
+use core::str::FromStr;
+
+/// Max. number of rewrites applied to a single address by a [`Rewriter`].
+const MAX_REWRITES: usize = 64;
+
+/// A pattern to match multi-addresses against.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Pattern {
+    src: String,
+    segs: Vec<Seg>,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+enum Seg {
+    /// A single protocol.
+    Proto(Codes, Value),
+    /// Any number of protocols, optionally captured.
+    Rest(Option<String>),
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+enum Codes {
+    Any,
+    Set(Vec<Code>),
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+enum Value {
+    Any,
+    Capture(String),
+    Literal(Vec<u8>),
+}
+
+/// The protocols captured by a pattern match.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct Captures(BTreeMap<String, MultiAddr>);
+
+impl Captures {
+    pub fn get(&self, name: &str) -> Option<&MultiAddr> {
+        self.0.get(name)
+    }
+
+    pub fn iter(&self) -> impl Iterator<Item = (&str, &MultiAddr)> {
+        self.0.iter().map(|(k, v)| (k.as_str(), v))
+    }
+}
+
+impl Pattern {
+    /// Match the whole address against this pattern.
+    ///
+    /// If more than one split of the address matches, the one that leaves
+    /// the leftmost `..` placeholders the shortest is chosen.
+    pub fn matches(&self, addr: &MultiAddr) -> Option<Captures> {
+        let protos = addr.iter().collect::<Vec<_>>();
+        let mut caps = Captures::default();
+        if match_segs(&self.segs, &protos, &mut caps) {
+            Some(caps)
+        } else {
+            None
+        }
+    }
+
+    /// The names of all captures.
+    fn captures(&self) -> impl Iterator<Item = (&str, bool)> {
+        self.segs.iter().filter_map(|s| match s {
+            Seg::Proto(_, Value::Capture(n)) => Some((n.as_str(), true)),
+            Seg::Rest(Some(n)) => Some((n.as_str(), false)),
+            _ => None,
+        })
+    }
+}
+
+fn match_segs(segs: &[Seg], protos: &[ProtoValue], caps: &mut Captures) -> bool {
+    let (seg, segs) = match segs.split_first() {
+        Some(x) => x,
+        None => return protos.is_empty(),
+    };
+    match seg {
+        Seg::Rest(name) => {
+            for n in 0..=protos.len() {
+                let (a, b) = protos.split_at(n);
+                if let Some(name) = name {
+                    caps.0.insert(name.clone(), to_multiaddr(a));
+                }
+                if match_segs(segs, b, caps) {
+                    return true;
+                }
+            }
+            if let Some(name) = name {
+                caps.0.remove(name);
+            }
+            false
+        }
+        Seg::Proto(codes, value) => {
+            let (p, protos) = match protos.split_first() {
+                Some(x) => x,
+                None => return false,
+            };
+            if let Codes::Set(cs) = codes {
+                if !cs.contains(&p.code()) {
+                    return false;
+                }
+            }
+            match value {
+                Value::Any => {}
+                Value::Literal(v) => {
+                    if *p.data() != v.as_slice() {
+                        return false;
+                    }
+                }
+                Value::Capture(name) => {
+                    caps.0
+                        .insert(name.clone(), to_multiaddr(core::slice::from_ref(p)));
+                }
+            }
+            if match_segs(segs, protos, caps) {
+                return true;
+            }
+            if let Value::Capture(name) = value {
+                caps.0.remove(name);
+            }
+            false
+        }
+    }
+}
+
+fn to_multiaddr(protos: &[ProtoValue]) -> MultiAddr {
+    MultiAddr::default()
+        .try_with(protos.iter().cloned())
+        .expect("valid protocols")
+}
+
+impl FromStr for Pattern {
+    type Err = Error;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let mut segs = Vec::new();
+        let mut toks = tokens(s)?;
+        while let Some(t) = toks.next() {
+            if t == ".." {
+                segs.push(Seg::Rest(None));
+                continue;
+            }
+            if let Some(name) = t.strip_prefix('$').and_then(|t| t.strip_suffix("..")) {
+                segs.push(Seg::Rest(Some(capture_name(name)?)));
+                continue;
+            }
+            let v = toks
+                .next()
+                .ok_or_else(|| Error::message(format!("missing value of {t:?} in {s:?}")))?;
+            let codes = if t == "*" {
+                Codes::Any
+            } else if let Some(set) = t.strip_prefix('{').and_then(|t| t.strip_suffix('}')) {
+                Codes::Set(set.split(',').map(code_of).collect::<Result<_, _>>()?)
+            } else {
+                Codes::Set(alloc::vec![code_of(t)?])
+            };
+            let value = if v == "*" {
+                Value::Any
+            } else if let Some(name) = v.strip_prefix('$') {
+                Value::Capture(capture_name(name)?)
+            } else if let Codes::Set(cs) = &codes {
+                if cs.len() != 1 {
+                    let m = format!("literal value {v:?} needs a single protocol in {s:?}");
+                    return Err(Error::message(m));
+                }
+                let prefix = t.trim_start_matches('{').trim_end_matches('}');
+                let a = MultiAddr::from_str(&format!("/{prefix}/{v}"))?;
+                let p = a.first().expect("one protocol");
+                Value::Literal(p.data().to_vec())
+            } else {
+                let m = format!("literal value {v:?} needs a single protocol in {s:?}");
+                return Err(Error::message(m));
+            };
+            segs.push(Seg::Proto(codes, value))
+        }
+        let this = Pattern {
+            src: s.to_string(),
+            segs,
+        };
+        let mut names = Vec::new();
+        for (n, _) in this.captures() {
+            if names.contains(&n) {
+                return Err(Error::message(format!("${n} is captured twice in {s:?}")));
+            }
+            names.push(n)
+        }
+        Ok(this)
+    }
+}
+
+impl fmt::Display for Pattern {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(&self.src)
+    }
+}
+
+/// A template to construct a multi-address from pattern captures.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Template {
+    src: String,
+    segs: Vec<TSeg>,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+enum TSeg {
+    /// Insert the captured protocols.
+    Splice(String),
+    /// A new protocol with the value of a captured protocol.
+    Value(&'static str, String),
+    /// Literal protocols.
+    Literal(MultiAddr),
+}
+
+impl Template {
+    /// Build a multi-address from the given captures.
+    pub fn expand(&self, caps: &Captures) -> Result<MultiAddr, Error> {
+        let mut ma = MultiAddr::default();
+        for s in &self.segs {
+            match s {
+                TSeg::Splice(name) => ma.try_extend(missing(caps, name)?)?,
+                TSeg::Value(prefix, name) => {
+                    // Values are moved between protocols through their textual
+                    // representation, e.g. `/node/x` becomes `/service/x`.
+                    let p = missing(caps, name)?.to_string();
+                    let v = p[1..].split_once('/').map(|(_, v)| v).unwrap_or_default();
+                    ma.try_extend(&MultiAddr::from_str(&format!("/{prefix}/{v}"))?)?
+                }
+                TSeg::Literal(a) => ma.try_extend(a)?,
+            }
+        }
+        Ok(ma)
+    }
+
+    fn captures(&self) -> impl Iterator<Item = (&str, bool)> {
+        self.segs.iter().filter_map(|s| match s {
+            TSeg::Splice(n) => Some((n.as_str(), false)),
+            TSeg::Value(_, n) => Some((n.as_str(), true)),
+            TSeg::Literal(_) => None,
+        })
+    }
+}
+
+fn missing<'a>(caps: &'a Captures, name: &str) -> Result<&'a MultiAddr, Error> {
+    caps.get(name)
+        .ok_or_else(|| Error::message(format!("missing capture ${name}")))
+}
+
+impl FromStr for Template {
+    type Err = Error;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let mut segs = Vec::new();
+        let mut toks = tokens(s)?;
+        while let Some(t) = toks.next() {
+            if let Some(name) = t.strip_prefix('$') {
+                segs.push(TSeg::Splice(capture_name(name)?));
+                continue;
+            }
+            let v = toks
+                .next()
+                .ok_or_else(|| Error::message(format!("missing value of {t:?} in {s:?}")))?;
+            if let Some(name) = v.strip_prefix('$') {
+                let prefix = default_registry()
+                    .prefixes()
+                    .find(|p| *p == t)
+                    .ok_or_else(|| Error::unregistered_prefix(t))?;
+                segs.push(TSeg::Value(prefix, capture_name(name)?))
+            } else {
+                segs.push(TSeg::Literal(MultiAddr::from_str(&format!("/{t}/{v}"))?))
+            }
+        }
+        Ok(Template {
+            src: s.to_string(),
+            segs,
+        })
+    }
+}
+
+impl fmt::Display for Template {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(&self.src)
+    }
+}
+
+/// A rewrite rule of the form `pattern => template`.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Rule {
+    pattern: Pattern,
+    template: Template,
+}
+
+impl Rule {
+    /// Create a rule, checking that the template only uses captures the
+    /// pattern defines, and that it only takes values of single protocols.
+    pub fn new(pattern: Pattern, template: Template) -> Result<Self, Error> {
+        for (name, single) in template.captures() {
+            match pattern.captures().find(|(n, _)| *n == name) {
+                None => {
+                    let m = format!("${name} is not captured by {pattern}");
+                    return Err(Error::message(m));
+                }
+                Some((_, false)) if single => {
+                    let m = format!("${name} captures more than one protocol in {pattern}");
+                    return Err(Error::message(m));
+                }
+                Some(_) => {}
+            }
+        }
+        Ok(Rule { pattern, template })
+    }
+
+    pub fn pattern(&self) -> &Pattern {
+        &self.pattern
+    }
+
+    pub fn template(&self) -> &Template {
+        &self.template
+    }
+
+    /// Rewrite the address if it matches the pattern of this rule.
+    pub fn apply(&self, addr: &MultiAddr) -> Result<Option<MultiAddr>, Error> {
+        match self.pattern.matches(addr) {
+            Some(caps) => self.template.expand(&caps).map(Some),
+            None => Ok(None),
+        }
+    }
+}
+
+impl FromStr for Rule {
+    type Err = Error;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let (p, t) = s
+            .split_once("=>")
+            .ok_or_else(|| Error::message(format!("expected `pattern => template`: {s:?}")))?;
+        Rule::new(p.trim().parse()?, t.trim().parse()?)
+    }
+}
+
+impl fmt::Display for Rule {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{} => {}", self.pattern, self.template)
+    }
+}
+
+/// An ordered list of rewrite rules.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct Rewriter {
+    rules: Vec<Rule>,
+}
+
+impl Rewriter {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn add(&mut self, r: Rule) -> &mut Self {
+        self.rules.push(r);
+        self
+    }
+
+    pub fn rules(&self) -> &[Rule] {
+        &self.rules
+    }
+
+    /// Rewrite the address until no rule matches any more.
+    ///
+    /// At each step the first matching rule is applied. Rules which keep
+    /// matching their own output are reported as an error.
+    pub fn rewrite(&self, addr: &MultiAddr) -> Result<MultiAddr, Error> {
+        let mut addr = addr.clone();
+        'next: for _ in 0..MAX_REWRITES {
+            for r in &self.rules {
+                if let Some(a) = r.apply(&addr)? {
+                    addr = a;
+                    continue 'next;
+                }
+            }
+            return Ok(addr);
+        }
+        Err(Error::message(format!(
+            "{addr} is still being rewritten after {MAX_REWRITES} steps"
+        )))
+    }
+}
+
+impl Extend<Rule> for Rewriter {
+    fn extend<T: IntoIterator<Item = Rule>>(&mut self, iter: T) {
+        self.rules.extend(iter)
+    }
+}
+
+impl FromIterator<Rule> for Rewriter {
+    fn from_iter<T: IntoIterator<Item = Rule>>(iter: T) -> Self {
+        Rewriter {
+            rules: iter.into_iter().collect(),
+        }
+    }
+}
+
+/// Split a pattern or template into its components.
+fn tokens(s: &str) -> Result<impl Iterator<Item = &str>, Error> {
+    if s.is_empty() {
+        return Ok(None.into_iter().flatten());
+    }
+    match s.strip_prefix('/') {
+        Some(r) if !r.split('/').any(str::is_empty) => Ok(Some(r.split('/')).into_iter().flatten()),
+        _ => Err(Error::message(format!("invalid pattern {s:?}"))),
+    }
+}
+
+fn code_of(prefix: &str) -> Result<Code, Error> {
+    default_registry()
+        .code_of(prefix)
+        .ok_or_else(|| Error::unregistered_prefix(prefix))
+}
+
+fn capture_name(s: &str) -> Result<String, Error> {
+    if !s.is_empty()
+        && s.chars()
+            .all(|c| c.is_alphanumeric() || c == '_' || c == '-')
+    {
+        Ok(s.to_string())
+    } else {
+        Err(Error::message(format!("invalid capture name {s:?}")))
+    }
+}
@@ -1,6 +1,8 @@
 use super::{Buffer, Checked, Code, Protocol};
 use crate::Error;
 use alloc::borrow::Cow;
+use alloc::string::String;
+use alloc::vec::Vec;
 use core::fmt;
 use core::ops::Deref;
 use core::str::{self, FromStr};
@@ -153,6 +155,51 @@ impl Protocol<'_> for Tcp {
     }
 }
 
+/// A UDP port number.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct Udp(pub u16);
+
+impl Udp {
+    pub fn new(v: u16) -> Self {
+        Udp(v)
+    }
+}
+
+impl Deref for Udp {
+    type Target = u16;
+
+    fn deref(&self) -> &Self::Target {
+        &self.0
+    }
+}
+
+impl Protocol<'_> for Udp {
+    const CODE: Code = Code::new(273);
+    const PREFIX: &'static str = "udp";
+
+    fn read_str(input: Checked<&str>) -> Result<Self, Error> {
+        u16::from_str(&input).map(Udp).map_err(Error::message)
+    }
+
+    fn read_bytes(input: Checked<&[u8]>) -> Result<Self, Error> {
+        let mut b = [0; 2];
+        b.copy_from_slice(&input);
+        Ok(Udp(u16::from_be_bytes(b)))
+    }
+
+    fn write_str(&self, f: &mut fmt::Formatter) -> Result<(), Error> {
+        write!(f, "/{}/{}", Self::PREFIX, self.0)?;
+        Ok(())
+    }
+
+    fn write_bytes(&self, buf: &mut dyn Buffer) {
+        let mut b = encode::u32_buffer();
+        let uvi = encode::u32(Self::CODE.into(), &mut b);
+        buf.extend_with(uvi);
+        buf.extend_with(&self.0.to_be_bytes())
+    }
+}
+
 macro_rules! gen_str_proto {
     ($t:ident, $c:literal, $p:literal) => {
         #[derive(Debug, Clone, PartialEq, Eq, PartialOrd, Ord, Hash)]
@@ -209,3 +256,102 @@ gen_str_proto!(Node, 72526, "node");
 gen_str_proto!(Project, 82526, "project");
 gen_str_proto!(Space, 92526, "space");
 gen_str_proto!(Secure, 99526, "secure");
+gen_str_proto!(Ble, 106526, "ble");
+gen_str_proto!(Alias, 107526, "alias");
+
+/// Like `gen_str_proto` but for values which are paths.
+///
+/// The binary form holds the path as is. In the string form the path
+/// is percent-encoded, so that its slashes are not taken as protocol
+/// separators, e.g. `/unix/%2Ftmp%2Fnode.sock`.
+macro_rules! gen_path_proto {
+    ($t:ident, $c:literal, $p:literal) => {
+        #[derive(Debug, Clone, PartialEq, Eq, PartialOrd, Ord, Hash)]
+        pub struct $t<'a>(Cow<'a, str>);
+
+        impl<'a> $t<'a> {
+            pub fn new<S: Into<Cow<'a, str>>>(s: S) -> Self {
+                Self(s.into())
+            }
+        }
+
+        impl Deref for $t<'_> {
+            type Target = str;
+
+            fn deref(&self) -> &Self::Target {
+                &self.0
+            }
+        }
+
+        impl<'a> Protocol<'a> for $t<'a> {
+            const CODE: Code = Code::new($c);
+            const PREFIX: &'static str = $p;
+
+            fn read_str(input: Checked<&'a str>) -> Result<Self, Error> {
+                percent_decode(input.0).map(Self)
+            }
+
+            fn read_bytes(input: Checked<&'a [u8]>) -> Result<Self, Error> {
+                let s = str::from_utf8(&input).map_err(Error::message)?;
+                Ok(Self(Cow::Borrowed(s)))
+            }
+
+            fn write_str(&self, f: &mut fmt::Formatter) -> Result<(), Error> {
+                write!(f, "/{}/", Self::PREFIX)?;
+                percent_encode(&self.0, f)
+            }
+
+            fn write_bytes(&self, buf: &mut dyn Buffer) {
+                let mut b = encode::u32_buffer();
+                let uvi = encode::u32(Self::CODE.into(), &mut b);
+                buf.extend_with(uvi);
+                let mut b = encode::usize_buffer();
+                let uvi = encode::usize(self.0.len(), &mut b);
+                buf.extend_with(uvi);
+                buf.extend_with(self.0.as_bytes())
+            }
+        }
+    };
+}
+
+// A WebSocket endpoint is given by the host and TCP port before it, and
+// these protocols add the request path, e.g. `/ip4/10.0.0.1/tcp/80/ws/%2F`.
+gen_path_proto!(Ws, 104526, "ws");
+gen_path_proto!(Wss, 105526, "wss");
+gen_path_proto!(Unix, 400, "unix");
+
+/// Write `s` with `%` and `/` percent-encoded.
+fn percent_encode(s: &str, f: &mut fmt::Formatter) -> Result<(), Error> {
+    for c in s.chars() {
+        match c {
+            '%' => f.write_str("%25")?,
+            '/' => f.write_str("%2F")?,
+            c => write!(f, "{c}")?,
+        }
+    }
+    Ok(())
+}
+
+/// Decode all percent-encoded bytes of `s`.
+fn percent_decode(s: &str) -> Result<Cow<'_, str>, Error> {
+    if !s.contains('%') {
+        return Ok(Cow::Borrowed(s));
+    }
+    let mut b = s.as_bytes();
+    let mut v = Vec::with_capacity(b.len());
+    while let Some((&x, rest)) = b.split_first() {
+        if x == b'%' {
+            let h = rest
+                .get(..2)
+                .and_then(|h| str::from_utf8(h).ok())
+                .and_then(|h| u8::from_str_radix(h, 16).ok())
+                .ok_or_else(|| Error::message("invalid percent-encoding"))?;
+            v.push(h);
+            b = &rest[2..]
+        } else {
+            v.push(x);
+            b = rest
+        }
+    }
+    String::from_utf8(v).map(Cow::Owned).map_err(Error::message)
+}
@@ -1,6 +1,8 @@
 use super::{Code, Codec, Protocol};
 use crate::codec::StdCodec;
-use crate::proto::{DnsAddr, Node, Project, Secure, Service, Space, Tcp};
+use crate::proto::{
+    Alias, Ble, DnsAddr, Node, Project, Secure, Service, Space, Tcp, Udp, Unix, Ws, Wss,
+};
 use alloc::collections::btree_map::BTreeMap;
 use alloc::sync::Arc;
 use core::fmt;
@@ -13,6 +15,7 @@ pub struct Registry {
 struct RegistryImpl {
     bytes: BTreeMap<Code, Arc<dyn Codec>>,
     strings: BTreeMap<&'static str, Arc<dyn Codec>>,
+    codes: BTreeMap<&'static str, Code>,
 }
 
 impl fmt::Debug for Registry {
@@ -37,6 +40,18 @@ impl Default for Registry {
         r.register(Space::CODE, Space::PREFIX, std_codec.clone());
         #[allow(clippy::redundant_clone)]
         r.register(Secure::CODE, Secure::PREFIX, std_codec.clone());
+        #[allow(clippy::redundant_clone)]
+        r.register(Udp::CODE, Udp::PREFIX, std_codec.clone());
+        #[allow(clippy::redundant_clone)]
+        r.register(Ws::CODE, Ws::PREFIX, std_codec.clone());
+        #[allow(clippy::redundant_clone)]
+        r.register(Wss::CODE, Wss::PREFIX, std_codec.clone());
+        #[allow(clippy::redundant_clone)]
+        r.register(Unix::CODE, Unix::PREFIX, std_codec.clone());
+        #[allow(clippy::redundant_clone)]
+        r.register(Ble::CODE, Ble::PREFIX, std_codec.clone());
+        #[allow(clippy::redundant_clone)]
+        r.register(Alias::CODE, Alias::PREFIX, std_codec.clone());
         #[cfg(feature = "std")]
         r.register(
             crate::proto::Ip4::CODE,
@@ -61,6 +76,10 @@ impl Registry {
         self.inner.strings.get(prefix).cloned()
     }
 
+    pub fn code_of(&self, prefix: &str) -> Option<Code> {
+        self.inner.codes.get(prefix).copied()
+    }
+
     pub fn codes(&self) -> impl Iterator<Item = Code> + '_ {
         self.inner.bytes.keys().copied()
     }
@@ -83,6 +102,7 @@ impl RegistryBuilder {
         RegistryBuilder(RegistryImpl {
             bytes: BTreeMap::new(),
             strings: BTreeMap::new(),
+            codes: BTreeMap::new(),
         })
     }
 
@@ -100,6 +120,7 @@ impl RegistryBuilder {
     {
         self.0.bytes.insert(code, codec.clone());
         self.0.strings.insert(prefix, codec);
+        self.0.codes.insert(prefix, code);
         self
     }
 
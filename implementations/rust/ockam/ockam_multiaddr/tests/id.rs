@@ -1,5 +1,7 @@
 use core::fmt;
-use ockam_multiaddr::proto::{DnsAddr, Ip4, Ip6, Node, Project, Secure, Service, Space, Tcp};
+use ockam_multiaddr::proto::{
+    Alias, Ble, DnsAddr, Ip4, Ip6, Node, Project, Secure, Service, Space, Tcp, Udp, Unix, Ws, Wss,
+};
 use ockam_multiaddr::{Code, Match, MultiAddr, Protocol};
 use quickcheck::{quickcheck, Arbitrary, Gen};
 use rand::distributions::{Alphanumeric, DistString};
@@ -132,6 +134,30 @@ quickcheck! {
                         addr.push_back(Space::new("space")).unwrap();
                         prot.push_back(Space::CODE);
                     }
+                    Udp::CODE => {
+                        addr.push_back(Udp::new(0)).unwrap();
+                        prot.push_back(Udp::CODE);
+                    }
+                    Ws::CODE => {
+                        addr.push_back(Ws::new("/")).unwrap();
+                        prot.push_back(Ws::CODE);
+                    }
+                    Wss::CODE => {
+                        addr.push_back(Wss::new("/ws")).unwrap();
+                        prot.push_back(Wss::CODE);
+                    }
+                    Unix::CODE => {
+                        addr.push_back(Unix::new("/tmp/node.sock")).unwrap();
+                        prot.push_back(Unix::CODE);
+                    }
+                    Ble::CODE => {
+                        addr.push_back(Ble::new("ble")).unwrap();
+                        prot.push_back(Ble::CODE);
+                    }
+                    Alias::CODE => {
+                        addr.push_back(Alias::new("alias")).unwrap();
+                        prot.push_back(Alias::CODE);
+                    }
                     _ => unreachable!()
                 }
             }
@@ -153,6 +179,12 @@ const PROTOS: &[Code] = &[
     Node::CODE,
     Project::CODE,
     Space::CODE,
+    Udp::CODE,
+    Ws::CODE,
+    Wss::CODE,
+    Unix::CODE,
+    Ble::CODE,
+    Alias::CODE,
 ];
 
 impl Arbitrary for Addr {
@@ -169,6 +201,12 @@ impl Arbitrary for Addr {
                 Project::CODE => a.push_back(Project::new(gen_string())).unwrap(),
                 Space::CODE => a.push_back(Space::new(gen_string())).unwrap(),
                 Node::CODE => a.push_back(Node::new(gen_string())).unwrap(),
+                Udp::CODE => a.push_back(Udp::new(u16::arbitrary(g))).unwrap(),
+                Ws::CODE => a.push_back(Ws::new(gen_path())).unwrap(),
+                Wss::CODE => a.push_back(Wss::new(gen_path())).unwrap(),
+                Unix::CODE => a.push_back(Unix::new(gen_path())).unwrap(),
+                Ble::CODE => a.push_back(Ble::new(gen_string())).unwrap(),
+                Alias::CODE => a.push_back(Alias::new(gen_string())).unwrap(),
                 _ => unreachable!(),
             }
         }
@@ -220,3 +258,36 @@ fn gen_string() -> String {
     s.retain(|c| c != '/');
     s
 }
+
+fn gen_path() -> String {
+    let mut g = rand::thread_rng();
+    let n = g.gen_range(0..4);
+    let mut p = String::new();
+    for _ in 0..n {
+        p.push('/');
+        p.push_str(&Alphanumeric.sample_string(&mut g, 7));
+        if g.gen() {
+            p.push('%')
+        }
+    }
+    p
+}
+
+#[test]
+fn paths_are_percent_encoded() {
+    let a = MultiAddr::from_str("/ip4/127.0.0.1/tcp/80/ws/%2Fchat%2Fv1/service/api").unwrap();
+    let ws = a.iter().nth(2).unwrap();
+    assert_eq!(&*ws.cast::<Ws>().unwrap(), "/chat/v1");
+    assert_eq!(
+        a.to_string(),
+        "/ip4/127.0.0.1/tcp/80/ws/%2Fchat%2Fv1/service/api"
+    );
+
+    let mut b = MultiAddr::default();
+    b.push_back(Unix::new("/tmp/100%.sock")).unwrap();
+    assert_eq!(b.to_string(), "/unix/%2Ftmp%2F100%25.sock");
+    assert_eq!(b, MultiAddr::from_str(&b.to_string()).unwrap());
+
+    assert!(MultiAddr::from_str("/unix/%2").is_err());
+    assert!(MultiAddr::from_str("/udp/65536").is_err());
+}
@@ -0,0 +1,78 @@
+use ockam_multiaddr::pattern::{Pattern, Rewriter, Rule};
+use ockam_multiaddr::MultiAddr;
+
+fn addr(s: &str) -> MultiAddr {
+    s.parse().unwrap()
+}
+
+#[test]
+fn match_placeholders() {
+    let p: Pattern = "/{ip4,dnsaddr}/$host/tcp/*/..".parse().unwrap();
+    let c = p
+        .matches(&addr("/dnsaddr/localhost/tcp/4000/service/api"))
+        .unwrap();
+    assert_eq!(c.get("host"), Some(&addr("/dnsaddr/localhost")));
+    assert!(p.matches(&addr("/ip4/127.0.0.1/tcp/4000")).is_some());
+    assert!(p.matches(&addr("/ip6/::1/tcp/4000")).is_none());
+    assert!(p.matches(&addr("/ip4/127.0.0.1")).is_none());
+
+    let p: Pattern = "/$a../service/api/$b..".parse().unwrap();
+    let c = p
+        .matches(&addr("/node/n1/service/api/service/api"))
+        .unwrap();
+    assert_eq!(c.get("a"), Some(&addr("/node/n1")));
+    assert_eq!(c.get("b"), Some(&addr("/service/api")));
+    assert!(p.matches(&addr("/node/n1/service/echo")).is_none());
+
+    let p: Pattern = "/*/*".parse().unwrap();
+    assert!(p.matches(&addr("/secure/api")).is_some());
+    assert!(p.matches(&addr("")).is_none());
+}
+
+#[test]
+fn invalid_patterns() {
+    for p in [
+        "node/n1",
+        "/node",
+        "/node//",
+        "/foo/bar",
+        "/{node,service}/n1",
+        "/*/n1",
+        "/node/$n/service/$n",
+        "/node/$",
+    ] {
+        assert!(p.parse::<Pattern>().is_err(), "{p}");
+    }
+    assert!("/node/$n.. => /service/$n".parse::<Rule>().is_err());
+    assert!("/node/$n => /service/$m".parse::<Rule>().is_err());
+    assert!("/node/$n".parse::<Rule>().is_err());
+}
+
+#[test]
+fn rewrite() {
+    let rules: Rewriter = [
+        "/$a../node/n1/$b.. => /$a/dnsaddr/localhost/tcp/6252/$b",
+        "/$a../project/$p/$b.. => /$a/node/$p/$b",
+        "/$a../node/$n/$b.. => /$a/service/$n/$b",
+    ]
+    .into_iter()
+    .map(|r| r.parse().unwrap())
+    .collect();
+
+    assert_eq!(
+        rules
+            .rewrite(&addr("/project/n1/service/forward_to_blue/node/n2"))
+            .unwrap(),
+        addr("/dnsaddr/localhost/tcp/6252/service/forward_to_blue/service/n2")
+    );
+    assert_eq!(
+        rules.rewrite(&addr("/service/echo")).unwrap(),
+        addr("/service/echo")
+    );
+
+    let rules: Rewriter = ["/$a.. => /$a/service/x"]
+        .into_iter()
+        .map(|r| r.parse().unwrap())
+        .collect();
+    assert!(rules.rewrite(&addr("/service/echo")).is_err());
+}
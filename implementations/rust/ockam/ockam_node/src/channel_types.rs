@@ -1,11 +1,306 @@
-/// Sender used to send payload messages
-pub type MessageSender<T> = crate::tokio::sync::mpsc::Sender<T>;
-/// Receiver used to receive payload messages
-pub type MessageReceiver<T> = crate::tokio::sync::mpsc::Receiver<T>;
+use crate::error::{NodeError, NodeReason, WorkerReason};
+use core::{
+    fmt,
+    sync::atomic::{AtomicUsize, Ordering},
+    task::{Context, Poll, Waker},
+};
+use ockam_core::compat::{
+    collections::VecDeque,
+    sync::{Arc, Mutex},
+    vec::Vec,
+};
+use ockam_core::Result;
 
-/// Create message channel
-pub fn message_channel<T>() -> (MessageSender<T>, MessageReceiver<T>) {
-    crate::tokio::sync::mpsc::channel(16)
+/// Default number of messages a worker mailbox can hold
+pub const DEFAULT_MAILBOX_CAPACITY: usize = 16;
+
+/// Behaviour of a worker mailbox when a message arrives while it is full
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum OverflowPolicy {
+    /// Make the sender wait until there is room in the mailbox
+    Block,
+    /// Discard the incoming message
+    DropNewest,
+    /// Discard the oldest queued message to make room for the incoming one
+    DropOldest,
+    /// Reject the incoming message with an error to the sender
+    Error,
+}
+
+/// Capacity and overflow behaviour of a worker mailbox
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct MailboxConfig {
+    capacity: usize,
+    overflow: OverflowPolicy,
+}
+
+impl MailboxConfig {
+    /// Create a mailbox configuration
+    ///
+    /// A capacity of `0` is treated as `1`.
+    pub fn new(capacity: usize, overflow: OverflowPolicy) -> Self {
+        Self {
+            capacity: capacity.max(1),
+            overflow,
+        }
+    }
+
+    /// Maximum number of queued messages
+    pub fn capacity(&self) -> usize {
+        self.capacity
+    }
+
+    /// What happens when the mailbox is full
+    pub fn overflow(&self) -> OverflowPolicy {
+        self.overflow
+    }
+}
+
+impl Default for MailboxConfig {
+    fn default() -> Self {
+        Self::new(DEFAULT_MAILBOX_CAPACITY, OverflowPolicy::Block)
+    }
+}
+
+/// Fill state of a worker mailbox, as reported by the router
+#[derive(Clone, Debug)]
+pub struct MailboxStats {
+    /// The mailbox configuration
+    pub config: MailboxConfig,
+    /// Number of currently queued messages
+    pub len: usize,
+    /// Number of messages dropped or rejected because the mailbox was full
+    pub overflows: usize,
+    /// Number of messages taken out of the mailbox by the worker
+    pub received: usize,
+}
+
+struct State<T> {
+    queue: VecDeque<T>,
+    senders: usize,
+    closed: bool,
+    recv_waker: Option<Waker>,
+    /// The waker of every blocked send, by send id
+    send_wakers: Vec<(usize, Waker)>,
+    next_send: usize,
+}
+
+struct Shared<T> {
+    config: MailboxConfig,
+    overflows: AtomicUsize,
+    received: AtomicUsize,
+    state: Mutex<State<T>>,
+}
+
+impl<T> Shared<T> {
+    fn with_state<R>(&self, f: impl FnOnce(&mut State<T>) -> R) -> R {
+        #[allow(clippy::unwrap_used)]
+        let mut state = self.state.lock().unwrap();
+        f(&mut state)
+    }
+}
+
+/// Sender used to send payload messages into a worker mailbox
+pub struct MessageSender<T> {
+    shared: Arc<Shared<T>>,
+}
+
+/// Receiver used to receive payload messages from a worker mailbox
+pub struct MessageReceiver<T> {
+    shared: Arc<Shared<T>>,
+}
+
+/// Create a bounded message channel for a worker mailbox
+pub fn message_channel<T>(config: MailboxConfig) -> (MessageSender<T>, MessageReceiver<T>) {
+    let shared = Arc::new(Shared {
+        config,
+        overflows: AtomicUsize::new(0),
+        received: AtomicUsize::new(0),
+        state: Mutex::new(State {
+            queue: VecDeque::with_capacity(config.capacity),
+            senders: 1,
+            closed: false,
+            recv_waker: None,
+            send_wakers: Vec::new(),
+            next_send: 0,
+        }),
+    });
+    (
+        MessageSender {
+            shared: Arc::clone(&shared),
+        },
+        MessageReceiver { shared },
+    )
+}
+
+impl<T> MessageSender<T> {
+    /// Queue a message, applying the mailbox [`OverflowPolicy`] if it is full
+    ///
+    /// Messages discarded by [`OverflowPolicy::DropNewest`] or
+    /// [`OverflowPolicy::DropOldest`] are not reported to the sender.
+    pub async fn send(&self, value: T) -> Result<()> {
+        let mut value = Some(value);
+        let slot = SendSlot::new(&self.shared);
+        futures::future::poll_fn(|cx| self.poll_send(cx, slot.id, &mut value)).await
+    }
+
+    fn poll_send(
+        &self,
+        cx: &mut Context<'_>,
+        id: usize,
+        value: &mut Option<T>,
+    ) -> Poll<Result<()>> {
+        let config = self.shared.config;
+        self.shared.with_state(|state| {
+            if state.closed {
+                return Poll::Ready(Err(NodeError::NodeState(NodeReason::Unknown).internal()));
+            }
+
+            if state.queue.len() >= config.capacity {
+                match config.overflow {
+                    OverflowPolicy::Block => {
+                        match state.send_wakers.iter_mut().find(|(i, _)| *i == id) {
+                            Some((_, waker)) if waker.will_wake(cx.waker()) => {}
+                            Some((_, waker)) => *waker = cx.waker().clone(),
+                            None => state.send_wakers.push((id, cx.waker().clone())),
+                        }
+                        return Poll::Pending;
+                    }
+                    OverflowPolicy::DropNewest => {
+                        self.shared.overflows.fetch_add(1, Ordering::Relaxed);
+                        value.take();
+                        return Poll::Ready(Ok(()));
+                    }
+                    OverflowPolicy::DropOldest => {
+                        self.shared.overflows.fetch_add(1, Ordering::Relaxed);
+                        state.queue.pop_front();
+                    }
+                    OverflowPolicy::Error => {
+                        self.shared.overflows.fetch_add(1, Ordering::Relaxed);
+                        return Poll::Ready(Err(
+                            NodeError::WorkerState(WorkerReason::MailboxFull).exhausted()
+                        ));
+                    }
+                }
+            }
+
+            if let Some(value) = value.take() {
+                state.queue.push_back(value);
+            }
+            if let Some(waker) = state.recv_waker.take() {
+                waker.wake();
+            }
+            Poll::Ready(Ok(()))
+        })
+    }
+
+    /// Current fill state of the mailbox
+    pub fn stats(&self) -> MailboxStats {
+        MailboxStats {
+            config: self.shared.config,
+            len: self.shared.with_state(|state| state.queue.len()),
+            overflows: self.shared.overflows.load(Ordering::Relaxed),
+            received: self.shared.received.load(Ordering::Relaxed),
+        }
+    }
+}
+
+/// Identifies a pending send, and forgets its waker once the send is over
+struct SendSlot<'a, T> {
+    shared: &'a Shared<T>,
+    id: usize,
+}
+
+impl<'a, T> SendSlot<'a, T> {
+    fn new(shared: &'a Shared<T>) -> Self {
+        let id = shared.with_state(|state| {
+            state.next_send = state.next_send.wrapping_add(1);
+            state.next_send
+        });
+        Self { shared, id }
+    }
+}
+
+impl<T> Drop for SendSlot<'_, T> {
+    fn drop(&mut self) {
+        self.shared
+            .with_state(|state| state.send_wakers.retain(|(i, _)| *i != self.id))
+    }
+}
+
+impl<T> Clone for MessageSender<T> {
+    fn clone(&self) -> Self {
+        self.shared.with_state(|state| state.senders += 1);
+        Self {
+            shared: Arc::clone(&self.shared),
+        }
+    }
+}
+
+impl<T> Drop for MessageSender<T> {
+    fn drop(&mut self) {
+        self.shared.with_state(|state| {
+            state.senders -= 1;
+            if state.senders == 0 {
+                if let Some(waker) = state.recv_waker.take() {
+                    waker.wake();
+                }
+            }
+        })
+    }
+}
+
+impl<T> fmt::Debug for MessageSender<T> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("MessageSender")
+            .field("config", &self.shared.config)
+            .finish()
+    }
+}
+
+impl<T> MessageReceiver<T> {
+    /// Receive the next message, or `None` once all senders are gone
+    pub async fn recv(&mut self) -> Option<T> {
+        futures::future::poll_fn(|cx| self.poll_recv(cx)).await
+    }
+
+    /// Poll for the next message
+    pub fn poll_recv(&mut self, cx: &mut Context<'_>) -> Poll<Option<T>> {
+        self.shared.with_state(|state| {
+            if let Some(value) = state.queue.pop_front() {
+                self.shared.received.fetch_add(1, Ordering::Relaxed);
+                for (_, waker) in state.send_wakers.drain(..) {
+                    waker.wake();
+                }
+                Poll::Ready(Some(value))
+            } else if state.senders == 0 {
+                Poll::Ready(None)
+            } else {
+                state.recv_waker = Some(cx.waker().clone());
+                Poll::Pending
+            }
+        })
+    }
+}
+
+impl<T> Drop for MessageReceiver<T> {
+    fn drop(&mut self) {
+        self.shared.with_state(|state| {
+            state.closed = true;
+            state.queue.clear();
+            for (_, waker) in state.send_wakers.drain(..) {
+                waker.wake();
+            }
+        })
+    }
+}
+
+impl<T> fmt::Debug for MessageReceiver<T> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("MessageReceiver")
+            .field("config", &self.shared.config)
+            .finish()
+    }
 }
 
 /// Router sender
@@ -29,3 +324,29 @@ pub type SmallReceiver<T> = crate::tokio::sync::mpsc::Receiver<T>;
 pub fn small_channel<T>() -> (SmallSender<T>, SmallReceiver<T>) {
     crate::tokio::sync::mpsc::channel(1)
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use futures::task::noop_waker;
+    use futures::FutureExt;
+
+    #[test]
+    fn blocked_send_keeps_a_single_waker() {
+        let (tx, mut rx) = message_channel::<u8>(MailboxConfig::new(1, OverflowPolicy::Block));
+        let waker = noop_waker();
+        let mut cx = Context::from_waker(&waker);
+        assert!(tx.send(1).boxed().poll_unpin(&mut cx).is_ready());
+
+        let mut blocked = tx.send(2).boxed();
+        for _ in 0..10 {
+            assert!(blocked.poll_unpin(&mut cx).is_pending());
+        }
+        assert_eq!(tx.shared.with_state(|s| s.send_wakers.len()), 1);
+
+        // A cancelled send leaves no waker behind
+        drop(blocked);
+        assert_eq!(tx.shared.with_state(|s| s.send_wakers.len()), 0);
+        assert_eq!(rx.poll_recv(&mut cx), Poll::Ready(Some(1)));
+    }
+}
@@ -1,12 +1,15 @@
 use crate::async_drop::AsyncDrop;
-use crate::channel_types::{message_channel, small_channel, SmallReceiver, SmallSender};
+use crate::channel_types::{
+    message_channel, small_channel, MailboxConfig, MailboxStats, MessageReceiver, SmallReceiver,
+    SmallSender,
+};
 use crate::tokio::{self, runtime::Handle, time::timeout};
 use crate::{
     error::*,
     parser,
     relay::{CtrlSignal, ProcessorRelay, RelayMessage},
     router::SenderPair,
-    Cancel, NodeMessage, ShutdownType, WorkerBuilder,
+    Cancel, NodeMessage, ShutdownType, WorkerBuilder, WorkerInfo,
 };
 use core::{
     sync::atomic::{AtomicUsize, Ordering},
@@ -16,7 +19,7 @@ use ockam_core::compat::{boxed::Box, string::String, sync::Arc, vec::Vec};
 use ockam_core::{
     errcode::{Kind, Origin},
     Address, AddressSet, AllowAll, AsyncTryClone, Error, LocalMessage, Mailbox, Mailboxes, Message,
-    Processor, Result, Route, TransportMessage, TransportType, Worker,
+    Processor, Result, Route, TraceContext, TransportMessage, TransportType, Worker,
 };
 use ockam_core::{AccessControl, LocalInfo};
 
@@ -53,9 +56,12 @@ pub struct Context {
     mailboxes: Mailboxes,
     sender: SmallSender<NodeMessage>,
     rt: Handle,
-    receiver: SmallReceiver<RelayMessage>,
+    receiver: MessageReceiver<RelayMessage>,
     async_drop_sender: Option<AsyncDropSender>,
     mailbox_count: Arc<AtomicUsize>,
+    tracing_context: Option<TraceContext>,
+    #[cfg(feature = "simulation")]
+    simulation_seed: Option<u64>,
 }
 
 impl Drop for Context {
@@ -126,7 +132,8 @@ impl Context {
     /// Create a new context
     ///
     /// This function returns a new instance of Context, the relay
-    /// sender pair, and relay control signal receiver.
+    /// sender pair, and relay control signal receiver.  The
+    /// `mailbox_config` bounds the message queue of the new context.
     ///
     /// `async_drop_sender` must be provided when creating a detached
     /// Context type (i.e. not backed by a worker relay).
@@ -134,10 +141,12 @@ impl Context {
         rt: Handle,
         sender: SmallSender<NodeMessage>,
         mailboxes: Mailboxes,
+        mailbox_config: MailboxConfig,
         async_drop_sender: Option<AsyncDropSender>,
     ) -> (Self, SenderPair, SmallReceiver<CtrlSignal>) {
-        let (mailbox_tx, receiver) = message_channel();
+        let (mailbox_tx, receiver) = message_channel(mailbox_config);
         let (ctrl_tx, ctrl_rx) = small_channel();
+        let access_control = mailboxes.main_mailbox().access_control().name();
         (
             Self {
                 rt,
@@ -146,10 +155,14 @@ impl Context {
                 receiver,
                 async_drop_sender,
                 mailbox_count: Arc::new(0.into()),
+                tracing_context: None,
+                #[cfg(feature = "simulation")]
+                simulation_seed: None,
             },
             SenderPair {
                 msgs: mailbox_tx,
                 ctrl: ctrl_tx,
+                access_control,
             },
             ctrl_rx,
         )
@@ -160,6 +173,35 @@ impl Context {
         self.mailboxes.main_address()
     }
 
+    /// Return the trace context attached to messages sent from this context
+    pub fn tracing_context(&self) -> Option<&TraceContext> {
+        self.tracing_context.as_ref()
+    }
+
+    /// Set the trace context attached to messages sent from this context
+    ///
+    /// Use [`TraceContext::new_root`] to start a new trace.  Workers
+    /// continue the trace of the message they are handling, if any,
+    /// and clear it once the message has been handled.
+    pub fn set_tracing_context(&mut self, tracing_context: Option<TraceContext>) {
+        self.tracing_context = tracing_context;
+    }
+
+    /// Return the seed of the simulated node this context belongs to
+    ///
+    /// Only the root context of a node built with
+    /// [`NodeBuilder::simulated`](crate::NodeBuilder::simulated) and
+    /// contexts detached from it carry the seed.
+    #[cfg(feature = "simulation")]
+    pub fn simulation_seed(&self) -> Option<u64> {
+        self.simulation_seed
+    }
+
+    #[cfg(feature = "simulation")]
+    pub(crate) fn set_simulation_seed(&mut self, seed: Option<u64>) {
+        self.simulation_seed = seed;
+    }
+
     /// Return all addresses of the current worker
     pub fn aliases(&self) -> AddressSet {
         self.mailboxes.aliases()
@@ -222,12 +264,19 @@ impl Context {
 
         // Create a new context and get access to the mailbox senders
         let addresses = mailboxes.addresses();
-        let (ctx, sender, _) = Self::new(
+        let (mut ctx, sender, _) = Self::new(
             self.rt.clone(),
             self.sender.clone(),
             mailboxes,
+            MailboxConfig::default(),
             Some(drop_sender),
         );
+        // Detached contexts act on behalf of their parent
+        ctx.tracing_context = self.tracing_context;
+        #[cfg(feature = "simulation")]
+        {
+            ctx.simulation_seed = self.simulation_seed;
+        }
 
         // Create a "detached relay" and register it with the router
         let (msg, mut rx) =
@@ -290,6 +339,31 @@ impl Context {
         Ok(())
     }
 
+    /// Start a new worker instance with a custom mailbox configuration
+    ///
+    /// Like [`start_worker()`](Self::start_worker), but the worker's
+    /// mailbox is bounded by the given [`MailboxConfig`] instead of
+    /// the default capacity and [`OverflowPolicy::Block`].
+    ///
+    /// [`OverflowPolicy::Block`]: crate::channel_types::OverflowPolicy::Block
+    pub async fn start_worker_with_mailbox<NM, NW, S>(
+        &self,
+        address: S,
+        worker: NW,
+        mailbox_config: MailboxConfig,
+    ) -> Result<()>
+    where
+        S: Into<AddressSet>,
+        NM: Message + Send + 'static,
+        NW: Worker<Context = Context, Message = NM>,
+    {
+        WorkerBuilder::with_inherited_access_control(self, address, worker)
+            .mailbox_config(mailbox_config)
+            .start(self)
+            .await?;
+        Ok(())
+    }
+
     /// Start a new processor instance at the given address set
     ///
     /// A processor is an asynchronous piece of code that runs a
@@ -313,8 +387,13 @@ impl Context {
         let main_mailbox = Mailbox::new(addr, Arc::new(AllowAll)); // TODO FIXME
         let mailboxes = Mailboxes::new(main_mailbox, vec![]);
 
-        let (ctx, senders, ctrl_rx) =
-            Context::new(self.rt.clone(), self.sender.clone(), mailboxes, None);
+        let (ctx, senders, ctrl_rx) = Context::new(
+            self.rt.clone(),
+            self.sender.clone(),
+            mailboxes,
+            MailboxConfig::default(),
+            None,
+        );
 
         // Initialise the processor relay with the ctrl receiver
         ProcessorRelay::<P>::init(&self.rt, processor, ctx, ctrl_rx);
@@ -587,7 +666,8 @@ impl Context {
 
         // Pack the payload into a TransportMessage
         let payload = msg.encode().unwrap();
-        let mut transport_msg = TransportMessage::v1(route.clone(), Route::new(), payload);
+        let mut transport_msg = TransportMessage::v1(route.clone(), Route::new(), payload)
+            .with_tracing_context(self.tracing_context);
         transport_msg.return_route.modify().append(sending_address);
 
         // Pack transport message into a LocalMessage wrapper
@@ -597,7 +677,7 @@ impl Context {
         let msg = RelayMessage::new(addr, local_msg, route, needs_wrapping);
 
         // Send the packed user message with associated route
-        sender.send(msg).await.map_err(NodeError::from_send_err)?;
+        sender.send(msg).await?;
 
         Ok(())
     }
@@ -614,7 +694,12 @@ impl Context {
     ///
     /// [`Context::send`]: crate::Context::send
     /// [`TransportMessage`]: ockam_core::TransportMessage
-    pub async fn forward(&self, local_msg: LocalMessage) -> Result<()> {
+    pub async fn forward(&self, mut local_msg: LocalMessage) -> Result<()> {
+        // Record this hop as the parent of the next one
+        if self.tracing_context.is_some() {
+            local_msg.transport_mut().tracing_context = self.tracing_context;
+        }
+
         // First resolve the next hop in the route
         let (reply_tx, mut reply_rx) = small_channel();
         let next = local_msg.transport().onward_route.next().unwrap(); // TODO: communicate bad routes
@@ -635,7 +720,7 @@ impl Context {
         let msg = RelayMessage::new(addr, local_msg, onward, needs_wrapping);
 
         // Forward the message
-        sender.send(msg).await.map_err(NodeError::from_send_err)?;
+        sender.send(msg).await?;
 
         Ok(())
     }
@@ -763,6 +848,40 @@ impl Context {
             .take_workers()
     }
 
+    /// Return the mailbox fill state and overflow count of all workers
+    ///
+    /// Workers that are shutting down are not included.
+    pub async fn list_mailboxes(&self) -> Result<Vec<(Address, MailboxStats)>> {
+        let (msg, mut reply_rx) = NodeMessage::list_mailboxes();
+
+        self.sender
+            .send(msg)
+            .await
+            .map_err(NodeError::from_send_err)?;
+
+        reply_rx
+            .recv()
+            .await
+            .ok_or_else(|| NodeError::NodeState(NodeReason::Unknown).internal())??
+            .take_mailboxes()
+    }
+
+    /// Return a description of every address registered on this node
+    pub async fn list_worker_info(&self) -> Result<Vec<WorkerInfo>> {
+        let (msg, mut reply_rx) = NodeMessage::list_worker_info();
+
+        self.sender
+            .send(msg)
+            .await
+            .map_err(NodeError::from_send_err)?;
+
+        reply_rx
+            .recv()
+            .await
+            .ok_or_else(|| NodeError::NodeState(NodeReason::Unknown).internal())??
+            .take_worker_info()
+    }
+
     /// Register a router for a specific address type
     pub async fn register<A: Into<Address>>(&self, type_: TransportType, addr: A) -> Result<()> {
         self.register_impl(type_, addr.into()).await
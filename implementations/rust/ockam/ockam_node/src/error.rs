@@ -39,6 +39,10 @@ impl NodeError {
     pub fn conflict(self) -> Error {
         Error::new(Origin::Node, Kind::Conflict, self)
     }
+    /// Turn a NodeError into a Kind::ResourceExhausted ockam_core::Error
+    pub fn exhausted(self) -> Error {
+        Error::new(Origin::Node, Kind::ResourceExhausted, self)
+    }
     /// Turn a NodeError into a Kind::Internal ockam_core::Error
     pub fn internal(self) -> Error {
         Error::new(Origin::Node, Kind::Internal, self)
@@ -142,6 +146,8 @@ pub enum WorkerReason {
     Faulty,
     /// The worker is otherwise corrupt and can not be recovered
     Corrupt,
+    /// The worker mailbox is full and rejects new messages
+    MailboxFull,
 }
 
 impl fmt::Display for WorkerReason {
@@ -153,6 +159,7 @@ impl fmt::Display for WorkerReason {
                 Self::Shutdown => "target worker is shutting down",
                 Self::Faulty => "target worker is faulty and waiting for supervisor",
                 Self::Corrupt => "target worker is corrupt and can not be recovered",
+                Self::MailboxFull => "target worker mailbox is full",
             }
         )
     }
@@ -40,7 +40,37 @@ pub struct Executor {
 
 impl Default for Executor {
     fn default() -> Self {
-        let rt = Runtime::new().unwrap();
+        Self::with_runtime(Runtime::new().unwrap())
+    }
+}
+
+impl Executor {
+    /// Create a new Ockam node [`Executor`] instance
+    pub fn new() -> Self {
+        Executor::default()
+    }
+
+    /// Create an [`Executor`] running a deterministic simulation
+    ///
+    /// All workers are scheduled on a single thread and the clock is
+    /// virtual: it only advances when every task is idle, jumping
+    /// straight to the next pending timer.  `Context::sleep`,
+    /// `receive_timeout` and `DelayedEvent` therefore complete
+    /// instantly in wall-clock time while keeping their ordering.
+    ///
+    /// Use [`SimNetwork`](crate::SimNetwork) to connect several
+    /// simulated nodes together.
+    #[cfg(feature = "simulation")]
+    pub fn simulated() -> Self {
+        let rt = tokio::runtime::Builder::new_current_thread()
+            .enable_all()
+            .start_paused(true)
+            .build()
+            .unwrap();
+        Self::with_runtime(rt)
+    }
+
+    fn with_runtime(rt: Runtime) -> Self {
         let router = Router::new();
         #[cfg(feature = "metrics")]
         let metrics = Metrics::new(&rt, router.get_metrics_readout());
@@ -51,13 +81,6 @@ impl Default for Executor {
             metrics,
         }
     }
-}
-
-impl Executor {
-    /// Create a new Ockam node [`Executor`] instance
-    pub fn new() -> Self {
-        Executor::default()
-    }
 
     /// Get access to the internal message sender
     pub(crate) fn sender(&self) -> SmallSender<NodeMessage> {
@@ -53,9 +53,15 @@ mod executor;
 mod local_info;
 mod messages;
 mod node;
+#[cfg(feature = "opentelemetry")]
+pub mod opentelemetry;
 mod parser;
 mod relay;
 mod router;
+#[cfg(feature = "simulation")]
+pub mod simulation;
+#[cfg(feature = "std")]
+mod supervisor;
 mod worker_builder;
 
 pub use cancel::*;
@@ -65,6 +71,10 @@ pub use error::*;
 pub use executor::*;
 pub use local_info::*;
 pub use messages::*;
+#[cfg(feature = "simulation")]
+pub use simulation::{SimLink, SimNetwork, SIM};
+#[cfg(feature = "std")]
+pub use supervisor::*;
 pub use worker_builder::WorkerBuilder;
 
 pub use node::{NodeBuilder, NullWorker};
@@ -1,4 +1,6 @@
-use crate::channel_types::{small_channel, MessageSender, SmallReceiver, SmallSender};
+use crate::channel_types::{
+    small_channel, MailboxStats, MessageSender, SmallReceiver, SmallSender,
+};
 use crate::{
     error::{NodeError, NodeReason, RouterReason, WorkerReason},
     relay::RelayMessage,
@@ -26,6 +28,10 @@ pub enum NodeMessage {
     },
     /// Return a list of all worker addresses
     ListWorkers(SmallSender<NodeReplyResult>),
+    /// Return the mailbox fill state of all workers
+    ListMailboxes(SmallSender<NodeReplyResult>),
+    /// Return a description of every registered address
+    ListWorkerInfo(SmallSender<NodeReplyResult>),
     /// Add an existing address to a cluster
     SetCluster(Address, String, SmallSender<NodeReplyResult>),
     /// Stop an existing worker
@@ -55,6 +61,8 @@ impl fmt::Display for NodeMessage {
         match self {
             NodeMessage::StartWorker { .. } => write!(f, "StartWorker"),
             NodeMessage::ListWorkers(_) => write!(f, "ListWorkers"),
+            NodeMessage::ListMailboxes(_) => write!(f, "ListMailboxes"),
+            NodeMessage::ListWorkerInfo(_) => write!(f, "ListWorkerInfo"),
             NodeMessage::SetCluster(_, _, _) => write!(f, "SetCluster"),
             NodeMessage::StopWorker(_, _, _) => write!(f, "StopWorker"),
             NodeMessage::StartProcessor(_, _, _) => write!(f, "StartProcessor"),
@@ -119,6 +127,18 @@ impl NodeMessage {
         (Self::ListWorkers(tx), rx)
     }
 
+    /// Create a list mailboxes message and reply receiver
+    pub fn list_mailboxes() -> (Self, SmallReceiver<NodeReplyResult>) {
+        let (tx, rx) = small_channel();
+        (Self::ListMailboxes(tx), rx)
+    }
+
+    /// Create a list worker info message and reply receiver
+    pub fn list_worker_info() -> (Self, SmallReceiver<NodeReplyResult>) {
+        let (tx, rx) = small_channel();
+        (Self::ListWorkerInfo(tx), rx)
+    }
+
     /// Create a set cluster message and reply receiver
     pub fn set_cluster(addr: Address, label: String) -> (Self, SmallReceiver<NodeReplyResult>) {
         let (tx, rx) = small_channel();
@@ -155,6 +175,29 @@ impl NodeMessage {
     }
 }
 
+/// Description of an address registered with the router
+#[derive(Clone, Debug)]
+pub struct WorkerInfo {
+    /// The primary address
+    pub address: Address,
+    /// Additional addresses of the same worker
+    pub aliases: Vec<Address>,
+    /// The cluster this worker belongs to, if any
+    pub cluster: Option<String>,
+    /// Whether this is a processor rather than a worker
+    pub processor: bool,
+    /// Whether this is a detached context without a worker relay
+    pub detached: bool,
+    /// Type name of the access control guarding the main address
+    pub access_control: String,
+    /// Number of messages waiting in the mailbox
+    pub queued: usize,
+    /// Number of messages taken out of the mailbox
+    pub handled: usize,
+    /// Start time in seconds since the Unix epoch, if a clock is available
+    pub started_at: Option<u64>,
+}
+
 /// The reply/result of a Node
 pub type NodeReplyResult = core::result::Result<RouterReply, Error>;
 
@@ -165,6 +208,10 @@ pub enum RouterReply {
     Ok,
     /// A list of worker addresses
     Workers(Vec<Address>),
+    /// Mailbox fill state per worker address
+    Mailboxes(Vec<(Address, MailboxStats)>),
+    /// Description of every registered address
+    WorkerInfo(Vec<WorkerInfo>),
     /// Message sender to a specific worker
     Sender {
         /// The address a message is being sent to
@@ -263,6 +310,16 @@ impl RouterReply {
         Ok(Self::Workers(v))
     }
 
+    /// Return [NodeReply::Mailboxes] for the given stats
+    pub fn mailboxes(v: Vec<(Address, MailboxStats)>) -> NodeReplyResult {
+        Ok(Self::Mailboxes(v))
+    }
+
+    /// Return [NodeReply::WorkerInfo] for the given descriptions
+    pub fn worker_info(v: Vec<WorkerInfo>) -> NodeReplyResult {
+        Ok(Self::WorkerInfo(v))
+    }
+
     /// Return [NodeReply::Sender] for the given information
     pub fn sender(
         addr: Address,
@@ -288,6 +345,22 @@ impl RouterReply {
         }
     }
 
+    /// Consume the wrapper and return [NodeReply::Mailboxes]
+    pub fn take_mailboxes(self) -> Result<Vec<(Address, MailboxStats)>> {
+        match self {
+            Self::Mailboxes(m) => Ok(m),
+            _ => Err(NodeError::NodeState(NodeReason::Unknown).internal()),
+        }
+    }
+
+    /// Consume the wrapper and return [NodeReply::WorkerInfo]
+    pub fn take_worker_info(self) -> Result<Vec<WorkerInfo>> {
+        match self {
+            Self::WorkerInfo(w) => Ok(w),
+            _ => Err(NodeError::NodeState(NodeReason::Unknown).internal()),
+        }
+    }
+
     /// Consume the wrapper and return [NodeReply::State]
     pub fn take_state(self) -> Result<bool> {
         match self {
@@ -1,3 +1,4 @@
+use crate::channel_types::MailboxConfig;
 use crate::{Context, Executor};
 use ockam_core::compat::sync::Arc;
 use ockam_core::{AccessControl, Address, AllowAll, Mailbox, Mailboxes};
@@ -21,6 +22,8 @@ where
 {
     access_control: AC,
     logging: bool,
+    #[cfg(feature = "simulation")]
+    simulation_seed: Option<u64>,
 }
 
 impl NodeBuilder<AllowAll> {
@@ -29,6 +32,8 @@ impl NodeBuilder<AllowAll> {
         Self {
             access_control: AllowAll,
             logging: true,
+            #[cfg(feature = "simulation")]
+            simulation_seed: None,
         }
     }
 }
@@ -42,6 +47,8 @@ where
         Self {
             access_control,
             logging: true,
+            #[cfg(feature = "simulation")]
+            simulation_seed: None,
         }
     }
 
@@ -53,6 +60,20 @@ where
         }
     }
 
+    /// Run this node on a deterministic simulated runtime
+    ///
+    /// The `seed` is available from the root context via
+    /// [`Context::simulation_seed`] and drives the loss decisions of a
+    /// [`SimNetwork`](crate::SimNetwork).  See
+    /// [`Executor::simulated`] for details.
+    #[cfg(feature = "simulation")]
+    pub fn simulated(self, seed: u64) -> Self {
+        Self {
+            simulation_seed: Some(seed),
+            ..self
+        }
+    }
+
     /// Consume this builder and yield a new Ockam Node
     #[inline]
     pub fn build(self) -> (Context, Executor) {
@@ -65,18 +86,30 @@ where
             self.access_control
         );
 
+        #[cfg(feature = "simulation")]
+        let mut exe = if self.simulation_seed.is_some() {
+            Executor::simulated()
+        } else {
+            Executor::new()
+        };
+        #[cfg(not(feature = "simulation"))]
         let mut exe = Executor::new();
         let addr: Address = "app".into();
 
         // The root application worker needs a mailbox and relay to accept
         // messages from workers, and to buffer incoming transcoded data.
-        let (ctx, sender, _) = Context::new(
+        #[allow(unused_mut)]
+        let (mut ctx, sender, _) = Context::new(
             exe.runtime().clone(),
             exe.sender(),
             Mailboxes::new(Mailbox::new(addr, Arc::new(self.access_control)), vec![]),
+            MailboxConfig::default(),
             None,
         );
 
+        #[cfg(feature = "simulation")]
+        ctx.set_simulation_seed(self.simulation_seed);
+
         // Register this mailbox handle with the executor
         exe.initialize_system("app", sender);
 
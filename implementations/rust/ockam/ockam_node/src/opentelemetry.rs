@@ -0,0 +1,97 @@
+//! Export of traced messages to OpenTelemetry
+//!
+//! When a `tracing_opentelemetry` layer records the spans of the
+//! process, the span of every hop is made a child of the span which
+//! sent the message, and its own identifier is propagated to the next
+//! hop.  A message can then be followed across nodes in any collector.
+
+use ockam_core::TraceContext;
+use opentelemetry_api::trace::{
+    SpanContext, SpanId, TraceContextExt, TraceFlags, TraceId, TraceState,
+};
+use opentelemetry_api::Context;
+use tracing::Span;
+use tracing_opentelemetry::OpenTelemetrySpanExt;
+
+/// Return the trace context of `span`
+///
+/// This is `None` when no OpenTelemetry layer records the span.
+pub fn span_trace_context(span: &Span) -> Option<TraceContext> {
+    let cx = span.context();
+    let sc = cx.span().span_context().clone();
+    if !sc.is_valid() {
+        return None;
+    }
+    TraceContext::new(
+        sc.trace_id().to_bytes(),
+        sc.span_id().to_bytes(),
+        sc.trace_flags().to_u8(),
+    )
+}
+
+/// Make `span` a child of the span which sent a message
+///
+/// Return the trace context of `span`, see [`span_trace_context`].
+pub fn continue_trace(span: &Span, parent: &TraceContext) -> Option<TraceContext> {
+    let remote = SpanContext::new(
+        TraceId::from_bytes(parent.trace_id()),
+        SpanId::from_bytes(parent.span_id()),
+        TraceFlags::new(parent.flags()),
+        true,
+        TraceState::default(),
+    );
+    span.set_parent(Context::new().with_remote_span_context(remote));
+    span_trace_context(span)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use core::time::Duration;
+    use ockam_core::async_trait;
+    use opentelemetry_api::sdk::export::trace::{ExportResult, SpanData, SpanExporter};
+    use opentelemetry_api::sdk::trace::TracerProvider;
+    use opentelemetry_api::trace::TracerProvider as _;
+    use std::sync::mpsc::{channel, Sender};
+    use tracing::info_span;
+    use tracing_subscriber::prelude::*;
+
+    #[derive(Debug)]
+    struct Exporter(Sender<SpanData>);
+
+    #[async_trait]
+    impl SpanExporter for Exporter {
+        async fn export(&mut self, batch: Vec<SpanData>) -> ExportResult {
+            for span in batch {
+                let _ = self.0.send(span);
+            }
+            Ok(())
+        }
+    }
+
+    #[test]
+    fn exported_span_is_a_child_of_the_sender() {
+        let (tx, rx) = channel();
+        let provider = TracerProvider::builder()
+            .with_simple_exporter(Exporter(tx))
+            .build();
+        let layer = tracing_opentelemetry::layer().with_tracer(provider.tracer("test"));
+        let subscriber = tracing_subscriber::registry().with(layer);
+
+        let parent = TraceContext::new_root();
+        let current = tracing::subscriber::with_default(subscriber, || {
+            continue_trace(&info_span!("ockam.hop"), &parent)
+        })
+        .expect("the span is recorded");
+        assert_eq!(current.trace_id(), parent.trace_id());
+
+        // The next hop is a child of the exported span
+        let span = rx.recv_timeout(Duration::from_secs(5)).unwrap();
+        assert_eq!(span.span_context.trace_id().to_bytes(), parent.trace_id());
+        assert_eq!(span.span_context.span_id().to_bytes(), current.span_id());
+        assert_eq!(span.parent_span_id.to_bytes(), parent.span_id());
+
+        // Without an OpenTelemetry layer spans have no trace context
+        assert!(span_trace_context(&info_span!("ockam.hop")).is_none());
+    }
+}
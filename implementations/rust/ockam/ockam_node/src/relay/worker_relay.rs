@@ -5,6 +5,7 @@ use crate::tokio::runtime::Handle;
 use crate::{parser, Context};
 use core::marker::PhantomData;
 use ockam_core::{Message, Result, Routed, Worker};
+use tracing::{field, Instrument, Span};
 
 /// Worker relay machinery
 ///
@@ -60,6 +61,33 @@ where
         Ok(routed)
     }
 
+    /// Create a span for handling a traced message
+    ///
+    /// The field names follow OpenTelemetry conventions so the span
+    /// can be exported with its trace and parent identifiers.
+    fn hop_span(&mut self, relay_msg: &RelayMessage) -> Span {
+        let parent = relay_msg.local_msg.transport().tracing_context;
+        match (parent, self.ctx.tracing_context().copied()) {
+            (Some(parent), Some(current)) => {
+                let span = info_span!(
+                    "ockam.hop",
+                    trace_id = %current.trace_id_hex(),
+                    span_id = field::Empty,
+                    parent_span_id = %parent.span_id_hex(),
+                    address = %relay_msg.addr,
+                );
+                // Messages sent by the handler are children of the exported span
+                #[cfg(feature = "opentelemetry")]
+                let current =
+                    crate::opentelemetry::continue_trace(&span, &parent).unwrap_or(current);
+                span.record("span_id", field::display(current.span_id_hex()));
+                self.ctx.set_tracing_context(Some(current));
+                span
+            }
+            _ => Span::none(),
+        }
+    }
+
     /// Receive and handle a single message
     ///
     /// Report errors as they occur, and signal whether the loop should
@@ -73,6 +101,17 @@ where
             }
         };
 
+        // Messages sent while handling this one continue its trace, and
+        // the trace does not extend to anything sent afterwards
+        let parent = relay_msg.local_msg.transport().tracing_context;
+        self.ctx.set_tracing_context(parent.map(|tc| tc.child()));
+        let res = self.handle_relay_message(relay_msg).await;
+        self.ctx.set_tracing_context(None);
+        res
+    }
+
+    /// Authorize and handle a message received by the worker
+    async fn handle_relay_message(&mut self, relay_msg: RelayMessage) -> Result<bool> {
         // Call the worker authorization function - pass errors up
         let routed = Self::wrap_direct_message(&relay_msg)?;
         if !self.worker.is_authorized(&mut self.ctx, routed).await? {
@@ -85,7 +124,11 @@ where
 
         // Call the worker handle function - pass errors up
         let routed = Self::wrap_direct_message(&relay_msg)?;
-        self.worker.handle_message(&mut self.ctx, routed).await?;
+        let span = self.hop_span(&relay_msg);
+        self.worker
+            .handle_message(&mut self.ctx, routed)
+            .instrument(span)
+            .await?;
 
         // Signal to the outer loop that we would like to run again
         Ok(true)
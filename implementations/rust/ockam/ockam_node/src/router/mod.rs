@@ -27,6 +27,8 @@ use ockam_core::{Address, Result, TransportType};
 pub struct SenderPair {
     pub msgs: MessageSender<RelayMessage>,
     pub ctrl: SmallSender<CtrlSignal>,
+    /// Name of the access control guarding the main mailbox
+    pub access_control: &'static str,
 }
 
 /// A combined address type and local worker router
@@ -96,6 +98,7 @@ impl Router {
                 AddressMeta {
                     processor: false,
                     detached: true,
+                    access_control: senders.access_control,
                 },
             ),
         );
@@ -247,6 +250,22 @@ impl Router {
                 .await
                 .map_err(|_| NodeError::NodeState(NodeReason::Unknown).internal())?,
 
+            ListMailboxes(sender) => sender
+                .send(RouterReply::mailboxes(
+                    self.map
+                        .internal
+                        .iter()
+                        .filter_map(|(addr, rec)| Some((addr.clone(), rec.mailbox_stats()?)))
+                        .collect(),
+                ))
+                .await
+                .map_err(|_| NodeError::NodeState(NodeReason::Unknown).internal())?,
+
+            ListWorkerInfo(sender) => sender
+                .send(RouterReply::worker_info(self.map.worker_info()))
+                .await
+                .map_err(|_| NodeError::NodeState(NodeReason::Unknown).internal())?,
+
             SetCluster(addr, label, reply) => {
                 debug!("Setting cluster on address {}", addr);
                 let msg = self.map.set_cluster(label, addr);
@@ -1,8 +1,8 @@
-use crate::channel_types::{MessageSender, SmallSender};
+use crate::channel_types::{MailboxStats, MessageSender, SmallSender};
 use crate::relay::{CtrlSignal, RelayMessage};
 use crate::{
     error::{NodeError, NodeReason},
-    NodeReplyResult, RouterReply,
+    NodeReplyResult, RouterReply, WorkerInfo,
 };
 use core::sync::atomic::{AtomicUsize, Ordering};
 use ockam_core::{
@@ -151,6 +151,35 @@ impl InternalMap {
             }
         }
     }
+
+    /// Describe all registered addresses
+    pub(super) fn worker_info(&self) -> Vec<WorkerInfo> {
+        self.internal
+            .iter()
+            .map(|(primary, rec)| {
+                let cluster = self
+                    .clusters
+                    .iter()
+                    .find(|(_, addrs)| addrs.contains(primary))
+                    .map(|(label, _)| label.clone());
+                rec.info(primary, cluster)
+            })
+            .collect()
+    }
+}
+
+/// Seconds since the Unix epoch, if a clock is available
+#[cfg(feature = "std")]
+fn unix_time() -> Option<u64> {
+    std::time::SystemTime::now()
+        .duration_since(std::time::UNIX_EPOCH)
+        .ok()
+        .map(|d| d.as_secs())
+}
+
+#[cfg(not(feature = "std"))]
+fn unix_time() -> Option<u64> {
+    None
 }
 
 /// Additional metadata for address records
@@ -158,6 +187,7 @@ impl InternalMap {
 pub struct AddressMeta {
     pub processor: bool,
     pub detached: bool,
+    pub access_control: &'static str,
 }
 
 #[derive(Debug)]
@@ -169,6 +199,7 @@ pub struct AddressRecord {
     ready: ReadyState,
     meta: AddressMeta,
     msg_count: Arc<AtomicUsize>,
+    started_at: Option<u64>,
 }
 
 impl AddressRecord {
@@ -178,6 +209,30 @@ impl AddressRecord {
     pub fn sender(&self) -> MessageSender<RelayMessage> {
         self.sender.clone().expect("No such sender!")
     }
+    /// Fill state of the mailbox, unless the worker is stopping
+    pub fn mailbox_stats(&self) -> Option<MailboxStats> {
+        self.sender.as_ref().map(|s| s.stats())
+    }
+    /// Describe this record for introspection
+    pub fn info(&self, primary: &Address, cluster: Option<String>) -> WorkerInfo {
+        let stats = self.mailbox_stats();
+        WorkerInfo {
+            address: primary.clone(),
+            aliases: self
+                .address_set
+                .iter()
+                .filter(|a| *a != primary)
+                .cloned()
+                .collect(),
+            cluster,
+            processor: self.meta.processor,
+            detached: self.meta.detached,
+            access_control: short_type_name(self.meta.access_control),
+            queued: stats.as_ref().map_or(0, |s| s.len),
+            handled: stats.as_ref().map_or(0, |s| s.received),
+            started_at: self.started_at,
+        }
+    }
     pub fn sender_drop(&mut self) {
         self.sender = None;
     }
@@ -196,6 +251,7 @@ impl AddressRecord {
             ready: ReadyState::Initialising(vec![]),
             msg_count,
             meta,
+            started_at: unix_time(),
         }
     }
 
@@ -244,6 +300,25 @@ impl AddressRecord {
     }
 }
 
+/// Remove the module paths from a type name
+///
+/// `ockam_core::access_control::all::AllAccessControl<ockam_core::AllowAll, ..>`
+/// becomes `AllAccessControl<AllowAll, ..>`.
+fn short_type_name(name: &str) -> String {
+    let last_segment = |path: &str| path.rsplit("::").next().unwrap_or(path).to_string();
+    let mut short = String::with_capacity(name.len());
+    let mut start = 0;
+    for (i, c) in name.char_indices() {
+        if !(c.is_alphanumeric() || c == '_' || c == ':') {
+            short.push_str(&last_segment(&name[start..i]));
+            short.push(c);
+            start = i + c.len_utf8();
+        }
+    }
+    short.push_str(&last_segment(&name[start..]));
+    short
+}
+
 /// Encode the run states a worker or processor can be in
 #[derive(Debug, PartialEq, Eq)]
 pub enum AddressState {
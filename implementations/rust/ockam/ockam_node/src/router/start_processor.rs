@@ -31,7 +31,11 @@ async fn start(
 
     debug!("Starting new processor '{}'", &addr);
 
-    let SenderPair { msgs, ctrl } = senders;
+    let SenderPair {
+        msgs,
+        ctrl,
+        access_control,
+    } = senders;
 
     let record = AddressRecord::new(
         addr.clone().into(),
@@ -46,6 +50,7 @@ async fn start(
         AddressMeta {
             processor: true,
             detached: false,
+            access_control,
         },
     );
 
@@ -38,7 +38,11 @@ async fn start(
 
     debug!("Starting new worker '{}'", addrs.first());
 
-    let SenderPair { msgs, ctrl } = senders;
+    let SenderPair {
+        msgs,
+        ctrl,
+        access_control,
+    } = senders;
 
     // Create an address record and insert it into the internal map
 
@@ -50,6 +54,7 @@ async fn start(
         AddressMeta {
             processor: false,
             detached,
+            access_control,
         },
     );
 
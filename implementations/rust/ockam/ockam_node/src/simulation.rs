@@ -0,0 +1,269 @@
+//! Deterministic in-memory network for simulated nodes
+//!
+//! A [`SimNetwork`] connects several nodes running on the same
+//! simulated runtime (see [`NodeBuilder::simulated`]).  Messages are
+//! routed through [`SIM`] addresses, and every link between two hosts
+//! can be given a latency and a loss rate, or be cut by a partition.
+//! Whether a message is lost is decided by a generator seeded with the
+//! seed of the node, so a given seed always yields the same sequence of
+//! loss decisions.  The other random values of a node, such as
+//! [`Address::random_local`] addresses or identity keys, are not
+//! seeded: a scenario replays the same network conditions, not the
+//! same bytes.
+//!
+//! Only the links between hosts are simulated.  TCP portals can be
+//! routed through [`SIM`] addresses, but their inlets and outlets still
+//! use real sockets on the local machine.
+//!
+//! ```ignore
+//! #[ockam::test(seed = 42)]
+//! async fn my_test(ctx: &mut Context) -> Result<()> {
+//!     let net = SimNetwork::new(ctx)?;
+//!     net.attach(ctx, "alice").await?;
+//!     let mut bob = net.start_node("bob").await?;
+//!     net.set_link("alice", "bob", SimLink::new().latency(Duration::from_millis(50)));
+//!     ctx.send(route![(SIM, "bob"), "echo"], "hello".to_string()).await?;
+//!     // ...
+//! }
+//! ```
+//!
+//! [`NodeBuilder::simulated`]: crate::NodeBuilder::simulated
+
+use crate::channel_types::MailboxConfig;
+use crate::router::Router;
+use crate::tokio::runtime::Handle;
+use crate::{Context, DetachedContext};
+use core::time::Duration;
+use ockam_core::compat::rand::prelude::{Rng, SeedableRng, StdRng};
+use ockam_core::compat::{
+    boxed::Box,
+    collections::{BTreeMap, BTreeSet},
+    string::{String, ToString},
+    sync::{Arc, Mutex},
+};
+use ockam_core::errcode::{Kind, Origin};
+use ockam_core::{
+    async_trait, Address, AllowAll, Any, Error, Mailbox, Mailboxes, Result, Routed, TransportType,
+    Worker,
+};
+
+/// Transport type of simulated network addresses
+pub const SIM: TransportType = TransportType::new(99);
+
+/// Properties of the link between two simulated hosts
+#[derive(Debug, Clone, Copy, Default, PartialEq)]
+pub struct SimLink {
+    latency: Duration,
+    loss: f64,
+}
+
+impl SimLink {
+    /// A link without latency or loss
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Delay every message by `latency` of virtual time
+    pub fn latency(mut self, latency: Duration) -> Self {
+        self.latency = latency;
+        self
+    }
+
+    /// Drop messages with the given probability, between 0 and 1
+    pub fn loss(mut self, loss: f64) -> Self {
+        self.loss = loss.clamp(0.0, 1.0);
+        self
+    }
+}
+
+struct NetworkState {
+    rng: StdRng,
+    hosts: BTreeMap<String, Arc<DetachedContext>>,
+    links: BTreeMap<(String, String), SimLink>,
+    default_link: SimLink,
+    partitions: BTreeSet<(String, String)>,
+    dropped: u64,
+}
+
+/// An in-memory network connecting simulated nodes
+///
+/// Cloning a `SimNetwork` yields a handle to the same network.
+#[derive(Clone)]
+pub struct SimNetwork {
+    rt: Handle,
+    state: Arc<Mutex<NetworkState>>,
+}
+
+impl SimNetwork {
+    /// Create a network seeded by the node of the given context
+    ///
+    /// Fails if `ctx` is not the root context of a simulated node.
+    pub fn new(ctx: &Context) -> Result<Self> {
+        let seed = ctx.simulation_seed().ok_or_else(|| {
+            Error::new(
+                Origin::Node,
+                Kind::Invalid,
+                "a SimNetwork requires a simulated node",
+            )
+        })?;
+        Ok(Self {
+            rt: ctx.runtime().clone(),
+            state: Arc::new(Mutex::new(NetworkState {
+                rng: StdRng::seed_from_u64(seed),
+                hosts: BTreeMap::new(),
+                links: BTreeMap::new(),
+                default_link: SimLink::default(),
+                partitions: BTreeSet::new(),
+                dropped: 0,
+            })),
+        })
+    }
+
+    /// Start a new node on the simulated runtime, attached as `host`
+    ///
+    /// The returned context is the root context of the new node and
+    /// should be stopped at the end of the simulation.
+    pub async fn start_node(&self, host: &str) -> Result<Context> {
+        let mut router = Router::new();
+        let addr: Address = "app".into();
+        let (ctx, sender, _) = Context::new(
+            self.rt.clone(),
+            router.sender(),
+            Mailboxes::new(Mailbox::new(addr.clone(), Arc::new(AllowAll)), vec![]),
+            MailboxConfig::default(),
+            None,
+        );
+        router.init(addr, sender);
+        self.rt.spawn(async move {
+            if let Err(e) = router.run().await {
+                error!("Simulated node router failed: {}", e);
+            }
+        });
+
+        self.attach(&ctx, host).await?;
+        Ok(ctx)
+    }
+
+    /// Attach the node of `ctx` to this network as `host`
+    ///
+    /// This registers a [`SIM`] router on the node, so that workers can
+    /// reach the workers of another host with `route![(SIM, "host"), ..]`.
+    pub async fn attach(&self, ctx: &Context, host: &str) -> Result<()> {
+        let inbox = ctx.new_detached(Address::random_local()).await?;
+        {
+            let mut state = self.state.lock().unwrap();
+            if state.hosts.contains_key(host) {
+                return Err(Error::new(
+                    Origin::Node,
+                    Kind::AlreadyExists,
+                    format!("host {} is already attached", host),
+                ));
+            }
+            state.hosts.insert(host.to_string(), Arc::new(inbox));
+        }
+
+        let router = Address::random_local();
+        let worker = SimRouter {
+            host: host.to_string(),
+            network: self.clone(),
+        };
+        ctx.start_worker(router.clone(), worker).await?;
+        ctx.register(SIM, router).await
+    }
+
+    /// Set the properties of the links between `a` and `b`, both ways
+    pub fn set_link(&self, a: &str, b: &str, link: SimLink) {
+        let mut state = self.state.lock().unwrap();
+        state.links.insert((a.to_string(), b.to_string()), link);
+        state.links.insert((b.to_string(), a.to_string()), link);
+    }
+
+    /// Set the properties of every link without explicit settings
+    pub fn set_default_link(&self, link: SimLink) {
+        self.state.lock().unwrap().default_link = link;
+    }
+
+    /// Drop every message exchanged between `a` and `b`
+    pub fn partition(&self, a: &str, b: &str) {
+        let mut state = self.state.lock().unwrap();
+        state.partitions.insert((a.to_string(), b.to_string()));
+        state.partitions.insert((b.to_string(), a.to_string()));
+    }
+
+    /// Remove the partition between `a` and `b`
+    pub fn heal(&self, a: &str, b: &str) {
+        let mut state = self.state.lock().unwrap();
+        state.partitions.remove(&(a.to_string(), b.to_string()));
+        state.partitions.remove(&(b.to_string(), a.to_string()));
+    }
+
+    /// Number of messages dropped so far by loss or partitions
+    pub fn dropped(&self) -> u64 {
+        self.state.lock().unwrap().dropped
+    }
+
+    /// Decide the fate of a message sent from `from` to `to`
+    ///
+    /// Returns the context to deliver it with and its latency, or
+    /// `None` if the message is lost.
+    fn route(&self, from: &str, to: &str) -> Option<(Arc<DetachedContext>, Duration)> {
+        let mut state = self.state.lock().unwrap();
+        let key = (from.to_string(), to.to_string());
+        let link = state.links.get(&key).copied().unwrap_or(state.default_link);
+        let lost =
+            state.partitions.contains(&key) || (link.loss > 0.0 && state.rng.gen_bool(link.loss));
+        match state.hosts.get(to).cloned() {
+            Some(inbox) if !lost => Some((inbox, link.latency)),
+            Some(_) => {
+                state.dropped += 1;
+                None
+            }
+            None => {
+                warn!("Unknown simulated host {}", to);
+                None
+            }
+        }
+    }
+}
+
+/// Worker forwarding [`SIM`] messages of one host through the network
+struct SimRouter {
+    host: String,
+    network: SimNetwork,
+}
+
+#[async_trait]
+impl Worker for SimRouter {
+    type Context = Context;
+    type Message = Any;
+
+    async fn handle_message(&mut self, ctx: &mut Context, msg: Routed<Any>) -> Result<()> {
+        let mut local_msg = msg.into_local_message();
+        let transport = local_msg.transport_mut();
+        let to = transport.onward_route.step()?;
+        transport
+            .return_route
+            .modify()
+            .prepend(Address::new(SIM, self.host.clone()));
+
+        let (inbox, latency) = match self.network.route(&self.host, to.address()) {
+            Some(delivery) => delivery,
+            None => {
+                debug!("Dropped simulated message {} -> {}", self.host, to);
+                return Ok(());
+            }
+        };
+
+        if latency.is_zero() {
+            inbox.forward(local_msg).await
+        } else {
+            ctx.runtime().spawn(async move {
+                tokio::time::sleep(latency).await;
+                if let Err(e) = inbox.forward(local_msg).await {
+                    warn!("Failed to deliver simulated message: {}", e);
+                }
+            });
+            Ok(())
+        }
+    }
+}
@@ -0,0 +1,452 @@
+//! Worker supervision trees
+//!
+//! A [`Supervisor`] is a worker that starts a set of child workers
+//! and restarts them at the same [`Address`] when they fail, either
+//! by returning an error from a [`Worker`] callback or by panicking.
+//! Supervisors can themselves be children of other supervisors: when
+//! a supervisor exceeds its restart intensity it stops its children
+//! and fails, handing the problem to its own supervisor.
+
+use crate::tokio::time::sleep;
+use crate::{Context, DetachedContext, WorkerBuilder};
+use core::{future::Future, pin::Pin, time::Duration};
+use futures::FutureExt;
+use ockam_core::compat::{
+    boxed::Box,
+    collections::VecDeque,
+    string::{String, ToString},
+    sync::Arc,
+    vec::Vec,
+};
+use ockam_core::{
+    async_trait,
+    errcode::{Kind, Origin},
+    Address, Error, Message, Result, Route, Routed, Worker,
+};
+use serde::{Deserialize, Serialize};
+use std::panic::AssertUnwindSafe;
+use std::time::Instant;
+
+/// How many times a restart is retried while the old worker is still shutting down
+const RESTART_ATTEMPTS: usize = 100;
+const RESTART_BACKOFF: Duration = Duration::from_millis(10);
+
+/// Which children are restarted when one of them fails
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum RestartStrategy {
+    /// Only restart the failed child
+    OneForOne,
+    /// Stop all children and restart them in start order
+    OneForAll,
+}
+
+/// Messages handled by a [`Supervisor`]
+#[derive(Serialize, Deserialize, Debug, Message)]
+pub enum SupervisorMessage {
+    /// A child at the given address failed and has been stopped
+    ChildFailed {
+        /// Address of the failed child
+        address: Address,
+        /// Description of the failure
+        reason: String,
+    },
+    /// Send [`SupervisorEvent`]s to the return route of this message
+    Subscribe,
+}
+
+/// Events published by a [`Supervisor`] to its subscribers
+#[derive(Serialize, Deserialize, Clone, Debug, Message)]
+pub enum SupervisorEvent {
+    /// A failed child was restarted
+    Restarted {
+        /// Address of the restarted child
+        address: Address,
+        /// Description of the failure that caused the restart
+        reason: String,
+    },
+    /// The restart intensity was exceeded, so the supervisor stopped
+    /// all its children and itself
+    GaveUp {
+        /// Address of the supervisor
+        supervisor: Address,
+        /// Description of the last failure
+        reason: String,
+    },
+}
+
+type StartFuture<'a> = Pin<Box<dyn Future<Output = Result<()>> + Send + 'a>>;
+type StartFn = Arc<dyn for<'a> Fn(&'a Context, Address) -> StartFuture<'a> + Send + Sync>;
+
+#[derive(Clone)]
+struct ChildSpec {
+    address: Address,
+    start: StartFn,
+}
+
+/// Configure and start a [`Supervisor`]
+///
+/// ```rust
+/// # use ockam_core::{Result, Worker, worker};
+/// # use ockam_node::Context;
+/// # struct MyWorker;
+/// # #[worker]
+/// # impl Worker for MyWorker {
+/// #     type Context = Context;
+/// #     type Message = String;
+/// # }
+/// use core::time::Duration;
+/// use ockam_node::{RestartStrategy, SupervisorBuilder};
+///
+/// async fn start_supervisor(ctx: &mut Context) -> Result<()> {
+///     SupervisorBuilder::new(RestartStrategy::OneForOne)
+///         .intensity(3, Duration::from_secs(5))
+///         .child("my-worker", || MyWorker)
+///         .start(ctx, "my-supervisor")
+///         .await
+/// }
+/// ```
+#[derive(Clone)]
+pub struct SupervisorBuilder {
+    strategy: RestartStrategy,
+    max_restarts: usize,
+    period: Duration,
+    children: Vec<ChildSpec>,
+}
+
+impl SupervisorBuilder {
+    /// Create a supervisor with the given strategy, allowing 3
+    /// restarts every 5 seconds
+    pub fn new(strategy: RestartStrategy) -> Self {
+        Self {
+            strategy,
+            max_restarts: 3,
+            period: Duration::from_secs(5),
+            children: Vec::new(),
+        }
+    }
+
+    /// Give up once more than `max_restarts` happen within `period`
+    pub fn intensity(mut self, max_restarts: usize, period: Duration) -> Self {
+        self.max_restarts = max_restarts;
+        self.period = period;
+        self
+    }
+
+    /// Add a child, created by `factory` on start and on every restart
+    ///
+    /// Children are started in the order they are added.
+    pub fn child<A, F, W, M>(mut self, address: A, factory: F) -> Self
+    where
+        A: Into<Address>,
+        F: Fn() -> W + Send + Sync + 'static,
+        W: Worker<Context = Context, Message = M>,
+        M: Message + Send + 'static,
+    {
+        let factory = Arc::new(factory);
+        let start: StartFn = Arc::new(move |ctx: &Context, address: Address| {
+            let worker = Supervised {
+                inner: factory(),
+                supervisor: ctx.address(),
+                failed: false,
+            };
+            Box::pin(async move {
+                WorkerBuilder::with_inherited_access_control(ctx, address, worker)
+                    .start(ctx)
+                    .await
+                    .map(|_| ())
+            })
+        });
+        self.children.push(ChildSpec {
+            address: address.into(),
+            start,
+        });
+        self
+    }
+
+    /// Create the supervisor worker without starting it
+    ///
+    /// This is useful to nest a supervisor in another one.
+    pub fn build(self) -> Supervisor {
+        Supervisor {
+            strategy: self.strategy,
+            max_restarts: self.max_restarts,
+            period: self.period,
+            children: self.children,
+            restarts: VecDeque::new(),
+            subscribers: Vec::new(),
+        }
+    }
+
+    /// Start the supervisor at the given address, which starts its children
+    pub async fn start(self, ctx: &mut Context, address: impl Into<Address>) -> Result<()> {
+        let address = address.into();
+        ctx.start_worker(address.clone(), self.build()).await?;
+        ctx.wait_for(address).await
+    }
+}
+
+/// A worker which restarts its children when they fail
+///
+/// See [`SupervisorBuilder`].
+pub struct Supervisor {
+    strategy: RestartStrategy,
+    max_restarts: usize,
+    period: Duration,
+    children: Vec<ChildSpec>,
+    restarts: VecDeque<Instant>,
+    subscribers: Vec<Route>,
+}
+
+impl Supervisor {
+    /// Create a detached context subscribed to the events of a supervisor
+    pub async fn subscribe(
+        ctx: &Context,
+        supervisor: impl Into<Address>,
+    ) -> Result<DetachedContext> {
+        let child = ctx.new_detached(Address::random_local()).await?;
+        child
+            .send(supervisor.into(), SupervisorMessage::Subscribe)
+            .await?;
+        Ok(child)
+    }
+
+    /// Record a restart and check whether the restart intensity was exceeded
+    fn intensity_exceeded(&mut self) -> bool {
+        let now = Instant::now();
+        self.restarts.push_back(now);
+        while let Some(t) = self.restarts.front() {
+            if now.duration_since(*t) > self.period {
+                self.restarts.pop_front();
+            } else {
+                break;
+            }
+        }
+        self.restarts.len() > self.max_restarts
+    }
+
+    async fn publish(&mut self, ctx: &Context, event: SupervisorEvent) {
+        let mut subscribers = Vec::with_capacity(self.subscribers.len());
+        for route in self.subscribers.drain(..) {
+            match ctx.send(route.clone(), event.clone()).await {
+                Ok(()) => subscribers.push(route),
+                Err(e) => debug!("Dropping supervisor subscriber {}: {}", route, e),
+            }
+        }
+        self.subscribers = subscribers;
+    }
+
+    /// Start a child and wait for it to initialise, after a previous
+    /// instance has finished shutting down
+    async fn start_child(ctx: &mut Context, child: &ChildSpec) -> Result<()> {
+        for _ in 0..RESTART_ATTEMPTS {
+            match (child.start)(ctx, child.address.clone()).await {
+                Err(e) if e.code().kind == Kind::AlreadyExists => sleep(RESTART_BACKOFF).await,
+                Err(e) => return Err(e),
+                Ok(()) => {
+                    // A child failing in `initialize` is reported separately
+                    if let Err(e) = ctx.wait_for(child.address.clone()).await {
+                        debug!("Supervised worker {} did not start: {}", child.address, e);
+                    }
+                    return Ok(());
+                }
+            }
+        }
+        Err(
+            Error::new(Origin::Node, Kind::Timeout, "child did not shut down")
+                .context("address", child.address.clone()),
+        )
+    }
+
+    async fn stop_children(&self, ctx: &Context, except: Option<&Address>) {
+        for child in self.children.iter().rev() {
+            if Some(&child.address) != except {
+                // The child may have failed and stopped already
+                let _ = ctx.stop_worker(child.address.clone()).await;
+            }
+        }
+    }
+
+    async fn child_failed(
+        &mut self,
+        ctx: &mut Context,
+        address: Address,
+        reason: String,
+    ) -> Result<()> {
+        if !self.children.iter().any(|c| c.address == address) {
+            warn!(
+                "Supervisor {} ignores unknown child {}",
+                ctx.address(),
+                address
+            );
+            return Ok(());
+        }
+        warn!("Supervised worker {} failed: {}", address, reason);
+
+        if self.intensity_exceeded() {
+            error!(
+                "Supervisor {} exceeded its restart intensity, giving up",
+                ctx.address()
+            );
+            self.stop_children(ctx, Some(&address)).await;
+            self.publish(
+                ctx,
+                SupervisorEvent::GaveUp {
+                    supervisor: ctx.address(),
+                    reason: reason.clone(),
+                },
+            )
+            .await;
+            ctx.stop_worker(ctx.address()).await?;
+            return Err(Error::new(Origin::Node, Kind::Cancelled, reason)
+                .context("supervisor", ctx.address()));
+        }
+
+        let restart: Vec<ChildSpec> = match self.strategy {
+            RestartStrategy::OneForOne => self
+                .children
+                .iter()
+                .filter(|c| c.address == address)
+                .cloned()
+                .collect(),
+            RestartStrategy::OneForAll => {
+                self.stop_children(ctx, Some(&address)).await;
+                self.children.clone()
+            }
+        };
+        for child in &restart {
+            Self::start_child(ctx, child).await?;
+        }
+        self.publish(ctx, SupervisorEvent::Restarted { address, reason })
+            .await;
+        Ok(())
+    }
+}
+
+#[async_trait]
+impl Worker for Supervisor {
+    type Message = SupervisorMessage;
+    type Context = Context;
+
+    async fn initialize(&mut self, ctx: &mut Context) -> Result<()> {
+        for child in &self.children {
+            Self::start_child(ctx, child).await?;
+        }
+        Ok(())
+    }
+
+    async fn shutdown(&mut self, ctx: &mut Context) -> Result<()> {
+        self.stop_children(ctx, None).await;
+        Ok(())
+    }
+
+    async fn handle_message(
+        &mut self,
+        ctx: &mut Context,
+        msg: Routed<SupervisorMessage>,
+    ) -> Result<()> {
+        let return_route = msg.return_route();
+        match msg.body() {
+            SupervisorMessage::ChildFailed { address, reason } => {
+                self.child_failed(ctx, address, reason).await
+            }
+            SupervisorMessage::Subscribe => {
+                self.subscribers.push(return_route);
+                Ok(())
+            }
+        }
+    }
+}
+
+/// Wraps a supervised worker to report its failures to the supervisor
+struct Supervised<W> {
+    inner: W,
+    supervisor: Address,
+    failed: bool,
+}
+
+impl<W> Supervised<W> {
+    async fn fail(&mut self, ctx: &Context, reason: String) -> Result<()> {
+        self.failed = true;
+        // Stop first, so that the supervisor can restart at the same address
+        if let Err(e) = ctx.stop_worker(ctx.address()).await {
+            debug!(
+                "Failed worker {} was already stopping: {}",
+                ctx.address(),
+                e
+            );
+        }
+        ctx.send(
+            self.supervisor.clone(),
+            SupervisorMessage::ChildFailed {
+                address: ctx.address(),
+                reason,
+            },
+        )
+        .await
+    }
+}
+
+fn panic_reason(panic: Box<dyn core::any::Any + Send>) -> String {
+    if let Some(s) = panic.downcast_ref::<&str>() {
+        format!("panicked: {}", s)
+    } else if let Some(s) = panic.downcast_ref::<String>() {
+        format!("panicked: {}", s)
+    } else {
+        "panicked".to_string()
+    }
+}
+
+#[async_trait]
+impl<W, M> Worker for Supervised<W>
+where
+    W: Worker<Context = Context, Message = M>,
+    M: Message + Send + 'static,
+{
+    type Message = M;
+    type Context = Context;
+
+    async fn initialize(&mut self, ctx: &mut Context) -> Result<()> {
+        let reason = match AssertUnwindSafe(self.inner.initialize(ctx))
+            .catch_unwind()
+            .await
+        {
+            Ok(Ok(())) => return Ok(()),
+            Ok(Err(e)) => e.to_string(),
+            Err(panic) => panic_reason(panic),
+        };
+        self.fail(ctx, reason).await
+    }
+
+    async fn shutdown(&mut self, ctx: &mut Context) -> Result<()> {
+        match AssertUnwindSafe(self.inner.shutdown(ctx))
+            .catch_unwind()
+            .await
+        {
+            Ok(res) => res,
+            Err(panic) => Err(Error::new(
+                Origin::Node,
+                Kind::Internal,
+                panic_reason(panic),
+            )),
+        }
+    }
+
+    async fn is_authorized(&mut self, ctx: &mut Context, msg: Routed<M>) -> Result<bool> {
+        if self.failed {
+            return ockam_core::deny();
+        }
+        self.inner.is_authorized(ctx, msg).await
+    }
+
+    async fn handle_message(&mut self, ctx: &mut Context, msg: Routed<M>) -> Result<()> {
+        let reason = match AssertUnwindSafe(self.inner.handle_message(ctx, msg))
+            .catch_unwind()
+            .await
+        {
+            Ok(Ok(())) => return Ok(()),
+            Ok(Err(e)) => e.to_string(),
+            Err(panic) => panic_reason(panic),
+        };
+        self.fail(ctx, reason).await
+    }
+}
@@ -1,5 +1,9 @@
+use crate::channel_types::{MailboxConfig, OverflowPolicy};
 use crate::compat::futures::FutureExt;
-use crate::{Context, NodeBuilder};
+use crate::{
+    Context, NodeBuilder, RestartStrategy, Supervisor, SupervisorBuilder, SupervisorEvent,
+    WorkerBuilder,
+};
 use core::sync::atomic::{AtomicBool, Ordering};
 use core::time::Duration;
 use ockam_core::compat::{
@@ -7,7 +11,9 @@ use ockam_core::compat::{
     string::{String, ToString},
     sync::Arc,
 };
-use ockam_core::{async_trait, Address, Any, Decodable, Message, LOCAL};
+use ockam_core::{
+    async_trait, Address, AllAccessControl, AllowAll, Any, Decodable, DenyAll, Message, LOCAL,
+};
 use ockam_core::{route, Processor, Result, Routed, Worker};
 use serde::{Deserialize, Serialize};
 use std::sync::atomic::{AtomicI8, AtomicU32};
@@ -560,3 +566,313 @@ async fn starting_worker_with_dup_address_should_fail(ctx: &mut Context) -> Resu
     assert!(ctx.start_worker("dummy_worker", DummyWorker).await.is_err());
     ctx.stop().await
 }
+
+struct StuckWorker {
+    release: Arc<AtomicBool>,
+}
+
+#[async_trait]
+impl Worker for StuckWorker {
+    type Message = String;
+    type Context = Context;
+
+    async fn handle_message(&mut self, _ctx: &mut Context, _msg: Routed<String>) -> Result<()> {
+        while !self.release.load(Ordering::Relaxed) {
+            sleep(Duration::from_millis(10)).await;
+        }
+        Ok(())
+    }
+}
+
+#[ockam_macros::test(crate = "crate")]
+async fn full_mailbox_overflow_policies(ctx: &mut Context) -> Result<()> {
+    let release = Arc::new(AtomicBool::new(false));
+    for (addr, policy) in [
+        ("drop_newest", OverflowPolicy::DropNewest),
+        ("drop_oldest", OverflowPolicy::DropOldest),
+        ("error", OverflowPolicy::Error),
+    ] {
+        let worker = StuckWorker {
+            release: release.clone(),
+        };
+        ctx.start_worker_with_mailbox(addr, worker, MailboxConfig::new(2, policy))
+            .await?;
+    }
+
+    for _ in 0..10 {
+        let sends = async {
+            ctx.send("drop_newest", "msg".to_string()).await?;
+            ctx.send("drop_oldest", "msg".to_string()).await
+        };
+        tokio::time::timeout(Duration::from_secs(1), sends)
+            .await
+            .expect("sending to a full mailbox should not block")?;
+        let _ = ctx.send("error", "msg".to_string()).await;
+    }
+    assert!(ctx.send("error", "msg".to_string()).await.is_err());
+
+    let mailboxes = ctx.list_mailboxes().await?;
+    for addr in ["drop_newest", "drop_oldest", "error"] {
+        let (_, stats) = mailboxes
+            .iter()
+            .find(|(a, _)| a == &Address::from_string(addr))
+            .unwrap();
+        assert_eq!(stats.len, 2);
+        // One message is being handled, two are queued
+        assert!(stats.overflows >= 7);
+    }
+
+    release.store(true, Ordering::Relaxed);
+    ctx.stop().await
+}
+
+struct FlakyWorker {
+    starts: Arc<AtomicU32>,
+}
+
+#[async_trait]
+impl Worker for FlakyWorker {
+    type Message = String;
+    type Context = Context;
+
+    async fn initialize(&mut self, _ctx: &mut Context) -> Result<()> {
+        self.starts.fetch_add(1, Ordering::Relaxed);
+        Ok(())
+    }
+
+    async fn handle_message(&mut self, ctx: &mut Context, msg: Routed<String>) -> Result<()> {
+        match msg.as_body().as_str() {
+            "fail" => Err(ockam_core::Error::new(
+                ockam_core::errcode::Origin::Node,
+                ockam_core::errcode::Kind::Invalid,
+                "told to fail",
+            )),
+            "panic" => panic!("told to panic"),
+            _ => ctx.send(msg.return_route(), msg.body()).await,
+        }
+    }
+}
+
+#[ockam_macros::test(crate = "crate")]
+async fn supervisor_restarts_failed_children(ctx: &mut Context) -> Result<()> {
+    let a = Arc::new(AtomicU32::new(0));
+    let b = Arc::new(AtomicU32::new(0));
+    let (a2, b2) = (a.clone(), b.clone());
+    SupervisorBuilder::new(RestartStrategy::OneForOne)
+        .child("flaky_a", move || FlakyWorker { starts: a2.clone() })
+        .child("flaky_b", move || FlakyWorker { starts: b2.clone() })
+        .start(ctx, "one_for_one")
+        .await?;
+    let mut events = Supervisor::subscribe(ctx, "one_for_one").await?;
+
+    for msg in ["fail", "panic"] {
+        ctx.send("flaky_a", msg.to_string()).await?;
+        match events.receive::<SupervisorEvent>().await?.take().body() {
+            SupervisorEvent::Restarted { address, .. } => {
+                assert_eq!(address, Address::from_string("flaky_a"))
+            }
+            e => panic!("unexpected event {:?}", e),
+        }
+    }
+    assert_eq!(a.load(Ordering::Relaxed), 3);
+    assert_eq!(b.load(Ordering::Relaxed), 1);
+
+    ctx.send("flaky_a", "ping".to_string()).await?;
+    assert_eq!(ctx.receive::<String>().await?.take().body(), "ping");
+
+    ctx.stop().await
+}
+
+#[ockam_macros::test(crate = "crate")]
+async fn supervisor_one_for_all_and_give_up(ctx: &mut Context) -> Result<()> {
+    let a = Arc::new(AtomicU32::new(0));
+    let b = Arc::new(AtomicU32::new(0));
+    let (a2, b2) = (a.clone(), b.clone());
+    SupervisorBuilder::new(RestartStrategy::OneForAll)
+        .intensity(1, Duration::from_secs(60))
+        .child("all_a", move || FlakyWorker { starts: a2.clone() })
+        .child("all_b", move || FlakyWorker { starts: b2.clone() })
+        .start(ctx, "one_for_all")
+        .await?;
+    let mut events = Supervisor::subscribe(ctx, "one_for_all").await?;
+
+    ctx.send("all_a", "fail".to_string()).await?;
+    let event = events.receive::<SupervisorEvent>().await?.take().body();
+    assert!(matches!(event, SupervisorEvent::Restarted { .. }));
+    assert_eq!(a.load(Ordering::Relaxed), 2);
+    assert_eq!(b.load(Ordering::Relaxed), 2);
+
+    ctx.send("all_b", "fail".to_string()).await?;
+    match events.receive::<SupervisorEvent>().await?.take().body() {
+        SupervisorEvent::GaveUp { supervisor, .. } => {
+            assert_eq!(supervisor, Address::from_string("one_for_all"))
+        }
+        e => panic!("unexpected event {:?}", e),
+    }
+    // Children are stopped before the supervisor gives up
+    assert!(ctx.send("all_a", "ping".to_string()).await.is_err());
+
+    ctx.stop().await
+}
+
+#[ockam_macros::test(crate = "crate")]
+async fn list_worker_info(ctx: &mut Context) -> Result<()> {
+    let release = Arc::new(AtomicBool::new(false));
+    let worker = StuckWorker {
+        release: release.clone(),
+    };
+    ctx.start_worker(vec!["inspect_main", "inspect_alias"], worker)
+        .await?;
+    ctx.set_cluster("inspect_cluster").await?;
+    let guarded = StuckWorker {
+        release: release.clone(),
+    };
+    let access_control = AllAccessControl::new(AllowAll, DenyAll);
+    WorkerBuilder::with_access_control(access_control, "inspect_guarded", guarded)
+        .start(ctx)
+        .await?;
+    for _ in 0..3 {
+        ctx.send("inspect_alias", "msg".to_string()).await?;
+    }
+    sleep(Duration::from_millis(50)).await;
+
+    let info = ctx.list_worker_info().await?;
+    let worker = info
+        .iter()
+        .find(|i| i.address == Address::from_string("inspect_main"))
+        .unwrap();
+    assert_eq!(worker.aliases, vec![Address::from_string("inspect_alias")]);
+    assert!(!worker.processor);
+    assert!(!worker.detached);
+    assert_eq!(worker.access_control, "AllowAll");
+    let guarded = info
+        .iter()
+        .find(|i| i.address == Address::from_string("inspect_guarded"))
+        .unwrap();
+    assert_eq!(
+        guarded.access_control,
+        "AllAccessControl<AllowAll, DenyAll>"
+    );
+    // One message is being handled, two are queued
+    assert_eq!(worker.handled, 1);
+    assert_eq!(worker.queued, 2);
+    assert!(worker.started_at.is_some());
+    let app = info.iter().find(|i| i.address == ctx.address()).unwrap();
+    assert_eq!(app.cluster.as_deref(), Some("inspect_cluster"));
+    assert!(app.detached);
+
+    release.store(true, Ordering::Relaxed);
+    ctx.stop().await
+}
+
+#[ockam_macros::test(crate = "crate")]
+async fn tracing_context_is_propagated(ctx: &mut Context) -> Result<()> {
+    let starts = Arc::new(AtomicU32::new(0));
+    ctx.start_worker("traced_echo", FlakyWorker { starts })
+        .await?;
+
+    let root = ockam_core::TraceContext::new_root();
+    let mut child = ctx.new_detached("traced_client").await?;
+    child.set_tracing_context(Some(root));
+    child.send("traced_echo", "ping".to_string()).await?;
+
+    let reply = child.receive::<String>().await?.take();
+    let tc = reply
+        .local_message()
+        .transport()
+        .tracing_context
+        .expect("reply should carry the trace context");
+    assert_eq!(tc.trace_id(), root.trace_id());
+    // The reply was sent from the span of the echo worker
+    assert_ne!(tc.span_id(), root.span_id());
+    // Receiving the reply does not change the trace of the client
+    assert_eq!(child.tracing_context(), Some(&root));
+
+    ctx.stop().await
+}
+
+#[cfg(feature = "simulation")]
+mod simulation {
+    use super::FlakyWorker;
+    use crate::simulation::{SimLink, SimNetwork, SIM};
+    use crate::{Context, DelayedEvent};
+    use core::time::Duration;
+    use ockam_core::compat::{string::ToString, sync::Arc};
+    use ockam_core::{route, Result};
+    use std::sync::atomic::AtomicU32;
+    use tokio::time::Instant;
+
+    // The timeout is measured on the virtual clock too
+    #[ockam_macros::test(crate = "crate", seed = 1, timeout = 7_200_000)]
+    async fn virtual_clock_skips_idle_time(ctx: &mut Context) -> Result<()> {
+        let wall = std::time::Instant::now();
+        let start = Instant::now();
+
+        ctx.sleep(Duration::from_secs(3600)).await;
+        assert_eq!(start.elapsed(), Duration::from_secs(3600));
+
+        let mut heartbeat = DelayedEvent::create(ctx, "app", "beat".to_string()).await?;
+        heartbeat.schedule(Duration::from_secs(60)).await?;
+        let beat = ctx.receive_timeout::<String>(120).await?.take();
+        assert_eq!(beat.body(), "beat");
+        assert_eq!(start.elapsed(), Duration::from_secs(3660));
+
+        assert!(ctx.receive_timeout::<String>(10 * 60).await.is_err());
+        assert_eq!(start.elapsed(), Duration::from_secs(4260));
+
+        assert!(wall.elapsed() < Duration::from_secs(5));
+        ctx.stop().await
+    }
+
+    #[ockam_macros::test(crate = "crate", seed = 7)]
+    async fn sim_network_latency_loss_and_partitions(ctx: &mut Context) -> Result<()> {
+        let net = SimNetwork::new(ctx)?;
+        net.attach(ctx, "a").await?;
+        let mut b = net.start_node("b").await?;
+        let starts = Arc::new(AtomicU32::new(0));
+        b.start_worker("echo", FlakyWorker { starts }).await?;
+
+        // Latency applies to both directions
+        net.set_link("a", "b", SimLink::new().latency(Duration::from_millis(50)));
+        let start = Instant::now();
+        ctx.send(route![(SIM, "b"), "echo"], "hello".to_string())
+            .await?;
+        let reply = ctx.receive::<String>().await?.take();
+        assert_eq!(start.elapsed(), Duration::from_millis(100));
+        assert_eq!(
+            reply.return_route(),
+            route![(SIM, "b"), "echo"],
+            "replies come back through the simulated network"
+        );
+        assert_eq!(reply.body(), "hello");
+
+        // Nothing gets through a partition
+        net.partition("a", "b");
+        ctx.send(route![(SIM, "b"), "echo"], "lost".to_string())
+            .await?;
+        assert!(ctx.receive_timeout::<String>(5).await.is_err());
+        assert_eq!(net.dropped(), 1);
+        net.heal("a", "b");
+
+        // Loss is decided by the seeded generator
+        net.set_link("a", "b", SimLink::new().loss(0.5));
+        let mut received = 0;
+        for i in 0..20 {
+            ctx.send(route![(SIM, "b"), "echo"], i.to_string()).await?;
+            if ctx.receive_timeout::<String>(1).await.is_ok() {
+                received += 1;
+            }
+        }
+        assert!(received > 0 && received < 20);
+        assert_eq!(received + net.dropped(), 21);
+
+        b.stop().await?;
+        ctx.stop().await
+    }
+
+    #[ockam_macros::test(crate = "crate")]
+    async fn sim_network_requires_simulated_node(ctx: &mut Context) -> Result<()> {
+        assert!(SimNetwork::new(ctx).is_err());
+        ctx.stop().await
+    }
+}
@@ -1,3 +1,4 @@
+use crate::channel_types::{MailboxConfig, OverflowPolicy};
 use crate::error::{NodeError, NodeReason};
 use crate::{relay::WorkerRelay, Context, NodeMessage};
 use ockam_core::compat::sync::Arc;
@@ -19,6 +20,7 @@ use ockam_core::{
 /// underlying worker that is created.
 pub struct WorkerBuilder<W> {
     mailboxes: Mailboxes,
+    mailbox_config: MailboxConfig,
     worker: W,
 }
 
@@ -34,7 +36,11 @@ where
     {
         let mailboxes = Mailboxes::from_address_set(address_set.into(), Arc::new(AllowAll));
 
-        Self { mailboxes, worker }
+        Self {
+            mailboxes,
+            mailbox_config: MailboxConfig::default(),
+            worker,
+        }
     }
 
     /// Create a worker which inherits access control from the given context
@@ -56,7 +62,11 @@ where
 
         let mailboxes = Mailboxes::from_address_set(address_set, access_control);
 
-        Self { mailboxes, worker }
+        Self {
+            mailboxes,
+            mailbox_config: MailboxConfig::default(),
+            worker,
+        }
     }
 
     /// Create a worker which uses the given access control
@@ -67,13 +77,39 @@ where
     {
         let mailboxes = Mailboxes::main(address.into(), Arc::new(access_control));
 
-        Self { mailboxes, worker }
+        Self {
+            mailboxes,
+            mailbox_config: MailboxConfig::default(),
+            worker,
+        }
     }
 
     /// Create a worker which uses the access control from the given
     /// [`Mailboxes`]
     pub fn with_mailboxes(mailboxes: Mailboxes, worker: W) -> Self {
-        Self { mailboxes, worker }
+        Self {
+            mailboxes,
+            mailbox_config: MailboxConfig::default(),
+            worker,
+        }
+    }
+
+    /// Set the capacity and overflow behaviour of the worker mailbox
+    pub fn mailbox_config(mut self, config: MailboxConfig) -> Self {
+        self.mailbox_config = config;
+        self
+    }
+
+    /// Set the number of messages the worker mailbox can hold
+    pub fn mailbox_capacity(mut self, capacity: usize) -> Self {
+        self.mailbox_config = MailboxConfig::new(capacity, self.mailbox_config.overflow());
+        self
+    }
+
+    /// Set what happens when a message is sent to a full mailbox
+    pub fn overflow_policy(mut self, overflow: OverflowPolicy) -> Self {
+        self.mailbox_config = MailboxConfig::new(self.mailbox_config.capacity(), overflow);
+        self
     }
 
     /// Consume this builder and start a new Ockam [`Worker`] from the given context
@@ -93,6 +129,7 @@ where
             context.runtime().clone(),
             context.sender().clone(),
             mailboxes,
+            self.mailbox_config,
             None,
         );
 
@@ -5,7 +5,7 @@ use tokio::net::{TcpListener, TcpStream};
 
 use ockam_core::compat::rand::random;
 use ockam_core::{route, Result};
-use ockam_node::Context;
+use ockam_node::{Context, SimLink, SimNetwork, SIM};
 use ockam_transport_tcp::TcpTransport;
 
 const LENGTH: usize = 32;
@@ -101,3 +101,47 @@ async fn portal__reverse_flow__should_succeed(ctx: &mut Context) -> Result<()> {
 
     Ok(())
 }
+
+#[allow(non_snake_case)]
+#[ockam_macros::test(seed = 7, timeout = 5000)]
+async fn portal__simulated_network__should_succeed(ctx: &mut Context) -> Result<()> {
+    let payload1 = generate_binary();
+    let payload2 = generate_binary();
+
+    let net = SimNetwork::new(ctx)?;
+    net.attach(ctx, "alice").await?;
+    let mut bob = net.start_node("bob").await?;
+    net.set_link(
+        "alice",
+        "bob",
+        SimLink::new().latency(Duration::from_millis(100)),
+    );
+
+    let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+    let outlet_tcp = TcpTransport::create(&bob).await?;
+    outlet_tcp
+        .create_outlet("outlet", listener.local_addr().unwrap().to_string())
+        .await?;
+    let inlet_tcp = TcpTransport::create(ctx).await?;
+    let (_, inlet_addr) = inlet_tcp
+        .create_inlet("127.0.0.1:0", route![(SIM, "bob"), "outlet"])
+        .await?;
+
+    tokio::spawn(async move {
+        let (mut stream, _) = listener.accept().await.unwrap();
+
+        read_assert_binary(&mut stream, payload1).await;
+        write_binary(&mut stream, payload2).await;
+    });
+
+    let mut stream = TcpStream::connect(inlet_addr).await.unwrap();
+    write_binary(&mut stream, payload1).await;
+    read_assert_binary(&mut stream, payload2).await;
+
+    let _ = bob.stop().await;
+    if let Err(e) = ctx.stop().await {
+        println!("Unclean stop: {}", e)
+    }
+
+    Ok(())
+}
@@ -43,6 +43,12 @@ pub enum VaultError {
     StorageError,
     /// Invalid Storage data
     InvalidStorageData,
+    /// Storage is encrypted and no key was given
+    StorageLocked,
+    /// Storage key does not match the encrypted storage
+    InvalidStorageKey,
+    /// Storage is already encrypted
+    StorageEncrypted,
 }
 
 impl ockam_core::compat::error::Error for VaultError {}
@@ -67,6 +73,9 @@ impl core::fmt::Display for VaultError {
             Self::InvalidSecretAttributes => write!(f, "invalid secret attributes"),
             Self::StorageError => write!(f, "invalid storage"),
             Self::InvalidStorageData => write!(f, "invalid storage data"),
+            Self::StorageLocked => write!(f, "storage is encrypted and no key was given"),
+            Self::InvalidStorageKey => write!(f, "invalid storage key"),
+            Self::StorageEncrypted => write!(f, "storage is already encrypted"),
         }
     }
 }
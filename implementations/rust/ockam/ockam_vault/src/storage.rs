@@ -1,3 +1,5 @@
 mod file_storage;
+mod storage_key;
 
 pub use file_storage::*;
+pub use storage_key::*;
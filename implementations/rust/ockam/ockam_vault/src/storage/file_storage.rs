@@ -1,3 +1,4 @@
+use super::{Cipher, Kdf, StorageKey};
 use crate::VaultError;
 use ockam_core::compat::boxed::Box;
 use ockam_core::vault::storage::Storage;
@@ -26,17 +27,65 @@ enum LegacySerializedVault {
     },
 }
 
+/// Vault file whose entries are encrypted at rest.
+///
+/// The ciphertext holds a [`LegacySerializedVault`].
+#[derive(Serialize, Deserialize)]
+#[serde(tag = "version")]
+#[non_exhaustive]
+enum EncryptedVault {
+    V2 {
+        kdf: Kdf,
+        nonce: String,
+        ciphertext: String,
+    },
+}
+
+/// Additional data authenticated along with the entries of an encrypted vault.
+const ENCRYPTED_VAULT_AAD: &[u8] = b"ockam vault V2";
+
 type Data = RwLock<BTreeMap<KeyId, VaultEntry>>;
 
 /// File Storage
+///
+/// Entries are encrypted at rest if the storage is created with a
+/// [`StorageKey`]. Existing plaintext vault files stay in plaintext until
+/// they are encrypted with [`FileStorage::encrypt`].
 pub struct FileStorage {
     path: PathBuf,
     temp_path: PathBuf,
     data: Data,
+    key: Option<StorageKey>,
+    cipher: Option<Cipher>,
 }
 
 impl FileStorage {
-    async fn deserialize(vault_bytes: &[u8]) -> Result<Data> {
+    /// Check if the vault file at the given path is encrypted.
+    pub fn is_encrypted(path: &Path) -> bool {
+        std::fs::read(path)
+            .ok()
+            .and_then(|bytes| serde_json::from_slice::<EncryptedVault>(&bytes).ok())
+            .is_some()
+    }
+
+    async fn deserialize(&mut self, vault_bytes: &[u8]) -> Result<Data> {
+        if let Ok(vault) = serde_json::from_slice::<EncryptedVault>(vault_bytes) {
+            let EncryptedVault::V2 {
+                kdf,
+                nonce,
+                ciphertext,
+            } = vault;
+            let key = self.key.as_ref().ok_or(VaultError::StorageLocked)?;
+            let cipher = Cipher::derive(kdf, key)?;
+            let plaintext = cipher.decrypt(&nonce, &ciphertext, ENCRYPTED_VAULT_AAD)?;
+            self.cipher = Some(cipher);
+            return Self::deserialize_entries(&plaintext).await;
+        }
+
+        Self::deserialize_entries(vault_bytes).await
+    }
+
+    async fn deserialize_entries(vault_bytes: &[u8]) -> Result<Data> {
         let vault: LegacySerializedVault =
             serde_json::from_slice(vault_bytes).map_err(|_| VaultError::InvalidStorageData)?;
 
@@ -86,7 +135,20 @@ impl FileStorage {
             next_id: 0,
         };
 
-        serde_json::to_vec(&v).map_err(|_| VaultError::StorageError.into())
+        let plaintext = serde_json::to_vec(&v).map_err(|_| VaultError::StorageError)?;
+
+        match &self.cipher {
+            None => Ok(plaintext),
+            Some(cipher) => {
+                let (nonce, ciphertext) = cipher.encrypt(&plaintext, ENCRYPTED_VAULT_AAD)?;
+                let v = EncryptedVault::V2 {
+                    kdf: cipher.kdf().clone(),
+                    nonce,
+                    ciphertext,
+                };
+                serde_json::to_vec(&v).map_err(|_| VaultError::StorageError.into())
+            }
+        }
     }
 
     fn get_temp_path(path: &Path) -> PathBuf {
@@ -129,10 +191,13 @@ impl FileStorage {
     /// If file doesn't exist, it will be created
     pub async fn init(&mut self) -> Result<()> {
         self.data = if !self.path.exists() {
+            if let Some(key) = &self.key {
+                self.cipher = Some(Cipher::create(key)?);
+            }
             Default::default()
         } else {
             let vault_bytes = std::fs::read(&self.path).map_err(|_| VaultError::StorageError)?;
-            Self::deserialize(&vault_bytes).await?
+            self.deserialize(&vault_bytes).await?
         };
 
         let _ = std::fs::remove_file(&self.temp_path);
@@ -151,9 +216,17 @@ impl FileStorage {
             path,
             temp_path: tmp_path,
             data: Default::default(),
+            key: None,
+            cipher: None,
         }
     }
 
+    /// Unlock an encrypted storage, or encrypt a new one, with the given key.
+    pub fn with_key(mut self, key: StorageKey) -> Self {
+        self.key = Some(key);
+        self
+    }
+
     /// Create and init Storage
     pub async fn create(path: PathBuf) -> Result<Self> {
         let mut s = Self::new(path);
@@ -162,6 +235,29 @@ impl FileStorage {
         Ok(s)
     }
 
+    /// Create and init Storage, unlocked or encrypted with the given key if any
+    pub async fn create_with_key(path: PathBuf, key: Option<StorageKey>) -> Result<Self> {
+        let mut s = Self::new(path);
+        s.key = key;
+        s.init().await?;
+
+        Ok(s)
+    }
+
+    /// Encrypt the existing plaintext vault file at the given path
+    pub async fn encrypt(path: PathBuf, key: StorageKey) -> Result<Self> {
+        if Self::is_encrypted(&path) {
+            return Err(VaultError::StorageEncrypted.into());
+        }
+        let mut s = Self::create(path).await?;
+        debug!("Encrypting vault at {:?}", &s.path);
+        s.cipher = Some(Cipher::create(&key)?);
+        s.key = Some(key);
+        s.flush_to_file().await?;
+
+        Ok(s)
+    }
+
     /// Clear the Storage
     pub async fn clear(&self) {
         if self.path.exists() {
@@ -252,4 +348,73 @@ mod tests {
         let attributes31 = vault.secret_attributes_get(&key_id3).await;
         assert!(attributes31.is_err());
     }
+
+    #[tokio::test]
+    #[allow(non_snake_case)]
+    async fn encrypted_storage__wrong_key__fails() {
+        let path = std::env::temp_dir().join(hex::encode(rand::random::<[u8; 16]>()));
+        let key = StorageKey::Passphrase("correct horse".into());
+        let storage = FileStorage::create_with_key(path.clone(), Some(key.clone()))
+            .await
+            .unwrap();
+        let vault = Vault::new(Some(Arc::new(storage)));
+        let attributes =
+            SecretAttributes::new(SecretType::Ed25519, SecretPersistence::Persistent, 0);
+        let key_id = vault.secret_generate(attributes).await.unwrap();
+
+        assert!(FileStorage::is_encrypted(&path));
+        let contents = std::fs::read_to_string(&path).unwrap();
+        assert!(!contents.contains(&key_id));
+
+        assert!(FileStorage::create(path.clone()).await.is_err());
+        let wrong = StorageKey::Passphrase("battery staple".into());
+        assert!(FileStorage::create_with_key(path.clone(), Some(wrong))
+            .await
+            .is_err());
+
+        let storage = FileStorage::create_with_key(path.clone(), Some(key))
+            .await
+            .unwrap();
+        assert!(storage.load(&key_id).await.is_ok());
+        storage.clear().await;
+    }
+
+    #[tokio::test]
+    #[allow(non_snake_case)]
+    async fn legacy_storage__opened_with_key__is_encrypted_explicitly() {
+        let dir = std::env::temp_dir();
+        let path = dir.join(hex::encode(rand::random::<[u8; 16]>()));
+        let key_file = dir.join(hex::encode(rand::random::<[u8; 16]>()));
+        std::fs::write(&key_file, rand::random::<[u8; 32]>()).unwrap();
+
+        let storage = FileStorage::create(path.clone()).await.unwrap();
+        let vault = Vault::new(Some(Arc::new(storage)));
+        let attributes =
+            SecretAttributes::new(SecretType::X25519, SecretPersistence::Persistent, 0);
+        let key_id = vault.secret_generate(attributes).await.unwrap();
+        assert!(!FileStorage::is_encrypted(&path));
+
+        let key = StorageKey::KeyFile(key_file.clone());
+        let storage = FileStorage::create_with_key(path.clone(), Some(key.clone()))
+            .await
+            .unwrap();
+        assert!(!FileStorage::is_encrypted(&path));
+        assert!(storage.load(&key_id).await.is_ok());
+
+        let storage = FileStorage::encrypt(path.clone(), key.clone())
+            .await
+            .unwrap();
+        assert!(FileStorage::is_encrypted(&path));
+        assert!(storage.load(&key_id).await.is_ok());
+        assert!(FileStorage::encrypt(path.clone(), key.clone())
+            .await
+            .is_err());
+
+        let storage = FileStorage::create_with_key(path.clone(), Some(key))
+            .await
+            .unwrap();
+        assert!(storage.load(&key_id).await.is_ok());
+        storage.clear().await;
+        let _ = std::fs::remove_file(key_file);
+    }
 }
@@ -0,0 +1,198 @@
+use crate::VaultError;
+use aes_gcm::aead::{generic_array::GenericArray, Aead, NewAead, Payload};
+use aes_gcm::Aes256Gcm;
+use argon2::{Algorithm, Argon2, Params, Version};
+use ockam_core::Result;
+use rand::{thread_rng, RngCore};
+use serde::{Deserialize, Serialize};
+use sha2::Sha256;
+use std::path::PathBuf;
+
+/// Environment variable holding the passphrase of an encrypted vault.
+pub const VAULT_PASSPHRASE_ENV: &str = "OCKAM_VAULT_PASSPHRASE";
+
+/// Environment variable holding the path to the key file of an encrypted vault.
+pub const VAULT_KEY_FILE_ENV: &str = "OCKAM_VAULT_KEY_FILE";
+
+const SALT_LEN: usize = 16;
+const NONCE_LEN: usize = 12;
+const KEY_LEN: usize = 32;
+
+/// Secret used to encrypt a [`FileStorage`](super::FileStorage) at rest.
+#[derive(Clone)]
+pub enum StorageKey {
+    /// Derive the encryption key from a passphrase, using Argon2id.
+    Passphrase(String),
+    /// Derive the encryption key from the contents of a file.
+    KeyFile(PathBuf),
+}
+
+impl core::fmt::Debug for StorageKey {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        match self {
+            StorageKey::Passphrase(_) => f.write_str("Passphrase(..)"),
+            StorageKey::KeyFile(p) => f.debug_tuple("KeyFile").field(p).finish(),
+        }
+    }
+}
+
+impl StorageKey {
+    /// Read the storage key from [`VAULT_PASSPHRASE_ENV`] or [`VAULT_KEY_FILE_ENV`].
+    pub fn from_env() -> Option<Self> {
+        if let Ok(p) = std::env::var(VAULT_PASSPHRASE_ENV) {
+            return Some(StorageKey::Passphrase(p));
+        }
+        std::env::var_os(VAULT_KEY_FILE_ENV).map(|p| StorageKey::KeyFile(p.into()))
+    }
+}
+
+/// How the encryption key of a vault file is derived.
+#[derive(Serialize, Deserialize, Clone)]
+#[serde(tag = "type")]
+pub(crate) enum Kdf {
+    Argon2id {
+        salt: String,
+        m_cost: u32,
+        t_cost: u32,
+        p_cost: u32,
+    },
+    KeyFile {
+        salt: String,
+    },
+}
+
+impl Kdf {
+    /// Key derivation parameters with a fresh salt, suitable for the given key.
+    fn new(key: &StorageKey) -> Self {
+        let mut salt = [0u8; SALT_LEN];
+        thread_rng().fill_bytes(&mut salt);
+        let salt = hex::encode(salt);
+        match key {
+            StorageKey::Passphrase(_) => Kdf::Argon2id {
+                salt,
+                m_cost: Params::DEFAULT_M_COST,
+                t_cost: Params::DEFAULT_T_COST,
+                p_cost: Params::DEFAULT_P_COST,
+            },
+            StorageKey::KeyFile(_) => Kdf::KeyFile { salt },
+        }
+    }
+
+    fn derive(&self, key: &StorageKey) -> Result<[u8; KEY_LEN]> {
+        let mut out = [0u8; KEY_LEN];
+        match (self, key) {
+            (
+                Kdf::Argon2id {
+                    salt,
+                    m_cost,
+                    t_cost,
+                    p_cost,
+                },
+                StorageKey::Passphrase(passphrase),
+            ) => {
+                let salt = hex::decode(salt).map_err(|_| VaultError::InvalidStorageData)?;
+                let params = Params::new(*m_cost, *t_cost, *p_cost, Some(KEY_LEN))
+                    .map_err(|_| VaultError::InvalidStorageData)?;
+                Argon2::new(Algorithm::Argon2id, Version::V0x13, params)
+                    .hash_password_into(passphrase.as_bytes(), &salt, &mut out)
+                    .map_err(|_| VaultError::InvalidStorageKey)?;
+            }
+            (Kdf::KeyFile { salt }, StorageKey::KeyFile(path)) => {
+                let salt = hex::decode(salt).map_err(|_| VaultError::InvalidStorageData)?;
+                let ikm = std::fs::read(path).map_err(|_| VaultError::InvalidStorageKey)?;
+                if ikm.is_empty() {
+                    return Err(VaultError::InvalidStorageKey.into());
+                }
+                hkdf::Hkdf::<Sha256>::new(Some(&salt), &ikm)
+                    .expand(b"ockam vault storage", &mut out)
+                    .map_err(|_| VaultError::HkdfExpandError)?;
+            }
+            _ => return Err(VaultError::InvalidStorageKey.into()),
+        }
+        Ok(out)
+    }
+}
+
+/// Key used to encrypt and decrypt a vault file.
+pub(crate) struct Cipher {
+    kdf: Kdf,
+    key: [u8; KEY_LEN],
+}
+
+impl Cipher {
+    /// Derive a key with fresh parameters.
+    pub(crate) fn create(key: &StorageKey) -> Result<Self> {
+        Self::derive(Kdf::new(key), key)
+    }
+
+    /// Derive a key with the parameters stored in an existing vault file.
+    pub(crate) fn derive(kdf: Kdf, key: &StorageKey) -> Result<Self> {
+        let key = kdf.derive(key)?;
+        Ok(Cipher { kdf, key })
+    }
+
+    pub(crate) fn kdf(&self) -> &Kdf {
+        &self.kdf
+    }
+
+    /// Encrypt the given plaintext, returning the hex encoded nonce and ciphertext.
+    pub(crate) fn encrypt(&self, plaintext: &[u8], aad: &[u8]) -> Result<(String, String)> {
+        let mut nonce = [0u8; NONCE_LEN];
+        thread_rng().fill_bytes(&mut nonce);
+        let ciphertext = Aes256Gcm::new(GenericArray::from_slice(&self.key))
+            .encrypt(
+                GenericArray::from_slice(&nonce),
+                Payload {
+                    msg: plaintext,
+                    aad,
+                },
+            )
+            .map_err(|_| VaultError::AeadAesGcmEncrypt)?;
+        Ok((hex::encode(nonce), hex::encode(ciphertext)))
+    }
+
+    /// Decrypt hex encoded ciphertext. Fails if the key is wrong.
+    pub(crate) fn decrypt(&self, nonce: &str, ciphertext: &str, aad: &[u8]) -> Result<Vec<u8>> {
+        let nonce = hex::decode(nonce).map_err(|_| VaultError::InvalidStorageData)?;
+        let ciphertext = hex::decode(ciphertext).map_err(|_| VaultError::InvalidStorageData)?;
+        if nonce.len() != NONCE_LEN {
+            return Err(VaultError::InvalidStorageData.into());
+        }
+        Aes256Gcm::new(GenericArray::from_slice(&self.key))
+            .decrypt(
+                GenericArray::from_slice(&nonce),
+                Payload {
+                    msg: &ciphertext,
+                    aad,
+                },
+            )
+            .map_err(|_| VaultError::InvalidStorageKey.into())
+    }
+}
+
+/// Data encrypted with a key derived from a [`StorageKey`], along with the
+/// parameters needed to derive that key again.
+#[derive(Serialize, Deserialize)]
+pub struct SealedData {
+    kdf: Kdf,
+    nonce: String,
+    ciphertext: String,
+}
+
+impl SealedData {
+    /// Encrypt `plaintext` with a key derived from `key` using fresh parameters.
+    pub fn seal(key: &StorageKey, plaintext: &[u8], aad: &[u8]) -> Result<Self> {
+        let cipher = Cipher::create(key)?;
+        let (nonce, ciphertext) = cipher.encrypt(plaintext, aad)?;
+        Ok(SealedData {
+            kdf: cipher.kdf,
+            nonce,
+            ciphertext,
+        })
+    }
+
+    /// Decrypt the sealed data. Fails if `key` or `aad` don't match.
+    pub fn open(&self, key: &StorageKey, aad: &[u8]) -> Result<Vec<u8>> {
+        Cipher::derive(self.kdf.clone(), key)?.decrypt(&self.nonce, &self.ciphertext, aad)
+    }
+}
@@ -0,0 +1,63 @@
+use ockam_core::{
+    errcode::{Kind, Origin},
+    Error,
+};
+use std::os::raw::c_ulong;
+
+/// Represents the failures that can occur in
+/// a PKCS#11 vault
+#[derive(Clone, Copy, Debug)]
+pub enum Pkcs11Error {
+    /// The PKCS#11 module could not be loaded
+    ModuleLoad,
+    /// No token with the requested label
+    TokenNotFound,
+    /// A PKCS#11 function returned an error code
+    Function(&'static str, c_ulong),
+    /// Entry not found
+    EntryNotFound,
+    /// Invalid key type
+    InvalidKeyType,
+    /// Public key is invalid
+    InvalidPublicKey,
+    /// Invalid AES key length
+    InvalidAesKeyLength,
+    /// Invalid HKDF output type
+    InvalidHkdfOutputType,
+    /// Secrets never leave the token
+    NotExtractable,
+}
+
+impl ockam_core::compat::error::Error for Pkcs11Error {}
+impl core::fmt::Display for Pkcs11Error {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        match self {
+            Self::ModuleLoad => write!(f, "failed to load PKCS#11 module"),
+            Self::TokenNotFound => write!(f, "PKCS#11 token not found"),
+            Self::Function(name, rv) => write!(f, "{} failed with error {:#x}", name, rv),
+            Self::EntryNotFound => write!(f, "entry not found"),
+            Self::InvalidKeyType => write!(f, "invalid key type"),
+            Self::InvalidPublicKey => write!(f, "public key is invalid"),
+            Self::InvalidAesKeyLength => write!(f, "invalid AES key length"),
+            Self::InvalidHkdfOutputType => write!(f, "invalid HKDF output type"),
+            Self::NotExtractable => write!(f, "secret can not be extracted from the token"),
+        }
+    }
+}
+
+impl From<Pkcs11Error> for Error {
+    #[track_caller]
+    fn from(err: Pkcs11Error) -> Self {
+        use Pkcs11Error::*;
+        let kind = match err {
+            ModuleLoad | Function(..) => Kind::Io,
+            TokenNotFound | EntryNotFound => Kind::NotFound,
+            InvalidKeyType | InvalidPublicKey | InvalidAesKeyLength | InvalidHkdfOutputType => {
+                Kind::Misuse
+            }
+            NotExtractable => Kind::Unsupported,
+        };
+
+        Error::new(Origin::Vault, kind, err)
+    }
+}
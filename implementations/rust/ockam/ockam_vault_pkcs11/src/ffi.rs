@@ -0,0 +1,374 @@
+//! Subset of the PKCS#11 (v2.40 and v3.0) C interface used by this crate.
+//!
+//! Only the types, constants and functions needed by [`Pkcs11Vault`](crate::Pkcs11Vault)
+//! are declared. Unused entries of the function list are kept as opaque pointers
+//! so that the layout of [`CK_FUNCTION_LIST`] matches the specification.
+#![allow(non_camel_case_types, non_snake_case, missing_docs, dead_code)]
+
+use core::ffi::c_void;
+use std::os::raw::c_ulong;
+
+pub type CK_BYTE = u8;
+pub type CK_BBOOL = u8;
+pub type CK_ULONG = c_ulong;
+pub type CK_RV = CK_ULONG;
+pub type CK_FLAGS = CK_ULONG;
+pub type CK_SLOT_ID = CK_ULONG;
+pub type CK_SESSION_HANDLE = CK_ULONG;
+pub type CK_OBJECT_HANDLE = CK_ULONG;
+pub type CK_OBJECT_CLASS = CK_ULONG;
+pub type CK_KEY_TYPE = CK_ULONG;
+pub type CK_ATTRIBUTE_TYPE = CK_ULONG;
+pub type CK_MECHANISM_TYPE = CK_ULONG;
+pub type CK_USER_TYPE = CK_ULONG;
+
+pub const CK_TRUE: CK_BBOOL = 1;
+pub const CK_FALSE: CK_BBOOL = 0;
+
+pub const CKR_OK: CK_RV = 0x0;
+pub const CKR_FUNCTION_NOT_SUPPORTED: CK_RV = 0x54;
+pub const CKR_USER_ALREADY_LOGGED_IN: CK_RV = 0x100;
+pub const CKR_CRYPTOKI_ALREADY_INITIALIZED: CK_RV = 0x191;
+
+pub const CKF_RW_SESSION: CK_FLAGS = 0x2;
+pub const CKF_SERIAL_SESSION: CK_FLAGS = 0x4;
+pub const CKF_OS_LOCKING_OK: CK_FLAGS = 0x2;
+
+pub const CKU_USER: CK_USER_TYPE = 1;
+
+pub const CKO_DATA: CK_OBJECT_CLASS = 0x0;
+pub const CKO_PUBLIC_KEY: CK_OBJECT_CLASS = 0x2;
+pub const CKO_PRIVATE_KEY: CK_OBJECT_CLASS = 0x3;
+pub const CKO_SECRET_KEY: CK_OBJECT_CLASS = 0x4;
+
+pub const CKK_GENERIC_SECRET: CK_KEY_TYPE = 0x10;
+pub const CKK_AES: CK_KEY_TYPE = 0x1f;
+pub const CKK_EC_EDWARDS: CK_KEY_TYPE = 0x40;
+pub const CKK_EC_MONTGOMERY: CK_KEY_TYPE = 0x41;
+
+pub const CKA_CLASS: CK_ATTRIBUTE_TYPE = 0x0;
+pub const CKA_TOKEN: CK_ATTRIBUTE_TYPE = 0x1;
+pub const CKA_PRIVATE: CK_ATTRIBUTE_TYPE = 0x2;
+pub const CKA_LABEL: CK_ATTRIBUTE_TYPE = 0x3;
+pub const CKA_VALUE: CK_ATTRIBUTE_TYPE = 0x11;
+pub const CKA_KEY_TYPE: CK_ATTRIBUTE_TYPE = 0x100;
+pub const CKA_ID: CK_ATTRIBUTE_TYPE = 0x102;
+pub const CKA_SENSITIVE: CK_ATTRIBUTE_TYPE = 0x103;
+pub const CKA_ENCRYPT: CK_ATTRIBUTE_TYPE = 0x104;
+pub const CKA_DECRYPT: CK_ATTRIBUTE_TYPE = 0x105;
+pub const CKA_SIGN: CK_ATTRIBUTE_TYPE = 0x108;
+pub const CKA_VERIFY: CK_ATTRIBUTE_TYPE = 0x10a;
+pub const CKA_DERIVE: CK_ATTRIBUTE_TYPE = 0x10c;
+pub const CKA_VALUE_LEN: CK_ATTRIBUTE_TYPE = 0x161;
+pub const CKA_EXTRACTABLE: CK_ATTRIBUTE_TYPE = 0x162;
+pub const CKA_EC_PARAMS: CK_ATTRIBUTE_TYPE = 0x180;
+pub const CKA_EC_POINT: CK_ATTRIBUTE_TYPE = 0x181;
+
+pub const CKM_SHA256: CK_MECHANISM_TYPE = 0x250;
+pub const CKM_GENERIC_SECRET_KEY_GEN: CK_MECHANISM_TYPE = 0x350;
+pub const CKM_EXTRACT_KEY_FROM_KEY: CK_MECHANISM_TYPE = 0x365;
+pub const CKM_ECDH1_DERIVE: CK_MECHANISM_TYPE = 0x1050;
+pub const CKM_EC_EDWARDS_KEY_PAIR_GEN: CK_MECHANISM_TYPE = 0x1055;
+pub const CKM_EC_MONTGOMERY_KEY_PAIR_GEN: CK_MECHANISM_TYPE = 0x1056;
+pub const CKM_EDDSA: CK_MECHANISM_TYPE = 0x1057;
+pub const CKM_AES_KEY_GEN: CK_MECHANISM_TYPE = 0x1080;
+pub const CKM_AES_GCM: CK_MECHANISM_TYPE = 0x1087;
+pub const CKM_HKDF_DERIVE: CK_MECHANISM_TYPE = 0x402a;
+pub const CKM_HKDF_DATA: CK_MECHANISM_TYPE = 0x402b;
+
+pub const CKD_NULL: CK_ULONG = 0x1;
+
+pub const CKF_HKDF_SALT_KEY: CK_FLAGS = 0x4;
+
+/// DER encoded OID of Ed25519 (1.3.101.112), used as `CKA_EC_PARAMS`.
+pub const ED25519_PARAMS: &[u8] = &[0x06, 0x03, 0x2b, 0x65, 0x70];
+
+/// DER encoded OID of X25519 (1.3.101.110), used as `CKA_EC_PARAMS`.
+pub const X25519_PARAMS: &[u8] = &[0x06, 0x03, 0x2b, 0x65, 0x6e];
+
+#[repr(C)]
+#[derive(Debug, Clone, Copy, Default)]
+pub struct CK_VERSION {
+    pub major: CK_BYTE,
+    pub minor: CK_BYTE,
+}
+
+#[repr(C)]
+pub struct CK_ATTRIBUTE {
+    pub type_: CK_ATTRIBUTE_TYPE,
+    pub pValue: *mut c_void,
+    pub ulValueLen: CK_ULONG,
+}
+
+#[repr(C)]
+pub struct CK_MECHANISM {
+    pub mechanism: CK_MECHANISM_TYPE,
+    pub pParameter: *mut c_void,
+    pub ulParameterLen: CK_ULONG,
+}
+
+#[repr(C)]
+pub struct CK_C_INITIALIZE_ARGS {
+    pub CreateMutex: *mut c_void,
+    pub DestroyMutex: *mut c_void,
+    pub LockMutex: *mut c_void,
+    pub UnlockMutex: *mut c_void,
+    pub flags: CK_FLAGS,
+    pub pReserved: *mut c_void,
+}
+
+#[repr(C)]
+pub struct CK_GCM_PARAMS {
+    pub pIv: *mut CK_BYTE,
+    pub ulIvLen: CK_ULONG,
+    pub ulIvBits: CK_ULONG,
+    pub pAAD: *mut CK_BYTE,
+    pub ulAADLen: CK_ULONG,
+    pub ulTagBits: CK_ULONG,
+}
+
+#[repr(C)]
+pub struct CK_ECDH1_DERIVE_PARAMS {
+    pub kdf: CK_ULONG,
+    pub ulSharedDataLen: CK_ULONG,
+    pub pSharedData: *mut CK_BYTE,
+    pub ulPublicDataLen: CK_ULONG,
+    pub pPublicData: *mut CK_BYTE,
+}
+
+#[repr(C)]
+pub struct CK_HKDF_PARAMS {
+    pub bExtract: CK_BBOOL,
+    pub bExpand: CK_BBOOL,
+    pub prfHashMechanism: CK_MECHANISM_TYPE,
+    pub ulSaltType: CK_ULONG,
+    pub pSalt: *mut CK_BYTE,
+    pub ulSaltLen: CK_ULONG,
+    pub hSaltKey: CK_OBJECT_HANDLE,
+    pub pInfo: *mut CK_BYTE,
+    pub ulInfoLen: CK_ULONG,
+}
+
+/// Index of the first bit of the base key extracted by `CKM_EXTRACT_KEY_FROM_KEY`.
+pub type CK_EXTRACT_PARAMS = CK_ULONG;
+
+#[repr(C)]
+pub struct CK_TOKEN_INFO {
+    pub label: [CK_BYTE; 32],
+    pub manufacturerID: [CK_BYTE; 32],
+    pub model: [CK_BYTE; 16],
+    pub serialNumber: [CK_BYTE; 16],
+    pub flags: CK_FLAGS,
+    pub ulMaxSessionCount: CK_ULONG,
+    pub ulSessionCount: CK_ULONG,
+    pub ulMaxRwSessionCount: CK_ULONG,
+    pub ulRwSessionCount: CK_ULONG,
+    pub ulMaxPinLen: CK_ULONG,
+    pub ulMinPinLen: CK_ULONG,
+    pub ulTotalPublicMemory: CK_ULONG,
+    pub ulFreePublicMemory: CK_ULONG,
+    pub ulTotalPrivateMemory: CK_ULONG,
+    pub ulFreePrivateMemory: CK_ULONG,
+    pub hardwareVersion: CK_VERSION,
+    pub firmwareVersion: CK_VERSION,
+    pub utcTime: [CK_BYTE; 16],
+}
+
+/// A function of the list which is not used by this crate.
+type Unused = Option<unsafe extern "C" fn()>;
+
+#[repr(C)]
+pub struct CK_FUNCTION_LIST {
+    pub version: CK_VERSION,
+    pub C_Initialize: Option<unsafe extern "C" fn(*mut c_void) -> CK_RV>,
+    pub C_Finalize: Option<unsafe extern "C" fn(*mut c_void) -> CK_RV>,
+    pub C_GetInfo: Unused,
+    pub C_GetFunctionList: Unused,
+    pub C_GetSlotList:
+        Option<unsafe extern "C" fn(CK_BBOOL, *mut CK_SLOT_ID, *mut CK_ULONG) -> CK_RV>,
+    pub C_GetSlotInfo: Unused,
+    pub C_GetTokenInfo: Option<unsafe extern "C" fn(CK_SLOT_ID, *mut CK_TOKEN_INFO) -> CK_RV>,
+    pub C_GetMechanismList: Unused,
+    pub C_GetMechanismInfo: Unused,
+    pub C_InitToken: Unused,
+    pub C_InitPIN: Unused,
+    pub C_SetPIN: Unused,
+    pub C_OpenSession: Option<
+        unsafe extern "C" fn(
+            CK_SLOT_ID,
+            CK_FLAGS,
+            *mut c_void,
+            *mut c_void,
+            *mut CK_SESSION_HANDLE,
+        ) -> CK_RV,
+    >,
+    pub C_CloseSession: Option<unsafe extern "C" fn(CK_SESSION_HANDLE) -> CK_RV>,
+    pub C_CloseAllSessions: Unused,
+    pub C_GetSessionInfo: Unused,
+    pub C_GetOperationState: Unused,
+    pub C_SetOperationState: Unused,
+    pub C_Login: Option<
+        unsafe extern "C" fn(CK_SESSION_HANDLE, CK_USER_TYPE, *const CK_BYTE, CK_ULONG) -> CK_RV,
+    >,
+    pub C_Logout: Unused,
+    pub C_CreateObject: Option<
+        unsafe extern "C" fn(
+            CK_SESSION_HANDLE,
+            *mut CK_ATTRIBUTE,
+            CK_ULONG,
+            *mut CK_OBJECT_HANDLE,
+        ) -> CK_RV,
+    >,
+    pub C_CopyObject: Unused,
+    pub C_DestroyObject: Option<unsafe extern "C" fn(CK_SESSION_HANDLE, CK_OBJECT_HANDLE) -> CK_RV>,
+    pub C_GetObjectSize: Unused,
+    pub C_GetAttributeValue: Option<
+        unsafe extern "C" fn(
+            CK_SESSION_HANDLE,
+            CK_OBJECT_HANDLE,
+            *mut CK_ATTRIBUTE,
+            CK_ULONG,
+        ) -> CK_RV,
+    >,
+    pub C_SetAttributeValue: Option<
+        unsafe extern "C" fn(
+            CK_SESSION_HANDLE,
+            CK_OBJECT_HANDLE,
+            *mut CK_ATTRIBUTE,
+            CK_ULONG,
+        ) -> CK_RV,
+    >,
+    pub C_FindObjectsInit:
+        Option<unsafe extern "C" fn(CK_SESSION_HANDLE, *mut CK_ATTRIBUTE, CK_ULONG) -> CK_RV>,
+    pub C_FindObjects: Option<
+        unsafe extern "C" fn(
+            CK_SESSION_HANDLE,
+            *mut CK_OBJECT_HANDLE,
+            CK_ULONG,
+            *mut CK_ULONG,
+        ) -> CK_RV,
+    >,
+    pub C_FindObjectsFinal: Option<unsafe extern "C" fn(CK_SESSION_HANDLE) -> CK_RV>,
+    pub C_EncryptInit: Option<
+        unsafe extern "C" fn(CK_SESSION_HANDLE, *mut CK_MECHANISM, CK_OBJECT_HANDLE) -> CK_RV,
+    >,
+    pub C_Encrypt: Option<
+        unsafe extern "C" fn(
+            CK_SESSION_HANDLE,
+            *const CK_BYTE,
+            CK_ULONG,
+            *mut CK_BYTE,
+            *mut CK_ULONG,
+        ) -> CK_RV,
+    >,
+    pub C_EncryptUpdate: Unused,
+    pub C_EncryptFinal: Unused,
+    pub C_DecryptInit: Option<
+        unsafe extern "C" fn(CK_SESSION_HANDLE, *mut CK_MECHANISM, CK_OBJECT_HANDLE) -> CK_RV,
+    >,
+    pub C_Decrypt: Option<
+        unsafe extern "C" fn(
+            CK_SESSION_HANDLE,
+            *const CK_BYTE,
+            CK_ULONG,
+            *mut CK_BYTE,
+            *mut CK_ULONG,
+        ) -> CK_RV,
+    >,
+    pub C_DecryptUpdate: Unused,
+    pub C_DecryptFinal: Unused,
+    pub C_DigestInit: Unused,
+    pub C_Digest: Unused,
+    pub C_DigestUpdate: Unused,
+    pub C_DigestKey: Unused,
+    pub C_DigestFinal: Unused,
+    pub C_SignInit: Option<
+        unsafe extern "C" fn(CK_SESSION_HANDLE, *mut CK_MECHANISM, CK_OBJECT_HANDLE) -> CK_RV,
+    >,
+    pub C_Sign: Option<
+        unsafe extern "C" fn(
+            CK_SESSION_HANDLE,
+            *const CK_BYTE,
+            CK_ULONG,
+            *mut CK_BYTE,
+            *mut CK_ULONG,
+        ) -> CK_RV,
+    >,
+    pub C_SignUpdate: Unused,
+    pub C_SignFinal: Unused,
+    pub C_SignRecoverInit: Unused,
+    pub C_SignRecover: Unused,
+    pub C_VerifyInit: Unused,
+    pub C_Verify: Unused,
+    pub C_VerifyUpdate: Unused,
+    pub C_VerifyFinal: Unused,
+    pub C_VerifyRecoverInit: Unused,
+    pub C_VerifyRecover: Unused,
+    pub C_DigestEncryptUpdate: Unused,
+    pub C_DecryptDigestUpdate: Unused,
+    pub C_SignEncryptUpdate: Unused,
+    pub C_DecryptVerifyUpdate: Unused,
+    pub C_GenerateKey: Option<
+        unsafe extern "C" fn(
+            CK_SESSION_HANDLE,
+            *mut CK_MECHANISM,
+            *mut CK_ATTRIBUTE,
+            CK_ULONG,
+            *mut CK_OBJECT_HANDLE,
+        ) -> CK_RV,
+    >,
+    pub C_GenerateKeyPair: Option<
+        unsafe extern "C" fn(
+            CK_SESSION_HANDLE,
+            *mut CK_MECHANISM,
+            *mut CK_ATTRIBUTE,
+            CK_ULONG,
+            *mut CK_ATTRIBUTE,
+            CK_ULONG,
+            *mut CK_OBJECT_HANDLE,
+            *mut CK_OBJECT_HANDLE,
+        ) -> CK_RV,
+    >,
+    pub C_WrapKey: Unused,
+    pub C_UnwrapKey: Unused,
+    pub C_DeriveKey: Option<
+        unsafe extern "C" fn(
+            CK_SESSION_HANDLE,
+            *mut CK_MECHANISM,
+            CK_OBJECT_HANDLE,
+            *mut CK_ATTRIBUTE,
+            CK_ULONG,
+            *mut CK_OBJECT_HANDLE,
+        ) -> CK_RV,
+    >,
+    pub C_SeedRandom: Unused,
+    pub C_GenerateRandom:
+        Option<unsafe extern "C" fn(CK_SESSION_HANDLE, *mut CK_BYTE, CK_ULONG) -> CK_RV>,
+    pub C_GetFunctionStatus: Unused,
+    pub C_CancelFunction: Unused,
+    pub C_WaitForSlotEvent: Unused,
+}
+
+pub type C_GetFunctionList = unsafe extern "C" fn(*mut *const CK_FUNCTION_LIST) -> CK_RV;
+
+/// Build an attribute pointing to the given value.
+///
+/// The value must outlive every use of the returned attribute.
+pub fn attribute<T>(type_: CK_ATTRIBUTE_TYPE, value: &T) -> CK_ATTRIBUTE {
+    CK_ATTRIBUTE {
+        type_,
+        pValue: value as *const T as *mut c_void,
+        ulValueLen: core::mem::size_of::<T>() as CK_ULONG,
+    }
+}
+
+/// Build an attribute pointing to the given bytes.
+///
+/// The bytes must outlive every use of the returned attribute.
+pub fn bytes_attribute(type_: CK_ATTRIBUTE_TYPE, value: &[u8]) -> CK_ATTRIBUTE {
+    CK_ATTRIBUTE {
+        type_,
+        pValue: value.as_ptr() as *mut c_void,
+        ulValueLen: value.len() as CK_ULONG,
+    }
+}
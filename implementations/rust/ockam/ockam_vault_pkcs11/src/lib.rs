@@ -0,0 +1,13 @@
+//! PKCS#11 implementation of ockam_core::vault traits.
+//!
+//! This crate contains a vault which delegates all operations on secrets to a
+//! PKCS#11 token, such as a hardware security module, so that keys never leave it.
+#![warn(missing_docs, unused_import_braces)]
+
+mod error;
+mod ffi;
+mod token;
+mod vault;
+
+pub use error::*;
+pub use vault::*;
@@ -0,0 +1,447 @@
+use crate::ffi::*;
+use crate::Pkcs11Error;
+use core::ffi::c_void;
+use core::ptr;
+use ockam_core::Result;
+use std::path::Path;
+use std::sync::Mutex;
+use tracing::warn;
+
+fn check(name: &'static str, rv: CK_RV) -> Result<()> {
+    if rv == CKR_OK {
+        Ok(())
+    } else {
+        Err(Pkcs11Error::Function(name, rv).into())
+    }
+}
+
+/// Call a function of the module's function list, returning its result code.
+macro_rules! rv {
+    ($token:expr, $name:ident ( $($arg:expr),* $(,)? )) => {
+        match (*$token.functions).$name {
+            Some(f) => f($($arg),*),
+            None => CKR_FUNCTION_NOT_SUPPORTED,
+        }
+    };
+}
+
+/// Call a function of the module's function list, failing if it returns an error.
+macro_rules! call {
+    ($token:expr, $name:ident ( $($arg:expr),* $(,)? )) => {
+        check(stringify!($name), rv!($token, $name($($arg),*)))
+    };
+}
+
+/// A logged-in session on a PKCS#11 token.
+///
+/// All calls go through a single session, which is guarded by a mutex since
+/// PKCS#11 sessions must not be used concurrently.
+pub(crate) struct Token {
+    functions: *const CK_FUNCTION_LIST,
+    session: Mutex<CK_SESSION_HANDLE>,
+    // Keep the module loaded for as long as `functions` is used.
+    _library: libloading::Library,
+}
+
+// The function list is immutable and the session is only used under the mutex.
+unsafe impl Send for Token {}
+unsafe impl Sync for Token {}
+
+impl Token {
+    /// Load the PKCS#11 module at `module` and log into the token labelled `label`.
+    pub(crate) fn open(module: &Path, label: &str, pin: &str) -> Result<Self> {
+        unsafe {
+            let library = libloading::Library::new(module).map_err(|_| Pkcs11Error::ModuleLoad)?;
+            let get_function_list = library
+                .get::<C_GetFunctionList>(b"C_GetFunctionList\0")
+                .map_err(|_| Pkcs11Error::ModuleLoad)?;
+            let mut functions = ptr::null();
+            check("C_GetFunctionList", get_function_list(&mut functions))?;
+            if functions.is_null() {
+                return Err(Pkcs11Error::ModuleLoad.into());
+            }
+
+            let mut token = Token {
+                functions,
+                session: Mutex::new(0),
+                _library: library,
+            };
+
+            let mut args = CK_C_INITIALIZE_ARGS {
+                CreateMutex: ptr::null_mut(),
+                DestroyMutex: ptr::null_mut(),
+                LockMutex: ptr::null_mut(),
+                UnlockMutex: ptr::null_mut(),
+                flags: CKF_OS_LOCKING_OK,
+                pReserved: ptr::null_mut(),
+            };
+            // The module may have been initialized by another vault of this process
+            let rv = rv!(token, C_Initialize(&mut args as *mut _ as *mut c_void));
+            if rv != CKR_CRYPTOKI_ALREADY_INITIALIZED {
+                check("C_Initialize", rv)?;
+            }
+
+            let slot = token.find_slot(label)?;
+            let mut session = 0;
+            call!(
+                token,
+                C_OpenSession(
+                    slot,
+                    CKF_SERIAL_SESSION | CKF_RW_SESSION,
+                    ptr::null_mut(),
+                    ptr::null_mut(),
+                    &mut session,
+                )
+            )?;
+            *token.session.get_mut().unwrap() = session;
+
+            let rv = rv!(
+                token,
+                C_Login(session, CKU_USER, pin.as_ptr(), pin.len() as CK_ULONG)
+            );
+            if rv != CKR_USER_ALREADY_LOGGED_IN {
+                check("C_Login", rv)?;
+            }
+
+            Ok(token)
+        }
+    }
+
+    unsafe fn find_slot(&self, label: &str) -> Result<CK_SLOT_ID> {
+        let mut count = 0;
+        call!(self, C_GetSlotList(CK_TRUE, ptr::null_mut(), &mut count))?;
+        let mut slots = vec![0; count as usize];
+        call!(self, C_GetSlotList(CK_TRUE, slots.as_mut_ptr(), &mut count))?;
+        slots.truncate(count as usize);
+
+        for slot in slots {
+            let mut info: CK_TOKEN_INFO = core::mem::zeroed();
+            call!(self, C_GetTokenInfo(slot, &mut info))?;
+            // Labels are padded with blanks
+            let l = String::from_utf8_lossy(&info.label);
+            if l.trim_end() == label {
+                return Ok(slot);
+            }
+        }
+
+        Err(Pkcs11Error::TokenNotFound.into())
+    }
+
+    fn session(&self) -> std::sync::MutexGuard<'_, CK_SESSION_HANDLE> {
+        self.session.lock().unwrap()
+    }
+
+    /// Find all objects matching the given template.
+    pub(crate) fn find(&self, template: &mut [CK_ATTRIBUTE]) -> Result<Vec<CK_OBJECT_HANDLE>> {
+        let session = self.session();
+        let mut objects = Vec::new();
+        unsafe {
+            call!(
+                self,
+                C_FindObjectsInit(*session, template.as_mut_ptr(), template.len() as CK_ULONG)
+            )?;
+            let res = loop {
+                let mut batch = [0; 16];
+                let mut count = 0;
+                let res = call!(
+                    self,
+                    C_FindObjects(
+                        *session,
+                        batch.as_mut_ptr(),
+                        batch.len() as CK_ULONG,
+                        &mut count
+                    )
+                );
+                if res.is_err() || count == 0 {
+                    break res;
+                }
+                objects.extend_from_slice(&batch[..count as usize]);
+            };
+            call!(self, C_FindObjectsFinal(*session))?;
+            res?;
+        }
+        Ok(objects)
+    }
+
+    /// Read a single attribute of the given object.
+    pub(crate) fn attribute(
+        &self,
+        object: CK_OBJECT_HANDLE,
+        type_: CK_ATTRIBUTE_TYPE,
+    ) -> Result<Vec<u8>> {
+        let session = self.session();
+        let mut attr = CK_ATTRIBUTE {
+            type_,
+            pValue: ptr::null_mut(),
+            ulValueLen: 0,
+        };
+        unsafe {
+            call!(self, C_GetAttributeValue(*session, object, &mut attr, 1))?;
+            let mut value = vec![0u8; attr.ulValueLen as usize];
+            attr.pValue = value.as_mut_ptr() as *mut c_void;
+            call!(self, C_GetAttributeValue(*session, object, &mut attr, 1))?;
+            value.truncate(attr.ulValueLen as usize);
+            Ok(value)
+        }
+    }
+
+    /// Read a `CK_ULONG` attribute of the given object.
+    pub(crate) fn ulong_attribute(
+        &self,
+        object: CK_OBJECT_HANDLE,
+        type_: CK_ATTRIBUTE_TYPE,
+    ) -> Result<CK_ULONG> {
+        let session = self.session();
+        let mut value: CK_ULONG = 0;
+        let mut attr = CK_ATTRIBUTE {
+            type_,
+            pValue: &mut value as *mut CK_ULONG as *mut c_void,
+            ulValueLen: core::mem::size_of::<CK_ULONG>() as CK_ULONG,
+        };
+        unsafe {
+            call!(self, C_GetAttributeValue(*session, object, &mut attr, 1))?;
+        }
+        Ok(value)
+    }
+
+    pub(crate) fn set_attributes(
+        &self,
+        object: CK_OBJECT_HANDLE,
+        template: &mut [CK_ATTRIBUTE],
+    ) -> Result<()> {
+        let session = self.session();
+        unsafe {
+            call!(
+                self,
+                C_SetAttributeValue(
+                    *session,
+                    object,
+                    template.as_mut_ptr(),
+                    template.len() as CK_ULONG,
+                )
+            )
+        }
+    }
+
+    pub(crate) fn create_object(&self, template: &mut [CK_ATTRIBUTE]) -> Result<CK_OBJECT_HANDLE> {
+        let session = self.session();
+        let mut object = 0;
+        unsafe {
+            call!(
+                self,
+                C_CreateObject(
+                    *session,
+                    template.as_mut_ptr(),
+                    template.len() as CK_ULONG,
+                    &mut object,
+                )
+            )?;
+        }
+        Ok(object)
+    }
+
+    pub(crate) fn destroy_object(&self, object: CK_OBJECT_HANDLE) -> Result<()> {
+        let session = self.session();
+        unsafe { call!(self, C_DestroyObject(*session, object)) }
+    }
+
+    pub(crate) fn generate_key(
+        &self,
+        mechanism: CK_MECHANISM_TYPE,
+        template: &mut [CK_ATTRIBUTE],
+    ) -> Result<CK_OBJECT_HANDLE> {
+        let session = self.session();
+        let mut mechanism = CK_MECHANISM {
+            mechanism,
+            pParameter: ptr::null_mut(),
+            ulParameterLen: 0,
+        };
+        let mut object = 0;
+        unsafe {
+            call!(
+                self,
+                C_GenerateKey(
+                    *session,
+                    &mut mechanism,
+                    template.as_mut_ptr(),
+                    template.len() as CK_ULONG,
+                    &mut object,
+                )
+            )?;
+        }
+        Ok(object)
+    }
+
+    /// Generate a key pair, returning the public and private key handles.
+    pub(crate) fn generate_key_pair(
+        &self,
+        mechanism: CK_MECHANISM_TYPE,
+        public: &mut [CK_ATTRIBUTE],
+        private: &mut [CK_ATTRIBUTE],
+    ) -> Result<(CK_OBJECT_HANDLE, CK_OBJECT_HANDLE)> {
+        let session = self.session();
+        let mut mechanism = CK_MECHANISM {
+            mechanism,
+            pParameter: ptr::null_mut(),
+            ulParameterLen: 0,
+        };
+        let (mut pk, mut sk) = (0, 0);
+        unsafe {
+            call!(
+                self,
+                C_GenerateKeyPair(
+                    *session,
+                    &mut mechanism,
+                    public.as_mut_ptr(),
+                    public.len() as CK_ULONG,
+                    private.as_mut_ptr(),
+                    private.len() as CK_ULONG,
+                    &mut pk,
+                    &mut sk,
+                )
+            )?;
+        }
+        Ok((pk, sk))
+    }
+
+    pub(crate) fn derive_key(
+        &self,
+        mechanism: &mut CK_MECHANISM,
+        base: CK_OBJECT_HANDLE,
+        template: &mut [CK_ATTRIBUTE],
+    ) -> Result<CK_OBJECT_HANDLE> {
+        let session = self.session();
+        let mut object = 0;
+        unsafe {
+            call!(
+                self,
+                C_DeriveKey(
+                    *session,
+                    mechanism,
+                    base,
+                    template.as_mut_ptr(),
+                    template.len() as CK_ULONG,
+                    &mut object,
+                )
+            )?;
+        }
+        Ok(object)
+    }
+
+    pub(crate) fn sign(
+        &self,
+        mechanism: &mut CK_MECHANISM,
+        key: CK_OBJECT_HANDLE,
+        data: &[u8],
+    ) -> Result<Vec<u8>> {
+        let session = self.session();
+        unsafe {
+            call!(self, C_SignInit(*session, mechanism, key))?;
+            let mut len = 0;
+            call!(
+                self,
+                C_Sign(
+                    *session,
+                    data.as_ptr(),
+                    data.len() as CK_ULONG,
+                    ptr::null_mut(),
+                    &mut len,
+                )
+            )?;
+            let mut signature = vec![0u8; len as usize];
+            call!(
+                self,
+                C_Sign(
+                    *session,
+                    data.as_ptr(),
+                    data.len() as CK_ULONG,
+                    signature.as_mut_ptr(),
+                    &mut len,
+                )
+            )?;
+            signature.truncate(len as usize);
+            Ok(signature)
+        }
+    }
+
+    /// Encrypt `data` in a single part, with an output buffer of at most `max_len` bytes.
+    pub(crate) fn encrypt(
+        &self,
+        mechanism: &mut CK_MECHANISM,
+        key: CK_OBJECT_HANDLE,
+        data: &[u8],
+        max_len: usize,
+    ) -> Result<Vec<u8>> {
+        let session = self.session();
+        let mut out = vec![0u8; max_len];
+        let mut len = max_len as CK_ULONG;
+        unsafe {
+            call!(self, C_EncryptInit(*session, mechanism, key))?;
+            call!(
+                self,
+                C_Encrypt(
+                    *session,
+                    data.as_ptr(),
+                    data.len() as CK_ULONG,
+                    out.as_mut_ptr(),
+                    &mut len,
+                )
+            )?;
+        }
+        out.truncate(len as usize);
+        Ok(out)
+    }
+
+    /// Decrypt `data` in a single part, with an output buffer of at most `max_len` bytes.
+    pub(crate) fn decrypt(
+        &self,
+        mechanism: &mut CK_MECHANISM,
+        key: CK_OBJECT_HANDLE,
+        data: &[u8],
+        max_len: usize,
+    ) -> Result<Vec<u8>> {
+        let session = self.session();
+        let mut out = vec![0u8; max_len];
+        let mut len = max_len as CK_ULONG;
+        unsafe {
+            call!(self, C_DecryptInit(*session, mechanism, key))?;
+            call!(
+                self,
+                C_Decrypt(
+                    *session,
+                    data.as_ptr(),
+                    data.len() as CK_ULONG,
+                    out.as_mut_ptr(),
+                    &mut len,
+                )
+            )?;
+        }
+        out.truncate(len as usize);
+        Ok(out)
+    }
+
+    pub(crate) fn random(&self, len: usize) -> Result<Vec<u8>> {
+        let session = self.session();
+        let mut out = vec![0u8; len];
+        unsafe {
+            call!(
+                self,
+                C_GenerateRandom(*session, out.as_mut_ptr(), len as CK_ULONG)
+            )?;
+        }
+        Ok(out)
+    }
+}
+
+impl Drop for Token {
+    fn drop(&mut self) {
+        // The module is not finalized, as other vaults of this process may still use it.
+        let session = *self.session.get_mut().unwrap();
+        if session != 0 {
+            let rv = unsafe { rv!(self, C_CloseSession(session)) };
+            if rv != CKR_OK {
+                warn!("Failed to close PKCS#11 session: {:#x}", rv);
+            }
+        }
+    }
+}
@@ -0,0 +1,567 @@
+use crate::ffi::*;
+use crate::token::Token;
+use crate::Pkcs11Error;
+use core::ffi::c_void;
+use core::ptr;
+use ockam_core::vault::{
+    AsymmetricVault, Buffer, Hasher, KeyId, PublicKey, SecretAttributes, SecretKey,
+    SecretPersistence, SecretType, SecretVault, Signature, Signer, SymmetricVault, Verifier,
+    AES128_SECRET_LENGTH_U32, AES256_SECRET_LENGTH_U32, CURVE25519_PUBLIC_LENGTH_USIZE,
+    CURVE25519_SECRET_LENGTH_U32,
+};
+use ockam_core::{async_trait, compat::boxed::Box, Result};
+use ockam_vault::Vault;
+use sha2::{Digest, Sha256};
+use std::path::Path;
+use std::sync::Arc;
+
+/// Length of the authentication tag appended by AES-GCM.
+const AES_GCM_TAG_LEN: usize = 16;
+
+/// Length of the block of derived key material of each HKDF output.
+const HKDF_OUTPUT_LEN: usize = 32;
+
+/// Vault implementation which keeps secrets on a PKCS#11 token.
+///
+/// Secrets are generated on the token as sensitive, non-extractable objects, so
+/// they never leave it. The [`KeyId`] of a secret is stored as the `CKA_ID` of the
+/// corresponding token objects. For key pairs it is computed from the public key,
+/// the same way as in the software vault of `ockam_vault`.
+///
+/// Persistent secrets are token objects, while ephemeral ones are session objects
+/// which are gone once the vault is dropped.
+///
+/// Deriving secrets with HKDF, as secure channels do, requires a token supporting
+/// the `CKM_HKDF_DERIVE` mechanism of PKCS#11 v3.0.
+#[derive(Clone)]
+pub struct Pkcs11Vault {
+    token: Arc<Token>,
+}
+
+impl Pkcs11Vault {
+    /// Load the PKCS#11 module at `module` and open a session on the token
+    /// labelled `label`, logging in as user with the given PIN.
+    pub fn open(module: impl AsRef<Path>, label: &str, pin: &str) -> Result<Self> {
+        let token = Token::open(module.as_ref(), label, pin)?;
+        Ok(Self {
+            token: Arc::new(token),
+        })
+    }
+
+    /// Find the object of the given class holding the given key.
+    fn find(&self, key_id: &KeyId, class: CK_OBJECT_CLASS) -> Result<CK_OBJECT_HANDLE> {
+        let mut template = [
+            attribute(CKA_CLASS, &class),
+            bytes_attribute(CKA_ID, key_id.as_bytes()),
+        ];
+        self.token
+            .find(&mut template)?
+            .first()
+            .copied()
+            .ok_or_else(|| Pkcs11Error::EntryNotFound.into())
+    }
+
+    /// Find the private or secret key object holding the given key.
+    fn find_secret(&self, key_id: &KeyId) -> Result<(CK_OBJECT_HANDLE, CK_KEY_TYPE)> {
+        let object = self
+            .find(key_id, CKO_PRIVATE_KEY)
+            .or_else(|_| self.find(key_id, CKO_SECRET_KEY))?;
+        let key_type = self.token.ulong_attribute(object, CKA_KEY_TYPE)?;
+        Ok((object, key_type))
+    }
+
+    fn random_key_id(&self) -> Result<KeyId> {
+        Ok(hex::encode(self.token.random(32)?))
+    }
+
+    fn generate_key_pair(&self, stype: SecretType, token: CK_BBOOL) -> Result<KeyId> {
+        let (mechanism, params, usage) = match stype {
+            SecretType::Ed25519 => (CKM_EC_EDWARDS_KEY_PAIR_GEN, ED25519_PARAMS, CKA_SIGN),
+            SecretType::X25519 => (CKM_EC_MONTGOMERY_KEY_PAIR_GEN, X25519_PARAMS, CKA_DERIVE),
+            _ => return Err(Pkcs11Error::InvalidKeyType.into()),
+        };
+        let mut public = [
+            attribute(CKA_TOKEN, &token),
+            bytes_attribute(CKA_EC_PARAMS, params),
+        ];
+        let mut private = [
+            attribute(CKA_TOKEN, &token),
+            attribute(CKA_PRIVATE, &CK_TRUE),
+            attribute(CKA_SENSITIVE, &CK_TRUE),
+            attribute(CKA_EXTRACTABLE, &CK_FALSE),
+            attribute(usage, &CK_TRUE),
+        ];
+        let (pk, sk) = self
+            .token
+            .generate_key_pair(mechanism, &mut public, &mut private)?;
+
+        let public_key = decode_ec_point(&self.token.attribute(pk, CKA_EC_POINT)?)?;
+        let key_id = key_id_for_public_key(&public_key);
+        for object in [pk, sk] {
+            let mut template = [bytes_attribute(CKA_ID, key_id.as_bytes())];
+            self.token.set_attributes(object, &mut template)?;
+        }
+        Ok(key_id)
+    }
+
+    fn aes_gcm_mechanism(params: &mut CK_GCM_PARAMS) -> CK_MECHANISM {
+        CK_MECHANISM {
+            mechanism: CKM_AES_GCM,
+            pParameter: params as *mut CK_GCM_PARAMS as *mut c_void,
+            ulParameterLen: core::mem::size_of::<CK_GCM_PARAMS>() as CK_ULONG,
+        }
+    }
+
+    fn aes_gcm_params(nonce: &[u8], aad: &[u8]) -> CK_GCM_PARAMS {
+        CK_GCM_PARAMS {
+            pIv: nonce.as_ptr() as *mut CK_BYTE,
+            ulIvLen: nonce.len() as CK_ULONG,
+            ulIvBits: (nonce.len() * 8) as CK_ULONG,
+            pAAD: aad.as_ptr() as *mut CK_BYTE,
+            ulAADLen: aad.len() as CK_ULONG,
+            ulTagBits: (AES_GCM_TAG_LEN * 8) as CK_ULONG,
+        }
+    }
+
+    fn aes_key(&self, key_id: &KeyId) -> Result<CK_OBJECT_HANDLE> {
+        match self.find_secret(key_id)? {
+            (object, CKK_AES) => Ok(object),
+            _ => Err(Pkcs11Error::InvalidKeyType.into()),
+        }
+    }
+
+    fn buffer(&self, key_id: &KeyId) -> Result<CK_OBJECT_HANDLE> {
+        match self.find_secret(key_id)? {
+            (object, CKK_GENERIC_SECRET) => Ok(object),
+            _ => Err(Pkcs11Error::InvalidKeyType.into()),
+        }
+    }
+
+    /// Run HKDF from `base` with the given mechanism, returning one secret per output.
+    fn hkdf(
+        &self,
+        mechanism: CK_MECHANISM_TYPE,
+        base: CK_OBJECT_HANDLE,
+        salt: CK_OBJECT_HANDLE,
+        info: &[u8],
+        output_attributes: &[SecretAttributes],
+    ) -> Result<Vec<KeyId>> {
+        for attributes in output_attributes {
+            let length = attributes.length();
+            match attributes.stype() {
+                SecretType::Aes
+                    if length != AES256_SECRET_LENGTH_U32 && length != AES128_SECRET_LENGTH_U32 =>
+                {
+                    return Err(Pkcs11Error::InvalidAesKeyLength.into())
+                }
+                SecretType::Aes | SecretType::Buffer if length as usize <= HKDF_OUTPUT_LEN => {}
+                _ => return Err(Pkcs11Error::InvalidHkdfOutputType.into()),
+            }
+        }
+
+        let mut params = CK_HKDF_PARAMS {
+            bExtract: CK_TRUE,
+            bExpand: CK_TRUE,
+            prfHashMechanism: CKM_SHA256,
+            ulSaltType: CKF_HKDF_SALT_KEY,
+            pSalt: ptr::null_mut(),
+            ulSaltLen: 0,
+            hSaltKey: salt,
+            pInfo: info.as_ptr() as *mut CK_BYTE,
+            ulInfoLen: info.len() as CK_ULONG,
+        };
+        let mut mechanism = CK_MECHANISM {
+            mechanism,
+            pParameter: &mut params as *mut CK_HKDF_PARAMS as *mut c_void,
+            ulParameterLen: core::mem::size_of::<CK_HKDF_PARAMS>() as CK_ULONG,
+        };
+        let length = (output_attributes.len() * HKDF_OUTPUT_LEN) as CK_ULONG;
+        let mut template = [
+            attribute(CKA_CLASS, &CKO_SECRET_KEY),
+            attribute(CKA_KEY_TYPE, &CKK_GENERIC_SECRET),
+            attribute(CKA_TOKEN, &CK_FALSE),
+            attribute(CKA_VALUE_LEN, &length),
+            attribute(CKA_SENSITIVE, &CK_TRUE),
+            attribute(CKA_EXTRACTABLE, &CK_FALSE),
+            attribute(CKA_DERIVE, &CK_TRUE),
+        ];
+        let okm = self.token.derive_key(&mut mechanism, base, &mut template)?;
+
+        let mut secrets = Vec::with_capacity(output_attributes.len());
+        let mut res = Ok(());
+        for (i, attributes) in output_attributes.iter().enumerate() {
+            match self.extract_key(okm, i * HKDF_OUTPUT_LEN, *attributes) {
+                Ok(key_id) => secrets.push(key_id),
+                Err(e) => {
+                    res = Err(e);
+                    break;
+                }
+            }
+        }
+        self.token.destroy_object(okm)?;
+        if let Err(e) = res {
+            for key_id in secrets {
+                let _ = self.destroy(&key_id);
+            }
+            return Err(e);
+        }
+        Ok(secrets)
+    }
+
+    /// Create a secret from the bytes of `base` starting at `offset`.
+    fn extract_key(
+        &self,
+        base: CK_OBJECT_HANDLE,
+        offset: usize,
+        attributes: SecretAttributes,
+    ) -> Result<KeyId> {
+        let (key_type, usage) = secret_key_type(attributes.stype())?;
+        let mut bit: CK_EXTRACT_PARAMS = (offset * 8) as CK_ULONG;
+        let mut mechanism = CK_MECHANISM {
+            mechanism: CKM_EXTRACT_KEY_FROM_KEY,
+            pParameter: &mut bit as *mut CK_EXTRACT_PARAMS as *mut c_void,
+            ulParameterLen: core::mem::size_of::<CK_EXTRACT_PARAMS>() as CK_ULONG,
+        };
+
+        let key_id = self.random_key_id()?;
+        let token = token_object(attributes.persistence());
+        let length = attributes.length() as CK_ULONG;
+        let mut template = vec![
+            attribute(CKA_CLASS, &CKO_SECRET_KEY),
+            attribute(CKA_KEY_TYPE, &key_type),
+            bytes_attribute(CKA_ID, key_id.as_bytes()),
+            attribute(CKA_TOKEN, &token),
+            attribute(CKA_VALUE_LEN, &length),
+            attribute(CKA_SENSITIVE, &CK_TRUE),
+            attribute(CKA_EXTRACTABLE, &CK_FALSE),
+        ];
+        template.extend(usage.iter().map(|u| attribute(*u, &CK_TRUE)));
+        self.token.derive_key(&mut mechanism, base, &mut template)?;
+        Ok(key_id)
+    }
+
+    /// Destroy all the objects holding the given key.
+    fn destroy(&self, key_id: &KeyId) -> Result<()> {
+        let mut template = [bytes_attribute(CKA_ID, key_id.as_bytes())];
+        let objects = self.token.find(&mut template)?;
+        if objects.is_empty() {
+            return Err(Pkcs11Error::EntryNotFound.into());
+        }
+        for object in objects {
+            self.token.destroy_object(object)?;
+        }
+        Ok(())
+    }
+}
+
+/// The value of `CKA_TOKEN` for secrets with the given persistence.
+fn token_object(persistence: SecretPersistence) -> CK_BBOOL {
+    match persistence {
+        SecretPersistence::Persistent => CK_TRUE,
+        SecretPersistence::Ephemeral => CK_FALSE,
+    }
+}
+
+/// The key type and usage attributes of secret keys of the given type.
+fn secret_key_type(stype: SecretType) -> Result<(CK_KEY_TYPE, &'static [CK_ATTRIBUTE_TYPE])> {
+    match stype {
+        SecretType::Aes => Ok((CKK_AES, &[CKA_ENCRYPT, CKA_DECRYPT])),
+        SecretType::Buffer => Ok((CKK_GENERIC_SECRET, &[CKA_DERIVE])),
+        SecretType::Ed25519 | SecretType::X25519 => Err(Pkcs11Error::InvalidKeyType.into()),
+    }
+}
+
+/// Compute the [`KeyId`] of a key pair the way the software vault does.
+fn key_id_for_public_key(public_key: &[u8]) -> KeyId {
+    hex::encode(Sha256::digest(public_key))
+}
+
+/// Extract a Curve25519 public key from a `CKA_EC_POINT` value.
+///
+/// Tokens return either the raw key or the key wrapped in a DER octet string.
+fn decode_ec_point(point: &[u8]) -> Result<Vec<u8>> {
+    const LEN: usize = CURVE25519_PUBLIC_LENGTH_USIZE;
+    match point {
+        p if p.len() == LEN => Ok(p.to_vec()),
+        [0x04, l, p @ ..] if *l as usize == LEN && p.len() == LEN => Ok(p.to_vec()),
+        _ => Err(Pkcs11Error::InvalidPublicKey.into()),
+    }
+}
+
+fn secret_type(key_type: CK_KEY_TYPE) -> Result<SecretType> {
+    match key_type {
+        CKK_EC_EDWARDS => Ok(SecretType::Ed25519),
+        CKK_EC_MONTGOMERY => Ok(SecretType::X25519),
+        CKK_AES => Ok(SecretType::Aes),
+        CKK_GENERIC_SECRET => Ok(SecretType::Buffer),
+        _ => Err(Pkcs11Error::InvalidKeyType.into()),
+    }
+}
+
+#[async_trait]
+impl SecretVault for Pkcs11Vault {
+    /// Generate a secret on the token.
+    async fn secret_generate(&self, attributes: SecretAttributes) -> Result<KeyId> {
+        let token = token_object(attributes.persistence());
+
+        let (mechanism, usage): (_, &[CK_ATTRIBUTE_TYPE]) = match attributes.stype() {
+            SecretType::Ed25519 | SecretType::X25519 => {
+                return self.generate_key_pair(attributes.stype(), token);
+            }
+            SecretType::Aes => {
+                if attributes.length() != AES256_SECRET_LENGTH_U32
+                    && attributes.length() != AES128_SECRET_LENGTH_U32
+                {
+                    return Err(Pkcs11Error::InvalidAesKeyLength.into());
+                }
+                (CKM_AES_KEY_GEN, &[CKA_ENCRYPT, CKA_DECRYPT])
+            }
+            SecretType::Buffer => (CKM_GENERIC_SECRET_KEY_GEN, &[CKA_DERIVE]),
+        };
+
+        let key_id = self.random_key_id()?;
+        let length = attributes.length() as CK_ULONG;
+        let mut template = vec![
+            bytes_attribute(CKA_ID, key_id.as_bytes()),
+            attribute(CKA_TOKEN, &token),
+            attribute(CKA_VALUE_LEN, &length),
+            attribute(CKA_SENSITIVE, &CK_TRUE),
+            attribute(CKA_EXTRACTABLE, &CK_FALSE),
+        ];
+        template.extend(usage.iter().map(|u| attribute(*u, &CK_TRUE)));
+        self.token.generate_key(mechanism, &mut template)?;
+        Ok(key_id)
+    }
+
+    /// Import a secret to the token.
+    ///
+    /// Only AES keys and buffers can be imported. Key pairs must be generated on the token.
+    async fn secret_import(&self, secret: &[u8], attributes: SecretAttributes) -> Result<KeyId> {
+        let (key_type, usage) = secret_key_type(attributes.stype())?;
+        let token = token_object(attributes.persistence());
+
+        let key_id = self.random_key_id()?;
+        let mut template = vec![
+            attribute(CKA_CLASS, &CKO_SECRET_KEY),
+            attribute(CKA_KEY_TYPE, &key_type),
+            bytes_attribute(CKA_ID, key_id.as_bytes()),
+            attribute(CKA_TOKEN, &token),
+            bytes_attribute(CKA_VALUE, secret),
+            attribute(CKA_SENSITIVE, &CK_TRUE),
+            attribute(CKA_EXTRACTABLE, &CK_FALSE),
+        ];
+        template.extend(usage.iter().map(|u| attribute(*u, &CK_TRUE)));
+        self.token.create_object(&mut template)?;
+        Ok(key_id)
+    }
+
+    /// Secrets never leave the token, so this always fails.
+    async fn secret_export(&self, _key_id: &KeyId) -> Result<SecretKey> {
+        Err(Pkcs11Error::NotExtractable.into())
+    }
+
+    async fn secret_attributes_get(&self, key_id: &KeyId) -> Result<SecretAttributes> {
+        let (object, key_type) = self.find_secret(key_id)?;
+        let stype = secret_type(key_type)?;
+        let length = match stype {
+            SecretType::Ed25519 | SecretType::X25519 => CURVE25519_SECRET_LENGTH_U32,
+            SecretType::Aes | SecretType::Buffer => {
+                self.token.ulong_attribute(object, CKA_VALUE_LEN)? as u32
+            }
+        };
+        let persistence = match self.token.attribute(object, CKA_TOKEN)?.first() {
+            Some(&CK_TRUE) => SecretPersistence::Persistent,
+            _ => SecretPersistence::Ephemeral,
+        };
+        Ok(SecretAttributes::new(stype, persistence, length))
+    }
+
+    async fn secret_public_key_get(&self, key_id: &KeyId) -> Result<PublicKey> {
+        let object = self.find(key_id, CKO_PUBLIC_KEY)?;
+        let stype = secret_type(self.token.ulong_attribute(object, CKA_KEY_TYPE)?)?;
+        let public_key = decode_ec_point(&self.token.attribute(object, CKA_EC_POINT)?)?;
+        Ok(PublicKey::new(public_key, stype))
+    }
+
+    async fn secret_destroy(&self, key_id: KeyId) -> Result<()> {
+        self.destroy(&key_id)
+    }
+}
+
+#[async_trait]
+impl Signer for Pkcs11Vault {
+    /// Sign data with an Ed25519 key. XEdDSA signatures with X25519 keys are not supported.
+    async fn sign(&self, key_id: &KeyId, data: &[u8]) -> Result<Signature> {
+        let object = match self.find_secret(key_id)? {
+            (object, CKK_EC_EDWARDS) => object,
+            _ => return Err(Pkcs11Error::InvalidKeyType.into()),
+        };
+        let mut mechanism = CK_MECHANISM {
+            mechanism: CKM_EDDSA,
+            pParameter: ptr::null_mut(),
+            ulParameterLen: 0,
+        };
+        let signature = self.token.sign(&mut mechanism, object, data)?;
+        Ok(Signature::new(signature))
+    }
+}
+
+#[async_trait]
+impl Verifier for Pkcs11Vault {
+    /// Verify a signature in software, as it only involves the public key.
+    async fn verify(
+        &self,
+        signature: &Signature,
+        public_key: &PublicKey,
+        data: &[u8],
+    ) -> Result<bool> {
+        Vault::create().verify(signature, public_key, data).await
+    }
+}
+
+#[async_trait]
+impl Hasher for Pkcs11Vault {
+    async fn sha256(&self, data: &[u8]) -> Result<[u8; 32]> {
+        Ok(Sha256::digest(data).into())
+    }
+
+    /// Derive secrets on the token with HKDF-SHA256.
+    ///
+    /// As in the software vault, the salt and input key material must be buffers,
+    /// and each output is made of the first bytes of its own 32 bytes block of
+    /// derived key material.
+    async fn hkdf_sha256(
+        &self,
+        salt: &KeyId,
+        info: &[u8],
+        ikm: Option<&KeyId>,
+        output_attributes: Vec<SecretAttributes>,
+    ) -> Result<Vec<KeyId>> {
+        let salt = self.buffer(salt)?;
+        match ikm {
+            Some(ikm) => {
+                let ikm = self.buffer(ikm)?;
+                self.hkdf(CKM_HKDF_DERIVE, ikm, salt, info, &output_attributes)
+            }
+            None => {
+                // Without input key material, derive from an empty data object
+                let mut template = [
+                    attribute(CKA_CLASS, &CKO_DATA),
+                    attribute(CKA_TOKEN, &CK_FALSE),
+                    bytes_attribute(CKA_VALUE, &[]),
+                ];
+                let data = self.token.create_object(&mut template)?;
+                let res = self.hkdf(CKM_HKDF_DATA, data, salt, info, &output_attributes);
+                self.token.destroy_object(data)?;
+                res
+            }
+        }
+    }
+}
+
+#[async_trait]
+impl AsymmetricVault for Pkcs11Vault {
+    /// Compute a shared secret with an X25519 key.
+    ///
+    /// The resulting buffer is kept on the token as an ephemeral secret.
+    async fn ec_diffie_hellman(
+        &self,
+        secret: &KeyId,
+        peer_public_key: &PublicKey,
+    ) -> Result<KeyId> {
+        let object = match self.find_secret(secret)? {
+            (object, CKK_EC_MONTGOMERY) => object,
+            _ => return Err(Pkcs11Error::InvalidKeyType.into()),
+        };
+        if peer_public_key.stype() != SecretType::X25519
+            || peer_public_key.data().len() != CURVE25519_PUBLIC_LENGTH_USIZE
+        {
+            return Err(Pkcs11Error::InvalidPublicKey.into());
+        }
+
+        let mut params = CK_ECDH1_DERIVE_PARAMS {
+            kdf: CKD_NULL,
+            ulSharedDataLen: 0,
+            pSharedData: ptr::null_mut(),
+            ulPublicDataLen: peer_public_key.data().len() as CK_ULONG,
+            pPublicData: peer_public_key.data().as_ptr() as *mut CK_BYTE,
+        };
+        let mut mechanism = CK_MECHANISM {
+            mechanism: CKM_ECDH1_DERIVE,
+            pParameter: &mut params as *mut CK_ECDH1_DERIVE_PARAMS as *mut c_void,
+            ulParameterLen: core::mem::size_of::<CK_ECDH1_DERIVE_PARAMS>() as CK_ULONG,
+        };
+
+        let key_id = self.random_key_id()?;
+        let length = CURVE25519_SECRET_LENGTH_U32 as CK_ULONG;
+        let mut template = [
+            attribute(CKA_CLASS, &CKO_SECRET_KEY),
+            attribute(CKA_KEY_TYPE, &CKK_GENERIC_SECRET),
+            bytes_attribute(CKA_ID, key_id.as_bytes()),
+            attribute(CKA_TOKEN, &CK_FALSE),
+            attribute(CKA_VALUE_LEN, &length),
+            attribute(CKA_SENSITIVE, &CK_TRUE),
+            attribute(CKA_EXTRACTABLE, &CK_FALSE),
+            attribute(CKA_DERIVE, &CK_TRUE),
+        ];
+        self.token
+            .derive_key(&mut mechanism, object, &mut template)?;
+        Ok(key_id)
+    }
+
+    async fn compute_key_id_for_public_key(&self, public_key: &PublicKey) -> Result<KeyId> {
+        Ok(key_id_for_public_key(public_key.data()))
+    }
+}
+
+#[async_trait]
+impl SymmetricVault for Pkcs11Vault {
+    async fn aead_aes_gcm_encrypt(
+        &self,
+        key_id: &KeyId,
+        plaintext: &[u8],
+        nonce: &[u8],
+        aad: &[u8],
+    ) -> Result<Buffer<u8>> {
+        let object = self.aes_key(key_id)?;
+        let mut params = Self::aes_gcm_params(nonce, aad);
+        let mut mechanism = Self::aes_gcm_mechanism(&mut params);
+        self.token.encrypt(
+            &mut mechanism,
+            object,
+            plaintext,
+            plaintext.len() + AES_GCM_TAG_LEN,
+        )
+    }
+
+    async fn aead_aes_gcm_decrypt(
+        &self,
+        key_id: &KeyId,
+        cipher_text: &[u8],
+        nonce: &[u8],
+        aad: &[u8],
+    ) -> Result<Buffer<u8>> {
+        let object = self.aes_key(key_id)?;
+        let mut params = Self::aes_gcm_params(nonce, aad);
+        let mut mechanism = Self::aes_gcm_mechanism(&mut params);
+        self.token
+            .decrypt(&mut mechanism, object, cipher_text, cipher_text.len())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn decode_ec_point_accepts_raw_and_der() {
+        let raw = [7u8; 32];
+        assert_eq!(decode_ec_point(&raw).unwrap(), raw.to_vec());
+
+        let mut der = vec![0x04, 32];
+        der.extend_from_slice(&raw);
+        assert_eq!(decode_ec_point(&der).unwrap(), raw.to_vec());
+
+        assert!(decode_ec_point(&der[..20]).is_err());
+    }
+}
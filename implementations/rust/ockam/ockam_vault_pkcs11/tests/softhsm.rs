@@ -0,0 +1,114 @@
+//! Tests against a PKCS#11 token, such as one of SoftHSMv2.
+//!
+//! They are ignored by default and need `OCKAM_PKCS11_MODULE` to point to a
+//! PKCS#11 module. The token label and user PIN are read from `OCKAM_PKCS11_TOKEN`
+//! and `OCKAM_PKCS11_PIN` and default to `ockam` and `1234`:
+//!
+//! ```text
+//! softhsm2-util --init-token --free --label ockam --so-pin 1234 --pin 1234
+//! OCKAM_PKCS11_MODULE=/usr/lib/softhsm/libsofthsm2.so \
+//!     cargo test -p ockam_vault_pkcs11 -- --ignored
+//! ```
+
+use ockam_core::vault::{
+    SecretAttributes, SecretPersistence, SecretType, SecretVault, Signer, SymmetricVault,
+    AES256_SECRET_LENGTH_U32, CURVE25519_SECRET_LENGTH_U32,
+};
+use ockam_core::Result;
+use ockam_identity::Identity;
+use ockam_node::Context;
+use ockam_vault::Vault;
+use ockam_vault_pkcs11::Pkcs11Vault;
+
+fn vault() -> Pkcs11Vault {
+    let module = std::env::var("OCKAM_PKCS11_MODULE").expect("OCKAM_PKCS11_MODULE is not set");
+    let label = std::env::var("OCKAM_PKCS11_TOKEN").unwrap_or_else(|_| "ockam".into());
+    let pin = std::env::var("OCKAM_PKCS11_PIN").unwrap_or_else(|_| "1234".into());
+    Pkcs11Vault::open(module, &label, &pin).unwrap()
+}
+
+#[ignore]
+#[tokio::test]
+async fn ed25519_keys_stay_on_the_token() {
+    let vault = vault();
+    let attributes = SecretAttributes::new(
+        SecretType::Ed25519,
+        SecretPersistence::Ephemeral,
+        CURVE25519_SECRET_LENGTH_U32,
+    );
+    let key_id = vault.secret_generate(attributes).await.unwrap();
+
+    assert_eq!(
+        vault.secret_attributes_get(&key_id).await.unwrap(),
+        attributes
+    );
+    assert!(vault.secret_export(&key_id).await.is_err());
+
+    let public_key = vault.secret_public_key_get(&key_id).await.unwrap();
+    assert_eq!(public_key.stype(), SecretType::Ed25519);
+    assert_eq!(public_key.data().len(), 32);
+
+    let signature = vault.sign(&key_id, b"hello").await.unwrap();
+    assert_eq!(signature.as_ref().len(), 64);
+
+    vault.secret_destroy(key_id.clone()).await.unwrap();
+    assert!(vault.secret_attributes_get(&key_id).await.is_err());
+}
+
+#[ignore]
+#[tokio::test]
+async fn aes_gcm_roundtrip() {
+    let vault = vault();
+    let attributes = SecretAttributes::new(
+        SecretType::Aes,
+        SecretPersistence::Ephemeral,
+        AES256_SECRET_LENGTH_U32,
+    );
+    let key_id = vault.secret_generate(attributes).await.unwrap();
+    let nonce = [1u8; 12];
+
+    let ciphertext = vault
+        .aead_aes_gcm_encrypt(&key_id, b"hello", &nonce, b"aad")
+        .await
+        .unwrap();
+    assert_eq!(ciphertext.len(), 5 + 16);
+
+    let plaintext = vault
+        .aead_aes_gcm_decrypt(&key_id, &ciphertext, &nonce, b"aad")
+        .await
+        .unwrap();
+    assert_eq!(plaintext, b"hello");
+
+    assert!(vault
+        .aead_aes_gcm_decrypt(&key_id, &ciphertext, &nonce, b"other")
+        .await
+        .is_err());
+
+    vault.secret_destroy(key_id).await.unwrap();
+}
+
+#[ignore]
+#[ockam_macros::test]
+async fn identity_root_key_stays_on_the_token(ctx: &mut Context) -> Result<()> {
+    let identity = Identity::create(ctx, &vault()).await?;
+    let signature = identity.create_signature(b"hello", None).await?;
+
+    let public = identity.to_public().await?;
+    assert!(
+        public
+            .verify_signature(&signature, b"hello", None, &Vault::create())
+            .await?
+    );
+
+    // The root key is persistent, so it can be used from another session
+    let exported = identity.export().await?;
+    let imported = Identity::import(ctx, &exported, &vault()).await?;
+    let signature = imported.create_signature(b"again", None).await?;
+    assert!(
+        public
+            .verify_signature(&signature, b"again", None, &Vault::create())
+            .await?
+    );
+
+    ctx.stop().await
+}